@@ -1,7 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use lumina_core::event::InputEvent;
-use lumina_core::renderer::driver::ExecutorHandle;
-use lumina_core::renderer::Renderer;
+use lumina_core::renderer::headless::HeadlessRenderer;
 use lumina_core::ScriptManager;
 use lumina_core::{runtime::Ctx, OutputEvent};
 use std::sync::Arc;
@@ -34,28 +33,6 @@ fn make_script_source(lines: usize) -> String {
     buf
 }
 
-struct NullRenderer;
-impl Renderer for NullRenderer {
-    fn run_event_loop(&mut self, ctx: &mut Ctx, manager: Arc<ScriptManager>) {
-        let mut driver = ExecutorHandle::new(ctx, manager);
-
-        loop {
-            // 模拟驱动步进
-            let _waiting = driver.step(ctx);
-
-            // 模拟事件消耗
-            for out in ctx.drain() {
-                match out {
-                    OutputEvent::ShowChoice { .. } => driver.feed(ctx, InputEvent::ChoiceMade {index: 0}),
-                    OutputEvent::ShowDialogue {..} | OutputEvent::ShowNarration {..} => driver.feed(ctx, InputEvent::Continue),
-                    OutputEvent::End => return,
-                    _ => {}
-                }
-            }
-        }
-    }
-}
-
 static INIT: std::sync::Once = std::sync::Once::new();
 
 fn bench_executor(c: &mut Criterion) {
@@ -86,8 +63,12 @@ fn bench_executor(c: &mut Criterion) {
 
         b.iter(|| {
             let mut ctx = Ctx::default();
-            let mut renderer = NullRenderer;
-            renderer.run_event_loop(&mut ctx, manager_arc.clone());
+            // 选项一律选第一项
+            let mut renderer = HeadlessRenderer::new().with_responder(|ev| match ev {
+                OutputEvent::ShowChoice { .. } => Some(InputEvent::ChoiceMade { index: 0 }),
+                _ => None,
+            });
+            renderer.play_until_end(&mut ctx, manager_arc.clone(), usize::MAX).unwrap();
         });
     });
     let _ = std::fs::remove_dir_all(dir);
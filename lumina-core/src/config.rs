@@ -1,12 +1,97 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemConfig {
     pub assets_path: String, // 移到这里，Core也需要知道资源在哪
     pub script_path: String,
-    pub save_path:   String, // ✅ 新增
+    /// 存档目录；`auto` 或留空时使用系统的用户数据目录 (`<数据目录>/<game_id>/saves`)。
+    /// 默认为 `auto`，旧版本默认的 `saves/` 会在首次运行时复制到新目录；
+    /// 想继续放在游戏目录下请显式写 `save_path = "saves/"`
+    #[serde(default = "default_save_path")]
+    pub save_path:   String,
     pub log_path:    String, // ✅ 新增
     pub log_level:   String,
+    #[serde(default = "default_language")]
+    pub language:    String,
+    /// 新游戏的入口 label
+    #[serde(default = "default_start_label")]
+    pub start_label: String,
+    /// 文字逐字显示速度 (字/秒)，0 表示立即显示整行
+    #[serde(default = "default_text_speed")]
+    pub text_speed:  f32,
+    /// 自动模式下整行显示完后的停留时间 (秒)
+    #[serde(default = "default_auto_delay")]
+    pub auto_delay:  f32,
+    /// 自动模式下按字数追加的停留时间 (秒/字)
+    #[serde(default = "default_auto_delay_per_char")]
+    pub auto_delay_per_char: f32,
+    /// 内存中保留的对话历史条数，0 表示不限
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+    /// 写入存档的对话历史条数 (取最近的)，0 表示不限
+    #[serde(default = "default_save_history_limit")]
+    pub save_history_limit: usize,
+    /// 游戏标识，自动存档目录以此命名，发行后不要再改
+    #[serde(default = "default_game_id")]
+    pub game_id: String,
+    /// 自动存档轮流覆盖的槽位数，0 表示关闭自动存档
+    #[serde(default = "default_autosave_slots")]
+    pub autosave_slots: usize,
+    /// 进入新 label 之外，每显示这么多条对话也自动存档一次，0 表示只在进入 label 时存
+    #[serde(default = "default_autosave_lines")]
+    pub autosave_lines: usize,
+    /// 两次自动存档之间至少间隔的时间 (秒)
+    #[serde(default = "default_autosave_interval")]
+    pub autosave_interval: f32,
+}
+
+fn default_save_path() -> String {
+    "auto".into()
+}
+
+fn default_game_id() -> String {
+    "luminatale".into()
+}
+
+fn default_autosave_slots() -> usize {
+    3
+}
+
+fn default_autosave_lines() -> usize {
+    30
+}
+
+fn default_autosave_interval() -> f32 {
+    60.0
+}
+
+fn default_language() -> String {
+    "zh".into()
+}
+
+fn default_start_label() -> String {
+    "init".into()
+}
+
+fn default_text_speed() -> f32 {
+    40.0
+}
+
+fn default_auto_delay() -> f32 {
+    1.5
+}
+
+fn default_auto_delay_per_char() -> f32 {
+    0.03
+}
+
+fn default_history_limit() -> usize {
+    5000
+}
+
+fn default_save_history_limit() -> usize {
+    1000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +104,30 @@ pub struct AudioConfig {
     pub fade_in_sec:   f32,
     pub fade_out_sec:  f32,
     pub voice_link_char: String,
+    /// 推进到下一句时停止上一句仍在播放的语音
+    #[serde(default = "default_stop_voice_on_advance")]
+    pub stop_voice_on_advance: bool,
+    /// 没有写语音编号的对话是否按命名规则自动查找语音
+    #[serde(default)]
+    pub auto_voice: AutoVoice,
+    /// 语音播放期间音乐降低的分贝数，0 表示不压低
+    #[serde(default)]
+    pub duck_music_db: f32,
+}
+
+/// 对话语音的自动查找方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoVoice {
+    #[default]
+    Off,
+    /// 角色有 `voice_tag` 时尝试 `<voice_tag>_<label>_<n>`，`n` 为该对话在 label 中的序号；
+    /// 文件不存在时不播放
+    ByLine,
+}
+
+fn default_stop_voice_on_advance() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +136,147 @@ pub struct GraphicsConfig {
     pub preload_ahead: usize, // 原 ahead_step
     pub scene_zindex: usize,
     pub sprite_zindex: usize,
+    /// 图片序列过场动画的帧率
+    #[serde(default = "default_movie_fps")]
+    pub movie_fps: f32,
+    /// 立绘原图对应设计高度 (1080) 时的像素高度，如按 4K 出图填 2160；
+    /// 立绘先按 设计高度 / 参考高度 缩放，作者的 `scale` 在此基础上相乘。未设置时按原始像素绘制
+    #[serde(default)]
+    pub sprite_reference_height: Option<f32>,
+    /// 按立绘 (不含属性的资源名) 指定基础缩放，覆盖 `sprite_reference_height`
+    #[serde(default)]
+    pub sprite_scale: BTreeMap<String, f32>,
+}
+
+fn default_movie_fps() -> f32 {
+    24.0
+}
+
+/// 终端界面配色 (`[tui]`)：`theme` 选择内置主题，其余为可选的单项颜色覆盖，
+/// 取值为颜色名 (`red`、`lightblue`) 或 `#rrggbb`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuiConfig {
+    #[serde(default = "default_tui_theme")]
+    pub theme: String,
+    #[serde(default)]
+    pub border: Option<String>,
+    #[serde(default)]
+    pub speaker: Option<String>,
+    #[serde(default)]
+    pub narration: Option<String>,
+    #[serde(default)]
+    pub choice: Option<String>,
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+fn default_tui_theme() -> String {
+    "dark".into()
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            theme: default_tui_theme(),
+            border: None,
+            speaker: None,
+            narration: None,
+            choice: None,
+            command: None,
+        }
+    }
+}
+
+/// 调试选项 (`[debug]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebugConfig {
+    /// 允许 TUI 使用 `:jump` / `:set` / `:vars` 等调试命令，图形界面可按 F3 打开调试浮层
+    #[serde(default)]
+    pub enable_console: bool,
+    /// 脚本有语法错误时仍然加载，出错的语句执行到时在画面上提示
+    #[serde(default)]
+    pub allow_script_errors: bool,
+    /// 对话的说话人不是已定义角色时的处理：`allow` 忽略，`warn` 加载时记录日志并在运行时提示，
+    /// `deny` 拒绝加载项目
+    #[serde(default)]
+    pub unknown_speaker: LintLevel,
+    /// 编辑器预览端口，只监听 127.0.0.1；需要以 `preview` feature 编译，不填则关闭。
+    /// 连接须先发送存档目录下 `preview.token` 中的口令
+    #[serde(default)]
+    pub preview_port: Option<u16>,
+    /// 允许预览连接发送 `eval` 执行任意 Lua，默认关闭
+    #[serde(default)]
+    pub preview_eval: bool,
+    /// 跳过主菜单，启动后直接从 `system.start_label` 开始新游戏
+    #[serde(default)]
+    pub skip_menu: bool,
+    /// 启动后直接读取该槽位的存档 (同样跳过主菜单)，便于复现问题；
+    /// 脚本已改动时从存档所在 label 开头继续，读取失败时开始新游戏
+    #[serde(default)]
+    pub load_slot: Option<u32>,
+}
+
+/// 无障碍选项 (`[accessibility]`)，玩家在设置页的选择保存在 preferences.json 中并优先生效
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    /// 减少动态效果：关闭抖动与菜单按钮动画，缩短动画时长，界面切换只用淡入淡出
+    #[serde(default)]
+    pub reduce_motion: bool,
+    /// 朗读对话、旁白与选项 (TTS)
+    #[serde(default)]
+    pub self_voicing: bool,
+    /// 有配音的对话不再朗读
+    #[serde(default = "default_skip_voiced_lines")]
+    pub skip_voiced_lines: bool,
+    /// 朗读命令及参数，文本从标准输入传入。为空时以 `tts` feature 编译则用系统语音，
+    /// 否则按平台选择 (`espeak` / `say`)
+    #[serde(default)]
+    pub tts_command: Vec<String>,
+}
+
+fn default_skip_voiced_lines() -> bool {
+    true
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            reduce_motion: false,
+            self_voicing: false,
+            skip_voiced_lines: default_skip_voiced_lines(),
+            tts_command: Vec::new(),
+        }
+    }
+}
+
+/// 静态检查的严重程度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+
+/// 单个成就的展示信息 (`[achievements.<id>]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AchievementDef {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// 成就表：id -> 展示信息
+pub type AchievementsConfig = BTreeMap<String, AchievementDef>;
+
+impl SystemConfig {
+    /// 自动模式下，`chars` 个字的一行显示完后的停留时间 (秒)
+    pub fn auto_wait(&self, chars: usize) -> f32 {
+        self.auto_delay + self.auto_delay_per_char * chars as f32
+    }
 }
 
 impl Default for SystemConfig {
@@ -34,9 +284,20 @@ impl Default for SystemConfig {
         Self {
             assets_path: "assets/".into(),
             script_path: "game/".into(),
-            save_path:   "saves/".into(),
+            save_path:   default_save_path(),
             log_path:    "logs/".into(),
             log_level:   "info".into(),
+            language:    default_language(),
+            start_label: default_start_label(),
+            text_speed:  default_text_speed(),
+            auto_delay:  default_auto_delay(),
+            auto_delay_per_char: default_auto_delay_per_char(),
+            history_limit: default_history_limit(),
+            save_history_limit: default_save_history_limit(),
+            game_id:     default_game_id(),
+            autosave_slots: default_autosave_slots(),
+            autosave_lines: default_autosave_lines(),
+            autosave_interval: default_autosave_interval(),
         }
     }
 }
@@ -52,6 +313,9 @@ impl Default for AudioConfig {
             fade_in_sec: 0.2,
             fade_out_sec: 0.2,
             voice_link_char: "_".into(),
+            stop_voice_on_advance: default_stop_voice_on_advance(),
+            auto_voice: AutoVoice::default(),
+            duck_music_db: 0.0,
         }
     }
 }
@@ -63,6 +327,9 @@ impl Default for GraphicsConfig {
             preload_ahead: 20,
             scene_zindex: 0,
             sprite_zindex: 10,
+            movie_fps: default_movie_fps(),
+            sprite_reference_height: None,
+            sprite_scale: BTreeMap::new(),
         }
     }
 }
\ No newline at end of file
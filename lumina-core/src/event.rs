@@ -1,6 +1,33 @@
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::runtime::assets::PresentationMode;
 
-#[derive(Debug, Clone)]
+/// 事件序列化格式的版本 (录制回放、预览连接、崩溃报告共用)。
+/// 变体或字段有不兼容的改动时递增，读取方用 [`Envelope::into_event`] 校验
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// 带协议版本号的事件，落盘或跨进程传输时使用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<E> {
+    pub version: u32,
+    pub event: E,
+}
+
+impl<E> Envelope<E> {
+    pub fn new(event: E) -> Self {
+        Self { version: PROTOCOL_VERSION, event }
+    }
+
+    /// 版本与当前协议不一致时报错，不尝试解释内容
+    pub fn into_event(self) -> anyhow::Result<E> {
+        if self.version != PROTOCOL_VERSION {
+            anyhow::bail!("Event protocol version {} is not supported (expected {})", self.version, PROTOCOL_VERSION);
+        }
+        Ok(self.event)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LayoutConfig {
     pub x: f32,       // 0.0~1.0 相对屏幕宽
     pub y: f32,       // 0.0~1.0 相对屏幕高
@@ -8,7 +35,7 @@ pub struct LayoutConfig {
     pub anchor_y: f32,// 0.0~1.0
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransitionConfig {
     pub duration: f32,
     pub easing: String,
@@ -17,26 +44,44 @@ pub struct TransitionConfig {
     pub vague: Option<f32>,
 }
 
-#[derive(Debug, Clone)]
+/// 立绘帧动画的状态，`Talk` 在该立绘是当前说话者时代替 `Idle` 播放
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimState {
+    Idle,
+    Talk,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OutputEvent {
     ShowNarration { lines: Vec<String> },
     ShowDialogue { name: String, content: String },
-    ShowChoice { title: Option<String>, options: Vec<String> },
+    /// `timeout` 为限时选择的秒数，由渲染层倒计时，超时后发送 `ChoiceMade { index: default }`
+    ShowChoice { title: Option<String>, options: Vec<String>, timeout: Option<f32>, default: usize },
 
-    PlayAudio {channel: String, path: String, fade_in: f32, volume: f32 ,looping: bool},
+    /// `speculative` 为 true 时资源未必存在 (如自动查找的语音)，渲染层找不到时静默跳过
+    /// `pitch` 为播放速率，`None` 为原速；`intro` 为先播放一次的前奏，`path` 在其结束时无缝接上
+    PlayAudio {channel: String, path: String, fade_in: f32, volume: f32 ,looping: bool, loop_region: Option<(f32, Option<f32>)>, speculative: bool, pitch: Option<f32>, intro: Option<String>},
     StopAudio {channel: String, fade_out: f32},
+    /// 剧情等待 `channel` 上的声音播完，渲染层在其停止后发送 `Continue`
+    WaitAudioEnd { channel: String },
     
-    NewScene {transition: String},
+    /// 播放过场动画，剧情等待渲染层在播完或跳过后发送 `Continue`
+    PlayMovie { id: String, skippable: bool },
+
+    /// `background` 为背景的完整资源名，`None` 表示清空背景。
+    /// 以下四个事件的 `duration` 为脚本里 `with <转场> <秒数>` 写的时长，覆盖转场注册的默认时长
+    NewScene {background: Option<String>, transition: String, duration: Option<f32>},
     NewSprite {
         target: String,
         texture: String,
         pos_str: Option<String>,
         transition: Option<String>,
+        duration: Option<f32>,
         attrs: Vec<String>,
         defer_visual: bool,
     },
-    UpdateSprite { target:String, transition: String },
-    HideSprite { target:String, transition: Option<String> },
+    UpdateSprite { target:String, transition: String, duration: Option<f32> },
+    HideSprite { target:String, transition: Option<String>, duration: Option<f32> },
 
     Preload {
         images: Vec<String>,
@@ -45,25 +90,70 @@ pub enum OutputEvent {
     SetVolume {
         channel: String,
         value: f32,
+        /// 渐变时长 (秒)，0 为立即生效
+        fade: f32,
     },
     ModifyVisual {
         target: String,
         props: HashMap<String, f32>,
         duration: f32,
-        easing: String
+        easing: String,
+        /// 动画完成 id，渲染层在补间结束 (或被打断、跳过) 后回送 `AnimationDone`
+        on_done: Option<String>,
     },
     RegisterLayout { name: String, config: LayoutConfig },
     RegisterTransition { name: String, config: TransitionConfig },
+    /// 一次性抖动，`target` 为立绘名或 `"screen"` (整个画面)
+    Shake { target: String, intensity: f32, duration: f32 },
+    /// 全屏闪色，颜色为 RGBA
+    Flash { color: [u8; 4], duration: f32 },
+    /// 立绘帧动画：每秒 `fps` 帧，依次把 `frames` 中的帧名作为最后一个属性拼进资源名 (空字符串为不加)，
+    /// `frames` 为空时移除该状态的动画
+    SetSpriteAnim { target: String, state: AnimState, frames: Vec<String>, fps: f32, looping: bool },
+    AchievementUnlocked { id: String },
+    /// 角落里的短暂提示，不阻塞剧情
+    Toast { text: String, duration: f32 },
+    SetPresentation { mode: PresentationMode },
+    /// 调试命令的执行结果
+    DebugOutput { text: String },
+    /// 执行到解析失败的语句 (`Stmt::Error`)
+    ScriptError { line: usize, msg: String },
+    /// 不影响执行的脚本问题 (如未定义的说话人)，供调试提示
+    Warning { line: usize, msg: String },
+    /// 读档时存档所在的 label 已被修改，等待 `ResolveLoad` 决定是否从头重新开始
+    SaveMismatch { label: String },
+    /// 快速存档 (`load` 为 false) 或快速读档完成，渲染层显示短暂提示
+    QuickSaveDone { load: bool, ok: bool },
 
     StepDone,
     End,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum InputEvent {
     ChoiceMade { index: usize },
     Continue,
     Exit,
     SaveRequest { slot: u32 },
     LoadRequest { slot: u32 },
+    /// 快速存档 / 读档，使用专用文件，不占编号槽位
+    QuickSave,
+    QuickLoad,
+    ExportHistory { filename: String },
+    SetAuto { enabled: bool },
+    SetSkip { enabled: bool },
+    /// 回应 `SaveMismatch`：`restart` 为 true 时从该 label 开头继续，否则放弃读档
+    ResolveLoad { restart: bool },
+    DebugJump { label: String },
+    /// 从 label 的第 `pc` 条语句开始执行
+    DebugGoto { label: String, pc: usize },
+    DebugEval { code: String },
+    /// 重新加载脚本项目，停留在当前 label 的同一条语句
+    DebugReload,
+    /// 列出所有 label 的标题与定义位置，配合 `DebugJump` 跳转章节
+    DebugChapters,
+    /// 写入脚本变量 `f[name]`，如起名界面提交的玩家名
+    SetVar { name: String, value: String },
+    /// 带完成 id 的 `ModifyVisual` 播放结束
+    AnimationDone { id: String },
 }
\ No newline at end of file
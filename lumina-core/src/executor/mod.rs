@@ -12,11 +12,12 @@ use frame::Frame;
 use call_stack::CallStack;
 
 use crate::runtime::Ctx;
-use crate::config::GraphicsConfig;
+use crate::config::{AudioConfig, GraphicsConfig};
+use crate::runtime::assets::Audio;
 use crate::event::{OutputEvent, InputEvent};
 use crate::executor::walk::{walk_stmt, NextAction, StmtEffect};
 use crate::lua_glue::{self, CommandBuffer, LuaCommand};
-use crate::storager::types::FrameSnapshot;
+use crate::storager::types::{FrameSnapshot, LoadOutcome, PendingPrompt};
 use crate::manager::ScriptManager;
 
 #[derive(Clone)]
@@ -24,8 +25,12 @@ pub struct Executor {
     call_stack: CallStack,
     lua: Lua,
     cmd_buffer: CommandBuffer,
-    pending_choice: Option<Vec<(String, Vec<Stmt>)>>,
+    pending_choice: Option<Vec<(String, Arc<[Stmt]>)>>,
     pause: bool,
+    /// 导致当前等待的提示，存档时一并保存
+    prompt: Option<PendingPrompt>,
+    /// 读档恢复的提示，下一次 step 时重发
+    replay: bool,
 
     manager: Arc<ScriptManager>,
     dynamic_registry: HashSet<String>,
@@ -53,15 +58,17 @@ impl Executor {
             cmd_buffer,
             pending_choice: None,
             pause: false,
+            prompt: None,
+            replay: false,
             dynamic_registry: HashSet::new(),
             manager,
         };
 
         let sys_cfg: crate::config::SystemConfig = lumina_shared::config::get("system");
-        let boot_path = std::path::Path::new(&sys_cfg.script_path).join("boot.lua");
-        if boot_path.exists() {
-            info!("Loading boot script: {:?}", boot_path);
-            if let Ok(code) = std::fs::read_to_string(&boot_path) {
+        let scripts = lumina_shared::vfs::open_or_dir(&sys_cfg.script_path, lumina_shared::vfs::SCRIPTS);
+        if scripts.exists("boot.lua") {
+            info!("Loading boot script from {:?}", sys_cfg.script_path);
+            if let Ok(code) = scripts.read_to_string("boot.lua") {
                 if let Err(e) = exe.lua.load(&code)
                     .set_name("=boot.lua")
                     .exec() {
@@ -69,7 +76,7 @@ impl Executor {
                 }
             }
         } else {
-            warn!("boot.lua not found at {:?}, using default settings.", boot_path);
+            warn!("boot.lua not found in {:?}, using default settings.", sys_cfg.script_path);
         }
 
         exe
@@ -79,46 +86,129 @@ impl Executor {
         init_ctx_runtime(ctx);
         let global_chars = self.manager.collect_characters();
         ctx.characters.extend(global_chars);
-        self.perform_jump(label);
+        self.perform_jump(label).unwrap_or_else(|e| panic!("{}", e));
     }
 
-    pub fn feed(&mut self, ev: InputEvent) {
-        match ev {
-            InputEvent::ChoiceMade { index } => {
-                if let Some(mut arms) = self.pending_choice.take() {
-                    if index < arms.len() {
-                        let (block_id, selected_body) = arms.remove(index);
+    /// 调试：跳转到任意 label，丢弃当前等待的输入或选项
+    pub fn debug_jump(&mut self, label: &str) -> anyhow::Result<()> {
+        self.debug_goto(label, 0)
+    }
+
+    /// 读档发现脚本改动后，从 label 开头重新开始
+    pub fn restart_label(&mut self, label: &str) -> anyhow::Result<()> {
+        self.debug_goto(label, 0)
+    }
+
+    /// 调试：清空调用栈，从 label 的第 `pc` 条语句开始执行。
+    /// 该位置之前的状态 (变量、立绘等) 不会补上，需要作者自行设置
+    pub fn debug_goto(&mut self, label: &str, pc: usize) -> anyhow::Result<()> {
+        let body = self.get_block_arc(label)
+            .ok_or_else(|| anyhow::anyhow!("Label '{}' not found in project!", label))?;
+        if pc >= body.len() {
+            anyhow::bail!("Label '{}' has only {} statements, pc {} is out of range", label, body.len(), pc);
+        }
+
+        self.call_stack.clear();
+        self.call_stack.push(Frame::new(label.to_string(), body, pc));
+        self.pause = false;
+        self.pending_choice = None;
+        self.prompt = None;
+        Ok(())
+    }
 
-                        let frame = self.call_stack.top_mut().unwrap();
-                        frame.advance();
+    /// 调试：在当前 Lua 状态上执行代码，返回值格式化为文本 (表以 JSON 显示)
+    pub fn debug_eval(&self, code: &str) -> String {
+        self.try_debug_eval(code).unwrap_or_else(|e| format!("error: {}", e))
+    }
+
+    /// 同 [`Executor::debug_eval`]，出错时返回 Err
+    pub fn try_debug_eval(&self, code: &str) -> mlua::Result<String> {
+        let values = self.lua.load(code).set_name("=debug").eval::<mlua::MultiValue>()?;
+        if values.is_empty() {
+            return Ok("ok".to_string());
+        }
+        Ok(values.iter()
+            .map(|v| match v {
+                mlua::Value::Table(_) => lua_glue::vars::to_json(&self.lua, v)
+                    .and_then(|j| serde_json::to_string_pretty(&j).ok())
+                    .unwrap_or_else(|| "<table>".to_string()),
+                other => other.to_string().unwrap_or_else(|_| format!("<{}>", other.type_name())),
+            })
+            .collect::<Vec<_>>()
+            .join("\t"))
+    }
 
-                        let return_frame = Frame::new(frame.name.clone(), frame.stmts.clone(), frame.pc);
-                        self.call_stack.pop();
-                        self.call_stack.push(return_frame);
+    /// 调试：换用重新加载的脚本，从当前 label 的同一条语句继续 (超出范围时取最后一条)。
+    /// 调用栈只保留最上层一帧
+    pub fn reload(&mut self, manager: Arc<ScriptManager>) -> anyhow::Result<()> {
+        self.manager = manager;
+        let Some((label, pc)) = self.positions().pop() else { return Ok(()) };
+        let len = self.get_block_arc(&label)
+            .ok_or_else(|| anyhow::anyhow!("Label '{}' no longer exists after reload", label))?
+            .len();
+        self.debug_goto(&label, pc.min(len.saturating_sub(1)))
+    }
 
-                        self.call_stack.push(Frame::new(block_id, selected_body, 0));
+    pub fn feed(&mut self, ev: InputEvent) {
+        match ev {
+            InputEvent::ChoiceMade { index } => {
+                // 选择被消费后 pending_choice 即为 None，直到下一个提示出现前
+                // 渲染层连发的 ChoiceMade 都会在这里被丢弃
+                let arm_count = match &self.pending_choice {
+                    Some(arms) => arms.len(),
+                    None => {
+                        warn!("ChoiceMade({}) ignored: no pending choice", index);
+                        return;
                     }
+                };
+                if index >= arm_count {
+                    warn!("ChoiceMade({}) ignored: only {} options", index, arm_count);
+                    return;
                 }
+
+                let mut arms = self.pending_choice.take().unwrap();
+                self.prompt = None;
+                let (block_id, selected_body) = arms.swap_remove(index);
+                self.enter_block(block_id, selected_body);
             },
             InputEvent::Exit => {
                 self.call_stack.clear();
                 self.pause = false;
+                self.prompt = None;
                 if let Some(frame) = self.call_stack.top_mut(){
                     frame.advance();
                 }
             },
             InputEvent::Continue => {
+                if !self.pause {
+                    log::debug!("Continue ignored: not waiting for input");
+                    return;
+                }
                 self.pause = false;
+                self.prompt = None;
                 if let Some(frame) = self.call_stack.top_mut(){
                     frame.advance();
                 }
             }
+            InputEvent::SetVar { name, value } => {
+                let set = self.lua.globals().get::<mlua::Table>("f")
+                    .and_then(|f| f.set(name.as_str(), value));
+                if let Err(e) = set {
+                    warn!("SetVar '{}' failed: {}", name, e);
+                }
+            }
+            InputEvent::AnimationDone { id } => {
+                if let Err(e) = lua_glue::api::visual::animation_done(&self.lua, &id) {
+                    error!("Animation callback '{}' failed: {}", id, e);
+                }
+            }
             _ => {}
         }
     }
 
     pub fn sync_vars_to_ctx(&self, ctx: &mut Ctx) {
         ctx.var_f = lua_glue::extract_vars(&self.lua);
+        ctx.rng_state = lua_glue::api::random::rng_state(&self.lua);
 
         let sf_data = lua_glue::extract_sf(&self.lua);
 
@@ -131,6 +221,7 @@ impl Executor {
 
     pub fn sync_vars_from_ctx(&self, ctx: &mut Ctx) {
         lua_glue::inject_vars(&self.lua, &ctx.var_f);
+        lua_glue::api::random::set_rng_state(&self.lua, ctx.rng_state);
     }
 
     pub fn load_global_data(&self) {
@@ -150,27 +241,84 @@ impl Executor {
         }
     }
 
+    /// 调用栈上每一帧的 (label, pc)，栈底在前
+    pub fn positions(&self) -> Vec<(String, usize)> {
+        self.call_stack.stack.iter().map(|f| (f.name.clone(), f.pc)).collect()
+    }
+
     pub fn snapshot(&self) -> Vec<FrameSnapshot> {
         self.call_stack.stack
             .iter().map(|f| FrameSnapshot {
             label: f.name.clone(),
             pc: f.pc,
+            choice: match f.current() {
+                Some(Stmt::Choice { id, .. }) => id.clone(),
+                _ => None,
+            },
+            hash: self.manager.block_hash(&f.name).unwrap_or_default(),
         })
             .collect()
     }
 
-    pub fn restore(&mut self, snap: Vec<FrameSnapshot>) {
+    /// 恢复调用栈。代码块内容与存档时不同则放弃恢复，
+    /// 停在带 id 的选项上的帧例外：按选项 id 重新定位
+    pub fn restore(&mut self, snap: Vec<FrameSnapshot>) -> LoadOutcome {
         self.call_stack.clear();
+        let outer = snap.first().map(|fs| fs.label.clone()).unwrap_or_default();
+
         for fs in snap {
-            if let Some(body) = self.get_block_arc(&fs.label) {
-                let pc = if fs.pc > body.len() { 0 } else { fs.pc };
-                let frame = Frame::new(fs.label, body, pc);
-                self.call_stack.push(frame);
-            } else {
-                log::error!("Restore failed: Code block '{}' not found in project.", fs.label);
-                panic!("Save file mismatch or Script changed");
+            let Some(body) = self.get_block_arc(&fs.label) else {
+                warn!("Restore failed: code block '{}' not found in project", fs.label);
+                self.call_stack.clear();
+                return LoadOutcome::LabelChanged { label: outer };
+            };
+            let choice_pc = fs.choice.as_ref().and_then(|choice| {
+                body.iter().position(|s| matches!(s, Stmt::Choice { id: Some(id), .. } if id == choice))
+            });
+            let pc = match choice_pc {
+                Some(pc) => pc,
+                None if self.manager.block_hash(&fs.label) == Some(fs.hash) => fs.pc,
+                None => {
+                    warn!("Restore failed: code block '{}' changed since the save", fs.label);
+                    self.call_stack.clear();
+                    return LoadOutcome::LabelChanged { label: outer };
+                }
+            };
+            self.call_stack.push(Frame::new(fs.label, body, pc));
+        }
+        LoadOutcome::Restored
+    }
+
+    /// 当前章节名：最外层 label 的标题
+    pub fn chapter(&self) -> Option<String> {
+        let frame = self.call_stack.stack.first()?;
+        self.manager.label_title(&frame.name).map(str::to_string)
+    }
+
+    /// 当前等待中的提示，不在等待时为 `None`
+    pub fn pending_prompt(&self) -> Option<PendingPrompt> {
+        self.prompt.clone()
+    }
+
+    /// 读档时恢复等待状态：下一次 `step` 重发提示而不是重新执行当前语句。
+    /// 选项分支在脚本中已不存在时放弃恢复，退回到重新执行
+    pub fn restore_prompt(&mut self, prompt: PendingPrompt) {
+        if let PendingPrompt::Choice { arm_ids, .. } = &prompt {
+            let arms: Option<Vec<_>> = arm_ids.iter()
+                .map(|id| self.get_block_arc(id).map(|body| (id.clone(), body)))
+                .collect();
+            match arms {
+                Some(arms) => self.pending_choice = Some(arms),
+                None => {
+                    warn!("Saved choice no longer matches the script, re-executing it");
+                    return;
+                }
             }
+        } else {
+            self.pause = true;
         }
+        self.prompt = Some(prompt);
+        self.replay = true;
     }
 
     pub fn tick(&mut self, dt: f32) {
@@ -185,6 +333,13 @@ impl Executor {
             return false;
         }
 
+        if std::mem::take(&mut self.replay) {
+            if let Some(prompt) = &self.prompt {
+                ctx.push(prompt.to_event());
+            }
+            self.trigger_preload(ctx);
+        }
+
         if self.pending_choice.is_some() || self.pause {
             return true;
         }
@@ -212,24 +367,52 @@ impl Executor {
             match cmd {
                 LuaCommand::Jump(target) => {
                     info!("Lua Jump -> {}", target);
-                    self.perform_jump(&target);
+                    if let Err(e) = self.perform_jump(&target) {
+                        error!("Lua jump failed: {}", e);
+                    }
                 },
                 LuaCommand::SaveGlobal => {
                     info!("Lua requested global save.");
-                    let sf_data = lua_glue::extract_sf(&self.lua);
-
-                    if let Err(e) = crate::storager::save_global("global.json", &sf_data) {
-                        log::error!("Failed to save global.json: {}", e);
-                    } else {
-                        info!("Global data saved successfully.");
-                    }
+                    self.save_global_data();
                 },
-                LuaCommand::SetVolume {channel, value} => {
+                LuaCommand::SetVolume {channel, value, fade} => {
                     info!("Lua set volume: {} -> {}", channel, value);
-                    ctx.push(OutputEvent::SetVolume {channel, value});
+                    ctx.push(OutputEvent::SetVolume {channel, value, fade});
                 },
-                LuaCommand::ModifyVisual { target, props, duration, easing } => {
-                    ctx.push(OutputEvent::ModifyVisual { target, props, duration, easing });
+                LuaCommand::PlayAudio { channel, path, volume, fade_in, looping } => {
+                    let audio_cfg: AudioConfig = lumina_shared::config::get("audio");
+                    let audio = Audio {
+                        path,
+                        volume: volume.unwrap_or(audio_cfg.master_volume),
+                        fade_in: fade_in.unwrap_or(audio_cfg.fade_in_sec),
+                        fade_out: audio_cfg.fade_out_sec,
+                        looping: looping.unwrap_or(audio_cfg.music_loop),
+                        loop_region: None,
+                        pitch: None,
+                    };
+                    info!("Lua play audio: {} -> {}", channel, audio.path);
+                    ctx.push(OutputEvent::PlayAudio {
+                        channel: channel.clone(),
+                        path: audio.path.clone(),
+                        fade_in: audio.fade_in,
+                        volume: audio.volume,
+                        looping: audio.looping,
+                        loop_region: None,
+                        speculative: false,
+                        pitch: None,
+                        intro: None,
+                    });
+                    ctx.audios.insert(channel, Some(audio));
+                }
+                LuaCommand::StopAudio { channel, fade_out } => {
+                    let fade_out = fade_out
+                        .or_else(|| ctx.audios.get(&channel).and_then(|a| a.as_ref()).map(|a| a.fade_out))
+                        .unwrap_or(0.0);
+                    ctx.push(OutputEvent::StopAudio { channel: channel.clone(), fade_out });
+                    ctx.audios.insert(channel, None);
+                }
+                LuaCommand::ModifyVisual { target, props, duration, easing, on_done } => {
+                    ctx.push(OutputEvent::ModifyVisual { target, props, duration, easing, on_done });
                 },
                 LuaCommand::RegisterLayout { name, config } => {
                     ctx.push(OutputEvent::RegisterLayout { name, config });
@@ -240,17 +423,76 @@ impl Executor {
                 LuaCommand::MarkDynamic { name } => {
                     self.dynamic_registry.insert(name);
                 }
+                LuaCommand::Shake { target, intensity, duration } => {
+                    ctx.push(OutputEvent::Shake { target, intensity, duration });
+                }
+                LuaCommand::Flash { color, duration } => {
+                    ctx.push(OutputEvent::Flash { color, duration });
+                }
+                LuaCommand::SetAnim { target, state, frames, fps, looping } => {
+                    ctx.push(OutputEvent::SetSpriteAnim { target, state, frames, fps, looping });
+                }
+                LuaCommand::SetLanguage(lang) => {
+                    info!("Lua set language -> {}", lang);
+                    crate::i18n::set_language(&lang);
+                }
+                LuaCommand::ExportHistory { filename } => {
+                    match crate::storager::export_history(&filename, &ctx.dialogue_history) {
+                        Ok(path) => info!("Dialogue history exported to {:?}", path),
+                        Err(e) => error!("Failed to export history: {}", e),
+                    }
+                }
+                LuaCommand::DefineCharacter { id, name, image_tag, color } => {
+                    info!("Lua define character: {}", id);
+                    let ch = ctx.characters.entry(id.clone()).or_insert_with(|| crate::runtime::Character {
+                        id: id.clone(),
+                        name: id.clone(),
+                        image_tag: None,
+                        voice_tag: None,
+                        color: None,
+                        voice_volume: None,
+                        voice_pitch: None,
+                    });
+                    if let Some(name) = name { ch.name = name; }
+                    if image_tag.is_some() { ch.image_tag = image_tag; }
+                    if color.is_some() { ch.color = color; }
+                }
+                LuaCommand::SetCharacterName { id, name } => {
+                    match ctx.characters.get_mut(&id) {
+                        Some(ch) => ch.name = name,
+                        None => error!("set_character_name: unknown character '{}'", id),
+                    }
+                }
+                LuaCommand::UnlockAchievement { id } => {
+                    info!("Achievement unlocked: {}", id);
+                    self.save_global_data();
+                    ctx.push(OutputEvent::AchievementUnlocked { id });
+                }
+                LuaCommand::Toast { text, duration } => {
+                    ctx.push(OutputEvent::Toast { text, duration });
+                }
             }
         }
         true
     }
 
-    fn perform_jump(&mut self, label: &str) {
+    fn save_global_data(&self) {
+        let sf_data = lua_glue::extract_sf(&self.lua);
+
+        if let Err(e) = crate::storager::save_global("global.json", &sf_data) {
+            log::error!("Failed to save global.json: {}", e);
+        } else {
+            info!("Global data saved successfully.");
+        }
+    }
+
+    fn perform_jump(&mut self, label: &str) -> anyhow::Result<()> {
         let body = self.get_block_arc(label)
-            .unwrap_or_else(|| panic!("Label '{}' not found in project!", label));
+            .ok_or_else(|| anyhow::anyhow!("Label '{}' not found in project!", label))?;
 
         self.call_stack.clear();
         self.call_stack.push(Frame::new(label.to_string(), body, 0));
+        Ok(())
     }
     
     fn exec_current(&mut self, ctx: &mut Ctx) {
@@ -259,7 +501,9 @@ impl Executor {
             frame.current().expect("no stmt").clone()
         };
 
+        lua_glue::api::audio::sync_channels(&self.lua, &ctx.audios, &ctx.audio_state);
         let StmtEffect { events, next} = walk_stmt(ctx, &self.lua, &stmt, &self.dynamic_registry);
+        let prompt = events.iter().rev().find_map(PendingPrompt::from_event);
         ctx.event_queue.extend(events);
 
         match next {
@@ -268,38 +512,61 @@ impl Executor {
                     frame.advance();
                 }
             },
-            NextAction::WaitChoice(arms) => {
+            NextAction::WaitChoice(arm_ids) => {
                 self.trigger_preload(ctx);
+                self.prompt = prompt.map(|p| match p {
+                    PendingPrompt::Choice { title, options, timeout, default, .. } => {
+                        PendingPrompt::Choice { title, options, timeout, default, arm_ids: arm_ids.clone() }
+                    }
+                    other => other,
+                });
+                let arms = arm_ids.into_iter()
+                    .map(|id| {
+                        let body = self.get_block_arc(&id)
+                            .unwrap_or_else(|| panic!("choice block {} not found", id));
+                        (id, body)
+                    })
+                    .collect();
                 self.pending_choice = Some(arms);
             },
             NextAction::WaitInput => {
                 self.trigger_preload(ctx);
                 self.pause = true;
+                self.prompt = prompt;
             }
             NextAction::Jump(label) =>{
-                self.perform_jump(&label);
+                // 目标不存在时停止脚本并提示，不让整个程序崩溃
+                if let Err(e) = self.perform_jump(&label) {
+                    let line = match &stmt {
+                        Stmt::Jump { span, .. } => span.line,
+                        _ => 0,
+                    };
+                    error!("Jump failed at line {}: {}", line, e);
+                    ctx.push(OutputEvent::ScriptError { line, msg: e.to_string() });
+                    self.call_stack.clear();
+                }
             },
             NextAction::Call(target) => {
                 let body = self.get_block_arc(&target)
                     .unwrap_or_else(|| panic!("label {} not found", target));
-                let frame = self.call_stack.top_mut().unwrap();
-                let return_frame = Frame::new(frame.name.clone(),frame.stmts.clone(), frame.pc + 1);
-                self.call_stack.pop();
-                self.call_stack.push(return_frame);
-                self.call_stack.push(Frame::new(target,body, 0));
+                self.enter_block(target, body);
             },
-            NextAction::EnterBlock(block_id, stmts) => {
-                let frame = self.call_stack.top_mut().unwrap();
-                let return_frame = Frame::new(frame.name.clone(), frame.stmts.clone(), frame.pc + 1);
-
-                self.call_stack.pop();
-                self.call_stack.push(return_frame);
-
-                self.call_stack.push(Frame::new(block_id, Arc::from(stmts.as_slice()), 0));
+            NextAction::EnterBlock(block_id) => {
+                let body = self.get_block_arc(&block_id)
+                    .unwrap_or_else(|| panic!("block {} not found", block_id));
+                self.enter_block(block_id, body);
             }
         }
     }
 
+    /// 当前帧原地前进一句作为返回点，再压入子块
+    fn enter_block(&mut self, name: String, body: Arc<[Stmt]>) {
+        if let Some(frame) = self.call_stack.top_mut() {
+            frame.advance();
+        }
+        self.call_stack.push(Frame::new(name, body, 0));
+    }
+
     fn trigger_preload(&mut self, ctx: &mut Ctx) {
         let gf_cfg: GraphicsConfig = lumina_shared::config::get("graphics");
 
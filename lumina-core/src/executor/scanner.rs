@@ -1,5 +1,12 @@
+use crate::runtime::assets::asset_name;
 use crate::runtime::Ctx;
 use viviscript_core::ast::{AudioAction, ShowAttr, Stmt};
+use viviscript_core::lexer::{split_interpolation, StrPiece};
+
+/// 含 `{expr}` 的名字要到执行时才能确定，不做预加载
+fn is_dynamic(name: &str) -> bool {
+    name.contains('{') && split_interpolation(name).iter().any(|(p, _)| matches!(p, StrPiece::Interp(_)))
+}
 
 pub struct Scanner;
 
@@ -27,39 +34,36 @@ impl Scanner {
                     let base_name = ctx.characters.get(target)
                         .and_then(|c| c.image_tag.clone())
                         .unwrap_or_else(|| target.clone());
-                    let mut suffixes = Vec::new();
-                    if let Some(attr_list) = attrs {
-                        for attr in attr_list {
-                            if let ShowAttr::Add(tag) = attr {
-                                suffixes.push(tag.as_str());
-                            }
-                        }
-                    }
-                    let full_name = if suffixes.is_empty() {
-                        base_name
-                    } else {
-                        format!("{}_{}", base_name, suffixes.join("_"))
-                    };
+                    let adds: Vec<String> = attrs.iter().flatten()
+                        .filter_map(|attr| match attr {
+                            ShowAttr::Add(tag) => Some(tag.clone()),
+                            ShowAttr::Remove(_) => None,
+                        })
+                        .collect();
 
-                    images.push(full_name);
+                    if !is_dynamic(&base_name) && !adds.iter().any(|a| is_dynamic(a)) {
+                        images.push(asset_name(&base_name, &adds));
+                    }
                 },
                 Stmt::Scene { image, .. } => {
+                    // 与 walk 中背景 Sprite 的命名一致
                     if let Some(scene_img) = image {
-                        let mut parts = vec![scene_img.prefix.as_str()];
-                        if let Some(attrs) = &scene_img.attrs {
-                            for a in attrs {
-                                parts.push(a.as_str());
-                            }
+                        let attrs = scene_img.attrs.as_deref().unwrap_or_default();
+                        if !is_dynamic(&scene_img.prefix) && !attrs.iter().any(|a| is_dynamic(a)) {
+                            images.push(asset_name(&scene_img.prefix, attrs));
                         }
-                        images.push(parts.join("_"));
                     }
                 },
-                Stmt::Audio { action, channel, resource, .. } => {
+                Stmt::Audio { action, channel, resource, options, .. } => {
                     if *action == AudioAction::Play {
+                        // 前奏总以静态音频播放
+                        if let Some(intro) = options.intro.as_ref().filter(|i| !is_dynamic(i)) {
+                            audios.push(intro.clone());
+                        }
                         if let Some(res_path) = resource {
                             let is_bgm = channel == "music" || res_path.starts_with("bgm_");
 
-                            if !is_bgm {
+                            if !is_bgm && !is_dynamic(res_path) {
                                 audios.push(res_path.clone());
                             }
                         }
@@ -1,15 +1,17 @@
 use std::collections::HashSet;
 use std::ops::Add;
-use std::sync::OnceLock;
-use viviscript_core::ast::{Stmt, AudioAction, ShowAttr, Transition};
-use regex::Regex;
+use viviscript_core::ast::{Stmt, AudioAction, NvlAction, ShowAttr};
+use viviscript_core::lexer::{split_interpolation, StrPiece};
 use mlua::Lua;
 use lumina_shared::config;
 use crate::runtime::Ctx;
 use crate::event::OutputEvent;
-use crate::runtime::assets::{Audio, DialogueRecord, Sprite};
+use crate::runtime::assets::{Audio, DialogueRecord, PresentationMode, Sprite};
 use crate::lua_glue;
-use crate::config::{AudioConfig, GraphicsConfig};
+use crate::i18n;
+use crate::manager::choice_arm_id;
+use crate::markup;
+use crate::config::{AudioConfig, AutoVoice, DebugConfig, GraphicsConfig, LintLevel, SystemConfig};
 
 #[derive(Debug, Clone)]
 pub struct StmtEffect {
@@ -22,29 +24,61 @@ pub enum NextAction {
     Continue,
     Jump(String),
     Call(String),
-    WaitChoice(Vec<(String, Vec<Stmt>)>),
+    /// 各选项分支的块 id，块内容由预处理阶段登记在 label 表中
+    WaitChoice(Vec<String>),
     WaitInput,
-    EnterBlock(String, Vec<Stmt>),
+    EnterBlock(String),
+}
+
+/// 可插值字段的类别。
+///
+/// 支持 `{expr}` 插值的字段：
+/// - 文本：旁白、对话、角色名、选项标题与选项文本；
+/// - 名字：`scene` 的图片与属性、`show` / `hide` 的目标、属性与位置、转场名、
+///   音频资源与前奏、`movie` 的 id。
+///
+/// 转义规则与 lexer 一致：`{{` 为字面量 `{`，`\{...}` 原样保留为 `{...}`，空的或未闭合的 `{` 按普通文本处理。
+/// 插值不能跨行，多行旁白按行分别求值。标签、跳转目标、声道名不支持插值。
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    /// 显示给玩家的文本，富文本标记原样保留给渲染层解析
+    Text,
+    /// 资源名、立绘目标等，所有 `{}` 都按 Lua 表达式求值
+    Name,
+}
+
+fn interpolate_field(lua: &Lua, text: &str, field: Field) -> String {
+    if !text.contains('{') {
+        return text.to_string();
+    }
+    // 与 lexer 的插值模式使用同一套切分规则
+    split_interpolation(text).into_iter()
+        .map(|(piece, range)| match piece {
+            StrPiece::Text(t) => t,
+            StrPiece::Interp(expr) if field == Field::Text && markup::is_tag(&expr) => text[range].to_string(),
+            StrPiece::Interp(expr) => lua_glue::eval_string(lua, &expr),
+        })
+        .collect()
 }
 
 fn interpolate(lua: &Lua, text: &str) -> String {
-    // 缓存正则表达式，避免重复编译
-    static RE: OnceLock<Regex> = OnceLock::new();
-    let re = RE.get_or_init(|| Regex::new(r"(\\?)\{([^}]+)\}").unwrap());
+    interpolate_field(lua, text, Field::Text)
+}
 
-    re.replace_all(text, |caps: &regex::Captures| {
-        let prefix = &caps[1];
-        let mut expr = &caps[2];
+fn interpolate_name(lua: &Lua, text: &str) -> String {
+    interpolate_field(lua, text, Field::Name)
+}
 
-        if prefix == "\\" {
-            if expr.ends_with('\\') {
-                expr = &expr[..expr.len() - 1];
-            }
-            format!("{{{}}}", expr)
-        }else {
-            lua_glue::eval_string(lua, expr)
+/// 上一句的语音还登记在 `voice` 通道时，先发出停止事件再显示新的一句
+fn stop_previous_voice(ctx: &mut Ctx, audio_cfg: &AudioConfig, events: &mut Vec<OutputEvent>) {
+    if !audio_cfg.stop_voice_on_advance {
+        return;
+    }
+    if let Some(slot) = ctx.audios.get_mut("voice") {
+        if slot.take().is_some() {
+            events.push(OutputEvent::StopAudio { channel: "voice".to_string(), fade_out: 0.05 });
         }
-    }).to_string()
+    }
 }
 
 pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<String>) -> StmtEffect {
@@ -55,59 +89,109 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
 
     let mut events = Vec::new();
     let next = match stmt {
-        Stmt::CharacterDef{id,name,image_tag,voice_tag,..} => {
+        Stmt::CharacterDef{id,name,image_tag,voice_tag,color,voice_volume,voice_pitch,..} => {
             let cd = crate::runtime::Character {
                 id: id.clone(),
                 name: name.clone(),
                 voice_tag: voice_tag.clone(),
                 image_tag: image_tag.clone(),
+                color: color.clone(),
+                voice_volume: *voice_volume,
+                voice_pitch: *voice_pitch,
             };
             ctx.characters.insert(id.clone(), cd);
             NextAction::Continue
         },
-        Stmt::Narration { lines, .. } => {
-            let processed_lines: Vec<String> = lines.iter()
-                .map(|l| interpolate(lua, l))
-                .collect();
-
-            for i in &processed_lines{
-                ctx.dialogue_history.push(DialogueRecord {speaker: None, text: i.clone(), voice_path: None});
+        Stmt::Narration { lines, tl_id, .. } => {
+            stop_previous_voice(ctx, &audio_cfg, &mut events);
+            ctx.active_speaker = None;
+            let history_limit = config::get::<SystemConfig>("system").history_limit;
+            let mut processed_lines = Vec::new();
+            for l in lines {
+                // 空行是段落分隔：原样随事件交给渲染器留出间距，NVL 页中也留一行，不进入历史记录
+                if l.is_empty() {
+                    if ctx.presentation_mode == PresentationMode::Nvl && !ctx.nvl_page.is_empty() {
+                        ctx.nvl_page.push(DialogueRecord {speaker: None, text: String::new(), voice_path: None});
+                    }
+                    processed_lines.push(String::new());
+                    continue;
+                }
+                let text = interpolate(lua, &i18n::tl(tl_id.as_deref(), l));
+                let record = DialogueRecord {speaker: None, text: text.clone(), voice_path: None};
+                if ctx.presentation_mode == PresentationMode::Nvl {
+                    ctx.nvl_page.push(record.clone());
+                }
+                ctx.dialogue_history.push(record, history_limit);
+                processed_lines.push(text);
             }
             events.push(OutputEvent::ShowNarration { lines: processed_lines });
             NextAction::WaitInput
         },
-        Stmt::Dialogue {speaker, text, voice_index, ..} => {
+        Stmt::Dialogue {span, speaker, text, voice_index, tl_id, voice_line} => {
+            stop_previous_voice(ctx, &audio_cfg, &mut events);
             let mut name = speaker.name.clone();
             let mut path = None;
+            let mut speculative = false;
+            let mut volume = audio_cfg.voice_volume;
+            let mut pitch = None;
+            // 立绘以 image_tag 显示，未设置时与角色 id 同名
+            ctx.active_speaker = Some(ctx.characters.get(&name)
+                .and_then(|cn| cn.image_tag.clone())
+                .unwrap_or_else(|| name.clone()));
             if let Some(cn) = ctx.characters.get(&name) {
-                name = cn.name.clone();
+                // 角色名可含 {expr} 插值，对话时求值
+                name = interpolate(lua, &i18n::tl(None, &cn.name));
+                volume *= cn.voice_volume.unwrap_or(1.0);
+                pitch = cn.voice_pitch;
                 if let Some(vi) = voice_index {
                     path = Some(cn.to_owned().voice_tag.unwrap().add(&*audio_cfg.voice_link_char).add(vi));
+                } else if let (Some(tag), Some(line), AutoVoice::ByLine) = (&cn.voice_tag, voice_line, audio_cfg.auto_voice) {
+                    path = Some(format!("{}{}{}", tag, audio_cfg.voice_link_char, line));
+                    speculative = true;
                 }
             }
             if let Some(al) = &speaker.alias{
-                name = al.clone();
+                name = i18n::tl(None, al);
             }
             if path.is_some(){
                 ctx.audios.insert("voice".to_string(), Some(Audio{
                     path:path.clone().unwrap(), 
-                    volume: audio_cfg.voice_volume,
+                    volume,
                     fade_in: 0f32, 
                     fade_out: 0f32, 
-                    looping: false
+                    looping: false,
+                    loop_region: None,
+                    pitch,
                 }));
                 events.push(OutputEvent::PlayAudio {
                     channel: "voice".to_string(), 
                     path:path.clone().unwrap(), 
                     fade_in: 0f32, 
-                    volume: audio_cfg.voice_volume,
-                    looping: false});
+                    volume,
+                    looping: false,
+                    loop_region: None,
+                    speculative,
+                    pitch,
+                    intro: None});
             }
 
-            let final_text = interpolate(lua, text);
+            let final_text = interpolate(lua, &i18n::tl(tl_id.as_deref(), text));
 
-            ctx.dialogue_history.push(DialogueRecord {speaker: Some(name.clone()), text: final_text.clone(), voice_path: path.clone()});
+            let record = DialogueRecord {speaker: Some(name.clone()), text: final_text.clone(), voice_path: path.clone()};
+            if ctx.presentation_mode == PresentationMode::Nvl {
+                ctx.nvl_page.push(record.clone());
+            }
+            ctx.dialogue_history.push(record, config::get::<SystemConfig>("system").history_limit);
             events.push(OutputEvent::ShowDialogue {name, content: final_text.clone()});
+
+            // 排在文本之后，渲染层显示新的一句时不会把提示清掉
+            let unknown = speaker.alias.is_none() && !ctx.characters.contains_key(&speaker.name);
+            if unknown && config::get::<DebugConfig>("debug").unknown_speaker != LintLevel::Allow {
+                events.push(OutputEvent::Warning {
+                    line: span.line,
+                    msg: format!("speaker '{}' is not a defined character", speaker.name),
+                });
+            }
             NextAction::WaitInput
         },
         Stmt::Audio {action, channel, resource, options, ..} => {
@@ -115,86 +199,83 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
                 log::error!("Audio channel {} isn't registered", channel);
             }
             if matches!(action, AudioAction::Play){
-                let path = resource.clone().unwrap().to_string();
+                let path = interpolate_name(lua, resource.as_deref().unwrap());
+                let intro = options.intro.as_deref().map(|i| interpolate_name(lua, i));
                 let volume = options.volume.unwrap_or(audio_cfg.master_volume);
                 let fade_in = options.fade_in.unwrap_or(audio_cfg.fade_in_sec);
                 let fade_out = options.fade_out.unwrap_or(audio_cfg.fade_out_sec);
                 let looping = options.r#loop;
+                let loop_region = (options.loop_start.is_some() || options.loop_end.is_some())
+                    .then(|| (options.loop_start.unwrap_or(0.0), options.loop_end));
                 ctx.audios.insert(channel.to_string(), Some(Audio{
                     path: path.clone(),
-                    volume, fade_in, fade_out, looping
+                    volume, fade_in, fade_out, looping, loop_region, pitch: None
                 }));
-                events.push(OutputEvent::PlayAudio {channel:channel.to_string(), path: path.clone(), fade_in, volume, looping });
+                events.push(OutputEvent::PlayAudio {channel:channel.to_string(), path: path.clone(), fade_in, volume, looping, loop_region, speculative: false, pitch: None, intro });
             }else{
-                let fade_out = if let Some(k) = options.fade_out{
-                    k
-                } else {
-                    ctx.audios.get(channel).unwrap().clone().unwrap().fade_out
-                };
+                // 停止未在播放的声道时没有记录可取，退回默认淡出时长
+                let fade_out = options.fade_out
+                    .or_else(|| ctx.audios.get(channel).and_then(|a| a.as_ref()).map(|a| a.fade_out))
+                    .unwrap_or(audio_cfg.fade_out_sec);
                 events.push(OutputEvent::StopAudio {channel:channel.to_string(), fade_out});
                 ctx.audios.insert(channel.to_string(), None);
             }
             NextAction::Continue
         },
         Stmt::Scene {image, transition, ..} => {
-            if let Some(img) = image {
-                if let Some(layer) = ctx.layer_record.layer.get_mut("master") {
-                    layer.clear();
-                    layer.push(Sprite {
-                        target: img.clone().prefix, 
-                        attrs: img.attrs.clone().unwrap_or(vec![]), 
+            let duration = transition.as_ref().and_then(|t| t.duration);
+            let transition = transition.as_ref()
+                .map(|t| interpolate_name(lua, &t.effect))
+                .unwrap_or(gfx_cfg.default_transition);
+            if let Some(layer) = ctx.layer_record.layer.get_mut("master") {
+                layer.clear();
+                let background = image.as_ref().map(|img| {
+                    let bg = Sprite {
+                        target: interpolate_name(lua, &img.prefix),
+                        attrs: img.attrs.iter().flatten().map(|a| interpolate_name(lua, a)).collect(),
                         position: None,
                         zindex: 0usize
-                    });
-                    events.push(OutputEvent::NewScene {transition: transition.clone()
-                        .unwrap_or(Transition{effect: gfx_cfg.default_transition}).effect});
-                }
-            }else {
-                if let Some(layer) = ctx.layer_record.layer.get_mut("master") {
-                    layer.clear();
-                    events.push(OutputEvent::NewScene {transition: transition.clone()
-                        .unwrap_or(Transition{effect: gfx_cfg.default_transition}).effect});
-                }
+                    };
+                    let name = bg.full_asset_name();
+                    layer.push(bg);
+                    name
+                });
+                events.push(OutputEvent::NewScene {background, transition, duration});
             }
             NextAction::Continue
         }
         Stmt::Show {target, attrs, position, transition, ..}=>{
             let mut is_update = false;
+            let target = &interpolate_name(lua, target);
 
             let raw_trans = transition.as_ref()
                 .map(|t| t.effect.clone())
                 .unwrap_or_else(|| gfx_cfg.default_transition.clone());
+            let duration = transition.as_ref().and_then(|t| t.duration);
 
-            let trans_name = interpolate(lua, &raw_trans);
+            let trans_name = interpolate_name(lua, &raw_trans);
             if let Some(layer) = ctx.layer_record.layer.get_mut("master") {
                 if let Some(c) = layer.iter_mut().find(|x| x.target == *target) {
                     is_update = true;
 
                     if let Some(attrs_list) = attrs {
-                        for attr in attrs_list {
-                            match attr {
-                                ShowAttr::Add(a) => {
-                                    let val = interpolate(lua, a);
-                                    c.attrs.pop();
-                                    c.attrs.push(val);
-                                },
-                                ShowAttr::Remove(a) => {
-                                    let val = interpolate(lua, a);
-                                    if c.attrs.last() == Some(&val) {
-                                        c.attrs.pop();
-                                    }
-                                }
-                            }
-                        }
+                        let changes: Vec<ShowAttr> = attrs_list.iter()
+                            .map(|attr| match attr {
+                                ShowAttr::Add(a) => ShowAttr::Add(interpolate_name(lua, a)),
+                                ShowAttr::Remove(a) => ShowAttr::Remove(interpolate_name(lua, a)),
+                            })
+                            .collect();
+                        c.apply_attrs(&changes);
                     }
                     if let Some(pos_raw) = position {
-                        let pos = interpolate(lua, pos_raw);
+                        let pos = interpolate_name(lua, pos_raw);
                         c.position = Some(pos);
                     }
                     if dynamic_set.contains(&trans_name) {
                         events.push(OutputEvent::UpdateSprite {
                             target: target.clone(),
                             transition: "".to_string(),
+                            duration: None,
                         });
                         let code = format!("lumina.tween.run_dynamic('{}', '{}')", trans_name, target);
                         if let Err(e) = lua.load(&code).exec() {
@@ -204,6 +285,7 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
                         events.push(OutputEvent::UpdateSprite {
                             target: target.clone(),
                             transition: trans_name.clone(),
+                            duration,
                         });
                     }
                 }
@@ -211,13 +293,21 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
 
             if !is_update {
                 // [Step 3.1] 准备数据
-                let final_attrs: Vec<String> = attrs.clone().unwrap_or_default().into_iter()
-                    .filter_map(|x| match x {
-                        ShowAttr::Add(s) => Some(interpolate(lua, &s)),
-                        _ => None
-                    }).collect();
+                // 新立绘的属性是去重后的集合，`-attr` 没有可移除的对象
+                let mut final_attrs: Vec<String> = Vec::new();
+                for attr in attrs.iter().flatten() {
+                    match attr {
+                        ShowAttr::Add(a) => {
+                            let val = interpolate_name(lua, a);
+                            if !final_attrs.contains(&val) {
+                                final_attrs.push(val);
+                            }
+                        }
+                        ShowAttr::Remove(a) => log::warn!("Ignoring '-{}' on newly shown sprite '{}'", a, target),
+                    }
+                }
 
-                let final_pos = position.as_ref().map(|p| interpolate(lua, p));
+                let final_pos = position.as_ref().map(|p| interpolate_name(lua, p));
 
                 // [Step 3.2] 写入 Ctx
                 ctx.layer_record.layer.get_mut("master").unwrap().push(Sprite {
@@ -234,6 +324,7 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
                         texture: target.clone(),
                         pos_str: final_pos,
                         transition: None,
+                        duration: None,
                         attrs: final_attrs,
                         defer_visual: true,
                     });
@@ -248,6 +339,7 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
                         texture: target.clone(),
                         pos_str: final_pos,
                         transition: Some(trans_name),
+                        duration,
                         attrs: final_attrs,
                         defer_visual: false,
                     });
@@ -257,7 +349,9 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
             NextAction::Continue
         },
         Stmt::Hide {target, transition, ..} => {
-            let trans_opt = transition.as_ref().map(|t| interpolate(lua, &t.effect));
+            let target = &interpolate_name(lua, target);
+            let trans_opt = transition.as_ref().map(|t| interpolate_name(lua, &t.effect));
+            let duration = transition.as_ref().and_then(|t| t.duration);
 
             if let Some(layer) = ctx.layer_record.layer.get_mut("master") {
                 layer.retain(|x| x.target != *target);
@@ -266,58 +360,80 @@ pub fn walk_stmt(ctx: &mut Ctx, lua: &Lua, stmt: &Stmt, dynamic_set: &HashSet<St
             events.push(OutputEvent::HideSprite {
                 target: target.clone(),
                 transition: trans_opt,
+                duration,
             });
             NextAction::Continue
         }
+        Stmt::Nvl {action, ..} => {
+            match action {
+                NvlAction::On | NvlAction::Off => {
+                    let mode = if *action == NvlAction::On { PresentationMode::Nvl } else { PresentationMode::Adv };
+                    if ctx.presentation_mode != mode {
+                        ctx.presentation_mode = mode;
+                        events.push(OutputEvent::SetPresentation { mode });
+                    }
+                }
+                NvlAction::Clear => ctx.nvl_page.clear(),
+            }
+            NextAction::Continue
+        },
         Stmt::LuaBlock {code,..} => {
             lua.load(code).exec().unwrap_or_else(|e| log::error!("Lua: {}", e));
             NextAction::Continue
         },
-        Stmt::Choice {title, arms,id ,..}=>{
+        Stmt::Choice {title, arms, id, timeout, default, ..}=>{
             let base_id = id.as_ref().expect("AST not preprocessed! Call preload_script first.");
 
-            let processed_title = title.as_ref().map(|t| interpolate(lua, t));
+            let title_id = format!("{}_title", base_id);
+            let processed_title = title.as_ref().map(|t| interpolate(lua, &i18n::tl(Some(&title_id), t)));
 
-            let options: Vec<String> = arms.iter()
-                .map(|a| interpolate(lua, &a.text))
+            let options: Vec<String> = arms.iter().enumerate()
+                .map(|(idx, a)| interpolate(lua, &i18n::tl(Some(&choice_arm_id(base_id, idx)), &a.text)))
                 .collect();
 
-            let arms_data: Vec<(String, Vec<Stmt>)> = arms.iter().enumerate().map(|(idx, a)| {
-                let arm_id = format!("{}_opt{}", base_id, idx);
-                (arm_id, a.body.clone())
-            }).collect();
+            let arm_ids: Vec<String> = (0..arms.len())
+                .map(|idx| choice_arm_id(base_id, idx))
+                .collect();
 
-            ctx.push(OutputEvent::ShowChoice { title: processed_title, options });
-            NextAction::WaitChoice(arms_data)
+            events.push(OutputEvent::ShowChoice { title: processed_title, options, timeout: *timeout, default: *default });
+            NextAction::WaitChoice(arm_ids)
         },
         Stmt::If {branches, else_branch, id, ..} => {
             let base_id = id.as_ref().expect("AST not preprocessed! Call preload_script first.");
 
-            let mut matched = None;
-
-            for (idx, (cond_str, body)) in branches.iter().enumerate() {
-                if lua_glue::evel_bool(lua, cond_str) {
-                    let block_id = format!("{}_b{}", base_id, idx);
-                    matched = Some((block_id, body.clone()));
-                    break
-                }
-            }
-
-            if matched.is_none() {
-                if let Some(body) = else_branch {
-                    let block_id = format!("{}_else", base_id);
-                    matched = Some((block_id, body.clone()));
-                }
-            }
+            let matched = branches.iter()
+                .position(|(cond_str, _)| lua_glue::evel_bool(lua, cond_str))
+                .map(|idx| format!("{}_b{}", base_id, idx))
+                .or_else(|| else_branch.as_ref().map(|_| format!("{}_else", base_id)));
 
-            if let Some((bid, body)) = matched {
-                NextAction::EnterBlock(bid, body)
-            } else {
-                NextAction::Continue
+            match matched {
+                Some(block_id) => NextAction::EnterBlock(block_id),
+                None => NextAction::Continue,
             }
         },
+        Stmt::Movie {id, skippable, ..} => {
+            stop_previous_voice(ctx, &audio_cfg, &mut events);
+            events.push(OutputEvent::PlayMovie { id: interpolate_name(lua, id), skippable: *skippable });
+            NextAction::WaitInput
+        },
+        Stmt::WaitAudio {channel, ..} => {
+            events.push(OutputEvent::WaitAudioEnd { channel: channel.clone() });
+            NextAction::WaitInput
+        },
         Stmt::Jump {target,..} => NextAction::Jump(target.clone()),
         Stmt::Call {target,..} => NextAction::Call(target.clone()),
+        Stmt::Error {span, msg, recovered: true} => {
+            // 解析器已恢复的小问题 (未知参数、多余的 token 等)：跳过该语句，只给出警告
+            log::warn!("Skipped broken statement at line {}: {}", span.line, msg);
+            events.push(OutputEvent::Warning { line: span.line, msg: msg.clone() });
+            NextAction::Continue
+        },
+        Stmt::Error {span, msg, ..} => {
+            // 停在出错的位置，让玩家看到提示后再继续
+            log::error!("Script error at line {}: {}", span.line, msg);
+            events.push(OutputEvent::ScriptError { line: span.line, msg: msg.clone() });
+            NextAction::WaitInput
+        },
         _=> {NextAction::Continue}
 
     };
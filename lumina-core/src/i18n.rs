@@ -0,0 +1,216 @@
+//! 本地化：按语言加载脚本目录下的翻译表 (打包后读 pak 的脚本分区)：
+//!
+//! - `locales/<lang>.toml`：`key = "译文"`，或 [`ScriptManager::export_strings`](crate::ScriptManager::export_strings)
+//!   生成的 `[key]` 表 (`source` / `text`)；
+//! - `tl/<lang>.json`：`--extract-strings` 生成并增量合并的表，两者同时存在时 JSON 中的条目优先。
+//!
+//! 表的 key 优先使用预处理阶段分配的稳定行 id (如 `start@line_3`)，
+//! 也可以是原文的稳定哈希 (见 [`text_key`]) 或原文本身；查不到时回退原文。
+//! 玩家选择的语言写入存档目录的偏好文件，下次启动时沿用。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use lumina_shared::vfs::{self, VfsSource};
+use crate::config::SystemConfig;
+use crate::storager;
+
+/// 翻译表中的一条记录
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TlEntry {
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl TlEntry {
+    /// 兼容手写的 `"key": "译文"` 形式
+    pub fn from_value(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(text) => Self { text, ..Default::default() },
+            other => serde_json::from_value(other).unwrap_or_default(),
+        }
+    }
+
+    /// 同 [`from_value`](Self::from_value)，用于 TOML 表中的值
+    pub fn from_toml(value: toml::Value) -> Self {
+        match value {
+            toml::Value::String(text) => Self { text, ..Default::default() },
+            other => other.try_into().unwrap_or_default(),
+        }
+    }
+
+    /// 未翻译或已废弃的条目不参与查找
+    fn is_usable(&self) -> bool {
+        !self.text.is_empty() && self.status.as_deref() != Some("obsolete")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Localizer {
+    language: String,
+    table: HashMap<String, String>,
+}
+
+impl Localizer {
+    pub fn load(lang: &str) -> Self {
+        Self::from_source(lang, script_source().as_ref())
+    }
+
+    /// 从指定来源读取 `lang` 的翻译表，路径相对于脚本目录
+    pub fn from_source(lang: &str, scripts: &dyn VfsSource) -> Self {
+        let mut table = HashMap::new();
+
+        let toml_path = toml_entry(lang);
+        if let Some(content) = read_table(scripts, &toml_path) {
+            match toml::from_str::<HashMap<String, toml::Value>>(&content) {
+                Ok(t) => insert_entries(&mut table, t.into_iter().map(|(k, v)| (k, TlEntry::from_toml(v)))),
+                Err(e) => warn!("Locale file {:?} syntax error: {}", toml_path, e),
+            }
+        }
+
+        let json_path = locale_entry(lang);
+        if let Some(content) = read_table(scripts, &json_path) {
+            // 值可以是译文字符串，也可以是提取工具生成的 { source, text, status } 记录
+            match serde_json::from_str::<HashMap<String, serde_json::Value>>(&content) {
+                Ok(t) => insert_entries(&mut table, t.into_iter().map(|(k, v)| (k, TlEntry::from_value(v)))),
+                Err(e) => warn!("Locale file {:?} syntax error: {}", json_path, e),
+            }
+        }
+
+        if table.is_empty() {
+            info!("No translations for '{}' (looked in {:?} and {:?}), using source text.", lang, toml_path, json_path);
+        } else {
+            info!("Locale '{}' loaded: {} strings", lang, table.len());
+        }
+        Self { language: lang.to_string(), table }
+    }
+
+    pub fn lookup(&self, id: Option<&str>, text: &str) -> Option<&str> {
+        id.and_then(|id| self.table.get(id))
+            .or_else(|| self.table.get(&text_key(text)))
+            .or_else(|| self.table.get(text))
+            .map(|s| s.as_str())
+    }
+}
+
+fn read_table(scripts: &dyn VfsSource, path: &str) -> Option<String> {
+    if !scripts.exists(path) {
+        return None;
+    }
+    match scripts.read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            warn!("Failed to read locale file {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn insert_entries(table: &mut HashMap<String, String>, entries: impl Iterator<Item = (String, TlEntry)>) {
+    for (key, entry) in entries {
+        if entry.is_usable() {
+            table.insert(key, entry.text);
+        }
+    }
+}
+
+static LOCALIZER: OnceLock<RwLock<Localizer>> = OnceLock::new();
+
+fn localizer() -> &'static RwLock<Localizer> {
+    LOCALIZER.get_or_init(|| {
+        let lang = storager::load_preferences().language.unwrap_or_else(|| {
+            let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+            sys_cfg.language
+        });
+        RwLock::new(Localizer::load(&lang))
+    })
+}
+
+pub fn tl_dir() -> PathBuf {
+    let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+    Path::new(&sys_cfg.script_path).join("tl")
+}
+
+/// 翻译表在脚本目录 (或 pak 脚本分区) 中的路径
+fn locale_entry(lang: &str) -> String {
+    format!("tl/{}.json", lang)
+}
+
+fn toml_entry(lang: &str) -> String {
+    format!("locales/{}.toml", lang)
+}
+
+fn script_source() -> Arc<dyn VfsSource> {
+    let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+    vfs::open_or_dir(&sys_cfg.script_path, vfs::SCRIPTS)
+}
+
+pub fn locale_path(lang: &str) -> PathBuf {
+    tl_dir().join(format!("{}.json", lang))
+}
+
+/// `locales/` 与 `tl/` 目录下已有翻译表的语言，加上当前语言，按名称排序
+pub fn available_languages() -> Vec<String> {
+    let mut langs: Vec<String> = script_source().list().into_iter()
+        .filter_map(|p| {
+            let lang = p.strip_prefix("locales/").and_then(|r| r.strip_suffix(".toml"))
+                .or_else(|| p.strip_prefix("tl/")?.strip_suffix(".json"))?;
+            Some(lang.to_string())
+        })
+        .filter(|lang| !lang.contains('/'))
+        .collect();
+
+    let current = current_language();
+    if !langs.contains(&current) {
+        langs.push(current);
+    }
+    langs.sort();
+    langs.dedup();
+    langs
+}
+
+/// 原文的稳定 key (FNV-1a 64，十六进制)，不随编译或平台变化
+pub fn text_key(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in text.as_bytes() {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// 切换当前语言、重新加载翻译表，并记入偏好设置
+pub fn set_language(lang: &str) {
+    let new = Localizer::load(lang);
+    if let Ok(mut guard) = localizer().write() {
+        *guard = new;
+    }
+
+    let mut prefs = storager::load_preferences();
+    prefs.language = Some(lang.to_string());
+    if let Err(e) = storager::save_preferences(&prefs) {
+        warn!("Failed to save language preference: {}", e);
+    }
+}
+
+pub fn current_language() -> String {
+    localizer().read()
+        .map(|l| l.language.clone())
+        .unwrap_or_default()
+}
+
+/// 查找译文 (优先按行 id)，缺失时回退原文
+pub fn tl(id: Option<&str>, text: &str) -> String {
+    if let Ok(guard) = localizer().read() {
+        if let Some(s) = guard.lookup(id, text) {
+            return s.to_string();
+        }
+    }
+    text.to_string()
+}
@@ -6,6 +6,9 @@ pub mod renderer;
 pub mod storager;
 pub mod config;
 pub mod manager;
+pub mod i18n;
+pub mod markup;
+pub mod tts;
 
 pub use runtime::Ctx;
 pub use executor::Executor;
@@ -1,12 +1,91 @@
+use std::collections::{HashMap, HashSet};
 use mlua::{Lua, Table};
 use crate::lua_glue::types::{CommandBuffer, LuaCommand};
+use crate::runtime::assets::{Audio, ChannelState};
+
+/// 正在播放的声道，执行器每条语句前从 `ctx.audios` 同步，Lua 下发的命令也会立即更新
+#[derive(Debug, Default)]
+pub struct PlayingChannels(pub HashSet<String>);
+
+/// 渲染层回填的实际播放状态，执行器每条语句前从 `ctx.audio_state` 同步
+#[derive(Debug, Default)]
+pub struct AudioState(pub HashMap<String, ChannelState>);
+
+pub fn sync_channels(lua: &Lua, audios: &HashMap<String, Option<Audio>>, state: &HashMap<String, ChannelState>) {
+    if let Some(mut playing) = lua.app_data_mut::<PlayingChannels>() {
+        playing.0 = audios.iter()
+            .filter(|(_, a)| a.is_some())
+            .map(|(ch, _)| ch.clone())
+            .collect();
+    }
+    if let Some(mut audio_state) = lua.app_data_mut::<AudioState>() {
+        audio_state.0.clone_from(state);
+    }
+}
+
+fn channel_state(lua: &Lua, channel: &str) -> ChannelState {
+    lua.app_data_ref::<AudioState>()
+        .and_then(|s| s.0.get(channel).copied())
+        .unwrap_or_default()
+}
+
+fn mark_playing(lua: &Lua, channel: &str, playing: bool) {
+    if let Some(mut set) = lua.app_data_mut::<PlayingChannels>() {
+        if playing {
+            set.0.insert(channel.to_string());
+        } else {
+            set.0.remove(channel);
+        }
+    }
+}
 
 pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()> {
+    // 1. play_bgm(id, {volume, fade_in, loop})，缺省值取 audio 配置
+    let cb_bgm = cb.clone();
+    table.set("play_bgm", lua.create_function(move |lua, (id, opts): (String, Option<Table>)| {
+        let (volume, fade_in, looping) = match opts {
+            Some(t) => (t.get("volume")?, t.get("fade_in")?, t.get("loop")?),
+            None => (None, None, None),
+        };
+        mark_playing(lua, "music", true);
+        cb_bgm.push(LuaCommand::PlayAudio {
+            channel: "music".to_string(),
+            path: id,
+            volume,
+            fade_in,
+            looping,
+        });
+        Ok(())
+    })?)?;
+
+    // 2. stop(channel, fade_out)
+    let cb_stop = cb.clone();
+    table.set("stop", lua.create_function(move |lua, (channel, fade_out): (String, Option<f32>)| {
+        mark_playing(lua, &channel, false);
+        cb_stop.push(LuaCommand::StopAudio { channel, fade_out });
+        Ok(())
+    })?)?;
+
+    // 3. set_volume(channel, v, fade)
     let cb_vol = cb.clone();
-    table.set("set_volume", lua.create_function(move |_, (channel, val): (String, f32)| {
-        cb_vol.push(LuaCommand::SetVolume { channel, value: val });
+    table.set("set_volume", lua.create_function(move |_, (channel, val, fade): (String, f32, Option<f32>)| {
+        cb_vol.push(LuaCommand::SetVolume { channel, value: val, fade: fade.unwrap_or(0.0) });
         Ok(())
     })?)?;
 
+    // 4. is_playing(channel)
+    table.set("is_playing", lua.create_function(|lua, channel: String| {
+        Ok(lua.app_data_ref::<PlayingChannels>().is_some_and(|p| p.0.contains(&channel)))
+    })?)?;
+
+    // 5. audio_position(channel) / audio_playing(channel)：渲染层上一帧的实际状态，
+    // 与 is_playing 不同，声音自然播完后 audio_playing 也会变为 false
+    table.set("audio_position", lua.create_function(|lua, channel: String| {
+        Ok(channel_state(lua, &channel).position)
+    })?)?;
+    table.set("audio_playing", lua.create_function(|lua, channel: String| {
+        Ok(channel_state(lua, &channel).playing)
+    })?)?;
+
     Ok(())
-}
\ No newline at end of file
+}
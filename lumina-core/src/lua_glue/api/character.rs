@@ -0,0 +1,24 @@
+use mlua::{Lua, Table};
+use crate::lua_glue::types::{CommandBuffer, LuaCommand};
+
+pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()> {
+    // 1. define_character(id, {name, image_tag, color})，已定义的角色只更新给出的字段
+    let cb_def = cb.clone();
+    table.set("define_character", lua.create_function(move |_, (id, opts): (String, Option<Table>)| {
+        let (name, image_tag, color) = match opts {
+            Some(t) => (t.get("name")?, t.get("image_tag")?, t.get("color")?),
+            None => (None, None, None),
+        };
+        cb_def.push(LuaCommand::DefineCharacter { id, name, image_tag, color });
+        Ok(())
+    })?)?;
+
+    // 2. set_character_name(id, name)
+    let cb_name = cb.clone();
+    table.set("set_character_name", lua.create_function(move |_, (id, name): (String, String)| {
+        cb_name.push(LuaCommand::SetCharacterName { id, name });
+        Ok(())
+    })?)?;
+
+    Ok(())
+}
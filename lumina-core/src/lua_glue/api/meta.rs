@@ -0,0 +1,46 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use mlua::{Lua, Table};
+use crate::lua_glue::types::{CommandBuffer, LuaCommand};
+
+/// `sf` 表中存放成就解锁记录的字段名 (id -> 解锁时间戳)
+pub const ACHIEVEMENTS_KEY: &str = "achievements";
+
+fn achievements_table(lua: &Lua) -> mlua::Result<Table> {
+    let sf: Table = lua.globals().get("sf")?;
+    match sf.get::<Option<Table>>(ACHIEVEMENTS_KEY)? {
+        Some(t) => Ok(t),
+        None => {
+            let t = lua.create_table()?;
+            sf.set(ACHIEVEMENTS_KEY, t.clone())?;
+            Ok(t)
+        }
+    }
+}
+
+pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()> {
+    // 1. Unlock (重复解锁为 no-op，返回是否为首次解锁)
+    let cb_unlock = cb.clone();
+    table.set("unlock_achievement", lua.create_function(move |lua, id: String| {
+        let achievements = achievements_table(lua)?;
+        if achievements.contains_key(id.as_str())? {
+            return Ok(false);
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        achievements.set(id.as_str(), timestamp)?;
+
+        cb_unlock.push(LuaCommand::UnlockAchievement { id });
+        Ok(true)
+    })?)?;
+
+    // 2. Query
+    table.set("has_achievement", lua.create_function(|lua, id: String| {
+        let achievements = achievements_table(lua)?;
+        achievements.contains_key(id)
+    })?)?;
+
+    Ok(())
+}
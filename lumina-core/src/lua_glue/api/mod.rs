@@ -1,4 +1,7 @@
 pub mod system;
 pub mod audio;
 pub mod visual;
-pub mod log;
\ No newline at end of file
+pub mod log;
+pub mod meta;
+pub mod random;
+pub mod character;
\ No newline at end of file
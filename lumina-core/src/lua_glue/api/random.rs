@@ -0,0 +1,80 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use mlua::{Lua, Table};
+
+/// 引擎管理的确定性随机数发生器 (SplitMix64)，全部状态只有一个 u64，
+/// 挂在 Lua app data 上，存读档时经 `Ctx::rng_state` 同步
+pub struct LuaRng {
+    state: u64,
+}
+
+impl LuaRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// 以当前时间作为种子，用于新游戏
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// [0, 1) 区间的浮点数
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// [a, b] 闭区间的整数，调用方保证 a <= b
+    pub fn next_range(&mut self, a: i64, b: i64) -> i64 {
+        let span = (b as u64).wrapping_sub(a as u64).wrapping_add(1);
+        if span == 0 {
+            // a..=b 覆盖整个 i64
+            return self.next_u64() as i64;
+        }
+        let offset = ((self.next_u64() as u128 * span as u128) >> 64) as u64;
+        a.wrapping_add(offset as i64)
+    }
+}
+
+/// 读取当前 RNG 状态 (写入存档用)
+pub fn rng_state(lua: &Lua) -> u64 {
+    lua.app_data_ref::<LuaRng>().map(|r| r.state).unwrap_or(0)
+}
+
+/// 从存档状态恢复 RNG
+pub fn set_rng_state(lua: &Lua, state: u64) {
+    lua.set_app_data(LuaRng::new(state));
+}
+
+fn with_rng<R>(lua: &Lua, f: impl FnOnce(&mut LuaRng) -> R) -> mlua::Result<R> {
+    let mut rng = lua.app_data_mut::<LuaRng>()
+        .ok_or_else(|| mlua::Error::runtime("RNG not initialized"))?;
+    Ok(f(&mut rng))
+}
+
+pub fn register(lua: &Lua, table: &Table) -> mlua::Result<()> {
+    // 1. random() -> [0, 1)
+    table.set("random", lua.create_function(|lua, ()| {
+        with_rng(lua, |rng| rng.next_f64())
+    })?)?;
+
+    // 2. random_int(a, b) -> [a, b]
+    table.set("random_int", lua.create_function(|lua, (a, b): (i64, i64)| {
+        if a > b {
+            return Err(mlua::Error::runtime(format!("random_int: empty range [{}, {}]", a, b)));
+        }
+        with_rng(lua, |rng| rng.next_range(a, b))
+    })?)?;
+
+    Ok(())
+}
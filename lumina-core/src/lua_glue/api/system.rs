@@ -16,5 +16,35 @@ pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()
         Ok(())
     })?)?;
 
+    // 3. Language
+    let cb_lang = cb.clone();
+    table.set("set_language", lua.create_function(move |_, lang: String| {
+        cb_lang.push(LuaCommand::SetLanguage(lang));
+        Ok(())
+    })?)?;
+
+    table.set("get_language", lua.create_function(|_, ()| {
+        Ok(crate::i18n::current_language())
+    })?)?;
+
+    // 4. Export History (.json 导出 JSON，其余为纯文本，写入存档目录)
+    let cb_export = cb.clone();
+    table.set("export_history", lua.create_function(move |_, filename: String| {
+        cb_export.push(LuaCommand::ExportHistory { filename });
+        Ok(())
+    })?)?;
+
+    // 5. Toast，时长默认 2 秒
+    let cb_toast = cb.clone();
+    table.set("toast", lua.create_function(move |_, (text, duration): (String, Option<f32>)| {
+        cb_toast.push(LuaCommand::Toast { text, duration: duration.unwrap_or(2.0) });
+        Ok(())
+    })?)?;
+
+    // 6. 实际使用的存档目录
+    table.set("save_dir", lua.create_function(|_, ()| {
+        Ok(crate::storager::save_dir().to_string_lossy().to_string())
+    })?)?;
+
     Ok(())
 }
\ No newline at end of file
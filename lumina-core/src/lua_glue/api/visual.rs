@@ -1,12 +1,69 @@
-use mlua::{Lua, Table, Value};
+use mlua::{Function, Lua, Table, Value};
 use std::collections::HashMap;
-use crate::event::{LayoutConfig, TransitionConfig};
+use crate::event::{AnimState, LayoutConfig, TransitionConfig};
 use crate::lua_glue::types::{CommandBuffer, LuaCommand};
 
+/// 动画完成状态放在 Lua 注册表里，不随 `f` / `sf` 写入存档
+const ANIMATIONS_KEY: &str = "lumina_animations";
+/// 以函数作为完成回调时自动生成的 id 前缀
+const AUTO_ID_PREFIX: &str = "__anim:";
+
+/// `{ callbacks = { id -> fn }, done = { id -> true }, seq = n }`
+fn animations_table(lua: &Lua) -> mlua::Result<Table> {
+    if let Some(t) = lua.named_registry_value::<Option<Table>>(ANIMATIONS_KEY)? {
+        return Ok(t);
+    }
+    let t = lua.create_table()?;
+    t.set("callbacks", lua.create_table()?)?;
+    t.set("done", lua.create_table()?)?;
+    t.set("seq", 0)?;
+    lua.set_named_registry_value(ANIMATIONS_KEY, t.clone())?;
+    Ok(t)
+}
+
+/// 解析 `transform` 的 `on_done` 参数：字符串为动画 id，函数则注册为回调并生成 id
+fn completion_id(lua: &Lua, on_done: Value) -> mlua::Result<Option<String>> {
+    let anims = animations_table(lua)?;
+    let id = match on_done {
+        Value::Nil => return Ok(None),
+        Value::String(s) => s.to_str()?.to_string(),
+        Value::Function(f) => {
+            let seq = anims.get::<u64>("seq")? + 1;
+            anims.set("seq", seq)?;
+            let id = format!("{}{}", AUTO_ID_PREFIX, seq);
+            anims.get::<Table>("callbacks")?.set(id.as_str(), f)?;
+            id
+        }
+        other => return Err(mlua::Error::runtime(format!(
+            "transform on_done must be a string or function, got {}", other.type_name()
+        ))),
+    };
+    // 同名动画重新开始，之前的完成记录作废
+    anims.get::<Table>("done")?.set(id.as_str(), Value::Nil)?;
+    Ok(Some(id))
+}
+
+/// 渲染层报告动画 `id` 播放结束：记录完成并调用注册的回调
+pub fn animation_done(lua: &Lua, id: &str) -> mlua::Result<()> {
+    let anims = animations_table(lua)?;
+    if !id.starts_with(AUTO_ID_PREFIX) {
+        anims.get::<Table>("done")?.set(id, true)?;
+    }
+
+    let callbacks: Table = anims.get("callbacks")?;
+    if let Some(callback) = callbacks.get::<Option<Function>>(id)? {
+        callbacks.set(id, Value::Nil)?;
+        callback.call::<()>(id)?;
+    }
+    Ok(())
+}
+
 pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()> {
     let cb_transform = cb.clone();
 
-    table.set("transform", lua.create_function(move |_, (target, props, duration, easing): (String, Table, Option<f32>, Option<String>)| {
+    // lumina.transform(target, props, duration?, easing?, on_done?)
+    // on_done 为动画 id (配合 on_animation_done / animation_done) 或结束时调用的函数
+    table.set("transform", lua.create_function(move |lua, (target, props, duration, easing, on_done): (String, Table, Option<f32>, Option<String>, Value)| {
         let mut props_map = HashMap::new();
 
         for pair in props.pairs::<String, Value>() {
@@ -24,10 +81,26 @@ pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()
             props: props_map,
             duration: duration.unwrap_or(0.0), // 默认 0 秒 (瞬移)
             easing: easing.unwrap_or_else(|| "linear".into()),
+            on_done: completion_id(lua, on_done)?,
         });
         Ok(())
     })?)?;
 
+    // lumina.on_animation_done(id, fn)：动画已经结束时立即调用
+    table.set("on_animation_done", lua.create_function(|lua, (id, callback): (String, Function)| {
+        let anims = animations_table(lua)?;
+        if anims.get::<Table>("done")?.get::<bool>(id.as_str())? {
+            return callback.call::<()>(id);
+        }
+        anims.get::<Table>("callbacks")?.set(id, callback)
+    })?)?;
+
+    // lumina.animation_done(id)：供协程轮询等待
+    table.set("animation_done", lua.create_function(|lua, id: String| {
+        let done: Table = animations_table(lua)?.get("done")?;
+        done.get::<bool>(id)
+    })?)?;
+
     let cb_layout = cb.clone();
     table.set("register_layout", lua.create_function(move |_, (name, tbl): (String, Table)| {
         cb_layout.push(LuaCommand::RegisterLayout {
@@ -83,6 +156,55 @@ pub fn register(lua: &Lua, table: &Table, cb: &CommandBuffer) -> mlua::Result<()
         cb_mark.push(LuaCommand::MarkDynamic { name });
         Ok(())
     })?)?;
+
+    // lumina.shake(target, intensity?, duration?)，target 可为 "screen"
+    let cb_shake = cb.clone();
+    table.set("shake", lua.create_function(move |_, (target, intensity, duration): (String, Option<f32>, Option<f32>)| {
+        cb_shake.push(LuaCommand::Shake {
+            target,
+            intensity: intensity.unwrap_or(20.0),
+            duration: duration.unwrap_or(0.5),
+        });
+        Ok(())
+    })?)?;
+
+    // lumina.flash(color?, duration?)，颜色为 "#rrggbb" / "#rrggbbaa"
+    let cb_flash = cb.clone();
+    table.set("flash", lua.create_function(move |_, (color, duration): (Option<String>, Option<f32>)| {
+        let color = match color.as_deref() {
+            None => [255, 255, 255, 255],
+            Some(s) => parse_hex_color(s)
+                .ok_or_else(|| mlua::Error::runtime(format!("invalid flash color '{}'", s)))?,
+        };
+        cb_flash.push(LuaCommand::Flash { color, duration: duration.unwrap_or(0.3) });
+        Ok(())
+    })?)?;
+
+    // lumina.set_anim(target, frames, fps?, loop?, state?)，state 为 "idle" (默认) 或 "talk" (说话时播放)
+    // 帧名作为最后一个属性拼进资源名，"" 表示不加；frames 为空表时移除该状态的动画
+    let cb_anim = cb.clone();
+    table.set("set_anim", lua.create_function(move |_, (target, frames, fps, looping, state): (String, Vec<String>, Option<f32>, Option<bool>, Option<String>)| {
+        let fps = fps.unwrap_or(8.0);
+        if fps <= 0.0 {
+            return Err(mlua::Error::runtime(format!("set_anim fps must be positive, got {}", fps)));
+        }
+        let state = match state.as_deref() {
+            None | Some("idle") => AnimState::Idle,
+            Some("talk") => AnimState::Talk,
+            Some(other) => return Err(mlua::Error::runtime(format!("unknown anim state '{}'", other))),
+        };
+        cb_anim.push(LuaCommand::SetAnim { target, state, frames, fps, looping: looping.unwrap_or(true) });
+        Ok(())
+    })?)?;
     
     Ok(())
+}
+
+fn parse_hex_color(s: &str) -> Option<[u8; 4]> {
+    let hex = s.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([byte(0)?, byte(2)?, byte(4)?, if hex.len() == 8 { byte(6)? } else { 255 }])
 }
\ No newline at end of file
@@ -1,16 +1,44 @@
 pub mod types;
 pub mod api;
+pub mod vars;
 
 pub use types::{CommandBuffer, LuaCommand};
 
+use std::collections::HashMap;
 use std::path::Path;
-use mlua::{Lua, LuaSerdeExt, Table};
+use mlua::{Function, Lua, Table};
 use log::{error, info};
 use lumina_shared::config;
 use crate::config::SystemConfig;
 
+/// 已编译表达式的缓存，挂在 Lua 实例的 app data 上，
+/// 生命周期与 Lua 实例一致 (Executor clone 时共享同一份)
+#[derive(Default)]
+struct ExprCache {
+    funcs: HashMap<String, Function>,
+}
+
+/// 取出 `chunk` 对应的已编译函数，未命中时编译并缓存
+fn compiled_chunk(lua: &Lua, chunk: String) -> mlua::Result<Function> {
+    if let Some(cache) = lua.app_data_ref::<ExprCache>() {
+        if let Some(f) = cache.funcs.get(&chunk) {
+            return Ok(f.clone());
+        }
+    }
+
+    let func = lua.load(&chunk).into_function()?;
+    if let Some(mut cache) = lua.app_data_mut::<ExprCache>() {
+        cache.funcs.insert(chunk, func.clone());
+    }
+    Ok(func)
+}
+
 pub fn init_lua(lua: &Lua) -> CommandBuffer {
     let cmd_buffer = CommandBuffer::new();
+    lua.set_app_data(ExprCache::default());
+    lua.set_app_data(api::random::LuaRng::from_time());
+    lua.set_app_data(api::audio::PlayingChannels::default());
+    lua.set_app_data(api::audio::AudioState::default());
 
     let sys_cfg: SystemConfig = config::get("system");
     let script_root = Path::new(&sys_cfg.script_path);
@@ -50,6 +78,9 @@ pub fn init_lua(lua: &Lua) -> CommandBuffer {
     api::system::register(lua, &lumina, &cmd_buffer).expect("Failed to register system API");
     api::audio::register(lua, &lumina, &cmd_buffer).expect("Failed to register audio API");
     api::visual::register(lua, &lumina, &cmd_buffer).expect("Failed to register visual API");
+    api::meta::register(lua, &lumina, &cmd_buffer).expect("Failed to register meta API");
+    api::random::register(lua, &lumina).expect("Failed to register random API");
+    api::character::register(lua, &lumina, &cmd_buffer).expect("Failed to register character API");
 
     globals.set("_rust_log", rust_log).expect("Failed to set rust_log");
     globals.set("lumina", lumina).expect("Failed to set Lumina engine");
@@ -59,7 +90,7 @@ pub fn init_lua(lua: &Lua) -> CommandBuffer {
 pub fn evel_bool(lua: &Lua, expr: &str) -> bool {
     let chunk = format!("return {}", expr);
 
-    lua.load(&chunk).eval::<bool>().unwrap_or_else(|e| {
+    compiled_chunk(lua, chunk).and_then(|f| f.call::<bool>(())).unwrap_or_else(|e| {
         error!("Lua eval error for condition '{}': {}", expr, e);
         false
     })
@@ -68,7 +99,7 @@ pub fn evel_bool(lua: &Lua, expr: &str) -> bool {
 pub fn inject_vars(lua: &Lua, data: &serde_json::Value) {
     let globals = lua.globals();
 
-    match lua.to_value(data) {
+    match vars::to_lua(lua, data) {
         Ok(lua_val) => {
             if lua_val.is_nil() {
                 globals.set("f", lua.create_table().unwrap()).unwrap();
@@ -83,20 +114,16 @@ pub fn inject_vars(lua: &Lua, data: &serde_json::Value) {
 pub fn extract_vars(lua: &Lua) -> serde_json::Value {
     let globals = lua.globals();
 
-    if let Ok(val) = globals.get::<mlua::Value>("f") {
-        serde_json::to_value(&val).unwrap_or_else(|e| {
-            error!("Failed to serialize Lua 'f' table: {}", e);
-            serde_json::Value::Null
-        })
-    } else {
-        serde_json::Value::Null
+    match globals.get::<mlua::Value>("f") {
+        Ok(val) => vars::to_json(lua, &val).unwrap_or(serde_json::Value::Null),
+        Err(_) => serde_json::Value::Null,
     }
 }
 
 pub fn eval_string(lua: &Lua, expr: &str) -> String {
     let chunk = format!("return tostring({})", expr);
 
-    match lua.load(&chunk).eval::<String>() {
+    match compiled_chunk(lua, chunk).and_then(|f| f.call::<String>(())) {
         Ok(s) => s,
         Err(e) => {
             log::error!("Interpolation error for '{}': {}", expr, e);
@@ -107,7 +134,7 @@ pub fn eval_string(lua: &Lua, expr: &str) -> String {
 
 pub fn inject_sf(lua: &Lua, data: &serde_json::Value) {
     let globals = lua.globals();
-    match lua.to_value(data) {
+    match vars::to_lua(lua, data) {
         Ok(lua_val) => {
             if !lua_val.is_nil() {
                 globals.set("sf", lua_val).unwrap();
@@ -119,12 +146,8 @@ pub fn inject_sf(lua: &Lua, data: &serde_json::Value) {
 
 pub fn extract_sf(lua: &Lua) -> serde_json::Value {
     let globals = lua.globals();
-    if let Ok(val) = globals.get::<mlua::Value>("sf") {
-        serde_json::to_value(&val).unwrap_or_else(|e| {
-            error!("Failed to serialize Lua 'sf': {}", e);
-            serde_json::Value::Null
-        })
-    } else {
-        serde_json::Value::Null
+    match globals.get::<mlua::Value>("sf") {
+        Ok(val) => vars::to_json(lua, &val).unwrap_or(serde_json::Value::Null),
+        Err(_) => serde_json::Value::Null,
     }
 }
\ No newline at end of file
@@ -5,16 +5,41 @@ use std::sync::{Arc, Mutex};
 pub enum LuaCommand {
     Jump(String),
     SaveGlobal,
-    SetVolume { channel: String, value: f32 },
+    SetVolume { channel: String, value: f32, fade: f32 },
+    /// 未指定的参数由执行器按 audio 配置补全
+    PlayAudio {
+        channel: String,
+        path: String,
+        volume: Option<f32>,
+        fade_in: Option<f32>,
+        looping: Option<bool>,
+    },
+    StopAudio { channel: String, fade_out: Option<f32> },
     ModifyVisual {
         target: String,
         props: HashMap<String, f32>,
         duration: f32,
         easing: String,
+        on_done: Option<String>,
     },
     RegisterLayout { name: String, config: crate::event::LayoutConfig },
     RegisterTransition { name: String, config: crate::event::TransitionConfig },
     MarkDynamic { name: String },
+    Shake { target: String, intensity: f32, duration: f32 },
+    Flash { color: [u8; 4], duration: f32 },
+    SetAnim { target: String, state: crate::event::AnimState, frames: Vec<String>, fps: f32, looping: bool },
+    UnlockAchievement { id: String },
+    Toast { text: String, duration: f32 },
+    SetLanguage(String),
+    ExportHistory { filename: String },
+    /// 已存在的角色只覆盖给出的字段，否则新建 (name 缺省为 id)
+    DefineCharacter {
+        id: String,
+        name: Option<String>,
+        image_tag: Option<String>,
+        color: Option<String>,
+    },
+    SetCharacterName { id: String, name: String },
 }
 
 #[derive(Debug,Clone)]
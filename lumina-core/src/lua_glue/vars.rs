@@ -0,0 +1,127 @@
+//! `f` / `sf` 表与 JSON 之间的转换
+//!
+//! 约定：
+//! - 键为 1..=n 连续整数的表存为数组，其余 (含稀疏数组、混合键) 存为对象；
+//! - 空表默认存为 `{}`，由数组读回的空表带数组元表，仍存为 `[]`；
+//! - 对象中的整数键写成十进制字符串，读回时规范形式的整数字符串还原为整数键；
+//! - 函数、userdata 等无法存档的值以及循环引用会被跳过 (记录警告)，不影响其余变量。
+
+use std::collections::HashSet;
+use mlua::{Lua, LuaSerdeExt, Table, Value};
+use log::warn;
+use serde_json::{Map, Number};
+
+/// Lua 值转为 JSON，无法表示的值返回 `None`
+pub fn to_json(lua: &Lua, value: &Value) -> Option<serde_json::Value> {
+    to_json_inner(lua, value, &mut HashSet::new())
+}
+
+fn to_json_inner(lua: &Lua, value: &Value, path: &mut HashSet<usize>) -> Option<serde_json::Value> {
+    match value {
+        Value::Nil => Some(serde_json::Value::Null),
+        Value::LightUserData(ud) if ud.0.is_null() => Some(serde_json::Value::Null),
+        Value::Boolean(b) => Some(serde_json::Value::Bool(*b)),
+        Value::Integer(i) => Some(serde_json::Value::from(*i)),
+        Value::Number(n) => match Number::from_f64(*n) {
+            Some(n) => Some(serde_json::Value::Number(n)),
+            None => {
+                warn!("Lua number {} cannot be saved, dropped", n);
+                None
+            }
+        },
+        Value::String(s) => Some(serde_json::Value::String(s.to_string_lossy().to_string())),
+        Value::Table(t) => {
+            let ptr = t.to_pointer() as usize;
+            if !path.insert(ptr) {
+                warn!("Recursive Lua table cannot be saved, dropped");
+                return None;
+            }
+            let json = table_to_json(lua, t, path);
+            path.remove(&ptr);
+            Some(json)
+        }
+        other => {
+            warn!("Lua {} cannot be saved, dropped", other.type_name());
+            None
+        }
+    }
+}
+
+fn table_to_json(lua: &Lua, table: &Table, path: &mut HashSet<usize>) -> serde_json::Value {
+    let pairs: Vec<(Value, Value)> = table.clone().pairs::<Value, Value>()
+        .filter_map(|p| p.ok())
+        .collect();
+
+    if pairs.is_empty() {
+        let is_array = table.metatable().is_some_and(|mt| mt == lua.array_metatable());
+        return if is_array {
+            serde_json::Value::Array(Vec::new())
+        } else {
+            serde_json::Value::Object(Map::new())
+        };
+    }
+
+    let n = pairs.len() as i64;
+    let is_sequence = pairs.iter()
+        .all(|(k, _)| matches!(k, Value::Integer(i) if (1..=n).contains(i)));
+
+    if is_sequence {
+        let mut items = vec![serde_json::Value::Null; pairs.len()];
+        for (k, v) in &pairs {
+            if let Value::Integer(i) = k {
+                items[(*i - 1) as usize] = to_json_inner(lua, v, path).unwrap_or(serde_json::Value::Null);
+            }
+        }
+        return serde_json::Value::Array(items);
+    }
+
+    let mut map = Map::new();
+    for (k, v) in &pairs {
+        let key = match k {
+            Value::String(s) => s.to_string_lossy().to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::Boolean(b) => b.to_string(),
+            other => {
+                warn!("Lua table key of type {} cannot be saved, dropped", other.type_name());
+                continue;
+            }
+        };
+        if let Some(v) = to_json_inner(lua, v, path) {
+            map.insert(key, v);
+        }
+    }
+    serde_json::Value::Object(map)
+}
+
+/// JSON 转回 Lua 值，`null` 还原为 `nil`
+pub fn to_lua(lua: &Lua, json: &serde_json::Value) -> mlua::Result<Value> {
+    Ok(match json {
+        serde_json::Value::Null => Value::Nil,
+        serde_json::Value::Bool(b) => Value::Boolean(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Number(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::String(lua.create_string(s)?),
+        serde_json::Value::Array(items) => {
+            let t = lua.create_table_with_capacity(items.len(), 0)?;
+            for (i, item) in items.iter().enumerate() {
+                t.raw_set(i + 1, to_lua(lua, item)?)?;
+            }
+            t.set_metatable(Some(lua.array_metatable()))?;
+            Value::Table(t)
+        }
+        serde_json::Value::Object(map) => {
+            let t = lua.create_table_with_capacity(0, map.len())?;
+            for (k, v) in map {
+                let value = to_lua(lua, v)?;
+                match k.parse::<i64>() {
+                    Ok(i) if i.to_string() == *k => t.raw_set(i, value)?,
+                    _ => t.raw_set(k.as_str(), value)?,
+                }
+            }
+            Value::Table(t)
+        }
+    })
+}
@@ -1,14 +1,257 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::path::{Path};
 use std::sync::Arc;
-use walkdir::WalkDir;
+use lumina_shared::vfs;
 use log::{info};
 use anyhow::{Result, Context};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHasher};
 
-use viviscript_core::ast::{Script, Stmt};
+use viviscript_core::ast::{AudioOptions, ChoiceArm, SceneImage, Script, ShowAttr, Speaker, Stmt, Transition, UiProp, UiStmt};
 use viviscript_core::{lexer::Lexer, parser::Parser};
+use viviscript_core::visit::{walk_stmt, walk_stmt_mut, walk_stmts, walk_stmts_mut, Block, Visitor, VisitorMut};
 use crate::runtime::Character;
+use crate::config::{DebugConfig, LintLevel};
+use crate::i18n;
+
+/// [`ScriptManager::extract_strings`] 的统计结果
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractReport {
+    pub total: usize,
+    pub new: usize,
+    pub obsolete: usize,
+}
+
+/// [`ScriptManager::reachability`] 的结果，列表均按文件与行号 (分支块按 id) 排列
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReachabilityReport {
+    /// 从入口可达的 label
+    pub reachable_labels: Vec<LabelMeta>,
+    /// 从入口永远到不了的 label
+    pub unreachable_labels: Vec<LabelMeta>,
+    /// 到不了的选项与条件分支块 (`<scope>@choice_0_opt1`、`<scope>@if_0_b0` 等)
+    pub unreachable_blocks: Vec<String>,
+}
+
+/// 一个 label 的显示信息，用于存档的章节名与调试用的章节列表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelMeta {
+    pub id: String,
+    /// `-- @title: ...` 写明的标题，没有时为 label id
+    pub title: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// 项目静态检查发现的一处问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectIssue {
+    pub kind: IssueKind,
+    pub file: String,
+    pub line: usize,
+    pub msg: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// `jump` / `call` 的目标 label 不存在
+    UnresolvedJump,
+    /// 对话的说话人不是任何文件中定义的角色，也没有用 `@` 指定显示名
+    UnknownSpeaker,
+    /// `if` / `elif` 的条件不是合法的 Lua 表达式
+    InvalidCondition,
+    /// 条件中含有赋值 `=` (多半是想写 `==`)
+    AssignmentInCondition,
+}
+
+impl fmt::Display for ProjectIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.msg)
+    }
+}
+
+/// 项目检查中级别为 `deny` 的问题，`load_project` 以此拒绝加载
+#[derive(Debug, Clone)]
+pub struct ProjectError {
+    pub issues: Vec<ProjectIssue>,
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} problem(s) in script project:", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+/// 选项分支块的 id，同时是分支文本的翻译 key
+pub fn choice_arm_id(choice_id: &str, idx: usize) -> String {
+    format!("{}_opt{}", choice_id, idx)
+}
+
+/// 条件中是否有单独的 `=` (不计 `==`、`~=`、`<=`、`>=` 与字符串内的)
+fn has_assignment(cond: &str) -> bool {
+    let chars: Vec<char> = cond.chars().collect();
+    let mut quote = None;
+    for (i, &c) in chars.iter().enumerate() {
+        match quote {
+            Some(q) if c == q && chars.get(i.wrapping_sub(1)) != Some(&'\\') => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '=' => {
+                let prev = i.checked_sub(1).map(|j| chars[j]);
+                let next = chars.get(i + 1).copied();
+                if !matches!(prev, Some('=' | '~' | '<' | '>')) && next != Some('=') {
+                    return true;
+                }
+            }
+            None => {}
+        }
+    }
+    false
+}
+
+/// Lua 代码中以字符串字面量调用 `lumina.jump` 的目标
+fn lua_jump_targets(code: &str) -> Vec<String> {
+    code.match_indices("lumina.jump")
+        .filter_map(|(i, m)| {
+            let rest = code[i + m.len()..].trim_start().strip_prefix('(')?.trim_start();
+            let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let (target, tail) = rest[1..].split_once(quote)?;
+            tail.trim_start().starts_with(')').then(|| target.to_string())
+        })
+        .collect()
+}
+
+/// 代码块内容的哈希。不计入 span，只改动空白或注释不会改变结果
+fn content_hash(stmts: &[Stmt]) -> u64 {
+    let mut hasher = FxHasher::default();
+    hash_stmts(stmts, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_stmts(stmts: &[Stmt], h: &mut FxHasher) {
+    h.write_usize(stmts.len());
+    for stmt in stmts {
+        hash_stmt(stmt, h);
+    }
+}
+
+/// 逐字段哈希，跳过 span。字段全部解构出来，AST 增加字段时这里会编译失败，不会漏算
+fn hash_stmt(stmt: &Stmt, h: &mut FxHasher) {
+    std::mem::discriminant(stmt).hash(h);
+    match stmt {
+        Stmt::CharacterDef { span: _, id, name, image_tag, voice_tag, color, voice_volume, voice_pitch } => {
+            (id, name, image_tag, voice_tag, color).hash(h);
+            hash_f32(*voice_volume, h);
+            hash_f32(*voice_pitch, h);
+        }
+        Stmt::Label { span: _, id, title, body } => {
+            (id, title).hash(h);
+            hash_stmts(body, h);
+        }
+        Stmt::Choice { span: _, title, arms, id, timeout, default } => {
+            (title, id, default).hash(h);
+            hash_f32(*timeout, h);
+            h.write_usize(arms.len());
+            for ChoiceArm { text, body } in arms {
+                text.hash(h);
+                hash_stmts(body, h);
+            }
+        }
+        Stmt::Jump { span: _, target } | Stmt::Call { span: _, target } => target.hash(h),
+        Stmt::LuaBlock { span: _, code } => code.hash(h),
+        Stmt::Dialogue { span: _, speaker: Speaker { name, alias }, text, voice_index, tl_id, voice_line } => {
+            (name, alias, text, voice_index, tl_id, voice_line).hash(h);
+        }
+        Stmt::Narration { span: _, lines, line_spans: _, tl_id } => (lines, tl_id).hash(h),
+        Stmt::Audio { span: _, action, channel, resource, options } => {
+            std::mem::discriminant(action).hash(h);
+            (channel, resource).hash(h);
+            let AudioOptions { volume, fade_in, fade_out, r#loop, loop_start, loop_end, intro } = options;
+            for v in [volume, fade_in, fade_out, loop_start, loop_end] {
+                hash_f32(*v, h);
+            }
+            (r#loop, intro).hash(h);
+        }
+        Stmt::Hide { span: _, target, transition } => {
+            target.hash(h);
+            hash_transition(transition, h);
+        }
+        Stmt::Show { span: _, target, attrs, position, transition } => {
+            (target, position).hash(h);
+            attrs.as_ref().map(Vec::len).hash(h);
+            for attr in attrs.iter().flatten() {
+                std::mem::discriminant(attr).hash(h);
+                match attr {
+                    ShowAttr::Add(a) | ShowAttr::Remove(a) => a.hash(h),
+                }
+            }
+            hash_transition(transition, h);
+        }
+        Stmt::Scene { span: _, image, transition } => {
+            image.as_ref().map(|SceneImage { prefix, attrs }| (prefix, attrs)).hash(h);
+            hash_transition(transition, h);
+        }
+        Stmt::Nvl { span: _, action } => std::mem::discriminant(action).hash(h),
+        Stmt::Movie { span: _, id, skippable } => (id, skippable).hash(h),
+        Stmt::WaitAudio { span: _, channel } => channel.hash(h),
+        Stmt::Error { span: _, msg, recovered } => (msg, recovered).hash(h),
+        Stmt::If { span: _, branches, else_branch, id } => {
+            id.hash(h);
+            h.write_usize(branches.len());
+            for (cond, body) in branches {
+                cond.hash(h);
+                hash_stmts(body, h);
+            }
+            else_branch.is_some().hash(h);
+            if let Some(body) = else_branch {
+                hash_stmts(body, h);
+            }
+        }
+        Stmt::ScreenDef { span: _, id, root } => {
+            id.hash(h);
+            hash_ui(root, h);
+        }
+    }
+}
+
+fn hash_ui(stmts: &[UiStmt], h: &mut FxHasher) {
+    h.write_usize(stmts.len());
+    for stmt in stmts {
+        std::mem::discriminant(stmt).hash(h);
+        let props = match stmt {
+            UiStmt::Container { span: _, kind, props, children } => {
+                std::mem::discriminant(kind).hash(h);
+                hash_ui(children, h);
+                props
+            }
+            UiStmt::Widget { span: _, kind, value, props } => {
+                std::mem::discriminant(kind).hash(h);
+                value.hash(h);
+                props
+            }
+        };
+        h.write_usize(props.len());
+        for UiProp { key, val } in props {
+            (key, val).hash(h);
+        }
+    }
+}
+
+fn hash_transition(transition: &Option<Transition>, h: &mut FxHasher) {
+    transition.as_ref().map(|t| &t.effect).hash(h);
+    hash_f32(transition.as_ref().and_then(|t| t.duration), h);
+}
+
+fn hash_f32(value: Option<f32>, h: &mut FxHasher) {
+    value.map(f32::to_bits).hash(h);
+}
 
 /// 脚本管理器：负责加载、预处理和索引所有脚本
 pub struct ScriptManager {
@@ -21,6 +264,16 @@ pub struct ScriptManager {
     // 辅助数据
     pub source_cache: HashMap<String, String>,
     label_sources: HashMap<String, String>,
+    /// 显式选项 id -> 定义所在文件
+    choice_sources: HashMap<String, String>,
+    /// 与 `programs` 一一对应的脚本路径
+    program_files: Vec<String>,
+    /// 代码块 -> 内容哈希，存档据此判断脚本是否改动
+    block_hashes: FxHashMap<String, u64>,
+    /// 按加载顺序排列的 label 信息
+    label_meta: Vec<LabelMeta>,
+    /// 脚本路径 -> 其中 `Stmt::Error` 占位语句的数量
+    error_counts: HashMap<String, usize>,
 }
 
 impl ScriptManager {
@@ -29,194 +282,772 @@ impl ScriptManager {
             programs: Vec::new(),
             label_map: FxHashMap::default(),
             label_sources: HashMap::new(),
+            choice_sources: HashMap::new(),
             source_cache: HashMap::new(),
+            program_files: Vec::new(),
+            block_hashes: FxHashMap::default(),
+            label_meta: Vec::new(),
+            error_counts: HashMap::new(),
         }
     }
 
-    /// 扫描并加载项目
+    /// 扫描并加载项目，`root_dir` 为脚本目录或 `.pak` 归档
     pub fn load_project(&mut self, root_dir: impl AsRef<Path>) -> Result<()> {
         let root = root_dir.as_ref();
         info!("Scanning script project at: {:?}", root);
 
+        // 目录或 pak 归档的脚本分区
+        let source = vfs::open(root, vfs::SCRIPTS)
+            .with_context(|| format!("Failed to open script project: {:?}", root))?;
+        let mut files: Vec<String> = source.list().into_iter()
+            .filter(|p| p.ends_with(".vivi"))
+            .collect();
+        files.sort();
+
         let mut loaded_count = 0;
-        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.is_file() && path.extension().map_or(false, |e| e == "vivi") {
-                self.load_file(path)?;
-                loaded_count += 1;
-            }
+        for rel in &files {
+            let content = source.read_to_string(rel)
+                .with_context(|| format!("Failed to read script: {:?}", root.join(rel)))?;
+            self.load_source(&root.join(rel), content)?;
+            loaded_count += 1;
         }
 
         info!("Project loaded. Files: {}, Labels: {}", loaded_count, self.label_map.len());
+        self.finish_load()
+    }
+
+    /// 从内存加载脚本，每项为 `(文件名, 源码)`，文件名决定选项 id 等的作用域。
+    /// 与 `load_project` 一样在加载后做跨文件检查，供测试与工具使用
+    pub fn load_sources<'a>(&mut self, sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<()> {
+        for (name, src) in sources {
+            self.load_source(Path::new(name), src.to_string())?;
+        }
+        self.finish_load()
+    }
+
+    fn finish_load(&mut self) -> Result<()> {
+        self.block_hashes = self.label_map.iter()
+            .map(|(name, body)| (name.clone(), content_hash(body)))
+            .collect();
+
+        let debug_cfg: DebugConfig = lumina_shared::config::get("debug");
+        let mut denied = Vec::new();
+        for issue in self.check_project() {
+            // 未解析的跳转在执行到时才会出错，这里只做提示
+            let level = match issue.kind {
+                IssueKind::UnresolvedJump | IssueKind::AssignmentInCondition => LintLevel::Warn,
+                IssueKind::UnknownSpeaker => debug_cfg.unknown_speaker,
+                // 与语法错误一致，允许带错运行时执行到该条件按 false 处理
+                IssueKind::InvalidCondition if debug_cfg.allow_script_errors => LintLevel::Warn,
+                IssueKind::InvalidCondition => LintLevel::Deny,
+            };
+            match level {
+                LintLevel::Allow => {}
+                LintLevel::Warn => log::warn!("{}", issue),
+                LintLevel::Deny => {
+                    log::error!("{}", issue);
+                    denied.push(issue);
+                }
+            }
+        }
+        if !denied.is_empty() {
+            return Err(ProjectError { issues: denied }.into());
+        }
         Ok(())
     }
 
+    /// 跨文件的静态检查：未解析的 `jump` / `call` 目标、未定义的说话人、无法编译的条件。
+    /// 角色定义可以在任意文件中，不受加载顺序影响
+    pub fn check_project(&self) -> Vec<ProjectIssue> {
+        // 只用于编译条件，不执行任何代码
+        let lua = mlua::Lua::new();
+        let characters: HashSet<&str> = self.programs.iter()
+            .flat_map(|p| &p.body)
+            .filter_map(|stmt| match stmt {
+                Stmt::CharacterDef { id, .. } => Some(id.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut issues = Vec::new();
+        for (script, file) in self.programs.iter().zip(&self.program_files) {
+            self.check_block(&script.body, file, &characters, &lua, &mut issues);
+        }
+        issues
+    }
+
+    fn check_block(&self, stmts: &[Stmt], file: &str, characters: &HashSet<&str>, lua: &mlua::Lua, issues: &mut Vec<ProjectIssue>) {
+        let issue = |kind, line, msg| ProjectIssue { kind, file: file.to_string(), line, msg };
+        for stmt in stmts {
+            match stmt {
+                Stmt::Jump { span, target } | Stmt::Call { span, target } => {
+                    if !self.label_map.contains_key(target) {
+                        issues.push(issue(IssueKind::UnresolvedJump, span.line, format!("label '{}' is not defined", target)));
+                    }
+                },
+                Stmt::Dialogue { span, speaker, .. } => {
+                    // 用 `@` 指定了显示名的视为一次性的说话人
+                    if speaker.alias.is_none() && !characters.contains(speaker.name.as_str()) {
+                        issues.push(issue(IssueKind::UnknownSpeaker, span.line, format!("speaker '{}' is not a defined character", speaker.name)));
+                    }
+                },
+                Stmt::Label { body, .. } => self.check_block(body, file, characters, lua, issues),
+                Stmt::Choice { arms, .. } => {
+                    for arm in arms {
+                        self.check_block(&arm.body, file, characters, lua, issues);
+                    }
+                },
+                Stmt::If { span, branches, else_branch, .. } => {
+                    // elif 没有单独的位置信息，都记在 if 所在行
+                    for (cond, body) in branches {
+                        let assigns = has_assignment(cond);
+                        if let Err(e) = lua.load(format!("return {}", cond)).set_name("=condition").into_function() {
+                            let hint = if assigns { " (use '==' to compare)" } else { "" };
+                            issues.push(issue(IssueKind::InvalidCondition, span.line, format!("invalid condition '{}'{}: {}", cond, hint, e)));
+                        } else if assigns {
+                            issues.push(issue(IssueKind::AssignmentInCondition, span.line, format!("condition '{}' contains an assignment", cond)));
+                        }
+                        self.check_block(body, file, characters, lua, issues);
+                    }
+                    if let Some(body) = else_branch {
+                        self.check_block(body, file, characters, lua, issues);
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
     pub fn collect_characters(&self) -> HashMap<String, Character> {
-        let mut chars = HashMap::new();
+        let mut collector = CharacterCollector::default();
+        for script in &self.programs {
+            walk_stmts(&script.body, &mut collector);
+        }
+        collector.0
+    }
+
+    /// 提取所有可翻译文本 (对话/旁白/选项/角色名) 到 JSON 翻译表 (`tl/<lang>.json` 格式)。
+    /// 对话与选项以稳定行 id 为 key，角色名以原文哈希为 key，每条记录原文与译文。
+    ///
+    /// 目标文件已存在时做增量合并：保留已有译文，新增条目标记为 `new`，
+    /// 脚本中已不存在的条目保留并标记为 `obsolete`。
+    pub fn extract_strings(&self, path: impl AsRef<Path>) -> Result<ExtractReport> {
+        let path = path.as_ref();
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
         for script in &self.programs {
-            for stmt in &script.body {
-                if let Stmt::CharacterDef { id, name, image_tag, voice_tag, .. } = stmt {
-                    chars.insert(id.clone(), Character {
-                        id: id.clone(),
-                        name: name.clone(),
-                        image_tag: image_tag.clone(),
-                        voice_tag: voice_tag.clone(),
-                    });
+            Self::collect_strings(&script.body, &mut seen, &mut entries);
+        }
+
+        let mut existing: serde_json::Map<String, serde_json::Value> = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read translation file: {:?}", path))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Translation file is not a JSON object: {:?}", path))?
+        } else {
+            serde_json::Map::new()
+        };
+
+        let mut report = ExtractReport::default();
+        let mut table = serde_json::Map::new();
+        for (key, source) in entries {
+            let mut entry = i18n::TlEntry { source, ..Default::default() };
+            match existing.remove(&key) {
+                Some(old) => entry.text = i18n::TlEntry::from_value(old).text,
+                None => {
+                    entry.status = Some("new".into());
+                    report.new += 1;
                 }
             }
+            table.insert(key, serde_json::to_value(entry)?);
+            report.total += 1;
+        }
+        for (key, old) in existing {
+            let mut entry = i18n::TlEntry::from_value(old);
+            entry.status = Some("obsolete".into());
+            table.insert(key, serde_json::to_value(entry)?);
+            report.obsolete += 1;
+        }
+
+        let out = serde_json::to_string_pretty(&table)?;
+        std::fs::write(path, out)
+            .with_context(|| format!("Failed to write translation file: {:?}", path))?;
+        info!("Extracted {} strings to {:?} ({} new, {} obsolete)", report.total, path, report.new, report.obsolete);
+        Ok(report)
+    }
+
+    /// 把全部可翻译文本写成 `locales/<lang>.toml` 模板，返回条目数。
+    ///
+    /// key 为原文的稳定哈希 ([`i18n::text_key`])，相同原文只出现一次；
+    /// 每条是一个 `[key]` 表，`source` 为原文，`text` 留空待填。
+    pub fn export_strings(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        for script in &self.programs {
+            Self::collect_strings(&script.body, &mut seen, &mut entries);
+        }
+
+        let mut hashes = HashSet::new();
+        let mut out = String::from("# 翻译模板：填写各条的 text，留空的条目使用原文\n");
+        for (_, source) in entries {
+            let key = i18n::text_key(&source);
+            if !hashes.insert(key.clone()) {
+                continue;
+            }
+            out.push_str(&format!(
+                "\n[{}]\nsource = {}\ntext = \"\"\n",
+                key,
+                toml::Value::String(source)
+            ));
+        }
+
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {:?}", dir))?;
+        }
+        std::fs::write(path, out)
+            .with_context(|| format!("Failed to write translation template: {:?}", path))?;
+        info!("Exported {} strings to {:?}", hashes.len(), path);
+        Ok(hashes.len())
+    }
+
+    fn collect_strings(stmts: &[Stmt], seen: &mut HashSet<String>, out: &mut Vec<(String, String)>) {
+        fn push(key: String, s: &str, seen: &mut HashSet<String>, out: &mut Vec<(String, String)>) {
+            if !s.is_empty() && seen.insert(key.clone()) {
+                out.push((key, s.to_string()));
+            }
+        }
+
+        for stmt in stmts {
+            match stmt {
+                Stmt::CharacterDef { name, .. } => push(i18n::text_key(name), name, seen, out),
+                Stmt::Dialogue { speaker, text, tl_id, .. } => {
+                    if let Some(alias) = &speaker.alias {
+                        push(i18n::text_key(alias), alias, seen, out);
+                    }
+                    let key = tl_id.clone().unwrap_or_else(|| i18n::text_key(text));
+                    push(key, text, seen, out);
+                },
+                Stmt::Narration { lines, tl_id, .. } => {
+                    for l in lines.iter().filter(|l| !l.is_empty()) {
+                        let key = tl_id.clone().unwrap_or_else(|| i18n::text_key(l));
+                        push(key, l, seen, out);
+                    }
+                },
+                Stmt::Choice { title, arms, id, .. } => {
+                    let base_id = id.as_deref().unwrap_or_default();
+                    if let Some(t) = title {
+                        push(format!("{}_title", base_id), t, seen, out);
+                    }
+                    for (idx, arm) in arms.iter().enumerate() {
+                        push(choice_arm_id(base_id, idx), &arm.text, seen, out);
+                    }
+                    for arm in arms {
+                        Self::collect_strings(&arm.body, seen, out);
+                    }
+                },
+                Stmt::Label { body, .. } => Self::collect_strings(body, seen, out),
+                Stmt::If { branches, else_branch, .. } => {
+                    for (_, body) in branches {
+                        Self::collect_strings(body, seen, out);
+                    }
+                    if let Some(body) = else_branch {
+                        Self::collect_strings(body, seen, out);
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    /// 静态可达性分析：从入口 label 出发，沿 jump / call、选项分支、条件分支
+    /// (不求值，所有分支都算可达) 与 Lua 中以字面量调用的 `lumina.jump("...")` 遍历。
+    /// 嵌套在其他 label 内的 label 执行时会被跳过，只能通过跳转到达。
+    /// Lua 中计算出的跳转目标无法静态确定，不会被跟踪。
+    pub fn reachability(&self, entries: &[&str]) -> ReachabilityReport {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<String> = entries.iter().map(|e| e.to_string()).collect();
+
+        while let Some(name) = queue.pop_front() {
+            if visited.contains(&name) {
+                continue;
+            }
+            let Some(body) = self.get_label(&name) else { continue };
+            visited.insert(name);
+            Self::collect_edges(&body, &mut queue);
+        }
+
+        let (reachable_labels, unreachable_labels): (Vec<_>, Vec<_>) = self.label_meta.iter()
+            .cloned()
+            .partition(|m| visited.contains(&m.id));
+        let labels: HashSet<&str> = self.label_meta.iter().map(|m| m.id.as_str()).collect();
+        let mut unreachable_blocks: Vec<String> = self.label_map.keys()
+            .filter(|id| !labels.contains(id.as_str()) && !visited.contains(*id))
+            .cloned()
+            .collect();
+        unreachable_blocks.sort();
+
+        ReachabilityReport { reachable_labels, unreachable_labels, unreachable_blocks }
+    }
+
+    fn collect_edges(stmts: &[Stmt], queue: &mut VecDeque<String>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Jump { target, .. } | Stmt::Call { target, .. } => queue.push_back(target.clone()),
+                Stmt::Choice { arms, id: Some(base_id), .. } => {
+                    queue.extend((0..arms.len()).map(|idx| choice_arm_id(base_id, idx)));
+                },
+                Stmt::If { branches, else_branch, id: Some(base_id), .. } => {
+                    queue.extend((0..branches.len()).map(|idx| format!("{}_b{}", base_id, idx)));
+                    if else_branch.is_some() {
+                        queue.push_back(format!("{}_else", base_id));
+                    }
+                },
+                Stmt::LuaBlock { code, .. } => queue.extend(lua_jump_targets(code)),
+                _ => {}
+            }
+        }
+    }
+
+    /// 从 `label` 开始静态展开剧本，生成供审阅的文本稿。
+    /// jump/call 的目标依次追加为新的段落 (每个 label 只展开一次，避免循环)，
+    /// 选项与条件分支全部展开并标注，插值占位符原样保留。
+    pub fn export_transcript(&self, label: &str) -> Result<String> {
+        if self.get_label(label).is_none() {
+            anyhow::bail!("Label '{}' not found", label);
+        }
+
+        let mut out = String::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([label.to_string()]);
+
+        while let Some(name) = queue.pop_front() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+            out.push_str(&format!("== {} ==\n", name));
+            match self.get_label(&name) {
+                Some(body) => Self::transcribe(&body, 0, &mut out, &mut queue),
+                None => out.push_str("(label not found)\n"),
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    fn transcribe(stmts: &[Stmt], depth: usize, out: &mut String, queue: &mut VecDeque<String>) {
+        let indent = "    ".repeat(depth);
+        for stmt in stmts {
+            match stmt {
+                Stmt::Dialogue { speaker, text, .. } => {
+                    let name = match &speaker.alias {
+                        Some(alias) => format!("{} ({})", speaker.name, alias),
+                        None => speaker.name.clone(),
+                    };
+                    out.push_str(&format!("{}{}: {}\n", indent, name, text));
+                },
+                Stmt::Narration { lines, .. } => {
+                    for l in lines {
+                        if l.is_empty() {
+                            out.push('\n');
+                        } else {
+                            out.push_str(&format!("{}{}\n", indent, l));
+                        }
+                    }
+                },
+                Stmt::Choice { title, arms, .. } => {
+                    match title {
+                        Some(t) => out.push_str(&format!("{}[choice] {}\n", indent, t)),
+                        None => out.push_str(&format!("{}[choice]\n", indent)),
+                    }
+                    for (idx, arm) in arms.iter().enumerate() {
+                        out.push_str(&format!("{}  <{}> {}\n", indent, idx + 1, arm.text));
+                        Self::transcribe(&arm.body, depth + 1, out, queue);
+                    }
+                    out.push_str(&format!("{}[end choice]\n", indent));
+                },
+                Stmt::If { branches, else_branch, .. } => {
+                    for (idx, (cond, body)) in branches.iter().enumerate() {
+                        let kw = if idx == 0 { "if" } else { "elif" };
+                        out.push_str(&format!("{}[{} {}]\n", indent, kw, cond));
+                        Self::transcribe(body, depth + 1, out, queue);
+                    }
+                    if let Some(body) = else_branch {
+                        out.push_str(&format!("{}[else]\n", indent));
+                        Self::transcribe(body, depth + 1, out, queue);
+                    }
+                    out.push_str(&format!("{}[end if]\n", indent));
+                },
+                Stmt::Jump { target, .. } => {
+                    out.push_str(&format!("{}-> jump {}\n", indent, target));
+                    queue.push_back(target.clone());
+                },
+                Stmt::Call { target, .. } => {
+                    out.push_str(&format!("{}-> call {}\n", indent, target));
+                    queue.push_back(target.clone());
+                },
+                Stmt::Label { id, .. } => {
+                    out.push_str(&format!("{}-> label {}\n", indent, id));
+                    queue.push_back(id.clone());
+                },
+                _ => {}
+            }
         }
-        chars
     }
 
     pub fn get_label(&self, name: &str) -> Option<Arc<[Stmt]>> {
         self.label_map.get(name).cloned()
     }
 
-    fn load_file(&mut self, path: &Path) -> Result<()> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read script: {:?}", path))?;
+    /// 所有 label 的标题与定义位置，按文件与行号排列
+    pub fn label_meta(&self) -> &[LabelMeta] {
+        &self.label_meta
+    }
+
+    /// label 的标题，没有写标题时为 id；选项、条件分支块取所在 label
+    pub fn label_title(&self, name: &str) -> Option<&str> {
+        let id = name.split('@').next().unwrap_or(name);
+        self.label_meta.iter().find(|m| m.id == id).map(|m| m.title.as_str())
+    }
+
+    /// 脚本中无法执行、会被跳过或报错的语句 (`Stmt::Error`) 数量
+    pub fn error_count(&self, file: &str) -> usize {
+        self.error_counts.get(file).copied().unwrap_or(0)
+    }
+
+    /// 代码块 (label 或选项、条件分支) 的内容哈希
+    pub fn block_hash(&self, name: &str) -> Option<u64> {
+        self.block_hashes.get(name).copied()
+    }
 
+    /// `path` 只用于日志、文件 key 与检查报告，内容由调用方读取
+    fn load_source(&mut self, path: &Path, content: String) -> Result<()> {
         // 1. 解析
         let tokens = Lexer::new(&content).run();
-        let parse_result = Parser::new(&tokens).parse();
-
-        let mut ast = match parse_result {
-            Ok(script) => script,
-            Err(errors) => {
-                // 打印错误日志，而不是崩溃
-                log::error!("Syntax Error in {:?}:", path);
-                for err in errors {
-                    log::error!("   Line {}: {}", err.line, err.msg);
-                }
+        let (mut ast, errors) = Parser::new(&tokens).parse_lenient();
+
+        if !errors.is_empty() {
+            // 打印错误日志，而不是崩溃
+            log::error!("Syntax Error in {:?}:", path);
+            for err in &errors {
+                log::error!("   Line {}: {}", err.line, err.msg);
+            }
+
+            // 调试时可以带着错误继续运行，出错的语句已被替换为 Stmt::Error
+            let debug_cfg: DebugConfig = lumina_shared::config::get("debug");
+            if !debug_cfg.allow_script_errors {
                 anyhow::bail!("Parse failed for {:?}", path);
             }
-        };
+            log::warn!("Continuing with {} broken statement(s) in {:?}", errors.len(), path);
+        }
+
+        // 解析器自行恢复的小问题 (未知参数、多余的 token 等) 不阻止加载，执行到时跳过并给出警告
+        let mut broken = ErrorCollector::default();
+        walk_stmts(&ast.body, &mut broken);
+        for (line, msg) in &broken.recovered {
+            log::warn!("{:?} line {}: {} (statement will be skipped)", path, line, msg);
+        }
+        if broken.count > 0 {
+            log::warn!("{} error statement(s) in {:?}", broken.count, path);
+        }
+        self.error_counts.insert(path.to_string_lossy().to_string(), broken.count);
 
         let file_key = path.file_stem().unwrap().to_string_lossy().to_string();
+        self.register_choice_ids(&ast.body, &file_key)?;
 
         // 2. 预处理 (原本在 Executor 里的逻辑)
         // 展开 Narration
-        self.pre_narration_lines(&mut ast.body);
+        Self::pre_narration_lines(&mut ast.body);
+        // 必须在建立索引之前，索引中的代码块是 AST 的副本
+        Self::assign_voice_lines(&mut ast.body, &file_key, &mut 0);
 
         // 生成唯一 ID 并建立索引
         let mut dummy_map = FxHashMap::default();
-        self.preprocess_block(&mut ast.body, &file_key, &mut dummy_map);
+        Self::preprocess_block(&mut ast.body, &file_key, &mut dummy_map);
 
         // 3. 将收集到的 Label 放入全局表
         // 注意：这里我们不仅放入了顶层 Label，也放入了 Choice/If 产生的临时 Block
         self.label_map.extend(dummy_map);
         self.build_top_level_index(&ast.body, &file_key)?;
+        Self::collect_label_meta(&ast.body, &path.to_string_lossy(), &mut self.label_meta);
 
         let script_arc = Arc::new(ast);
         self.programs.push(script_arc);
+        self.program_files.push(path.to_string_lossy().to_string());
 
         self.source_cache.insert(path.to_string_lossy().to_string(), content);
         Ok(())
     }
 
     fn build_top_level_index(&mut self, stmts: &[Stmt], file_key: &str) -> Result<()> {
-        for stmt in stmts {
-            if let Stmt::Label { id, body, .. } = stmt {
-                if let Some(existing_file) = self.label_sources.get(id) {
-                    if existing_file != file_key {
-                        // 如果发现重名，直接报错，阻止游戏启动！
-                        anyhow::bail!(
-                            "Label collision detected!\n  Label '{}' is defined in:\n    1. {}\n    2. {}",
-                            id, existing_file, file_key
-                        );
-                    }
+        let mut labels = LabelCollector::default();
+        walk_stmts(stmts, &mut labels);
+        for (id, body) in labels.0 {
+            if let Some(existing_file) = self.label_sources.get(&id) {
+                if existing_file != file_key {
+                    // 如果发现重名，直接报错，阻止游戏启动！
+                    anyhow::bail!(
+                        "Label collision detected!\n  Label '{}' is defined in:\n    1. {}\n    2. {}",
+                        id, existing_file, file_key
+                    );
                 }
-                // 记录来源
-                self.label_sources.insert(id.clone(), file_key.to_string());
-                // 插入 Map
-                self.label_map.insert(id.clone(), Arc::from(body.as_slice()));
-                // 递归
-                self.build_top_level_index(body, file_key)?;
             }
+            // 记录来源
+            self.label_sources.insert(id.clone(), file_key.to_string());
+            // 插入 Map
+            self.label_map.insert(id, body);
         }
         Ok(())
     }
 
-    fn pre_narration_lines(&self, body: &mut Vec<Stmt>) {
-        let mut new_body = Vec::new();
-        for stmt in body.drain(..) {
+    fn collect_label_meta(stmts: &[Stmt], file: &str, out: &mut Vec<LabelMeta>) {
+        for stmt in stmts {
+            if let Stmt::Label { span, id, title, body } = stmt {
+                out.push(LabelMeta {
+                    id: id.clone(),
+                    title: title.clone().unwrap_or_else(|| id.clone()),
+                    file: file.to_string(),
+                    line: span.line,
+                });
+                Self::collect_label_meta(body, file, out);
+            }
+        }
+    }
+
+    /// 登记脚本中写明的选项 id (`choice ... as <id>`)，整个项目内不允许重复
+    fn register_choice_ids(&mut self, stmts: &[Stmt], file_key: &str) -> Result<()> {
+        for stmt in stmts {
             match stmt {
-                Stmt::Narration {span, lines} => {
-                    for l in lines {
-                        new_body.push(Stmt::Narration {span, lines: vec![l]});
+                Stmt::Choice { id, arms, span, .. } => {
+                    if let Some(id) = id {
+                        if let Some(existing_file) = self.choice_sources.get(id) {
+                            anyhow::bail!(
+                                "Choice id collision detected!\n  Choice '{}' is defined in:\n    1. {}\n    2. {} (line {})",
+                                id, existing_file, file_key, span.line
+                            );
+                        }
+                        self.choice_sources.insert(id.clone(), file_key.to_string());
+                    }
+                    for arm in arms {
+                        self.register_choice_ids(&arm.body, file_key)?;
                     }
                 },
-                Stmt::Label { span, id, mut body } => {
-                    self.pre_narration_lines(&mut body);
-                    new_body.push(Stmt::Label { span, id, body });
+                Stmt::Label { body, .. } => self.register_choice_ids(body, file_key)?,
+                Stmt::If { branches, else_branch, .. } => {
+                    for (_, body) in branches {
+                        self.register_choice_ids(body, file_key)?;
+                    }
+                    if let Some(body) = else_branch {
+                        self.register_choice_ids(body, file_key)?;
+                    }
+                },
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn pre_narration_lines(body: &mut Vec<Stmt>) {
+        NarrationSplitter.split(body);
+    }
+
+    /// 按 label 给对话编号 (`<label>_<n>`，从 1 开始)，选项与条件分支内的对话继续沿用所在 label 的计数。
+    /// 只数对话，插入旁白不会改变其后对话的编号
+    fn assign_voice_lines(stmts: &mut [Stmt], label: &str, counter: &mut usize) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Label { id, body, .. } => Self::assign_voice_lines(body, id, &mut 0),
+                Stmt::Dialogue { voice_line, .. } => {
+                    *counter += 1;
+                    *voice_line = Some(format!("{}_{}", label, counter));
                 },
-                Stmt::Choice { span, title, mut arms, id } => {
-                    for arm in &mut arms {
-                        self.pre_narration_lines(&mut arm.body);
+                Stmt::Choice { arms, .. } => {
+                    for arm in arms {
+                        Self::assign_voice_lines(&mut arm.body, label, counter);
                     }
-                    new_body.push(Stmt::Choice { span, title, arms, id });
-                }
-                Stmt::If { span, mut branches, mut else_branch, id } => {
-                    for (_, body) in &mut branches {
-                        self.pre_narration_lines(body);
+                },
+                Stmt::If { branches, else_branch, .. } => {
+                    for (_, body) in branches {
+                        Self::assign_voice_lines(body, label, counter);
                     }
-                    if let Some(body) = &mut else_branch {
-                        self.pre_narration_lines(body);
+                    if let Some(body) = else_branch {
+                        Self::assign_voice_lines(body, label, counter);
                     }
-                    new_body.push(Stmt::If { span, branches, else_branch, id });
-                }
-                _ => new_body.push(stmt),
+                },
+                _ => {}
             }
         }
-        *body = new_body;
     }
 
     fn preprocess_block(
-        &self,
         stmts: &mut [Stmt],
         scope_name: &str,
         map: &mut FxHashMap<String, Arc<[Stmt]>>
     ) {
-        let mut counters: HashMap<&str, usize> = HashMap::new();
+        let mut pass = BlockPreprocessor {
+            scope: scope_name.to_string(),
+            counters: HashMap::new(),
+            parents: Vec::new(),
+            map,
+        };
+        walk_stmts_mut(stmts, &mut pass);
+    }
+}
 
-        for stmt in stmts {
-            match stmt {
-                Stmt::Label {id, body, ..} => {
-                    self.preprocess_block(body, id, map);
-                    // Label 本身会在 build_top_level_index 里被收集，这里主要是为了递归处理其内部的 If/Choice
-                },
-                Stmt::If { branches, else_branch, id, .. } => {
-                    let count = counters.entry("if").or_insert(0);
-                    let base_id = format!("{}@if_{}", scope_name, count);
-                    *count += 1;
-                    *id = Some(base_id.clone());
+/// 收集所有 `character` 定义
+#[derive(Default)]
+struct CharacterCollector(HashMap<String, Character>);
 
-                    for (idx, (_, body)) in branches.iter_mut().enumerate() {
-                        let branch_id = format!("{}_b{}", base_id, idx);
-                        self.preprocess_block(body, &branch_id, map);
-                        map.insert(branch_id.clone(), Arc::from(body.as_slice()));
-                    }
+impl Visitor for CharacterCollector {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if let Stmt::CharacterDef { id, name, image_tag, voice_tag, color, voice_volume, voice_pitch, .. } = stmt {
+            self.0.insert(id.clone(), Character {
+                id: id.clone(),
+                name: name.clone(),
+                image_tag: image_tag.clone(),
+                voice_tag: voice_tag.clone(),
+                color: color.clone(),
+                voice_volume: *voice_volume,
+                voice_pitch: *voice_pitch,
+            });
+        }
+        walk_stmt(self, stmt);
+    }
+}
 
-                    if let Some(body) = else_branch {
-                        let branch_id = format!("{}_else", base_id);
-                        self.preprocess_block(body, &branch_id, map);
-                        map.insert(branch_id.clone(), Arc::from(body.as_slice()));
-                    }
-                },
-                Stmt::Choice { arms, id, .. } => {
-                    let count = counters.entry("choice").or_insert(0);
-                    let base_id = format!("{}@choice_{}", scope_name, count);
-                    *count += 1;
-                    *id = Some(base_id.clone());
+/// 统计 `Stmt::Error`，并记下解析器已恢复的那些 (行号, 信息)
+#[derive(Default)]
+struct ErrorCollector {
+    count: usize,
+    recovered: Vec<(usize, String)>,
+}
 
-                    for (idx, arm) in arms.iter_mut().enumerate() {
-                        let arm_id = format!("{}_opt{}", base_id, idx);
-                        self.preprocess_block(&mut arm.body, &arm_id, map);
-                        map.insert(arm_id.clone(), Arc::from(arm.body.as_slice()));
-                    }
-                },
-                _ => {}
+impl Visitor for ErrorCollector {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if let Stmt::Error { span, msg, recovered } = stmt {
+            self.count += 1;
+            if *recovered {
+                self.recovered.push((span.line, msg.clone()));
+            }
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+/// 按出现顺序收集所有 label (含嵌套的) 及其代码块
+#[derive(Default)]
+struct LabelCollector(Vec<(String, Arc<[Stmt]>)>);
+
+impl Visitor for LabelCollector {
+    fn visit_label(&mut self, id: &str, body: &[Stmt]) {
+        self.0.push((id.to_string(), Arc::from(body)));
+        walk_stmts(body, self);
+    }
+}
+
+/// 把多行旁白按行展开成多条语句
+struct NarrationSplitter;
+
+impl NarrationSplitter {
+    fn split(&mut self, body: &mut Vec<Stmt>) {
+        let mut new_body = Vec::with_capacity(body.len());
+        for stmt in body.drain(..) {
+            let Stmt::Narration { span, lines, line_spans, tl_id } = stmt else {
+                new_body.push(stmt);
+                continue;
+            };
+            // 空行不单独等待点击，作为段落分隔并入下一行 (以空字符串开头)
+            let mut pending = Vec::new();
+            let mut pending_spans = Vec::new();
+            for (i, l) in lines.into_iter().enumerate() {
+                let line_span = line_spans.get(i).copied().unwrap_or(span);
+                pending_spans.push(line_span);
+                let blank = l.is_empty();
+                pending.push(l);
+                if blank {
+                    continue;
+                }
+                new_body.push(Stmt::Narration {
+                    span: line_span,
+                    lines: std::mem::take(&mut pending),
+                    line_spans: std::mem::take(&mut pending_spans),
+                    tl_id: tl_id.clone(),
+                });
             }
         }
+        *body = new_body;
+        walk_stmts_mut(body, self);
+    }
+}
+
+impl VisitorMut for NarrationSplitter {
+    fn visit_block_mut(&mut self, _block: Block<'_>, stmts: &mut Vec<Stmt>) {
+        self.split(stmts);
     }
-}
\ No newline at end of file
+}
+
+/// 给 If/Choice 生成 id、给对话与旁白生成翻译 id，并把选项与条件分支的代码块登记到 `map`
+struct BlockPreprocessor<'m> {
+    scope: String,
+    counters: HashMap<&'static str, usize>,
+    /// 正在遍历的 If/Choice 的 id，分支块的 id 由它派生
+    parents: Vec<String>,
+    map: &'m mut FxHashMap<String, Arc<[Stmt]>>,
+}
+
+impl VisitorMut for BlockPreprocessor<'_> {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        let parent = match stmt {
+            Stmt::If { id, .. } => {
+                let count = self.counters.entry("if").or_insert(0);
+                let base_id = format!("{}@if_{}", self.scope, count);
+                *count += 1;
+                *id = Some(base_id.clone());
+                Some(base_id)
+            },
+            Stmt::Choice { id, .. } => {
+                // 显式 id 的选项也占一个序号，给某个选项加上 id 不会改变其余选项生成的 id
+                let count = self.counters.entry("choice").or_insert(0);
+                let base_id = id.get_or_insert_with(|| format!("{}@choice_{}", self.scope, count)).clone();
+                *count += 1;
+                Some(base_id)
+            },
+            Stmt::Dialogue { tl_id, .. } | Stmt::Narration { tl_id, .. } => {
+                // 翻译表的 key：作用域内按出现顺序编号 (旁白已按行展开)
+                let count = self.counters.entry("line").or_insert(0);
+                *tl_id = Some(format!("{}@line_{}", self.scope, count));
+                *count += 1;
+                None
+            },
+            _ => None,
+        };
+        match parent {
+            Some(parent) => {
+                self.parents.push(parent);
+                walk_stmt_mut(self, stmt);
+                self.parents.pop();
+            },
+            None => walk_stmt_mut(self, stmt),
+        }
+    }
+
+    fn visit_block_mut(&mut self, block: Block<'_>, stmts: &mut Vec<Stmt>) {
+        let parent = self.parents.last().map(String::as_str).unwrap_or_default();
+        let scope = match block {
+            // Label 本身会在 build_top_level_index 里被收集，这里只处理其内部的语句
+            Block::Label(id) => id.to_string(),
+            Block::Arm(idx) => choice_arm_id(parent, idx),
+            Block::Branch(idx) => format!("{}_b{}", parent, idx),
+            Block::Else => format!("{}_else", parent),
+        };
+        let outer_scope = std::mem::replace(&mut self.scope, scope);
+        let outer_counters = std::mem::take(&mut self.counters);
+        walk_stmts_mut(stmts, self);
+        let scope = std::mem::replace(&mut self.scope, outer_scope);
+        self.counters = outer_counters;
+        if !matches!(block, Block::Label(_)) {
+            self.map.insert(scope, Arc::from(stmts.as_slice()));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! 对话富文本标记
+//!
+//! 目前支持注音 (ruby / furigana)：`{rt=かんじ}漢字{/rt}`。
+//! 格式不完整的标记按普通文本处理。
+
+const RUBY_OPEN: &str = "{rt=";
+const RUBY_CLOSE: &str = "{/rt}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextRun {
+    Plain(String),
+    Ruby { base: String, annotation: String },
+}
+
+impl TextRun {
+    /// 还原为标记文本
+    pub fn to_markup(&self) -> String {
+        match self {
+            TextRun::Plain(s) => s.clone(),
+            TextRun::Ruby { base, annotation } => format!("{}{}}}{}{}", RUBY_OPEN, annotation, base, RUBY_CLOSE),
+        }
+    }
+}
+
+/// `{...}` 内的内容是否为标记 (而非插值表达式)
+pub fn is_tag(inner: &str) -> bool {
+    inner.starts_with(&RUBY_OPEN[1..]) || inner == &RUBY_CLOSE[1..RUBY_CLOSE.len() - 1]
+}
+
+/// 解析标记文本为 run 序列，相邻的普通文本会合并
+pub fn parse(text: &str) -> Vec<TextRun> {
+    let mut runs = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(RUBY_OPEN) {
+        plain.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        match parse_ruby(tail) {
+            Some((run, consumed)) => {
+                if !plain.is_empty() {
+                    runs.push(TextRun::Plain(std::mem::take(&mut plain)));
+                }
+                runs.push(run);
+                rest = &tail[consumed..];
+            }
+            None => {
+                plain.push_str(RUBY_OPEN);
+                rest = &tail[RUBY_OPEN.len()..];
+            }
+        }
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        runs.push(TextRun::Plain(plain));
+    }
+    runs
+}
+
+/// 解析以 `{rt=` 开头的片段，返回 run 与消耗的字节数
+fn parse_ruby(s: &str) -> Option<(TextRun, usize)> {
+    let body = &s[RUBY_OPEN.len()..];
+    let ann_end = body.find('}')?;
+    let annotation = &body[..ann_end];
+
+    let after = &body[ann_end + 1..];
+    let base_end = after.find(RUBY_CLOSE)?;
+    let base = &after[..base_end];
+    if base.is_empty() || base.contains(RUBY_OPEN) {
+        return None;
+    }
+
+    let consumed = RUBY_OPEN.len() + ann_end + 1 + base_end + RUBY_CLOSE.len();
+    Some((TextRun::Ruby { base: base.to_string(), annotation: annotation.to_string() }, consumed))
+}
+
+/// 去除标记得到纯文本，注音写在括号里 (TUI / 历史记录用)
+pub fn to_plain(text: &str) -> String {
+    parse(text).iter()
+        .map(|run| match run {
+            TextRun::Plain(s) => s.clone(),
+            TextRun::Ruby { base, annotation } if annotation.is_empty() => base.clone(),
+            TextRun::Ruby { base, annotation } => format!("{}({})", base, annotation),
+        })
+        .collect()
+}
+
+/// 打字机逐个显示的单位：普通字符各为一个单位，注音 (底字 + 注音) 整体为一个单位。
+/// 每个单位以标记文本表示，前 N 个拼接即为显示到第 N 步的文本
+pub fn reveal_units(text: &str) -> Vec<String> {
+    parse(text).iter()
+        .flat_map(|run| match run {
+            TextRun::Plain(s) => s.chars().map(String::from).collect::<Vec<_>>(),
+            ruby => vec![ruby.to_markup()],
+        })
+        .collect()
+}
@@ -1,24 +1,186 @@
-use std::sync::Arc;
-use crate::{storager, Ctx, Executor};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::{storager, Ctx, Executor, OutputEvent};
+use crate::config::{AudioConfig, SystemConfig};
 use crate::event::InputEvent;
 use crate::manager::ScriptManager;
+use crate::runtime::assets::Audio;
+use crate::storager::types::LoadOutcome;
+use crate::tts::{SelfVoicing, TtsBackend};
+
+/// 单帧内最多推进的语句数，防止脚本死循环卡住渲染
+pub const MAX_STEPS_PER_FRAME: usize = 100;
+
+/// 最近一次 step 后的执行位置，崩溃报告从这里读取
+static LAST_STATE: Mutex<Option<DebugSnapshot>> = Mutex::new(None);
+
+/// 执行位置快照：调用栈上每一帧的 (label, pc)，栈底在前
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugSnapshot {
+    pub frames: Vec<(String, usize)>,
+}
+
+impl fmt::Display for DebugSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.frames.last() {
+            Some((label, pc)) => {
+                write!(f, "label '{}' pc {}", label, pc)?;
+                let stack: Vec<String> = self.frames.iter().map(|(l, pc)| format!("{}:{}", l, pc)).collect();
+                write!(f, " (stack: {})", stack.join(" > "))
+            }
+            None => f.write_str("<script finished>"),
+        }
+    }
+}
+
+/// 自动存档的触发条件：进入新 label 或显示了足够多的对话，并且距上次自动存档足够久
+struct Autosave {
+    slots: usize,
+    lines: usize,
+    interval: Duration,
+    /// 最近一次 step 时所在的 label (不含选项分支后缀)
+    label: Option<String>,
+    /// 进入了新 label，等待下一个等待点存档
+    due: bool,
+    /// 上次自动存档时的对话总数
+    saved_lines: usize,
+    last_write: Instant,
+}
+
+impl Autosave {
+    fn from_config() -> Self {
+        let cfg: SystemConfig = lumina_shared::config::get("system");
+        Self {
+            slots: cfg.autosave_slots,
+            lines: cfg.autosave_lines,
+            interval: Duration::from_secs_f32(cfg.autosave_interval.max(0.0)),
+            label: None,
+            due: false,
+            saved_lines: 0,
+            last_write: Instant::now(),
+        }
+    }
+
+    fn observe(&mut self, label: Option<&str>) {
+        let label = label.map(|l| l.split('@').next().unwrap_or(l));
+        if self.label.as_deref() != label {
+            // 开局或读档后第一次看到的 label 不算进入新 label
+            self.due |= self.label.is_some();
+            self.label = label.map(str::to_string);
+        }
+    }
+
+    fn should_save(&self, lines: usize) -> bool {
+        self.slots > 0
+            && (self.due || (self.lines > 0 && lines >= self.saved_lines + self.lines))
+            && self.last_write.elapsed() >= self.interval
+    }
+
+    /// 刚存档或读档，重新开始计数
+    fn reset(&mut self, lines: usize) {
+        self.due = false;
+        self.saved_lines = lines;
+        self.last_write = Instant::now();
+    }
+}
 
 pub struct ExecutorHandle{
     exe: Executor,
     manager: Arc<ScriptManager>,
+    auto: bool,
+    skip: bool,
+    /// 脚本已改动、等待玩家决定是否重新开始的读档：(存档的 ctx, executor, label)
+    pending_load: Option<(Ctx, Executor, String)>,
+    /// 开启自助朗读时的 TTS
+    voicing: Option<SelfVoicing>,
+    autosave: Autosave,
 }
 
 impl ExecutorHandle {
-    pub fn new(ctx: &mut Ctx, manager: Arc<ScriptManager>) -> Self {
+    /// 从 `entry` label 开始新游戏 (通常为 `SystemConfig::start_label`)
+    pub fn new(ctx: &mut Ctx, manager: Arc<ScriptManager>, entry: &str) -> Self {
         let mut exe = Executor::new(manager.clone());
         exe.load_global_data();
-        exe.start(ctx, "init");
-        Self { exe, manager }
+        exe.start(ctx, entry);
+        #[cfg(feature = "preview")]
+        super::preview::ensure_started();
+        Self {
+            exe,
+            manager,
+            auto: false,
+            skip: false,
+            pending_load: None,
+            voicing: SelfVoicing::from_config(),
+            autosave: Autosave::from_config(),
+        }
     }
 
+    /// 自动模式：整行显示完后停留一段时间自动继续
+    #[inline]
+    pub fn is_auto(&self) -> bool { self.auto }
+
+    /// 快进模式：不等待输入直接继续，遇到选项停下
     #[inline]
-    pub fn step(&mut self, ctx: &mut Ctx) -> bool { self.exe.step(ctx) }
+    pub fn is_skip(&self) -> bool { self.skip }
+
+    pub fn step(&mut self, ctx: &mut Ctx) -> bool {
+        #[cfg(feature = "preview")]
+        self.poll_preview(ctx);
+        let waiting = self.exe.step(ctx);
+        self.record_state();
+        waiting
+    }
+
+    /// 循环 step 直到等待输入或达到 `max_steps`，返回是否在等待输入。
+    /// 停在等待点时按需自动存档
+    pub fn step_until_wait(&mut self, ctx: &mut Ctx, max_steps: usize) -> bool {
+        for _ in 0..max_steps {
+            if self.step(ctx) {
+                self.maybe_autosave(ctx);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 最近一次 step 后的执行位置，可在任意线程 (包括 panic hook) 中调用；
+    /// 状态正被其他线程写入时返回 None 而不是阻塞
+    pub fn debug_snapshot() -> Option<DebugSnapshot> {
+        match LAST_STATE.try_lock() {
+            Ok(state) => state.clone(),
+            Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner().clone(),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        }
+    }
+
+    fn record_state(&mut self) {
+        let frames = self.exe.positions();
+        self.autosave.observe(frames.last().map(|(label, _)| label.as_str()));
+        let mut state = LAST_STATE.lock().unwrap_or_else(|e| e.into_inner());
+        if state.as_ref().is_none_or(|s| s.frames != frames) {
+            *state = Some(DebugSnapshot { frames });
+        }
+    }
     
+    /// 替换 TTS 后端并开启自助朗读
+    pub fn set_tts_backend(&mut self, backend: Box<dyn TtsBackend>, skip_voiced_lines: bool) {
+        self.voicing = Some(SelfVoicing::new(backend, skip_voiced_lines));
+    }
+
+    /// 取出本帧的输出事件，快进时合并被覆盖的文本、音频与立绘事件；开启朗读时朗读其中的文本
+    pub fn drain(&mut self, ctx: &mut Ctx) -> Vec<OutputEvent> {
+        let events = if self.skip { ctx.drain_coalesced() } else { ctx.drain() };
+        if let Some(voicing) = &mut self.voicing {
+            if self.skip {
+                voicing.cancel();
+            } else {
+                voicing.observe(&events);
+            }
+        }
+        events
+    }
+
     #[inline]
     pub fn tick(&mut self, dt: f32) { self.exe.tick(dt); }
 
@@ -27,33 +189,266 @@ impl ExecutorHandle {
         match ev {
             InputEvent::SaveRequest {slot} => {
                 log::info!("Try to save request slot: {}", slot);
-
-                self.exe.sync_vars_to_ctx(ctx);
-
-                storager::save(&format!("save{}.bin", slot), ctx.clone(), self.exe.clone())
-                    .unwrap_or_else(|e| log::error!("save failed: {}", e));
-                self.exe.feed(InputEvent::Continue);
-                log::info!("Save finished");
+                self.save_file(ctx, &storager::slot_filename(slot));
             }
             InputEvent::LoadRequest { slot } => {
                 log::info!("Load request slot: {}", slot);
-                match storager::load(&format!("save{}.bin", slot), self.manager.clone()) {
-                    Ok((new_ctx, new_exe)) => {
-                        *ctx = new_ctx;
-                        ctx.dialogue_history.pop();
+                self.load_file(ctx, &storager::slot_filename(slot));
+            }
+            InputEvent::QuickSave => self.quick_save(ctx),
+            InputEvent::QuickLoad => self.quick_load(ctx),
+            InputEvent::ResolveLoad { restart } => {
+                let Some((new_ctx, mut new_exe, label)) = self.pending_load.take() else {
+                    log::warn!("ResolveLoad ignored: no pending load");
+                    return;
+                };
+                if !restart {
+                    log::info!("Load aborted");
+                    return;
+                }
+                match new_exe.restart_label(&label) {
+                    Ok(()) => self.apply_load(ctx, new_ctx, new_exe),
+                    Err(e) => log::error!("Load failed: {}", e),
+                }
+            }
+            InputEvent::ExportHistory { filename } => {
+                match storager::export_history(&filename, &ctx.dialogue_history) {
+                    Ok(path) => log::info!("Dialogue history exported to {:?}", path),
+                    Err(e) => log::error!("Export history failed: {}", e),
+                }
+            }
+            // 自动与快进互斥，开启一个时关闭另一个
+            InputEvent::SetAuto { enabled } => {
+                self.auto = enabled;
+                if enabled { self.skip = false; }
+            }
+            InputEvent::SetSkip { enabled } => {
+                self.skip = enabled;
+                if enabled { self.auto = false; }
+            }
+            InputEvent::DebugJump { .. } | InputEvent::DebugGoto { .. }
+            | InputEvent::DebugEval { .. } | InputEvent::DebugReload | InputEvent::DebugChapters => {
+                let text = self.debug_command(ev).unwrap_or_else(|e| format!("error: {}", e));
+                ctx.push(OutputEvent::DebugOutput { text });
+            }
+            _ => {
+                // 推进时打断朗读
+                if matches!(ev, InputEvent::Continue | InputEvent::ChoiceMade { .. }) {
+                    if let Some(voicing) = &mut self.voicing {
+                        voicing.cancel();
+                    }
+                }
+                self.exe.feed(ev)
+            }
+        }
+    }
+
+    /// 执行调试命令，返回要显示的文本
+    fn debug_command(&mut self, ev: InputEvent) -> anyhow::Result<String> {
+        match ev {
+            InputEvent::DebugJump { label } => {
+                self.exe.debug_jump(&label)?;
+                Ok(format!("jump -> {}", label))
+            }
+            InputEvent::DebugGoto { label, pc } => {
+                self.exe.debug_goto(&label, pc)?;
+                Ok(format!("goto -> {} @ {}", label, pc))
+            }
+            InputEvent::DebugEval { code } => Ok(self.exe.try_debug_eval(&code)?),
+            InputEvent::DebugReload => {
+                let sys_cfg: crate::config::SystemConfig = lumina_shared::config::get("system");
+                let mut manager = ScriptManager::new();
+                manager.load_project(&sys_cfg.script_path)?;
+                self.manager = Arc::new(manager);
+                self.exe.reload(self.manager.clone())?;
+                self.record_state();
+                Ok(format!("reloaded {}", sys_cfg.script_path))
+            }
+            InputEvent::DebugChapters => Ok(self.manager.label_meta().iter()
+                .map(|m| format!("{} — {} ({}:{})", m.id, m.title, m.file, m.line))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            other => anyhow::bail!("not a debug command: {:?}", other),
+        }
+    }
 
-                        new_exe.sync_vars_from_ctx(ctx);
+    /// 执行编辑器预览发来的命令并回复执行后的位置，结果同时显示在调试输出中
+    #[cfg(feature = "preview")]
+    fn poll_preview(&mut self, ctx: &mut Ctx) {
+        use super::preview::{self, PreviewResponse};
 
-                        new_exe.load_global_data();
-                        self.exe = new_exe;
-                        log::info!("Load finished");
-                    }
-                    Err(e) => {
-                        log::error!("Load failed: {:?}", e);
-                    }
+        while let Some(req) = preview::try_recv() {
+            let result = self.debug_command(req.command.into_input_event());
+            let (label, pc) = self.exe.positions().pop().unzip();
+            let response = match result {
+                Ok(text) => {
+                    ctx.push(OutputEvent::DebugOutput { text: text.clone() });
+                    PreviewResponse { ok: true, label, pc, output: Some(text), error: None }
+                }
+                Err(e) => {
+                    ctx.push(OutputEvent::DebugOutput { text: format!("error: {}", e) });
+                    PreviewResponse::error(e.to_string(), label.zip(pc))
                 }
+            };
+            let _ = req.reply.send(response);
+        }
+    }
+
+    /// 主菜单“继续游戏”：读取存档，脚本已改动时不再询问，直接从该 label 开头继续
+    pub fn continue_from(&mut self, ctx: &mut Ctx, filename: &str) -> bool {
+        if !self.load_file(ctx, filename) {
+            return false;
+        }
+        if self.pending_load.is_some() {
+            self.feed(ctx, InputEvent::ResolveLoad { restart: true });
+        }
+        true
+    }
+
+    /// 存到快速存档专用文件，与编号槽位互不影响
+    pub fn quick_save(&mut self, ctx: &mut Ctx) {
+        let ok = self.save_file(ctx, storager::QUICK_SAVE_FILE);
+        ctx.push(OutputEvent::QuickSaveDone { load: false, ok });
+    }
+
+    /// 读取快速存档；脚本已改动时与普通读档一样等待 `ResolveLoad`
+    pub fn quick_load(&mut self, ctx: &mut Ctx) {
+        let ok = storager::quick_save_exists() && self.load_file(ctx, storager::QUICK_SAVE_FILE);
+        if !ok {
+            log::warn!("No usable quick save");
+        }
+        ctx.push(OutputEvent::QuickSaveDone { load: true, ok });
+    }
+
+    /// 等待读档确认时不存，避免覆盖玩家想读的进度
+    fn maybe_autosave(&mut self, ctx: &mut Ctx) {
+        let lines = ctx.dialogue_history.total();
+        if self.pending_load.is_some() || !self.autosave.should_save(lines) {
+            return;
+        }
+        let filename = storager::autosave_filename(storager::next_autosave_slot(self.autosave.slots));
+        self.save_file(ctx, &filename);
+        // 失败时同样等待下一个间隔，不在每个等待点重试写盘
+        self.autosave.reset(lines);
+    }
+
+    /// 存档不推进剧情，读档时重发正在等待的提示
+    fn save_file(&mut self, ctx: &mut Ctx, filename: &str) -> bool {
+        self.exe.sync_vars_to_ctx(ctx);
+        match storager::save(filename, ctx.clone(), self.exe.clone()) {
+            Ok(()) => {
+                log::info!("Save finished: {}", filename);
+                true
+            }
+            Err(e) => {
+                log::error!("save failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 返回存档是否读取成功 (包括等待确认的情况)
+    fn load_file(&mut self, ctx: &mut Ctx, filename: &str) -> bool {
+        match storager::load(filename, self.manager.clone()) {
+            Ok((new_ctx, new_exe, LoadOutcome::Restored)) => self.apply_load(ctx, new_ctx, new_exe),
+            Ok((new_ctx, new_exe, LoadOutcome::LabelChanged { label })) => {
+                log::warn!("Save {} was made in label '{}', which has changed", filename, label);
+                ctx.push(OutputEvent::SaveMismatch { label: label.clone() });
+                self.pending_load = Some((new_ctx, new_exe, label));
+            }
+            Err(e) => {
+                log::error!("Load failed: {:?}", e);
+                return false;
             }
-            _ => self.exe.feed(ev),
         }
+        true
     }
-}
\ No newline at end of file
+
+    fn apply_load(&mut self, ctx: &mut Ctx, new_ctx: Ctx, new_exe: Executor) {
+        // 读档前在响的声道：脚本记录的加上渲染层报告的 (如 Lua 直接播放的)
+        let active: Vec<String> = ctx.audios.iter()
+            .filter(|(_, a)| a.is_some())
+            .map(|(ch, _)| ch.clone())
+            .chain(ctx.audio_state.iter().filter(|(_, s)| s.playing).map(|(ch, _)| ch.clone()))
+            .collect();
+        *ctx = new_ctx;
+
+        new_exe.sync_vars_from_ctx(ctx);
+
+        new_exe.load_global_data();
+        self.exe = new_exe;
+        self.autosave.label = None;
+        self.autosave.reset(ctx.dialogue_history.total());
+        restore_scene(ctx);
+        restore_audio(ctx, &active);
+        ctx.push(OutputEvent::SetPresentation { mode: ctx.presentation_mode });
+        log::info!("Load finished");
+    }
+}
+
+/// 读档后根据存档中的 `ctx.audios` 重新下发音频事件，
+/// 让渲染层恢复各通道的播放状态（带短淡入），空通道则停止；
+/// `active` 中存档没有记录的声道同样停止，避免读档前的声音继续播放
+fn restore_audio(ctx: &mut Ctx, active: &[String]) {
+    let audio_cfg: AudioConfig = lumina_shared::config::get("audio");
+
+    let mut stale: Vec<String> = active.iter()
+        .filter(|ch| !ctx.audios.contains_key(*ch))
+        .cloned()
+        .collect();
+    stale.sort();
+    stale.dedup();
+    for channel in stale {
+        log::info!("Stop audio [{}]: not in save", channel);
+        ctx.push(OutputEvent::StopAudio { channel, fade_out: 0.0 });
+    }
+
+    let mut channels: Vec<(String, Option<Audio>)> = ctx.audios
+        .iter()
+        .map(|(ch, a)| (ch.clone(), a.clone()))
+        .collect();
+    channels.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (channel, audio) in channels {
+        match audio {
+            Some(a) => {
+                log::info!("Restore audio [{}]: {}", channel, a.path);
+                // 语音可能是自动查找的，文件不一定存在
+                let speculative = channel == "voice";
+                ctx.push(OutputEvent::PlayAudio {
+                    channel,
+                    path: a.path,
+                    fade_in: audio_cfg.fade_in_sec,
+                    volume: a.volume,
+                    looping: a.looping,
+                    loop_region: a.loop_region,
+                    speculative,
+                    pitch: a.pitch,
+                    // 读档时前奏早已播完，直接从循环段开始
+                    intro: None,
+                });
+            }
+            None => ctx.push(OutputEvent::StopAudio { channel, fade_out: 0.0 }),
+        }
+    }
+}
+
+/// 读档后按存档中的图层记录重建画面：背景 (zindex 0) 与立绘都不带转场直接出现
+fn restore_scene(ctx: &mut Ctx) {
+    let Some(sprites) = ctx.layer_record.layer.get("master").cloned() else { return };
+
+    let background = sprites.iter().find(|s| s.zindex == 0).map(|s| s.full_asset_name());
+    ctx.push(OutputEvent::NewScene { background, transition: String::new(), duration: None });
+
+    for sprite in sprites.into_iter().filter(|s| s.zindex > 0) {
+        ctx.push(OutputEvent::NewSprite {
+            texture: sprite.target.clone(),
+            target: sprite.target,
+            pos_str: sprite.position,
+            transition: None,
+            duration: None,
+            attrs: sprite.attrs,
+            defer_visual: false,
+        });
+    }
+}
@@ -0,0 +1,231 @@
+//! 无界面渲染器：记录全部输出事件，按预设的输入推进脚本，供集成测试与基准测试使用
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use anyhow::{anyhow, bail};
+use crate::{Ctx, OutputEvent, ScriptManager};
+use crate::event::InputEvent;
+use crate::renderer::Renderer;
+use crate::renderer::driver::ExecutorHandle;
+
+/// `run_event_loop` 使用的步数上限
+pub const DEFAULT_MAX_STEPS: usize = 100_000;
+
+/// 带序号的输出事件，序号在整个运行过程中递增
+#[derive(Debug, Clone)]
+pub struct RecordedEvent {
+    pub seq: usize,
+    pub event: OutputEvent,
+}
+
+type Responder = Box<dyn FnMut(&OutputEvent) -> Option<InputEvent>>;
+
+/// 每个等待点按以下顺序决定输入：
+/// 1. 预设输入队列 (`with_inputs`) 的下一项；
+/// 2. 回调 (`with_responder`) 的返回值；
+/// 3. 选项取 `with_choices` 队列的下一项，文本与过场动画直接 `Continue`。
+///
+/// 都没有给出应答的选项视为错误。
+#[derive(Default)]
+pub struct HeadlessRenderer {
+    events: Vec<RecordedEvent>,
+    inputs: VecDeque<InputEvent>,
+    choices: VecDeque<usize>,
+    responder: Option<Responder>,
+}
+
+impl HeadlessRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_inputs(mut self, inputs: impl IntoIterator<Item = InputEvent>) -> Self {
+        self.inputs.extend(inputs);
+        self
+    }
+
+    /// 依次回答遇到的选项 (从 0 开始的序号)
+    pub fn with_choices(mut self, choices: impl IntoIterator<Item = usize>) -> Self {
+        self.choices.extend(choices);
+        self
+    }
+
+    /// 回调收到导致等待的事件，返回 `None` 时沿用默认应答
+    pub fn with_responder(mut self, f: impl FnMut(&OutputEvent) -> Option<InputEvent> + 'static) -> Self {
+        self.responder = Some(Box::new(f));
+        self
+    }
+
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// 按顺序列出旁白与对话，对话记为 `名字: 内容`
+    pub fn dialogue_sequence(&self) -> Vec<String> {
+        self.events.iter()
+            .filter_map(|r| match &r.event {
+                OutputEvent::ShowNarration { lines } => Some(lines.join("\n")),
+                OutputEvent::ShowDialogue { name, content } => Some(format!("{}: {}", name, content)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn assert_dialogue_sequence(&self, expected: &[&str]) {
+        let actual = self.dialogue_sequence();
+        assert_eq!(actual, expected, "dialogue sequence mismatch");
+    }
+
+    /// 从 `init` 开始运行到 `End`，超过 `max_steps` 仍未结束 (通常是死循环) 时返回错误
+    pub fn play_until_end(&mut self, ctx: &mut Ctx, manager: Arc<ScriptManager>, max_steps: usize) -> anyhow::Result<()> {
+        self.play_from(ctx, manager, "init", max_steps)
+    }
+
+    /// 同 `play_until_end`，从指定 label 开始
+    pub fn play_from(&mut self, ctx: &mut Ctx, manager: Arc<ScriptManager>, entry: &str, max_steps: usize) -> anyhow::Result<()> {
+        let mut driver = ExecutorHandle::new(ctx, manager, entry);
+        let mut prompt: Option<OutputEvent> = None;
+
+        for _ in 0..max_steps {
+            let waiting = driver.step(ctx);
+
+            for event in ctx.drain() {
+                let end = matches!(event, OutputEvent::End);
+                if matches!(event,
+                    OutputEvent::ShowNarration { .. } | OutputEvent::ShowDialogue { .. }
+                    | OutputEvent::ShowChoice { .. } | OutputEvent::ScriptError { .. }
+                    | OutputEvent::PlayMovie { .. } | OutputEvent::WaitAudioEnd { .. })
+                {
+                    prompt = Some(event.clone());
+                }
+                self.events.push(RecordedEvent { seq: self.events.len(), event });
+                if end {
+                    return Ok(());
+                }
+            }
+
+            if waiting {
+                let prompt = prompt.as_ref()
+                    .ok_or_else(|| anyhow!("Waiting for input without any prompt"))?;
+                let input = self.respond(prompt)?;
+                driver.feed(ctx, input);
+            }
+        }
+        bail!("Script did not end within {} steps", max_steps)
+    }
+
+    fn respond(&mut self, prompt: &OutputEvent) -> anyhow::Result<InputEvent> {
+        if let Some(input) = self.inputs.pop_front() {
+            return Ok(input);
+        }
+        if let Some(input) = self.responder.as_mut().and_then(|f| f(prompt)) {
+            return Ok(input);
+        }
+        match prompt {
+            OutputEvent::ShowChoice { options, .. } => self.choices.pop_front()
+                .map(|index| InputEvent::ChoiceMade { index })
+                .ok_or_else(|| anyhow!("No answer for choice {:?}", options)),
+            _ => Ok(InputEvent::Continue),
+        }
+    }
+}
+
+impl Renderer for HeadlessRenderer {
+    fn run_event_loop(&mut self, ctx: &mut Ctx, manager: Arc<ScriptManager>) {
+        if let Err(e) = self.play_until_end(ctx, manager, DEFAULT_MAX_STEPS) {
+            log::error!("Headless run failed: {}", e);
+        }
+    }
+}
+
+type ChoiceStrategy = Box<dyn FnMut(Option<&str>, &[String]) -> usize>;
+
+/// 脚本集成测试驱动：加载脚本，按选择策略回答选项，从入口运行到 `End` 并收集全部输出事件。
+///
+/// 使用前需已初始化配置 (`lumina_shared::config::init`)。
+///
+/// ```ignore
+/// let run = HeadlessRunner::from_source(src)?
+///     .choose(|_title, options| options.iter().position(|o| o == "A").unwrap())
+///     .run()?;
+/// assert!(run.events.iter().any(|e| matches!(e, OutputEvent::PlayAudio { path, .. } if path == "theme_a")));
+/// ```
+pub struct HeadlessRunner {
+    manager: Arc<ScriptManager>,
+    entry: String,
+    max_steps: usize,
+    strategy: Option<ChoiceStrategy>,
+}
+
+/// `HeadlessRunner::run` 的结果
+pub struct RunReport {
+    /// 按产生顺序排列的全部输出事件，以 `End` 结尾
+    pub events: Vec<OutputEvent>,
+    /// 运行结束时的上下文，可检查变量、角色与历史记录
+    pub ctx: Ctx,
+}
+
+impl HeadlessRunner {
+    pub fn new(manager: Arc<ScriptManager>) -> Self {
+        Self {
+            manager,
+            entry: "init".to_string(),
+            max_steps: DEFAULT_MAX_STEPS,
+            strategy: None,
+        }
+    }
+
+    /// 从单个脚本源码加载
+    pub fn from_source(src: &str) -> anyhow::Result<Self> {
+        Self::from_sources([("main.vivi", src)])
+    }
+
+    /// 从多个 `(文件名, 源码)` 加载，可测试跨文件的跳转与角色定义
+    pub fn from_sources<'a>(sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> anyhow::Result<Self> {
+        let mut manager = ScriptManager::new();
+        manager.load_sources(sources)?;
+        Ok(Self::new(Arc::new(manager)))
+    }
+
+    /// 从脚本目录或 `.pak` 归档加载
+    pub fn from_dir(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let mut manager = ScriptManager::new();
+        manager.load_project(path)?;
+        Ok(Self::new(Arc::new(manager)))
+    }
+
+    /// 入口 label，默认 `init`
+    pub fn entry(mut self, label: impl Into<String>) -> Self {
+        self.entry = label.into();
+        self
+    }
+
+    /// 步数上限，默认 `DEFAULT_MAX_STEPS`
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// 选择策略：收到选项标题与选项文本，返回所选序号。未设置时遇到选项即报错
+    pub fn choose(mut self, f: impl FnMut(Option<&str>, &[String]) -> usize + 'static) -> Self {
+        self.strategy = Some(Box::new(f));
+        self
+    }
+
+    /// 运行到 `End`；未设置策略却遇到选项、或超过步数上限时返回错误
+    pub fn run(self) -> anyhow::Result<RunReport> {
+        let mut renderer = HeadlessRenderer::new();
+        if let Some(mut strategy) = self.strategy {
+            renderer = renderer.with_responder(move |ev| match ev {
+                OutputEvent::ShowChoice { title, options, .. } =>
+                    Some(InputEvent::ChoiceMade { index: strategy(title.as_deref(), options) }),
+                _ => None,
+            });
+        }
+
+        let mut ctx = Ctx::default();
+        renderer.play_from(&mut ctx, self.manager, &self.entry, self.max_steps)?;
+        let events = renderer.events.into_iter().map(|r| r.event).collect();
+        Ok(RunReport { events, ctx })
+    }
+}
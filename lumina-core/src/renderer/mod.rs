@@ -1,6 +1,11 @@
 #[cfg(feature = "tui")]
 pub mod terminal;
+#[cfg(feature = "tui")]
+pub mod theme;
 pub mod driver;
+#[cfg(feature = "preview")]
+pub mod preview;
+pub mod headless;
 
 use std::sync::Arc;
 use crate::manager::ScriptManager;
@@ -0,0 +1,240 @@
+//! 编辑器预览：在 `127.0.0.1:<debug.preview_port>` 上接收换行分隔的 JSON 命令，
+//! 交给 [`ExecutorHandle`](super::driver::ExecutorHandle) 在下一次 step 时执行，每条命令回复一行 JSON。
+//!
+//! 本机任何程序 (包括浏览器里的网页) 都能连上这个端口，所以连接的第一行必须是
+//! 本次启动随机生成的口令，口令写在存档目录下只有当前用户可读的 `preview.token` 中；
+//! 第一行像 HTTP 请求时直接断开。`eval` 只有在 `debug.preview_eval` 打开时才会执行。
+//!
+//! ```text
+//! -> {"token":"9f0c…"}
+//! <- {"ok":true,"label":null,"pc":null,"output":null,"error":null}
+//! -> {"cmd":"goto","label":"chapter1","pc":3}
+//! <- {"ok":true,"label":"chapter1","pc":3,"output":"goto -> chapter1 @ 3","error":null}
+//! ```
+
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use crate::config::DebugConfig;
+use crate::event::InputEvent;
+use crate::storager;
+
+/// 口令文件名，位于存档目录下
+pub const TOKEN_FILE: &str = "preview.token";
+
+/// 连接的第一行，证明对方能读到口令文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewAuth {
+    pub token: String,
+}
+
+/// 监听选项
+#[derive(Debug, Clone)]
+pub struct PreviewOptions {
+    pub token: String,
+    /// 是否接受 `eval`
+    pub allow_eval: bool,
+}
+
+/// 编辑器发来的命令
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum PreviewCommand {
+    /// 重新加载脚本，停留在当前位置
+    Reload,
+    Goto { label: String, #[serde(default)] pc: usize },
+    Eval { code: String },
+}
+
+impl PreviewCommand {
+    pub fn into_input_event(self) -> InputEvent {
+        match self {
+            PreviewCommand::Reload => InputEvent::DebugReload,
+            PreviewCommand::Goto { label, pc } => InputEvent::DebugGoto { label, pc },
+            PreviewCommand::Eval { code } => InputEvent::DebugEval { code },
+        }
+    }
+}
+
+/// 对每条命令的回复，`label` / `pc` 为执行后的位置 (脚本已结束时为空)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewResponse {
+    pub ok: bool,
+    pub label: Option<String>,
+    pub pc: Option<usize>,
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PreviewResponse {
+    pub fn error(msg: impl Into<String>, position: Option<(String, usize)>) -> Self {
+        let (label, pc) = position.unzip();
+        Self { ok: false, label, pc, output: None, error: Some(msg.into()) }
+    }
+}
+
+/// 等待驱动执行的命令，回复经 `reply` 送回连接线程
+pub struct PreviewRequest {
+    pub command: PreviewCommand,
+    pub reply: Sender<PreviewResponse>,
+}
+
+static REQUESTS: OnceLock<Option<Mutex<Receiver<PreviewRequest>>>> = OnceLock::new();
+
+/// 按 `debug.preview_port` 启动监听，只在第一次调用时生效。
+/// 口令写不进文件时不启动，免得开着一个谁也连不上的端口
+pub fn ensure_started() {
+    REQUESTS.get_or_init(|| {
+        let cfg = lumina_shared::config::get::<DebugConfig>("debug");
+        let port = cfg.preview_port?;
+
+        let token = new_token();
+        let token_path = token_path();
+        if let Err(e) = write_token(&token_path, &token) {
+            log::error!("Failed to write preview token {:?}: {}", token_path, e);
+            return None;
+        }
+        if cfg.preview_eval {
+            log::warn!("Preview eval is enabled: connections holding the token can run Lua");
+        }
+
+        match start(port, PreviewOptions { token, allow_eval: cfg.preview_eval }) {
+            Ok(rx) => Some(Mutex::new(rx)),
+            Err(e) => {
+                log::error!("Failed to start preview server on port {}: {}", port, e);
+                None
+            }
+        }
+    });
+}
+
+/// 口令文件的位置
+pub fn token_path() -> PathBuf {
+    storager::save_dir().join(TOKEN_FILE)
+}
+
+/// 128 位随机口令 (十六进制)。`RandomState` 的密钥来自系统随机源，外部无法推算
+pub fn new_token() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    (0..2u8)
+        .map(|i| {
+            let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+            hasher.write_u8(i);
+            hasher.write_u128(nanos);
+            hasher.write_u32(std::process::id());
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// 写入口令文件，Unix 上权限为 0600；其他平台依赖存档目录本身只有当前用户可访问
+pub fn write_token(path: &Path, token: &str) -> std::io::Result<()> {
+    // 先删掉旧文件，保证新建时的权限生效
+    let _ = fs::remove_file(path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(token.as_bytes())
+}
+
+/// 在 127.0.0.1 上监听，`port` 为 0 时由系统分配
+pub fn start(port: u16, options: PreviewOptions) -> std::io::Result<Receiver<PreviewRequest>> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    log::info!("Preview server listening on {}", listener.local_addr()?);
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("preview".into())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let tx = tx.clone();
+                        let options = options.clone();
+                        std::thread::spawn(move || {
+                            if let Err(e) = serve(stream, tx, &options) {
+                                log::debug!("Preview connection closed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => log::warn!("Preview accept failed: {}", e),
+                }
+            }
+        })?;
+    Ok(rx)
+}
+
+/// 取出一条等待执行的命令，未启动时总是 None
+pub fn try_recv() -> Option<PreviewRequest> {
+    let rx = REQUESTS.get()?.as_ref()?;
+    rx.lock().ok()?.try_recv().ok()
+}
+
+/// 第一行是否像 HTTP 请求行，如 `POST / HTTP/1.1`
+fn looks_like_http(line: &str) -> bool {
+    const METHODS: [&str; 9] = ["GET", "POST", "PUT", "HEAD", "OPTIONS", "DELETE", "PATCH", "CONNECT", "TRACE"];
+    let method = line.split_whitespace().next().unwrap_or_default();
+    METHODS.iter().any(|m| method.eq_ignore_ascii_case(m)) || line.contains(" HTTP/")
+}
+
+fn send(writer: &mut TcpStream, response: &PreviewResponse) -> std::io::Result<()> {
+    let mut out = serde_json::to_string(response)?;
+    out.push('\n');
+    writer.write_all(out.as_bytes())
+}
+
+fn serve(stream: TcpStream, tx: Sender<PreviewRequest>, options: &PreviewOptions) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut lines = BufReader::new(stream).lines();
+
+    // 第一行：口令。HTTP 请求不回复，直接断开
+    let Some(first) = lines.next().transpose()? else { return Ok(()) };
+    if looks_like_http(&first) {
+        log::warn!("Preview: rejected an HTTP request");
+        return Ok(());
+    }
+    match serde_json::from_str::<PreviewAuth>(&first) {
+        Ok(auth) if auth.token == options.token => {
+            send(&mut writer, &PreviewResponse { ok: true, label: None, pc: None, output: None, error: None })?;
+        }
+        _ => {
+            log::warn!("Preview: rejected a connection without a valid token");
+            return send(&mut writer, &PreviewResponse::error("invalid token", None));
+        }
+    }
+
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<PreviewCommand>(&line) {
+            Ok(PreviewCommand::Eval { .. }) if !options.allow_eval => {
+                PreviewResponse::error("eval is disabled (set debug.preview_eval = true)", None)
+            }
+            Ok(command) => {
+                let (reply, wait) = mpsc::channel();
+                // 游戏已退出时驱动不再回复
+                if tx.send(PreviewRequest { command, reply }).is_err() {
+                    return Ok(());
+                }
+                wait.recv().unwrap_or_else(|_| PreviewResponse::error("request dropped", None))
+            }
+            Err(e) => PreviewResponse::error(format!("invalid command: {}", e), None),
+        };
+        send(&mut writer, &response)?;
+    }
+    Ok(())
+}
@@ -5,19 +5,216 @@ use ratatui::{
         execute, event,
         event::{Event, KeyEventKind, KeyCode}
     },
-    text::{Line, Text},
-    layout::{Layout, Constraint, Direction},
-    widgets::{Block, Borders, Paragraph, ListItem, List},
+    text::{Line, Span, Text},
+    style::{Modifier, Style},
+    layout::{Layout, Constraint, Direction, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, ListItem, List, ListState},
     Terminal
 };
 use std::{io::Stdout, io};
+use std::collections::HashMap;
 use std::sync::Arc;
-use crate::{Ctx, OutputEvent, event::InputEvent, renderer::{Renderer, driver::ExecutorHandle}, ScriptManager};
+use std::time::Instant;
+use lumina_shared::logging::{self, LogFilter};
+use crate::{Ctx, OutputEvent, event::InputEvent, markup, runtime::{Character, assets::DialogueRecord}, renderer::{Renderer, theme::Theme, driver::{ExecutorHandle, MAX_STEPS_PER_FRAME}}, ScriptManager};
+use crate::config::{DebugConfig, SystemConfig, TuiConfig};
+use crate::storager::{self, types::SaveInfo};
+
+/// 存档菜单至少列出的槽位数
+const MIN_SAVE_SLOTS: u32 = 9;
 
 pub struct TuiRenderer {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     input_buf: String,
     current_text: CurrentText,
+    reveal: Reveal,
+    save_menu: Option<SaveMenu>,
+    history_view: Option<HistoryView>,
+    /// `log [level] [module]` 打开的最近日志视图，Esc / Enter 关闭
+    log_view: Option<LogFilter>,
+    /// 是否允许 `:` 调试命令
+    debug_console: bool,
+    /// 最近一条调试命令的输出，显示在 Current 面板底部
+    debug_output: Option<String>,
+    theme: Theme,
+}
+
+/// 全屏历史记录视图 (`h` 切换)，滚动以折行后的行为单位
+#[derive(Debug, Default)]
+struct HistoryView {
+    scroll: u16,
+    /// 停在最新一条，历史增加时跟随到底部
+    follow: bool,
+    /// 上一帧的可见行数与最大滚动量，供翻页和边界使用
+    page: u16,
+    max_scroll: u16,
+}
+
+impl HistoryView {
+    fn new() -> Self {
+        Self { follow: true, ..Default::default() }
+    }
+
+    fn scroll_by(&mut self, delta: i32) {
+        let current = if self.follow { self.max_scroll } else { self.scroll };
+        self.scroll = (current as i32 + delta).clamp(0, self.max_scroll as i32) as u16;
+        self.follow = self.scroll >= self.max_scroll;
+    }
+
+    fn history_text<'a>(history: impl Iterator<Item = &'a DialogueRecord>, theme: &Theme, characters: &HashMap<String, Character>) -> Text<'static> {
+        let lines: Vec<Line> = history
+            .map(|rec| {
+                let mut spans = Vec::new();
+                spans.push(if rec.voice_path.is_some() {
+                    Span::styled("♪ ", theme.playing)
+                } else {
+                    Span::raw("  ")
+                });
+                if let Some(name) = &rec.speaker {
+                    spans.push(Span::styled(format!("{}: ", name), theme.speaker_style(name, characters)));
+                }
+                spans.push(Span::styled(markup::to_plain(&rec.text), theme.narration));
+                Line::from(spans)
+            })
+            .collect();
+        Text::from(lines)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SaveMenuMode {
+    Save,
+    Load,
+}
+
+/// `save` / `load` 不带参数时弹出的槽位列表
+#[derive(Debug)]
+struct SaveMenu {
+    mode: SaveMenuMode,
+    /// (槽位, 已有存档)
+    slots: Vec<(u32, Option<SaveInfo>)>,
+    selected: usize,
+    /// 存档时选中已有槽位，等待再次回车确认覆盖
+    confirm_overwrite: bool,
+}
+
+impl SaveMenu {
+    fn open(mode: SaveMenuMode) -> Self {
+        let saves = storager::list_saves();
+        let slots = match mode {
+            // 存档：列出固定数量的槽位 (以及更大编号的已有存档) 和一个空的新槽位
+            SaveMenuMode::Save => {
+                let last = saves.last().map_or(0, |s| s.slot);
+                let count = MIN_SAVE_SLOTS.max(last + 1);
+                let mut by_slot: std::collections::HashMap<u32, SaveInfo> =
+                    saves.into_iter().map(|s| (s.slot, s)).collect();
+                (1..=count).map(|slot| (slot, by_slot.remove(&slot))).collect()
+            }
+            SaveMenuMode::Load => saves.into_iter().map(|s| (s.slot, Some(s))).collect(),
+        };
+        Self { mode, slots, selected: 0, confirm_overwrite: false }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.slots.is_empty() {
+            return;
+        }
+        let len = self.slots.len() as isize;
+        self.selected = ((self.selected as isize + delta).rem_euclid(len)) as usize;
+        self.confirm_overwrite = false;
+    }
+
+    /// 回车：返回要执行的存/读档事件，覆盖存档需先确认一次
+    fn confirm(&mut self) -> Option<InputEvent> {
+        let (slot, info) = self.slots.get(self.selected)?;
+        match self.mode {
+            SaveMenuMode::Save if info.is_some() && !self.confirm_overwrite => {
+                self.confirm_overwrite = true;
+                None
+            }
+            SaveMenuMode::Save => Some(InputEvent::SaveRequest { slot: *slot }),
+            SaveMenuMode::Load => Some(InputEvent::LoadRequest { slot: *slot }),
+        }
+    }
+
+    fn title(&self) -> String {
+        match (self.mode, self.confirm_overwrite) {
+            (SaveMenuMode::Save, true) => {
+                let slot = self.slots[self.selected].0;
+                format!("Save — slot {} 已有存档，再次回车覆盖，Esc 取消", slot)
+            }
+            (SaveMenuMode::Save, false) => "Save — ↑/↓ 选择，回车存档，Esc 取消".to_string(),
+            (SaveMenuMode::Load, _) => "Load — ↑/↓ 选择，回车读档，Esc 取消".to_string(),
+        }
+    }
+
+    fn items(&self) -> Vec<ListItem<'static>> {
+        if self.slots.is_empty() {
+            return vec![ListItem::new("(没有存档)")];
+        }
+        self.slots.iter()
+            .map(|(slot, info)| match info {
+                Some(info) => ListItem::new(Text::from(vec![
+                    Line::from(format!("#{:<3} {}  [{}]", slot, info.time_string(), info.chapter)),
+                    Line::from(format!("     {}", info.preview)),
+                ])),
+                None => ListItem::new(Text::from(vec![
+                    Line::from(format!("#{:<3} (空)", slot)),
+                    Line::from(""),
+                ])),
+            })
+            .collect()
+    }
+}
+
+/// 逐字显示进度，按字符 (而非字节或显示宽度) 计数，
+/// 截断后的文本仍交给 ratatui 折行，宽字符不会被拆开
+#[derive(Debug)]
+struct Reveal {
+    shown: f32,
+    total: usize,
+    /// 全部显示完的时刻，自动模式据此计时
+    done_at: Option<Instant>,
+}
+
+impl Reveal {
+    fn start(total: usize) -> Self {
+        let mut r = Self { shown: 0.0, total, done_at: None };
+        if total == 0 {
+            r.finish();
+        }
+        r
+    }
+
+    /// `speed` 为字/秒，不大于 0 时立即显示完
+    fn advance(&mut self, dt: f32, speed: f32) {
+        if self.is_done() {
+            return;
+        }
+        if speed <= 0.0 {
+            self.finish();
+            return;
+        }
+        self.shown += dt * speed;
+        if self.shown >= self.total as f32 {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.shown = self.total as f32;
+        if self.done_at.is_none() {
+            self.done_at = Some(Instant::now());
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.done_at.is_some()
+    }
+
+    fn visible(&self) -> usize {
+        (self.shown as usize).min(self.total)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -29,30 +226,98 @@ enum CurrentText {
     Choice {
         title: Option<String>,
         options: Vec<String>,
+        selected: usize,
+        timer: Option<ChoiceTimer>,
     },
+    ScriptError { line: usize, msg: String },
+    /// 终端无法播放视频，只显示占位提示
+    Movie { id: String },
+}
+
+/// 限时选择的倒计时
+#[derive(Debug, Clone)]
+struct ChoiceTimer {
+    remaining: f32,
+    total: f32,
+    default: usize,
+}
+
+impl ChoiceTimer {
+    const BAR_WIDTH: usize = 20;
+
+    /// 倒计时进度条，如 `⏱ [██████░░░░] 3.2s`
+    fn bar(&self) -> String {
+        let ratio = (self.remaining / self.total).clamp(0.0, 1.0);
+        let filled = (ratio * Self::BAR_WIDTH as f32).ceil() as usize;
+        format!(
+            "⏱ [{}{}] {:.1}s  超时选择 {}",
+            "█".repeat(filled),
+            "░".repeat(Self::BAR_WIDTH - filled),
+            self.remaining.max(0.0),
+            self.default + 1,
+        )
+    }
 }
 
 impl CurrentText {
-    fn to_text(&self) -> Text<'static> {
+    /// 参与逐字显示的字符数
+    fn reveal_len(&self) -> usize {
+        match self {
+            CurrentText::Narration(lines) => lines.chars().count(),
+            CurrentText::Dialogue { content, .. } => content.chars().count(),
+            _ => 0,
+        }
+    }
+
+    /// `height` 为面板内可用行数，选项过多时以选中项为中心滚动显示；
+    /// `visible` 为旁白/对话已显示的字符数
+    fn to_text(&self, height: usize, visible: usize, theme: &Theme, characters: &HashMap<String, Character>) -> Text<'static> {
         match self {
             CurrentText::Empty => Text::raw(""),
-            CurrentText::Narration(lines) => Text::raw(lines.clone()),
+            CurrentText::ScriptError { line, msg } => Text::styled(
+                format!("脚本错误 (line {}): {}", line, msg),
+                theme.error,
+            ),
+            CurrentText::Movie { id } => Text::styled(format!("[过场动画: {}]", id), theme.narration),
+            CurrentText::Narration(lines) => Text::styled(take_chars(lines, visible), theme.narration),
             CurrentText::Dialogue { name, content } => {
                 let mut lines = Vec::new();
-                lines.push(Line::from(format!("「{}」", name)));
-                lines.push(Line::from(content.clone()));
+                lines.push(Line::styled(format!("「{}」", name), theme.speaker_style(name, characters)));
+                lines.push(Line::styled(take_chars(content, visible), theme.narration));
                 Text::from(lines)
             }
-            CurrentText::Choice { title, options } => {
+            CurrentText::Choice { title, options, selected, timer } => {
                 let mut lines = Vec::new();
                 if let Some(t) = title {
-                    lines.push(Line::from(t.clone()));
+                    lines.push(Line::styled(t.clone(), theme.narration));
                 }
-                for (idx, opt) in options.iter().enumerate() {
-                    lines.push(Line::from(format!("  {}. {}", idx + 1, opt)));
+
+                // 标题 + 空行 + 提示 (+ 倒计时) 之外的行数留给选项
+                let reserved = lines.len() + 2 + timer.is_some() as usize;
+                let visible = height.saturating_sub(reserved).max(1);
+                let start = selected
+                    .saturating_sub(visible / 2)
+                    .min(options.len().saturating_sub(visible));
+
+                for (idx, opt) in options.iter().enumerate().skip(start).take(visible) {
+                    if idx == *selected {
+                        lines.push(Line::styled(
+                            format!("> {}. {}", idx + 1, opt),
+                            theme.choice.add_modifier(Modifier::REVERSED),
+                        ));
+                    } else {
+                        lines.push(Line::styled(format!("  {}. {}", idx + 1, opt), theme.choice));
+                    }
                 }
                 lines.push(Line::from(""));
-                lines.push(Line::from("请输入数字进行选择"));
+                lines.push(Line::styled(format!(
+                    "↑/↓ 选择，回车确认 (也可输入数字)  [{}/{}]",
+                    selected + 1,
+                    options.len()
+                ), theme.dim));
+                if let Some(timer) = timer {
+                    lines.push(Line::styled(timer.bar(), theme.error));
+                }
                 Text::from(lines)
             }
         }
@@ -70,10 +335,65 @@ impl TuiRenderer {
             terminal,
             input_buf: String::new(),
             current_text: CurrentText::Empty,
+            reveal: Reveal::start(0),
+            save_menu: None,
+            history_view: None,
+            log_view: None,
+            debug_console: lumina_shared::config::get::<DebugConfig>("debug").enable_console,
+            debug_output: None,
+            theme: Theme::from_config(&lumina_shared::config::get::<TuiConfig>("tui")),
         })
     }
 
-    fn try_read_key(&mut self) -> io::Result<Option<InputEvent>> {
+    /// 历史视图打开时的按键处理，不影响正在等待的输入
+    fn history_key(&mut self, code: KeyCode) {
+        let Some(view) = self.history_view.as_mut() else { return };
+        let page = view.page.max(1) as i32;
+        match code {
+            KeyCode::Char('h') | KeyCode::Esc => self.history_view = None,
+            KeyCode::Up => view.scroll_by(-1),
+            KeyCode::Down => view.scroll_by(1),
+            KeyCode::PageUp => view.scroll_by(-page),
+            KeyCode::PageDown => view.scroll_by(page),
+            KeyCode::Home => {
+                view.scroll = 0;
+                view.follow = false;
+            }
+            KeyCode::End => view.follow = true,
+            _ => {}
+        }
+    }
+
+    /// 存档菜单打开时的按键处理
+    fn menu_key(&mut self, code: KeyCode) -> Option<InputEvent> {
+        let menu = self.save_menu.as_mut()?;
+        match code {
+            KeyCode::Esc => {
+                self.save_menu = None;
+                None
+            }
+            KeyCode::Up => {
+                menu.move_selection(-1);
+                None
+            }
+            KeyCode::Down => {
+                menu.move_selection(1);
+                None
+            }
+            KeyCode::Enter => {
+                let ev = menu.confirm();
+                if ev.is_some() {
+                    self.save_menu = None;
+                }
+                ev
+            }
+            _ => None,
+        }
+    }
+
+    /// 读取按键并转换为输入事件。逐字显示未完成时任意按键先让其显示完，
+    /// 此时空回车只用于补全文本，不会继续；save/load/quit 等命令照常生效
+    fn try_read_key(&mut self, auto: bool, skip: bool) -> io::Result<Option<InputEvent>> {
         if !event::poll(std::time::Duration::from_millis(50))? {
             return Ok(None);
         }
@@ -82,25 +402,105 @@ impl TuiRenderer {
             return Ok(None);
         }
 
-        Ok(match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => Some(InputEvent::Exit),
+        if self.history_view.is_some() {
+            self.history_key(key.code);
+            return Ok(None);
+        }
+        if self.log_view.is_some() {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                self.log_view = None;
+            }
+            return Ok(None);
+        }
+        if key.code == KeyCode::Char('h') && self.input_buf.is_empty() && self.save_menu.is_none() {
+            self.history_view = Some(HistoryView::new());
+            return Ok(None);
+        }
+
+        let revealing = !self.reveal.is_done();
+        self.reveal.finish();
+
+        if self.save_menu.is_some() {
+            return Ok(self.menu_key(key.code));
+        }
+
+        let ev = match key.code {
+            // 输入框有内容时 q 作为普通字符，便于输入 quit、:set f.quest ...
+            KeyCode::Char('q') if self.input_buf.is_empty() => Some(InputEvent::Exit),
+            KeyCode::Esc => Some(InputEvent::Exit),
+            KeyCode::Up => {
+                self.move_selection(-1);
+                None
+            }
+            KeyCode::Down => {
+                self.move_selection(1);
+                None
+            }
             KeyCode::Char(c) => {
                 self.input_buf.push(c);
+                self.jump_selection();
                 None
             }
             KeyCode::Backspace => {
                 self.input_buf.pop();
+                self.jump_selection();
                 None
             }
             KeyCode::Enter => {
                 let line = std::mem::take(&mut self.input_buf);
-                parse_command(&line)
+                match &self.current_text {
+                    // 选项模式下空回车确认当前高亮项
+                    CurrentText::Choice { selected, .. } if line.trim().is_empty() => {
+                        Some(InputEvent::ChoiceMade { index: *selected })
+                    }
+                    // 不带槽位号时打开存档菜单
+                    _ if line.trim() == "save" => {
+                        self.save_menu = Some(SaveMenu::open(SaveMenuMode::Save));
+                        None
+                    }
+                    _ if line.trim() == "load" => {
+                        self.save_menu = Some(SaveMenu::open(SaveMenuMode::Load));
+                        None
+                    }
+                    _ if line.split_whitespace().next() == Some("log") => {
+                        self.log_view = Some(LogFilter::parse(line.trim().trim_start_matches("log")));
+                        None
+                    }
+                    _ => parse_command(&line, auto, skip, self.debug_console),
+                }
             }
             _ => None,
+        };
+
+        Ok(match ev {
+            Some(InputEvent::Continue) if revealing => None,
+            ev => ev,
         })
     }
 
-    fn draw(&mut self, ctx: &Ctx) -> io::Result<()> {
+    /// 上下移动高亮项，首尾循环
+    fn move_selection(&mut self, delta: isize) {
+        if let CurrentText::Choice { options, selected, .. } = &mut self.current_text {
+            if options.is_empty() {
+                return;
+            }
+            let len = options.len() as isize;
+            *selected = ((*selected as isize + delta).rem_euclid(len)) as usize;
+        }
+    }
+
+    /// 输入框中的数字若是合法序号，高亮跳到对应选项
+    fn jump_selection(&mut self) {
+        if let CurrentText::Choice { options, selected, .. } = &mut self.current_text {
+            if let Ok(n) = self.input_buf.trim().parse::<usize>() {
+                if (1..=options.len()).contains(&n) {
+                    *selected = n - 1;
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, ctx: &Ctx, auto: bool, skip: bool) -> io::Result<()> {
         self.terminal.draw(|f| {
             let size = f.area();
             let chunks = Layout::default()
@@ -122,73 +522,141 @@ impl TuiRenderer {
             let hist_area = bottom_main[1];
             let cmd_area = chunks[2];
 
-            let scene_block = Block::default()
+            let theme = &self.theme;
+            let block = |title: String| Block::default()
                 .borders(Borders::ALL)
-                .title("Scene / Sprites");
+                .border_style(theme.border)
+                .title(title);
+
+            let scene_block = block("Scene / Sprites".into());
             let mut scene_text = vec![Line::from("Layers:")];
             for (layer_name, sprites) in &ctx.layer_record.layer {
                 scene_text.push(Line::from(format!("  [{}]:", layer_name)));
                 for sp in sprites {
-                    scene_text.push(Line::from(format!(
-                        "    {}  {:?}",
-                        sp.target,
-                        sp.attrs.join(",")
-                    )))
+                    scene_text.push(Line::from(format!("    {}", sp.full_asset_name())))
                 }
             }
             let scene_paragraph = Paragraph::new(Text::from(scene_text)).block(scene_block);
             f.render_widget(scene_paragraph, left);
 
-            let audio_block = Block::default()
-                .borders(Borders::ALL)
-                .title("Audio Queue");
+            let audio_block = block("Audio Queue".into());
             let mut audio_text = vec![];
             for (ch, aud_opt) in &ctx.audios {
+                // 播放中的通道高亮，已停止的通道变暗
                 if let Some(audio) = aud_opt {
-                    audio_text.push(Line::from(format!(
+                    audio_text.push(Line::styled(format!(
                         "{}: {} ▶ {}% {}",
                         ch,
                         audio.path,
                         (audio.volume * 100.0) as u8,
                         if audio.looping { "loop" } else { "once" }
-                    )));
+                    ), theme.playing));
                 } else {
-                    audio_text.push(Line::from(format!("{}: --", ch)));
+                    audio_text.push(Line::styled(format!("{}: --", ch), theme.dim));
                 }
             }
             let audio_paragraph = Paragraph::new(Text::from(audio_text)).block(audio_block);
             f.render_widget(audio_paragraph, right);
 
-            let dialog_block = Block::default().borders(Borders::ALL).title("Current");
+            let dialog_block = block("Current".into());
+            let mut current = self.current_text.to_text(
+                dialog_area.height.saturating_sub(2) as usize,
+                self.reveal.visible(),
+                theme,
+                &ctx.characters,
+            );
+            if let Some(output) = &self.debug_output {
+                current.lines.push(Line::from(""));
+                current.lines.extend(output.lines().map(|l| Line::styled(l.to_string(), theme.dim)));
+            }
             f.render_widget(
-                Paragraph::new(self.current_text.to_text())
+                Paragraph::new(current)
                     .block(dialog_block)
                     .wrap(ratatui::widgets::Wrap { trim: false }),
                 dialog_area,
             );
 
-            let hist_block = Block::default()
-                .borders(Borders::ALL)
-                .title("History");
+            let hist_block = block("History".into());
+            let total = ctx.dialogue_history.total();
             let hist_items: Vec<ListItem> = ctx
-                .dialogue_history
-                .iter()
+                .history_slice(total.saturating_sub(15)..)
                 .rev()
-                .take(15)
                 .map(|rec| {
-                    let speaker = rec.speaker.as_deref().unwrap_or("Narrator");
-
-                    ListItem::new(format!("{}: {}", speaker, rec.text))
+                    let speaker = match &rec.speaker {
+                        Some(name) => Span::styled(name.clone(), theme.speaker_style(name, &ctx.characters)),
+                        None => Span::styled("Narrator", theme.dim),
+                    };
+                    ListItem::new(Line::from(vec![
+                        speaker,
+                        Span::styled(format!(": {}", markup::to_plain(&rec.text)), theme.narration),
+                    ]))
                 })
                 .collect();
             let hist_list = List::new(hist_items).block(hist_block);
             f.render_widget(hist_list, hist_area);
 
-            let cmd_block = Block::default().borders(Borders::ALL).title("Command");
+            let mut cmd_title = String::from("Command");
+            if auto { cmd_title.push_str(" [AUTO]"); }
+            if skip { cmd_title.push_str(" [SKIP]"); }
+            let cmd_block = block(cmd_title);
             f.render_widget(
-                Paragraph::new(format!("> {}", self.input_buf)).block(cmd_block),
+                Paragraph::new(format!("> {}", self.input_buf)).style(theme.command).block(cmd_block),
                 cmd_area,
             );
+
+            let main_area = Rect { height: chunks[0].height + chunks[1].height, ..chunks[0] };
+
+            // 历史视图覆盖上方的主区域，过长的行自动折行
+            if let Some(view) = &mut self.history_view {
+                let paragraph = Paragraph::new(HistoryView::history_text(ctx.history_slice(..), theme, &ctx.characters))
+                    .wrap(ratatui::widgets::Wrap { trim: false });
+                let inner_w = main_area.width.saturating_sub(2);
+                view.page = main_area.height.saturating_sub(2);
+                let total = paragraph.line_count(inner_w) as u16;
+                view.max_scroll = total.saturating_sub(view.page);
+                if view.follow || view.scroll > view.max_scroll {
+                    view.scroll = view.max_scroll;
+                }
+
+                let title = format!(
+                    "History [{}/{}] — ↑/↓ PgUp/PgDn 滚动，End 最新，h/Esc 返回",
+                    (view.scroll + view.page).min(total),
+                    total
+                );
+                f.render_widget(Clear, main_area);
+                f.render_widget(
+                    paragraph
+                        .block(block(title))
+                        .scroll((view.scroll, 0)),
+                    main_area,
+                );
+            }
+
+            // 日志视图覆盖上方的主区域，只显示放得下的最新几条
+            if let Some(filter) = &self.log_view {
+                let rows = main_area.height.saturating_sub(2) as usize;
+                let lines: Vec<Line> = logging::recent_filtered(rows, filter).iter()
+                    .map(|e| Line::styled(format!("[{}] {}: {}", e.level, e.target, e.message), theme.log_style(e.level)))
+                    .collect();
+                let mut title = format!("Log [{}", filter.level);
+                if let Some(module) = &filter.module {
+                    title.push_str(&format!(" {}", module));
+                }
+                title.push_str("] — Esc/Enter 返回");
+                f.render_widget(Clear, main_area);
+                f.render_widget(Paragraph::new(Text::from(lines)).block(block(title)), main_area);
+            }
+
+            // 存档菜单覆盖上方的主区域
+            if let Some(menu) = &self.save_menu {
+                let mut state = ListState::default().with_selected(Some(menu.selected));
+                let list = List::new(menu.items())
+                    .block(block(menu.title()))
+                    .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                    .highlight_symbol("> ");
+                f.render_widget(Clear, main_area);
+                f.render_stateful_widget(list, main_area, &mut state);
+            }
         })?;
 
         Ok(())
@@ -197,38 +665,137 @@ impl TuiRenderer {
 
 impl Renderer for TuiRenderer {
     fn run_event_loop(&mut self, ctx: &mut Ctx, manager: Arc<ScriptManager>) {
-        let mut driver = ExecutorHandle::new(ctx, manager);
+        let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+        let mut driver = ExecutorHandle::new(ctx, manager, &sys_cfg.start_label);
+        if let Some(slot) = lumina_shared::config::get::<DebugConfig>("debug").load_slot {
+            if !driver.continue_from(ctx, &storager::slot_filename(slot)) {
+                log::warn!("Failed to load slot {}, starting a new game", slot);
+            }
+        }
+        let text_speed = storager::load_preferences().text_speed();
+        let mut last_tick = Instant::now();
 
         loop {
-            let waiting = driver.step(ctx);
+            // 与 InGameScreen 一致：一帧内推进到等待点，每个等待点只消费一次输入
+            let waiting = driver.step_until_wait(ctx, MAX_STEPS_PER_FRAME);
 
-            for out in ctx.drain() {
+            let mut finished_animations = Vec::new();
+            let mut audio_done = false;
+            for out in driver.drain(ctx) {
                 if matches!(out, OutputEvent::End) {
                     return;
                 }
+                // 终端不播放动画，带完成 id 的动画立即视为结束
+                if let OutputEvent::ModifyVisual { on_done: Some(id), .. } = out {
+                    finished_animations.push(id);
+                    continue;
+                }
+                // 终端不播放音频，等待的声音立即视为播完
+                if let OutputEvent::WaitAudioEnd { .. } = out {
+                    audio_done = true;
+                    continue;
+                }
+                if let OutputEvent::DebugOutput { text } = out {
+                    self.debug_output = Some(text);
+                    continue;
+                }
+                if let OutputEvent::Warning { line, msg } = out {
+                    self.debug_output = Some(format!("warning (line {}): {}", line, msg));
+                    continue;
+                }
+                if let OutputEvent::QuickSaveDone { load, ok } = out {
+                    self.debug_output = Some(match (load, ok) {
+                        (false, true) => "Quick saved".to_string(),
+                        (false, false) => "Quick save failed".to_string(),
+                        (true, true) => "Quick loaded".to_string(),
+                        (true, false) => "No quick save to load".to_string(),
+                    });
+                    continue;
+                }
+                if let OutputEvent::Toast { text, .. } = out {
+                    self.debug_output = Some(text);
+                    continue;
+                }
+                if let OutputEvent::SaveMismatch { label } = out {
+                    self.debug_output = Some(format!(
+                        "label '{}' changed since this save: 'restart' to replay it from the start, 'abort' to cancel",
+                        label
+                    ));
+                    continue;
+                }
                 self.current_text = match out {
                     OutputEvent::ShowNarration { lines } => {
-                        CurrentText::Narration(lines.join("\n"))
+                        CurrentText::Narration(markup::to_plain(&lines.join("\n")))
                     }
                     OutputEvent::ShowDialogue { name, content } => {
-                        CurrentText::Dialogue { name, content }
+                        CurrentText::Dialogue { name, content: markup::to_plain(&content) }
                     }
-                    OutputEvent::ShowChoice { title, options } => {
-                        CurrentText::Choice { title, options }
+                    OutputEvent::ShowChoice { title, options, timeout, default } => {
+                        let timer = timeout.map(|t| ChoiceTimer { remaining: t, total: t, default });
+                        CurrentText::Choice { title, options, selected: 0, timer }
                     }
+                    OutputEvent::ScriptError { line, msg } => CurrentText::ScriptError { line, msg },
+                    OutputEvent::PlayMovie { id, .. } => CurrentText::Movie { id },
                     _ => continue,
                 };
+                self.reveal = Reveal::start(self.current_text.reveal_len());
+                self.debug_output = None;
+            }
+            for id in finished_animations {
+                driver.feed(ctx, InputEvent::AnimationDone { id });
+            }
+            if audio_done {
+                driver.feed(ctx, InputEvent::Continue);
             }
 
-            if let Err(e) = self.draw(ctx) {
+            // 逐字显示沿用按键轮询的节奏，按实际经过的时间推进
+            let now = Instant::now();
+            let dt = now.duration_since(last_tick).as_secs_f32();
+            self.reveal.advance(dt, text_speed);
+            last_tick = now;
+
+            // 限时选择在存档菜单、历史视图打开时暂停计时
+            let mut timed_out = None;
+            if self.save_menu.is_none() && self.history_view.is_none() {
+                if let CurrentText::Choice { timer, .. } = &mut self.current_text {
+                    if let Some(t) = timer.as_mut() {
+                        t.remaining -= dt;
+                    }
+                    if timer.as_ref().is_some_and(|t| t.remaining <= 0.0) {
+                        timed_out = timer.take().map(|t| t.default);
+                    }
+                }
+            }
+
+            let (auto, skip) = (driver.is_auto(), driver.is_skip());
+            if let Err(e) = self.draw(ctx, auto, skip) {
                 log::error!("TUI draw error: {}", e);
                 return;
             }
 
+            if let (true, Some(index)) = (waiting, timed_out) {
+                driver.feed(ctx, InputEvent::ChoiceMade { index });
+                continue;
+            }
+
             if waiting {
-                match self.try_read_key() {
+                match self.try_read_key(auto, skip) {
+                    // 读档后当前语句会重新执行，下一帧随新下发的文本重绘
                     Ok(Some(ev)) => driver.feed(ctx, ev),
-                    Ok(None) => {}
+                    Ok(None) => {
+                        // 自动/快进遇到选项 (含限时选择，倒计时照常进行) 或打开存档菜单、历史视图时停下
+                        let paused = matches!(self.current_text, CurrentText::Choice { .. })
+                            || self.save_menu.is_some()
+                            || self.history_view.is_some();
+                        if skip && !paused {
+                            self.reveal.finish();
+                            driver.feed(ctx, InputEvent::Continue);
+                        } else if auto && !paused && self.reveal.done_at
+                            .is_some_and(|t| t.elapsed().as_secs_f32() >= sys_cfg.auto_wait(self.reveal.total))
+                        {
+                            driver.feed(ctx, InputEvent::Continue);
+                        }
+                    }
                     Err(e) => {
                         log::error!("TUI key read error: {}", e);
                         return;
@@ -246,7 +813,62 @@ impl Drop for TuiRenderer {
     }
 }
 
-fn parse_command(line: &str) -> Option<InputEvent> {
+fn take_chars(s: &str, n: usize) -> String {
+    s.chars().take(n).collect()
+}
+
+/// `on` / `off` 显式开关，省略参数时切换当前状态
+fn parse_switch(arg: Option<&str>, current: bool) -> Option<bool> {
+    match arg {
+        None => Some(!current),
+        Some("on") => Some(true),
+        Some("off") => Some(false),
+        _ => None,
+    }
+}
+
+/// `:` 开头的调试命令：`:jump label`、`:goto label [pc]`、`:set f.flag true`、`:vars`、`:savedir`、`:eval code`、`:reload`、`:chapters`
+fn parse_debug_command(cmd: &str) -> Option<InputEvent> {
+    let cmd = cmd.trim();
+    let (name, rest) = cmd.split_once(char::is_whitespace).unwrap_or((cmd, ""));
+    let rest = rest.trim();
+    match name {
+        "jump" if !rest.is_empty() => Some(InputEvent::DebugJump { label: rest.to_string() }),
+        "goto" if !rest.is_empty() => {
+            let (label, pc) = rest.split_once(char::is_whitespace).unwrap_or((rest, "0"));
+            match pc.trim().parse::<usize>() {
+                Ok(pc) => Some(InputEvent::DebugGoto { label: label.to_string(), pc }),
+                Err(_) => {
+                    log::warn!("Invalid pc for goto: '{}'", pc.trim());
+                    None
+                }
+            }
+        }
+        "set" => {
+            let (var, value) = rest.split_once(char::is_whitespace)?;
+            Some(InputEvent::DebugEval { code: format!("{} = {}", var, value.trim()) })
+        }
+        "vars" => Some(InputEvent::DebugEval { code: "return f".to_string() }),
+        "savedir" => Some(InputEvent::DebugEval { code: "return lumina.save_dir()".to_string() }),
+        "eval" if !rest.is_empty() => Some(InputEvent::DebugEval { code: rest.to_string() }),
+        "reload" => Some(InputEvent::DebugReload),
+        "chapters" => Some(InputEvent::DebugChapters),
+        _ => {
+            log::warn!("Unknown debug command: ':{}'", cmd);
+            None
+        }
+    }
+}
+
+fn parse_command(line: &str, auto: bool, skip: bool, debug: bool) -> Option<InputEvent> {
+    if let Some(cmd) = line.trim().strip_prefix(':') {
+        if !debug {
+            log::warn!("Debug commands are disabled (set debug.enable_console = true)");
+            return None;
+        }
+        return parse_debug_command(cmd);
+    }
+
     let parts: Vec<&str> = line.split_whitespace().collect();
     match parts.get(0).copied() {
         Some("save") => parts
@@ -257,6 +879,17 @@ fn parse_command(line: &str) -> Option<InputEvent> {
             .get(1)
             .and_then(|s| s.parse::<u32>().ok())
             .map(|slot| InputEvent::LoadRequest { slot }),
+        Some("quicksave") | Some("qs") => Some(InputEvent::QuickSave),
+        Some("quickload") | Some("ql") => Some(InputEvent::QuickLoad),
+        Some("export") => Some(InputEvent::ExportHistory {
+            filename: parts.get(1).unwrap_or(&"history.txt").to_string(),
+        }),
+        Some("auto") => parse_switch(parts.get(1).copied(), auto)
+            .map(|enabled| InputEvent::SetAuto { enabled }),
+        Some("skip") => parse_switch(parts.get(1).copied(), skip)
+            .map(|enabled| InputEvent::SetSkip { enabled }),
+        Some("restart") => Some(InputEvent::ResolveLoad { restart: true }),
+        Some("abort") => Some(InputEvent::ResolveLoad { restart: false }),
         Some("exit") | Some("quit") => Some(InputEvent::Exit),
         Some("continue") | Some("c") | Some("") => Some(InputEvent::Continue),
         None => Some(InputEvent::Continue),
@@ -0,0 +1,122 @@
+//! TUI 配色：内置 dark / light 两套主题，可用 `[tui]` 配置覆盖单项颜色
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use ratatui::style::{Color, Modifier, Style};
+use crate::config::TuiConfig;
+use crate::runtime::Character;
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub border: Style,
+    pub speaker: Style,
+    pub narration: Style,
+    pub choice: Style,
+    pub command: Style,
+    pub error: Style,
+    pub dim: Style,
+    pub playing: Style,
+    /// 未指定 `color=` 的角色按名字哈希取色
+    pub palette: Vec<Color>,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            border: Style::default().fg(Color::DarkGray),
+            speaker: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            narration: Style::default().fg(Color::White),
+            choice: Style::default().fg(Color::LightYellow),
+            command: Style::default().fg(Color::LightGreen),
+            error: Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            dim: Style::default().fg(Color::DarkGray),
+            playing: Style::default().fg(Color::LightGreen),
+            palette: vec![
+                Color::LightCyan, Color::LightMagenta, Color::LightYellow,
+                Color::LightGreen, Color::LightBlue, Color::LightRed,
+            ],
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            border: Style::default().fg(Color::Gray),
+            speaker: Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            narration: Style::default().fg(Color::Black),
+            choice: Style::default().fg(Color::Magenta),
+            command: Style::default().fg(Color::Blue),
+            error: Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            dim: Style::default().fg(Color::Gray),
+            playing: Style::default().fg(Color::Green),
+            palette: vec![
+                Color::Blue, Color::Magenta, Color::Red,
+                Color::Green, Color::Cyan, Color::Yellow,
+            ],
+        }
+    }
+
+    /// 以 `theme` 选择的内置主题为底，应用配置中的单项颜色；
+    /// 无法解析的颜色记录日志并保留主题默认值
+    pub fn from_config(cfg: &TuiConfig) -> Self {
+        let mut theme = match cfg.theme.as_str() {
+            "light" => Self::light(),
+            "dark" => Self::dark(),
+            other => {
+                log::warn!("Unknown TUI theme '{}', using 'dark'", other);
+                Self::dark()
+            }
+        };
+
+        let overrides = [
+            ("border", &cfg.border, &mut theme.border),
+            ("speaker", &cfg.speaker, &mut theme.speaker),
+            ("narration", &cfg.narration, &mut theme.narration),
+            ("choice", &cfg.choice, &mut theme.choice),
+            ("command", &cfg.command, &mut theme.command),
+        ];
+        for (key, value, style) in overrides {
+            if let Some(value) = value {
+                match parse_color(value) {
+                    Some(color) => *style = style.fg(color),
+                    None => log::warn!("Invalid color '{}' for tui.{}, using theme default", value, key),
+                }
+            }
+        }
+        theme
+    }
+
+    /// 说话人的样式：优先使用角色定义的 `color=`，否则按名字稳定取色
+    pub fn speaker_style(&self, name: &str, characters: &HashMap<String, Character>) -> Style {
+        let defined = characters.values()
+            .find(|c| c.name == name || c.id == name)
+            .and_then(|c| c.color.as_deref())
+            .and_then(parse_color);
+
+        let color = defined.unwrap_or_else(|| {
+            let hash = name.bytes().fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+            self.palette[hash as usize % self.palette.len()]
+        });
+        self.speaker.fg(color)
+    }
+
+    /// 日志视图中按级别取样式
+    pub fn log_style(&self, level: log::Level) -> Style {
+        match level {
+            log::Level::Error => self.error,
+            log::Level::Warn => self.choice,
+            log::Level::Info => self.narration,
+            log::Level::Debug | log::Level::Trace => self.dim,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// 颜色名 (`red`、`lightblue`) 或 `#rrggbb`
+fn parse_color(s: &str) -> Option<Color> {
+    Color::from_str(s.trim()).ok()
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use viviscript_core::ast::ShowAttr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Character {
@@ -7,6 +8,14 @@ pub struct Character {
     pub name: String,
     pub image_tag: Option<String>,
     pub voice_tag: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    /// 语音音量相对 `audio.voice_volume` 的倍数
+    #[serde(default)]
+    pub voice_volume: Option<f32>,
+    /// 语音播放速率 (同时改变音高)
+    #[serde(default)]
+    pub voice_pitch: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +25,20 @@ pub struct Audio {
     pub fade_in: f32,
     pub fade_out: f32,
     pub looping: bool,
+    /// 循环区间 (起点秒数, 终点秒数)，终点缺省为曲末；`None` 为整曲循环
+    #[serde(default)]
+    pub loop_region: Option<(f32, Option<f32>)>,
+    /// 播放速率，`None` 为原速
+    #[serde(default)]
+    pub pitch: Option<f32>,
+}
+
+/// 渲染层每帧回填的声道播放状态，比实际播放晚一帧
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChannelState {
+    /// 当前播放位置 (秒)
+    pub position: f64,
+    pub playing: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +48,14 @@ pub struct DialogueRecord {
     pub voice_path: Option<String>,
 }
 
+/// 文本呈现方式：底部对话框 (ADV) 或全屏翻页 (NVL)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PresentationMode {
+    #[default]
+    Adv,
+    Nvl,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Layers{
     pub arrange: Vec<String>,
@@ -37,4 +68,60 @@ pub struct Sprite {
     pub attrs: Vec<String>,
     pub position: Option<String>,
     pub zindex: usize,
-}
\ No newline at end of file
+}
+
+/// 图片资源的命名约定：`<target>_<attr0>_<attr1>…`，属性按槽位顺序拼接。
+///
+/// 立绘与 `scene` 背景共用此规则：`scene room day rain` 对应 `room_day_rain`。
+/// 资源按文件名 (不含扩展名) 索引，所在目录不参与命名，
+/// 因此 `images/bg/room_day.png` 同样是 `room_day`；需要 `bg_` 前缀时请写进脚本 (`scene bg_room day`)。
+pub fn asset_name(target: &str, attrs: &[String]) -> String {
+    let mut name = target.to_string();
+    for attr in attrs {
+        name.push('_');
+        name.push_str(attr);
+    }
+    name
+}
+
+impl Sprite {
+    /// 当前属性对应的图片资源名
+    pub fn full_asset_name(&self) -> String {
+        asset_name(&self.target, &self.attrs)
+    }
+
+    /// 按 `ShowAttr` 的槽位规则更新属性：第 k 个 `Add` 对应第 k 个槽位，
+    /// 已有的属性保持不动，否则替换该槽位或追加；所有 `Remove` 在之后按值删除。
+    ///
+    /// 槽位上的属性若也在本句中 `Add`，不会被替换，新属性改为追加；
+    /// 同一句里既加又删的属性视为没有加，不占槽位
+    pub fn apply_attrs(&mut self, changes: &[ShowAttr]) {
+        let removed: Vec<&String> = changes.iter().filter_map(|c| match c {
+            ShowAttr::Remove(a) => Some(a),
+            ShowAttr::Add(_) => None,
+        }).collect();
+        let adds: Vec<&String> = changes.iter().filter_map(|c| match c {
+            ShowAttr::Add(a) if !removed.contains(&a) => Some(a),
+            _ => None,
+        }).collect();
+
+        for (slot, attr) in adds.iter().enumerate() {
+            if self.attrs.contains(attr) {
+                continue;
+            }
+            match self.attrs.get_mut(slot) {
+                Some(current) if !adds.contains(&&*current) => *current = (*attr).clone(),
+                _ => self.attrs.push((*attr).clone()),
+            }
+        }
+
+        for change in changes {
+            if let ShowAttr::Remove(attr) = change {
+                match self.attrs.iter().position(|a| a == attr) {
+                    Some(i) => { self.attrs.remove(i); },
+                    None => log::warn!("Sprite '{}' has no attribute '{}' to remove", self.target, attr),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+use crate::event::OutputEvent;
+
+/// 快进时合并一批输出事件，只保留对最终画面有影响的部分：
+/// - 连续的旁白/对话只保留最后一条；
+/// - 同一通道的 `PlayAudio` / `StopAudio` 只保留最后一条；
+/// - `NewScene` 只保留最后一个，之前的立绘事件一并丢弃；
+/// - 同一立绘在最后一次 `NewSprite` / `HideSprite` 之前的事件丢弃，连续的 `UpdateSprite` 只保留最后一条；
+///   带完成 id 的 `ModifyVisual` 保留，保证完成回调仍会触发。
+///
+/// 选项、脚本错误与 `End` 从不丢弃，并作为分界：分界两侧的事件互不合并。
+/// 其余事件原样保留，保留下来的事件维持原有顺序。
+pub fn coalesce(events: Vec<OutputEvent>) -> Vec<OutputEvent> {
+    let mut keep = vec![true; events.len()];
+    let mut segment = Segment::default();
+
+    for (i, event) in events.iter().enumerate().rev() {
+        keep[i] = match event {
+            OutputEvent::ShowChoice { .. } | OutputEvent::ScriptError { .. } | OutputEvent::End => {
+                segment = Segment::default();
+                true
+            }
+            OutputEvent::ShowDialogue { .. } | OutputEvent::ShowNarration { .. } => {
+                !std::mem::replace(&mut segment.text, true)
+            }
+            OutputEvent::PlayAudio { channel, .. } | OutputEvent::StopAudio { channel, .. } => {
+                segment.audio.insert(channel.as_str())
+            }
+            OutputEvent::NewScene { .. } => !std::mem::replace(&mut segment.scene, true),
+            OutputEvent::NewSprite { target, .. } | OutputEvent::HideSprite { target, .. } => {
+                segment.sprite_visible(target) && segment.settled.insert(target.as_str())
+            }
+            OutputEvent::UpdateSprite { target, .. } => {
+                segment.sprite_visible(target) && segment.updated.insert(target.as_str())
+            }
+            OutputEvent::ModifyVisual { target, on_done, .. } => {
+                on_done.is_some() || segment.sprite_visible(target)
+            }
+            _ => true,
+        };
+    }
+
+    events.into_iter()
+        .zip(keep)
+        .filter_map(|(event, keep)| keep.then_some(event))
+        .collect()
+}
+
+/// 自后向前扫描时，当前分界段内已经见过的事件
+#[derive(Default)]
+struct Segment<'a> {
+    text: bool,
+    scene: bool,
+    audio: HashSet<&'a str>,
+    /// 之后还有 `NewSprite` / `HideSprite` 的立绘
+    settled: HashSet<&'a str>,
+    /// 之后还有 `UpdateSprite` 的立绘
+    updated: HashSet<&'a str>,
+}
+
+impl Segment<'_> {
+    /// 该立绘事件是否仍会影响最终画面
+    fn sprite_visible(&self, target: &str) -> bool {
+        !self.scene && !self.settled.contains(target)
+    }
+}
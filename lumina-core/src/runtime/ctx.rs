@@ -1,19 +1,40 @@
 use std::collections::{HashMap, VecDeque};
+use std::collections::vec_deque::Iter;
+use std::ops::RangeBounds;
 use serde::{Deserialize, Serialize};
-use crate::runtime::assets::{Audio, Character,DialogueRecord,Layers};
+use crate::runtime::assets::{Audio, ChannelState, Character,DialogueRecord,Layers,PresentationMode};
+use crate::runtime::history::History;
+use crate::runtime::coalesce::coalesce;
 use crate::event::OutputEvent;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Ctx {
     pub characters: HashMap<String, Character>,
     pub audios: HashMap<String, Option<Audio>>,
-    pub dialogue_history: Vec<DialogueRecord>,
+    pub dialogue_history: History,
     pub layer_record: Layers,
 
+    #[serde(default)]
+    pub presentation_mode: PresentationMode,
+    /// NVL 模式下当前页已显示的文本，`nvl clear` 时清空
+    #[serde(default)]
+    pub nvl_page: Vec<DialogueRecord>,
+    /// 正在说话的角色对应的立绘 target，旁白时为 None，供渲染层高亮立绘
+    #[serde(default)]
+    pub active_speaker: Option<String>,
+
     #[serde(default)]
     #[serde(with = "json_as_string")]
     pub var_f: serde_json::Value,
 
+    /// `lumina.random` 的 RNG 状态，读档后从此处继续
+    #[serde(default)]
+    pub rng_state: u64,
+
+    /// 各声道的实际播放状态，由渲染层每帧写入，不存档
+    #[serde(skip)]
+    pub audio_state: HashMap<String, ChannelState>,
+
     #[serde(skip)]
     pub event_queue: VecDeque<OutputEvent>,
 }
@@ -28,6 +49,14 @@ impl Ctx {
     pub fn drain(&mut self) -> Vec<OutputEvent> {
         self.event_queue.drain(..).collect()
     }
+    /// 取出事件并合并被后续事件覆盖的部分，供快进使用，规则见 [`coalesce`]
+    pub fn drain_coalesced(&mut self) -> Vec<OutputEvent> {
+        coalesce(self.drain())
+    }
+    /// 按绝对序号读取对话历史，已被丢弃的部分不返回
+    pub fn history_slice(&self, range: impl RangeBounds<usize>) -> Iter<'_, DialogueRecord> {
+        self.dialogue_history.slice(range)
+    }
 }
 
 mod json_as_string {
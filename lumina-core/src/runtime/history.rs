@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+use std::collections::vec_deque::Iter;
+use std::ops::{Bound, RangeBounds};
+use serde::{Deserialize, Serialize};
+use crate::runtime::assets::DialogueRecord;
+
+/// 对话历史：超过上限时丢弃最旧的记录。
+/// 每条记录有本局内稳定的绝对序号，丢弃旧记录不会改变其余记录的序号。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    records: VecDeque<DialogueRecord>,
+    /// 已丢弃的记录数，即 `records[0]` 的绝对序号
+    first: usize,
+}
+
+impl History {
+    /// 追加一条记录，`limit` 为 0 时不限制条数
+    pub fn push(&mut self, record: DialogueRecord, limit: usize) {
+        self.records.push_back(record);
+        if limit > 0 {
+            self.keep_last(limit);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<DialogueRecord> {
+        self.records.pop_back()
+    }
+
+    pub fn last(&self) -> Option<&DialogueRecord> {
+        self.records.back()
+    }
+
+    /// 仍保留在内存中的条数
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// 最早一条保留记录的绝对序号
+    pub fn first_index(&self) -> usize {
+        self.first
+    }
+
+    /// 本局累计的记录数 (含已丢弃的)，也是下一条记录的绝对序号
+    pub fn total(&self) -> usize {
+        self.first + self.records.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&DialogueRecord> {
+        index.checked_sub(self.first).and_then(|i| self.records.get(i))
+    }
+
+    pub fn iter(&self) -> Iter<'_, DialogueRecord> {
+        self.records.iter()
+    }
+
+    /// 按绝对序号取一段记录，超出保留范围的部分被截掉
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Iter<'_, DialogueRecord> {
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => usize::MAX,
+        };
+        let start = start.clamp(self.first, self.total()) - self.first;
+        let end = end.clamp(self.first, self.total()) - self.first;
+        self.records.range(start..end.max(start))
+    }
+
+    /// 只保留最近的 `n` 条
+    pub fn keep_last(&mut self, n: usize) {
+        let excess = self.records.len().saturating_sub(n);
+        self.records.drain(..excess);
+        self.first += excess;
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a DialogueRecord;
+    type IntoIter = Iter<'a, DialogueRecord>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
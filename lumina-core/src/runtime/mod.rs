@@ -1,5 +1,7 @@
 pub mod ctx;
 pub mod assets;
+pub mod history;
+pub mod coalesce;
 
 pub use ctx::Ctx;
 pub use assets::Character;
\ No newline at end of file
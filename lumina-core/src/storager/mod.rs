@@ -1,49 +1,232 @@
 pub mod types;
 
-use crate::storager::types::{GlobalSave, SaveFile};
-use crate::{Ctx, Executor, ScriptManager};
+use crate::storager::types::{GlobalSave, LoadOutcome, Preferences, SaveFile, SaveInfo};
+use crate::{markup, Ctx, Executor, ScriptManager};
+use crate::runtime::history::History;
 use crate::config::SystemConfig;
+use crate::lua_glue::api::meta::ACHIEVEMENTS_KEY;
+use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Once};
+use std::time::{SystemTime, UNIX_EPOCH};
+use directories::ProjectDirs;
 
-fn get_save_path(filename: &str) -> PathBuf {
+/// `system.save_path` 取此值 (或留空) 时存到系统的用户数据目录
+pub const AUTO_SAVE_PATH: &str = "auto";
+/// 旧版本默认的相对存档目录，自动模式首次运行时从这里迁移存档
+const LEGACY_SAVE_PATH: &str = "saves/";
+
+static MIGRATE: Once = Once::new();
+
+/// 实际使用的存档目录，不存在时创建
+pub fn save_dir() -> PathBuf {
     let cfg: SystemConfig = lumina_shared::config::get("system");
-    let dir = Path::new(&cfg.save_path);
+    let dir = resolve_save_dir(&cfg);
 
     if !dir.exists() {
-        let _ = fs::create_dir_all(dir);
+        let _ = fs::create_dir_all(&dir);
+    }
+    if is_auto(&cfg.save_path) {
+        MIGRATE.call_once(|| migrate_legacy_saves(Path::new(LEGACY_SAVE_PATH), &dir));
+    }
+    dir
+}
+
+fn is_auto(save_path: &str) -> bool {
+    let save_path = save_path.trim();
+    save_path.is_empty() || save_path.eq_ignore_ascii_case(AUTO_SAVE_PATH)
+}
+
+/// 按配置解析存档目录，不做任何文件操作。自动模式下找不到用户目录时退回旧的相对目录
+pub fn resolve_save_dir(cfg: &SystemConfig) -> PathBuf {
+    if !is_auto(&cfg.save_path) {
+        return PathBuf::from(&cfg.save_path);
+    }
+    match ProjectDirs::from("", "", &cfg.game_id) {
+        Some(dirs) => dirs.data_dir().join("saves"),
+        None => {
+            log::warn!("No user data directory found, saving to '{}'", LEGACY_SAVE_PATH);
+            PathBuf::from(LEGACY_SAVE_PATH)
+        }
+    }
+}
+
+/// 新目录里还没有文件时，把旧目录下的存档复制过去 (旧文件保留)，返回复制的文件数
+pub fn migrate_legacy_saves(legacy: &Path, dir: &Path) -> usize {
+    if !legacy.is_dir() || same_dir(legacy, dir) {
+        return 0;
+    }
+    let has_files = fs::read_dir(dir).map(|mut d| d.next().is_some()).unwrap_or(false);
+    if has_files {
+        return 0;
+    }
+    let Ok(entries) = fs::read_dir(legacy) else { return 0 };
+
+    let mut copied = 0;
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_file()) {
+            continue;
+        }
+        let target = dir.join(entry.file_name());
+        match fs::copy(entry.path(), &target) {
+            Ok(_) => copied += 1,
+            Err(e) => log::warn!("Failed to migrate save {:?}: {}", entry.path(), e),
+        }
+    }
+    if copied > 0 {
+        log::info!("Migrated {} save file(s) from {:?} to {:?}", copied, legacy, dir);
+    }
+    copied
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
     }
+}
 
-    dir.join(filename)
+fn get_save_path(filename: &str) -> PathBuf {
+    save_dir().join(filename)
 }
 
-pub fn save(filename: &str, ctx: Ctx, exe: Executor) -> anyhow::Result<()> {
+pub fn save(filename: &str, mut ctx: Ctx, exe: Executor) -> anyhow::Result<()> {
     let full_path = get_save_path(filename);
 
+    // 存档只带最近的若干条历史，绝对序号随之保留
+    let cfg: SystemConfig = lumina_shared::config::get("system");
+    if cfg.save_history_limit > 0 {
+        ctx.dialogue_history.keep_last(cfg.save_history_limit);
+    }
+
     let file = File::create(full_path)?;
     let mut writer = BufWriter::new(file);
     let save = SaveFile {
-        ctx: ctx.clone(),
-        stack: exe.snapshot()
+        ctx,
+        stack: exe.snapshot(),
+        prompt: exe.pending_prompt(),
+        chapter: exe.chapter(),
     };
     let config = bincode::config::standard();
     bincode::serde::encode_into_std_write(&save, &mut writer, config)?;
     Ok(())
 }
 
-pub fn load(filename: &str, manager: Arc<ScriptManager>) -> anyhow::Result<(Ctx, Executor)> {
-    let full_path = get_save_path(filename);
-    let file = File::open(full_path)?;
+/// 读取存档并恢复 Executor。脚本改动导致无法恢复时返回 [`LoadOutcome::LabelChanged`]，
+/// 由调用方决定重新开始该 label 还是放弃读档
+pub fn load(filename: &str, manager: Arc<ScriptManager>) -> anyhow::Result<(Ctx, Executor, LoadOutcome)> {
+    let save = read_save(&get_save_path(filename))?;
+    let mut exe = Executor::new(manager);
+
+    let outcome = exe.restore(save.stack);
+    if outcome == LoadOutcome::Restored {
+        if let Some(prompt) = save.prompt {
+            exe.restore_prompt(prompt);
+        }
+    }
+    Ok((save.ctx, exe, outcome))
+}
+
+/// 槽位对应的存档文件名
+pub fn slot_filename(slot: u32) -> String {
+    format!("save{}.bin", slot)
+}
+
+/// 快速存档的文件名，不会被 `list_saves` 当作槽位
+pub const QUICK_SAVE_FILE: &str = "quicksave.bin";
+
+pub fn quick_save_exists() -> bool {
+    get_save_path(QUICK_SAVE_FILE).is_file()
+}
+
+/// 自动存档槽位的文件名，不会被 `list_saves` 当作手动槽位
+pub fn autosave_filename(index: usize) -> String {
+    format!("autosave{}.bin", index)
+}
+
+/// 下一次自动存档写入的槽位：先用空槽，都有存档时覆盖最旧的
+pub fn next_autosave_slot(slots: usize) -> usize {
+    (0..slots.max(1))
+        .min_by_key(|&i| modified(&get_save_path(&autosave_filename(i))))
+        .unwrap_or(0)
+}
+
+/// 主菜单“继续游戏”读取的存档文件名：手动、快速与自动存档中最新的一个，无法解析的跳过
+pub fn continue_save() -> Option<String> {
+    let Ok(dir) = fs::read_dir(save_dir()) else { return None };
+
+    dir.filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let is_save = name == QUICK_SAVE_FILE
+                || ((name.starts_with("save") || name.starts_with("autosave")) && name.ends_with(".bin"));
+            if !is_save {
+                return None;
+            }
+            let path = e.path();
+            let info = save_info(&path, 0)?;
+            // 同一秒内的存档按精确的修改时间区分
+            Some(((info.timestamp, modified(&path)), name))
+        })
+        .max_by_key(|(key, _)| *key)
+        .map(|(_, name)| name)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn read_save(path: &Path) -> anyhow::Result<SaveFile> {
+    let file = File::open(path)?;
     let mut reader = BufReader::new(file);
     let config = bincode::config::standard();
-    let save: SaveFile = bincode::serde::decode_from_std_read(&mut reader, config)?;
-    let mut exe = Executor::new(manager);
+    Ok(bincode::serde::decode_from_std_read(&mut reader, config)?)
+}
 
-    exe.restore(save.stack);
-    Ok((save.ctx, exe))
+/// 列出存档目录中的所有槽位存档，按槽位排序；无法解析的文件跳过
+pub fn list_saves() -> Vec<SaveInfo> {
+    let Ok(dir) = fs::read_dir(save_dir()) else { return Vec::new() };
+
+    let mut saves: Vec<SaveInfo> = dir
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let slot = name.strip_prefix("save")?.strip_suffix(".bin")?.parse::<u32>().ok()?;
+            save_info(&e.path(), slot)
+        })
+        .collect();
+
+    saves.sort_by_key(|s| s.slot);
+    saves
+}
+
+/// 读取存档的概要信息，无法解析时记录日志并返回 None
+fn save_info(path: &Path, slot: u32) -> Option<SaveInfo> {
+    let save = read_save(path)
+        .map_err(|err| log::warn!("Skip unreadable save {:?}: {}", path, err))
+        .ok()?;
+
+    let timestamp = modified(path)
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let label = save.stack.first()
+        .map(|f| f.label.split('@').next().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    let preview = save.ctx.dialogue_history.last()
+        .map(|rec| {
+            let text = markup::to_plain(&rec.text);
+            match &rec.speaker {
+                Some(speaker) => format!("{}: {}", speaker, text),
+                None => text,
+            }
+        })
+        .unwrap_or_default();
+
+    let chapter = save.chapter.unwrap_or_else(|| label.clone());
+    Some(SaveInfo { slot, timestamp, label, chapter, preview })
 }
 
 pub fn save_global(filename: &str, data: &serde_json::Value) -> anyhow::Result<()> {
@@ -71,4 +254,79 @@ pub fn load_global(filename: &str) -> anyhow::Result<serde_json::Value> {
 
     let save: GlobalSave = serde_json::from_reader(reader)?;
     Ok(save.sf)
-}
\ No newline at end of file
+}
+
+/// 导出对话历史到存档目录：`.json` 结尾导出 JSON，否则导出 UTF-8 纯文本。
+/// 返回实际写入的路径
+pub fn export_history(filename: &str, history: &History) -> anyhow::Result<PathBuf> {
+    // 只取文件名部分，避免写到存档目录之外
+    let name = Path::new(filename)
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid export filename: {:?}", filename))?;
+    let full_path = get_save_path(&name.to_string_lossy());
+
+    let file = File::create(&full_path)?;
+    let mut writer = BufWriter::new(file);
+
+    if full_path.extension().map_or(false, |e| e == "json") {
+        serde_json::to_writer_pretty(&mut writer, &history.iter().collect::<Vec<_>>())?;
+    } else {
+        for rec in history {
+            let text = markup::to_plain(&rec.text);
+            match &rec.speaker {
+                Some(speaker) => write!(writer, "{}: {}", speaker, text)?,
+                None => write!(writer, "{}", text)?,
+            }
+            if let Some(voice) = &rec.voice_path {
+                write!(writer, "  [voice: {}]", voice)?;
+            }
+            writeln!(writer)?;
+        }
+    }
+    writer.flush()?;
+    Ok(full_path)
+}
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+/// 写入玩家偏好 (音量、文字速度、语言等)，与存档槽和 `global.json` 分开保存
+pub fn save_preferences(prefs: &Preferences) -> anyhow::Result<()> {
+    let file = File::create(get_save_path(PREFERENCES_FILE))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, prefs)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// 读取偏好设置，文件不存在或损坏时返回默认值
+pub fn load_preferences() -> Preferences {
+    let full_path = get_save_path(PREFERENCES_FILE);
+    if !full_path.exists() {
+        return Preferences::default();
+    }
+
+    File::open(&full_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|f| Ok(serde_json::from_reader(BufReader::new(f))?))
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to read preferences {:?}: {}", full_path, e);
+            Preferences::default()
+        })
+}
+
+/// 读取全局存档中已解锁的成就 (id -> 解锁时间戳)
+pub fn load_achievements(filename: &str) -> HashMap<String, u64> {
+    let sf = load_global(filename).unwrap_or_else(|e| {
+        log::warn!("Check global data: {}", e);
+        serde_json::Value::Null
+    });
+
+    sf.get(ACHIEVEMENTS_KEY)
+        .and_then(|v| v.as_object())
+        .map(|obj| {
+            obj.iter()
+                .map(|(id, ts)| (id.clone(), ts.as_u64().unwrap_or(0)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,4 +1,7 @@
 use crate::runtime::Ctx;
+use crate::event::OutputEvent;
+use crate::config::{AccessibilityConfig, SystemConfig};
+use std::collections::BTreeMap;
 
 use serde::{Serialize, Deserialize};
 
@@ -6,12 +9,168 @@ use serde::{Serialize, Deserialize};
 pub struct FrameSnapshot {
     pub(crate) label: String,
     pub(crate) pc:    usize,
+    /// 帧停在选项上时记下选项 id，读档时据此重新定位，脚本改动后 pc 失效也能恢复
+    #[serde(default)]
+    pub(crate) choice: Option<String>,
+    /// 存档时代码块的内容哈希，见 [`ScriptManager::block_hash`](crate::ScriptManager::block_hash)
+    #[serde(default)]
+    pub(crate) hash: u64,
+}
+
+/// 恢复调用栈的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadOutcome {
+    Restored,
+    /// 存档所在的代码块已被修改或删除，调用栈没有恢复。
+    /// `label` 为最外层的 label，可以选择从它的开头重新开始
+    LabelChanged { label: String },
+}
+
+/// 存档时 Executor 正在等待的提示。读档后原样重发，
+/// 不再重新执行所在语句，避免重复写入历史记录或重放其副作用
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum PendingPrompt {
+    Narration { lines: Vec<String> },
+    Dialogue { name: String, content: String },
+    ScriptError { line: usize, msg: String },
+    Movie { id: String, skippable: bool },
+    AudioWait { channel: String },
+    /// `arm_ids` 为各选项对应的代码块
+    Choice {
+        title: Option<String>,
+        options: Vec<String>,
+        timeout: Option<f32>,
+        default: usize,
+        arm_ids: Vec<String>,
+    },
+}
+
+impl PendingPrompt {
+    /// 从导致等待的输出事件构造，选项的 `arm_ids` 由调用方补上
+    pub fn from_event(ev: &OutputEvent) -> Option<Self> {
+        match ev {
+            OutputEvent::ShowNarration { lines } => Some(Self::Narration { lines: lines.clone() }),
+            OutputEvent::ShowDialogue { name, content } => {
+                Some(Self::Dialogue { name: name.clone(), content: content.clone() })
+            }
+            OutputEvent::ScriptError { line, msg } => Some(Self::ScriptError { line: *line, msg: msg.clone() }),
+            OutputEvent::PlayMovie { id, skippable } => Some(Self::Movie { id: id.clone(), skippable: *skippable }),
+            OutputEvent::WaitAudioEnd { channel } => Some(Self::AudioWait { channel: channel.clone() }),
+            OutputEvent::ShowChoice { title, options, timeout, default } => Some(Self::Choice {
+                title: title.clone(),
+                options: options.clone(),
+                timeout: *timeout,
+                default: *default,
+                arm_ids: Vec::new(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn to_event(&self) -> OutputEvent {
+        match self {
+            Self::Narration { lines } => OutputEvent::ShowNarration { lines: lines.clone() },
+            Self::Dialogue { name, content } => {
+                OutputEvent::ShowDialogue { name: name.clone(), content: content.clone() }
+            }
+            Self::ScriptError { line, msg } => OutputEvent::ScriptError { line: *line, msg: msg.clone() },
+            Self::Movie { id, skippable } => OutputEvent::PlayMovie { id: id.clone(), skippable: *skippable },
+            Self::AudioWait { channel } => OutputEvent::WaitAudioEnd { channel: channel.clone() },
+            Self::Choice { title, options, timeout, default, .. } => OutputEvent::ShowChoice {
+                title: title.clone(),
+                options: options.clone(),
+                timeout: *timeout,
+                default: *default,
+            },
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SaveFile {
     pub ctx: Ctx,
     pub stack: Vec<FrameSnapshot>,
+    /// 存档时正在等待的输入或选项，`None` 表示当前语句尚未执行
+    #[serde(default)]
+    pub prompt: Option<PendingPrompt>,
+    /// 存档时的章节名，见 [`ScriptManager::label_title`](crate::ScriptManager::label_title)
+    #[serde(default)]
+    pub chapter: Option<String>,
+}
+
+/// 存档列表中一个槽位的概要信息
+#[derive(Debug, Clone)]
+pub struct SaveInfo {
+    pub slot: u32,
+    /// 存档文件修改时间 (Unix 秒)
+    pub timestamp: u64,
+    /// 存档时所在的最外层 label
+    pub label: String,
+    /// 章节标题，旧存档或没有标题时为 label id
+    pub chapter: String,
+    /// 最后一条对话的纯文本
+    pub preview: String,
+}
+
+impl SaveInfo {
+    /// `YYYY-MM-DD HH:MM` (UTC)
+    pub fn time_string(&self) -> String {
+        let days = (self.timestamp / 86400) as i64;
+        let secs = self.timestamp % 86400;
+
+        // 公历换算 (Howard Hinnant, civil_from_days)
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// 玩家偏好设置，保存在 `preferences.json`。
+/// 与存档 (进度、`f`) 和 `global.json` (`sf`) 无关，跨周目保留；未设置的项使用 config 中的默认值
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Preferences {
+    #[serde(default)]
+    pub language: Option<String>,
+    /// 音频通道 (music / sound / voice) 的音量系数，0.0~1.0
+    #[serde(default)]
+    pub volumes: BTreeMap<String, f32>,
+    /// 文字逐字显示速度 (字/秒)
+    #[serde(default)]
+    pub text_speed: Option<f32>,
+    /// 下次启动时是否全屏
+    #[serde(default)]
+    pub fullscreen: bool,    /// 减少动态效果，未设置时使用 `[accessibility]` 中的值
+    #[serde(default)]
+    pub reduce_motion: Option<bool>,
+}
+
+impl Preferences {
+    /// 通道音量系数，未设置时为 1.0
+    pub fn volume(&self, channel: &str) -> f32 {
+        self.volumes.get(channel).copied().unwrap_or(1.0)
+    }
+
+    pub fn text_speed(&self) -> f32 {
+        self.text_speed.unwrap_or_else(|| {
+            let cfg: SystemConfig = lumina_shared::config::get("system");
+            cfg.text_speed
+        })
+    }
+
+    pub fn reduce_motion(&self) -> bool {
+        self.reduce_motion.unwrap_or_else(|| {
+            let cfg: AccessibilityConfig = lumina_shared::config::get("accessibility");
+            cfg.reduce_motion
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Default)]
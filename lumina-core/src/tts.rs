@@ -0,0 +1,180 @@
+//! 自助朗读 (self-voicing)：把对话、旁白与选项交给 TTS 后端朗读，TUI 与 Skia 共用 `ExecutorHandle` 中的这一处挂钩
+
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use crate::config::AccessibilityConfig;
+use crate::{markup, OutputEvent};
+
+pub trait TtsBackend: Send {
+    /// 朗读一段文本，打断正在朗读的内容
+    fn speak(&mut self, text: &str);
+    fn stop(&mut self);
+}
+
+/// 调用外部朗读程序 (如 `espeak`、`say`)，每段文本启动一个进程，停止时结束该进程。
+/// 文本经标准输入传入，以 `-` 开头的台词不会被当成命令行选项
+pub struct CommandTts {
+    program: String,
+    args: Vec<String>,
+    child: Option<Child>,
+}
+
+impl CommandTts {
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self { program: program.into(), args, child: None }
+    }
+
+    /// 按 `tts_command` 配置创建，未配置时使用平台默认命令；不支持的平台返回 `None`
+    pub fn from_config(cfg: &AccessibilityConfig) -> Option<Self> {
+        // 两者不带文本参数时都从标准输入读取
+        if let Some((program, args)) = cfg.tts_command.split_first() {
+            return Some(Self::new(program.clone(), args.to_vec()));
+        }
+        if cfg!(target_os = "macos") {
+            Some(Self::new("say", vec![]))
+        } else if cfg!(target_os = "linux") {
+            Some(Self::new("espeak", vec![]))
+        } else {
+            None
+        }
+    }
+}
+
+impl TtsBackend for CommandTts {
+    fn speak(&mut self, text: &str) {
+        self.stop();
+        let spawned = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                log::warn!("TTS command '{}' failed: {}", self.program, e);
+                return;
+            }
+        };
+        // 写完即关闭标准输入，朗读程序读到 EOF 后开始朗读
+        let written = child.stdin.take().map(|mut stdin| stdin.write_all(text.as_bytes()));
+        if let Some(Err(e)) = written {
+            log::warn!("TTS command '{}' did not accept text: {}", self.program, e);
+        }
+        self.child = Some(child);
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for CommandTts {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 系统语音 API (Windows SAPI / WinRT、macOS AVFoundation、Linux speech-dispatcher)
+#[cfg(feature = "tts")]
+pub struct SystemTts(tts::Tts);
+
+#[cfg(feature = "tts")]
+impl SystemTts {
+    pub fn new() -> Result<Self, tts::Error> {
+        tts::Tts::default().map(Self)
+    }
+}
+
+#[cfg(feature = "tts")]
+impl TtsBackend for SystemTts {
+    fn speak(&mut self, text: &str) {
+        if let Err(e) = self.0.speak(text, true) {
+            log::warn!("TTS speak failed: {}", e);
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Err(e) = self.0.stop() {
+            log::warn!("TTS stop failed: {}", e);
+        }
+    }
+}
+
+/// 按配置选择后端：显式配置的命令优先，其次是系统语音 (`tts` feature)，最后是平台默认命令
+pub fn backend_from_config(cfg: &AccessibilityConfig) -> Option<Box<dyn TtsBackend>> {
+    #[cfg(feature = "tts")]
+    if cfg.tts_command.is_empty() {
+        match SystemTts::new() {
+            Ok(backend) => return Some(Box::new(backend)),
+            Err(e) => log::warn!("System TTS unavailable, falling back to a command: {}", e),
+        }
+    }
+    CommandTts::from_config(cfg).map(|b| Box::new(b) as Box<dyn TtsBackend>)
+}
+
+/// 事件对应的朗读文本：对话带说话人，选项读标题与编号后的各项
+pub fn speech_text(ev: &OutputEvent) -> Option<String> {
+    match ev {
+        OutputEvent::ShowNarration { lines } => {
+            Some(lines.iter()
+                .filter(|l| !l.is_empty())
+                .map(|l| markup::to_plain(l))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        OutputEvent::ShowDialogue { name, content } => {
+            Some(format!("{}: {}", markup::to_plain(name), markup::to_plain(content)))
+        }
+        OutputEvent::ShowChoice { title, options, .. } => {
+            let mut parts: Vec<String> = title.iter().map(|t| markup::to_plain(t)).collect();
+            parts.extend(options.iter().enumerate()
+                .map(|(i, opt)| format!("{}. {}", i + 1, markup::to_plain(opt))));
+            Some(parts.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+pub struct SelfVoicing {
+    backend: Box<dyn TtsBackend>,
+    skip_voiced_lines: bool,
+}
+
+impl SelfVoicing {
+    pub fn new(backend: Box<dyn TtsBackend>, skip_voiced_lines: bool) -> Self {
+        Self { backend, skip_voiced_lines }
+    }
+
+    /// 按 `[accessibility]` 配置创建，未开启朗读时返回 `None`
+    pub fn from_config() -> Option<Self> {
+        let cfg: AccessibilityConfig = lumina_shared::config::get("accessibility");
+        if !cfg.self_voicing {
+            return None;
+        }
+        match backend_from_config(&cfg) {
+            Some(backend) => Some(Self::new(backend, cfg.skip_voiced_lines)),
+            None => {
+                log::warn!("Self-voicing enabled but no TTS command for this platform, set accessibility.tts_command");
+                None
+            }
+        }
+    }
+
+    /// 朗读本帧事件中最后一段文本；明确指定了配音的对话按配置跳过
+    pub fn observe(&mut self, events: &[OutputEvent]) {
+        let Some(text) = events.iter().rev().find_map(speech_text) else { return };
+        let voiced = events.iter().any(|e| matches!(e,
+            OutputEvent::PlayAudio { channel, speculative: false, .. } if channel == "voice"));
+        if voiced && self.skip_voiced_lines {
+            self.backend.stop();
+            return;
+        }
+        self.backend.speak(&text);
+    }
+
+    pub fn cancel(&mut self) {
+        self.backend.stop();
+    }
+}
@@ -0,0 +1,29 @@
+use mlua::Lua;
+use lumina_core::lua_glue::{self, api::visual};
+
+#[test]
+fn completion_runs_callbacks_and_marks_done() {
+    let _ = lumina_shared::config::init("test_dummy.toml");
+    let lua = Lua::new();
+    let cb = lua_glue::init_lua(&lua);
+
+    lua.load(r#"
+        hits = {}
+        lumina.transform("yuki", { x = 100 }, 0.5, "linear", "walk")
+        lumina.on_animation_done("walk", function(id) table.insert(hits, id) end)
+        lumina.transform("hana", { alpha = 0 }, 0.5, "linear", function() table.insert(hits, "fade") end)
+    "#).exec().unwrap();
+    assert_eq!(cb.drain().len(), 2);
+    assert!(!lua.load(r#"return lumina.animation_done("walk")"#).eval::<bool>().unwrap());
+
+    visual::animation_done(&lua, "walk").unwrap();
+    visual::animation_done(&lua, "__anim:1").unwrap();
+    let hits: Vec<String> = lua.load("return hits").eval().unwrap();
+    assert_eq!(hits, vec!["walk", "fade"]);
+    assert!(lua.load(r#"return lumina.animation_done("walk")"#).eval::<bool>().unwrap());
+
+    // 已完成的动画注册回调时立即调用
+    lua.load(r#"lumina.on_animation_done("walk", function() table.insert(hits, "late") end)"#).exec().unwrap();
+    let hits: Vec<String> = lua.load("return hits").eval().unwrap();
+    assert_eq!(hits.last().map(String::as_str), Some("late"));
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use lumina_core::event::InputEvent;
+use lumina_core::renderer::driver::{ExecutorHandle, MAX_STEPS_PER_FRAME};
+use lumina_core::storager;
+use lumina_core::{Ctx, ScriptManager};
+
+#[test]
+fn autosaves_on_new_label_and_rotates_slots() {
+    let root = std::env::temp_dir().join(format!("lumina_test_autosave_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("game")).unwrap();
+    let save_dir = root.join("saves");
+    let overrides = [
+        format!("system.save_path={:?}", save_dir.to_string_lossy()),
+        "system.autosave_slots=2".to_string(),
+        "system.autosave_lines=0".to_string(),
+        "system.autosave_interval=0.0".to_string(),
+    ];
+    let _ = lumina_shared::config::init_with("test_dummy.toml", &overrides);
+
+    std::fs::write(root.join("game/test.vivi"), r#"
+label init
+    : one
+    jump second
+enlb
+label second
+    : two
+    jump third
+enlb
+label third
+    : three
+enlb
+"#).unwrap();
+    let mut manager = ScriptManager::new();
+    manager.load_project(root.join("game")).unwrap();
+
+    let mut ctx = Ctx::default();
+    let mut driver = ExecutorHandle::new(&mut ctx, Arc::new(manager), "init");
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    // 开局的第一个 label 不触发自动存档
+    assert_eq!(storager::continue_save(), None);
+
+    driver.feed(&mut ctx, InputEvent::Continue);
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    assert!(save_dir.join(storager::autosave_filename(0)).is_file());
+    assert_eq!(storager::continue_save().as_deref(), Some("autosave0.bin"));
+    assert_eq!(storager::next_autosave_slot(2), 1);
+
+    driver.feed(&mut ctx, InputEvent::Continue);
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    assert!(save_dir.join(storager::autosave_filename(1)).is_file());
+    // 自动存档与手动槽位分开
+    assert!(storager::list_saves().is_empty());
+
+    // “继续游戏”取所有存档中最新的一个
+    driver.feed(&mut ctx, InputEvent::QuickSave);
+    assert_eq!(storager::continue_save().as_deref(), Some(storager::QUICK_SAVE_FILE));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,132 @@
+use lumina_core::OutputEvent;
+use lumina_core::runtime::coalesce::coalesce;
+
+fn say(text: &str) -> OutputEvent {
+    OutputEvent::ShowNarration { lines: vec![text.to_string()] }
+}
+
+fn dialogue(name: &str, text: &str) -> OutputEvent {
+    OutputEvent::ShowDialogue { name: name.to_string(), content: text.to_string() }
+}
+
+fn play(channel: &str, path: &str) -> OutputEvent {
+    OutputEvent::PlayAudio {
+        channel: channel.to_string(), path: path.to_string(),
+        fade_in: 0.0, volume: 1.0, looping: false, loop_region: None, speculative: false, pitch: None, intro: None,
+    }
+}
+
+fn stop(channel: &str) -> OutputEvent {
+    OutputEvent::StopAudio { channel: channel.to_string(), fade_out: 0.0 }
+}
+
+fn show(target: &str, texture: &str) -> OutputEvent {
+    OutputEvent::NewSprite {
+        target: target.to_string(), texture: texture.to_string(),
+        pos_str: None, transition: None, duration: None, attrs: vec![], defer_visual: false,
+    }
+}
+
+fn update(target: &str, transition: &str) -> OutputEvent {
+    OutputEvent::UpdateSprite { target: target.to_string(), transition: transition.to_string(), duration: None }
+}
+
+fn hide(target: &str) -> OutputEvent {
+    OutputEvent::HideSprite { target: target.to_string(), transition: None, duration: None }
+}
+
+fn modify(target: &str) -> OutputEvent {
+    OutputEvent::ModifyVisual { target: target.to_string(), props: Default::default(), duration: 0.0, easing: "linear".into(), on_done: None }
+}
+
+fn scene(transition: &str) -> OutputEvent {
+    OutputEvent::NewScene { background: None, transition: transition.to_string(), duration: None }
+}
+
+fn choice() -> OutputEvent {
+    OutputEvent::ShowChoice { title: None, options: vec!["a".into()], timeout: None, default: 0 }
+}
+
+/// 把事件压成简短标签便于比较
+fn labels(events: Vec<OutputEvent>) -> Vec<String> {
+    coalesce(events).into_iter()
+        .map(|e| match e {
+            OutputEvent::ShowNarration { lines } => format!("say {}", lines.join("")),
+            OutputEvent::ShowDialogue { name, content } => format!("say {}: {}", name, content),
+            OutputEvent::ShowChoice { .. } => "choice".into(),
+            OutputEvent::PlayAudio { channel, path, .. } => format!("play {} {}", channel, path),
+            OutputEvent::StopAudio { channel, .. } => format!("stop {}", channel),
+            OutputEvent::NewScene { transition, .. } => format!("scene {}", transition),
+            OutputEvent::NewSprite { target, texture, .. } => format!("show {} {}", target, texture),
+            OutputEvent::UpdateSprite { target, transition, .. } => format!("update {} {}", target, transition),
+            OutputEvent::HideSprite { target, .. } => format!("hide {}", target),
+            OutputEvent::ModifyVisual { target, .. } => format!("modify {}", target),
+            OutputEvent::ScriptError { line, .. } => format!("error {}", line),
+            OutputEvent::End => "end".into(),
+            other => format!("{:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn text_keeps_only_latest() {
+    assert_eq!(labels(vec![say("a"), dialogue("b", "hi"), say("c")]), ["say c"]);
+    assert_eq!(labels(vec![say("a"), dialogue("b", "hi")]), ["say b: hi"]);
+    assert!(labels(vec![]).is_empty());
+}
+
+#[test]
+fn audio_keeps_last_per_channel() {
+    assert_eq!(
+        labels(vec![play("music", "a"), play("sound", "x"), play("music", "b"), stop("sound")]),
+        ["play music b", "stop sound"],
+    );
+    assert_eq!(labels(vec![stop("music"), play("music", "a")]), ["play music a"]);
+}
+
+#[test]
+fn sprites_collapse_to_final_state() {
+    // 最后一次 show/hide 之前的事件都被覆盖
+    assert_eq!(labels(vec![show("a", "1"), update("a", "d"), hide("a")]), ["hide a"]);
+    assert_eq!(labels(vec![hide("a"), show("a", "2")]), ["show a 2"]);
+    // 之后的 update/modify 保留，连续 update 只留最后一条
+    assert_eq!(
+        labels(vec![show("a", "1"), update("a", "d"), modify("a"), update("a", "e")]),
+        ["show a 1", "modify a", "update a e"],
+    );
+    // 不同立绘互不影响
+    assert_eq!(
+        labels(vec![show("a", "1"), show("b", "1"), hide("a")]),
+        ["show b 1", "hide a"],
+    );
+}
+
+#[test]
+fn scene_drops_earlier_sprites() {
+    assert_eq!(
+        labels(vec![show("a", "1"), scene("x"), update("a", "d"), scene("y"), show("b", "1")]),
+        ["scene y", "show b 1"],
+    );
+}
+
+#[test]
+fn choice_error_and_end_are_barriers() {
+    assert_eq!(
+        labels(vec![say("a"), say("b"), choice(), say("c"), say("d")]),
+        ["say b", "choice", "say d"],
+    );
+    assert_eq!(
+        labels(vec![play("music", "a"), OutputEvent::ScriptError { line: 3, msg: String::new() }, play("music", "b")]),
+        ["play music a", "error 3", "play music b"],
+    );
+    assert_eq!(labels(vec![choice(), choice(), OutputEvent::End, OutputEvent::End]), ["choice", "choice", "end", "end"]);
+}
+
+#[test]
+fn other_events_are_kept_in_order() {
+    let preload = || OutputEvent::Preload { images: vec![], audios: vec![] };
+    let out = labels(vec![preload(), say("a"), preload(), say("b"), OutputEvent::StepDone]);
+    assert_eq!(out.len(), 4);
+    assert_eq!(out[2], "say b");
+    assert_eq!(out[3], "StepDone");
+}
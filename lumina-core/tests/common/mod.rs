@@ -0,0 +1,44 @@
+//! 集成测试共用的脚本项目夹具：把脚本写进临时目录再交给 `ScriptManager` 加载
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use lumina_core::ScriptManager;
+
+/// 在 `<temp>/lumina_test_<name>` 下写出项目文件，`files` 为 (相对路径, 内容)。
+/// 同名目录先清空，调用方负责删除
+pub fn project_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    // 忽略重复初始化，测试环境没有 config 文件
+    let _ = lumina_shared::config::init("test_dummy.toml");
+
+    let dir = std::env::temp_dir().join(format!("lumina_test_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, src) in files {
+        let path = dir.join(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, src).unwrap();
+    }
+    dir
+}
+
+/// 加载多文件项目，加载完即删除临时目录；加载失败时一并返回错误
+pub fn try_load_files(name: &str, files: &[(&str, &str)]) -> (ScriptManager, anyhow::Result<()>) {
+    let dir = project_dir(name, files);
+    let mut manager = ScriptManager::new();
+    let result = manager.load_project(&dir);
+    let _ = std::fs::remove_dir_all(&dir);
+    (manager, result)
+}
+
+pub fn load_files(name: &str, files: &[(&str, &str)]) -> ScriptManager {
+    let (manager, result) = try_load_files(name, files);
+    result.expect("Failed to load test project");
+    manager
+}
+
+/// 只有一个 `test.vivi` 的项目
+pub fn load(name: &str, src: &str) -> ScriptManager {
+    load_files(name, &[("test.vivi", src)])
+}
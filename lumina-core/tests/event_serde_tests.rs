@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+use lumina_core::event::{AnimState, Envelope, InputEvent, LayoutConfig, TransitionConfig, PROTOCOL_VERSION};
+use lumina_core::runtime::assets::PresentationMode;
+use lumina_core::OutputEvent;
+
+/// 没有通配分支：新增变体时这里编译失败，提醒补上样例
+fn output_name(ev: &OutputEvent) -> &'static str {
+    match ev {
+        OutputEvent::ShowNarration { .. } => "ShowNarration",
+        OutputEvent::ShowDialogue { .. } => "ShowDialogue",
+        OutputEvent::ShowChoice { .. } => "ShowChoice",
+        OutputEvent::PlayAudio { .. } => "PlayAudio",
+        OutputEvent::StopAudio { .. } => "StopAudio",
+        OutputEvent::PlayMovie { .. } => "PlayMovie",
+        OutputEvent::WaitAudioEnd { .. } => "WaitAudioEnd",
+        OutputEvent::NewScene { .. } => "NewScene",
+        OutputEvent::NewSprite { .. } => "NewSprite",
+        OutputEvent::UpdateSprite { .. } => "UpdateSprite",
+        OutputEvent::HideSprite { .. } => "HideSprite",
+        OutputEvent::Preload { .. } => "Preload",
+        OutputEvent::SetVolume { .. } => "SetVolume",
+        OutputEvent::ModifyVisual { .. } => "ModifyVisual",
+        OutputEvent::RegisterLayout { .. } => "RegisterLayout",
+        OutputEvent::RegisterTransition { .. } => "RegisterTransition",
+        OutputEvent::Shake { .. } => "Shake",
+        OutputEvent::Flash { .. } => "Flash",
+        OutputEvent::SetSpriteAnim { .. } => "SetSpriteAnim",
+        OutputEvent::AchievementUnlocked { .. } => "AchievementUnlocked",
+        OutputEvent::Toast { .. } => "Toast",
+        OutputEvent::SetPresentation { .. } => "SetPresentation",
+        OutputEvent::DebugOutput { .. } => "DebugOutput",
+        OutputEvent::ScriptError { .. } => "ScriptError",
+        OutputEvent::Warning { .. } => "Warning",
+        OutputEvent::SaveMismatch { .. } => "SaveMismatch",
+        OutputEvent::QuickSaveDone { .. } => "QuickSaveDone",
+        OutputEvent::StepDone => "StepDone",
+        OutputEvent::End => "End",
+    }
+}
+
+fn input_name(ev: &InputEvent) -> &'static str {
+    match ev {
+        InputEvent::ChoiceMade { .. } => "ChoiceMade",
+        InputEvent::Continue => "Continue",
+        InputEvent::Exit => "Exit",
+        InputEvent::SaveRequest { .. } => "SaveRequest",
+        InputEvent::LoadRequest { .. } => "LoadRequest",
+        InputEvent::QuickSave => "QuickSave",
+        InputEvent::QuickLoad => "QuickLoad",
+        InputEvent::ExportHistory { .. } => "ExportHistory",
+        InputEvent::SetAuto { .. } => "SetAuto",
+        InputEvent::SetSkip { .. } => "SetSkip",
+        InputEvent::ResolveLoad { .. } => "ResolveLoad",
+        InputEvent::DebugJump { .. } => "DebugJump",
+        InputEvent::DebugGoto { .. } => "DebugGoto",
+        InputEvent::DebugEval { .. } => "DebugEval",
+        InputEvent::DebugReload => "DebugReload",
+        InputEvent::DebugChapters => "DebugChapters",
+        InputEvent::SetVar { .. } => "SetVar",
+        InputEvent::AnimationDone { .. } => "AnimationDone",
+    }
+}
+
+fn s(v: &str) -> String {
+    v.to_string()
+}
+
+/// 每个变体一个样例，字段尽量取非默认值
+fn output_samples() -> Vec<OutputEvent> {
+    vec![
+        OutputEvent::ShowNarration { lines: vec![s("a"), s("b")] },
+        OutputEvent::ShowDialogue { name: s("Yuki"), content: s("hi") },
+        OutputEvent::ShowChoice { title: Some(s("?")), options: vec![s("x"), s("y")], timeout: Some(5.0), default: 1 },
+        OutputEvent::PlayAudio {
+            channel: s("music"), path: s("theme"), fade_in: 0.5, volume: 0.8, looping: true,
+            loop_region: Some((1.5, Some(30.0))), speculative: true, pitch: Some(1.2),
+            intro: Some(s("town_intro")),
+        },
+        OutputEvent::StopAudio { channel: s("music"), fade_out: 1.0 },
+        OutputEvent::PlayMovie { id: s("op"), skippable: true },
+        OutputEvent::WaitAudioEnd { channel: s("sound") },
+        OutputEvent::NewScene { background: Some(s("bg_room")), transition: s("fade"), duration: Some(1.2) },
+        OutputEvent::NewSprite {
+            target: s("yuki"), texture: s("yuki"), pos_str: Some(s("left")), transition: Some(s("dissolve")), duration: Some(0.8),
+            attrs: vec![s("smile")], defer_visual: true,
+        },
+        OutputEvent::UpdateSprite { target: s("yuki"), transition: s("dissolve"), duration: Some(0.4) },
+        OutputEvent::HideSprite { target: s("yuki"), transition: Some(s("fade")), duration: Some(2.0) },
+        OutputEvent::Preload { images: vec![s("bg_room")], audios: vec![s("theme")] },
+        OutputEvent::SetVolume { channel: s("voice"), value: 0.25, fade: 0.5 },
+        OutputEvent::ModifyVisual {
+            target: s("yuki"), props: HashMap::from([(s("x"), 100.0)]), duration: 0.5,
+            easing: s("ease_out"), on_done: Some(s("walk")),
+        },
+        OutputEvent::RegisterLayout {
+            name: s("far_left"),
+            config: LayoutConfig { x: 0.1, y: 1.0, anchor_x: 0.5, anchor_y: 1.0 },
+        },
+        OutputEvent::RegisterTransition {
+            name: s("slide"),
+            config: TransitionConfig {
+                duration: 0.4, easing: s("linear"), props: HashMap::from([(s("x"), (Some(-200.0), 0.0))]),
+                mask_img: Some(s("rule")), vague: Some(0.2),
+            },
+        },
+        OutputEvent::Shake { target: s("screen"), intensity: 20.0, duration: 0.5 },
+        OutputEvent::Flash { color: [255, 0, 0, 128], duration: 0.3 },
+        OutputEvent::SetSpriteAnim {
+            target: s("alice"),
+            state: AnimState::Talk,
+            frames: vec![s(""), s("mouth_open")],
+            fps: 6.0,
+            looping: true,
+        },
+        OutputEvent::AchievementUnlocked { id: s("first_end") },
+        OutputEvent::Toast { text: s("Autosaved"), duration: 1.5 },
+        OutputEvent::SetPresentation { mode: PresentationMode::Nvl },
+        OutputEvent::DebugOutput { text: s("ok") },
+        OutputEvent::ScriptError { line: 3, msg: s("bad") },
+        OutputEvent::Warning { line: 4, msg: s("hmm") },
+        OutputEvent::SaveMismatch { label: s("init") },
+        OutputEvent::QuickSaveDone { load: true, ok: false },
+        OutputEvent::StepDone,
+        OutputEvent::End,
+    ]
+}
+
+fn input_samples() -> Vec<InputEvent> {
+    vec![
+        InputEvent::ChoiceMade { index: 2 },
+        InputEvent::Continue,
+        InputEvent::Exit,
+        InputEvent::SaveRequest { slot: 3 },
+        InputEvent::LoadRequest { slot: 4 },
+        InputEvent::QuickSave,
+        InputEvent::QuickLoad,
+        InputEvent::ExportHistory { filename: s("history.json") },
+        InputEvent::SetAuto { enabled: true },
+        InputEvent::SetSkip { enabled: true },
+        InputEvent::ResolveLoad { restart: true },
+        InputEvent::DebugJump { label: s("chapter2") },
+        InputEvent::DebugGoto { label: s("chapter2"), pc: 7 },
+        InputEvent::DebugEval { code: s("return f") },
+        InputEvent::DebugReload,
+        InputEvent::DebugChapters,
+        InputEvent::SetVar { name: s("player_name"), value: s("Alice") },
+        InputEvent::AnimationDone { id: s("walk") },
+    ]
+}
+
+#[test]
+fn every_output_event_round_trips() {
+    let samples = output_samples();
+    let names: HashSet<&str> = samples.iter().map(output_name).collect();
+    assert_eq!(names.len(), samples.len(), "each OutputEvent variant needs exactly one sample");
+
+    for ev in samples {
+        let json = serde_json::to_string(&Envelope::new(ev.clone())).unwrap();
+        let back: Envelope<OutputEvent> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.into_event().unwrap(), ev, "{} did not round-trip", output_name(&ev));
+    }
+}
+
+#[test]
+fn every_input_event_round_trips() {
+    let samples = input_samples();
+    let names: HashSet<&str> = samples.iter().map(input_name).collect();
+    assert_eq!(names.len(), samples.len(), "each InputEvent variant needs exactly one sample");
+
+    for ev in samples {
+        let json = serde_json::to_string(&Envelope::new(ev.clone())).unwrap();
+        let back: Envelope<InputEvent> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.into_event().unwrap(), ev, "{} did not round-trip", input_name(&ev));
+    }
+}
+
+#[test]
+fn mismatched_protocol_version_is_rejected() {
+    let json = format!(r#"{{"version":{},"event":"Continue"}}"#, PROTOCOL_VERSION + 1);
+    let envelope: Envelope<InputEvent> = serde_json::from_str(&json).unwrap();
+    assert!(envelope.into_event().is_err());
+}
@@ -0,0 +1,805 @@
+use std::sync::Arc;
+use lumina_core::event::InputEvent;
+use lumina_core::runtime::assets::PresentationMode;
+use lumina_core::renderer::driver::{DebugSnapshot, ExecutorHandle, MAX_STEPS_PER_FRAME};
+use lumina_core::storager::types::{LoadOutcome, SaveFile};
+use lumina_core::{Ctx, Executor, OutputEvent, ScriptManager};
+
+mod common;
+
+fn load_manager(name: &str, src: &str) -> Arc<ScriptManager> {
+    Arc::new(common::load(name, src))
+}
+
+fn setup(name: &str, src: &str) -> (Ctx, Executor) {
+    let mut ctx = Ctx::default();
+    let mut exe = Executor::new(load_manager(name, src));
+    exe.start(&mut ctx, "init");
+    (ctx, exe)
+}
+
+/// 步进直到 Executor 等待输入，返回期间产生的事件
+fn run_until_wait(ctx: &mut Ctx, exe: &mut Executor) -> Vec<OutputEvent> {
+    for _ in 0..100 {
+        if exe.step(ctx) {
+            break;
+        }
+    }
+    ctx.drain()
+}
+
+fn narration(events: &[OutputEvent]) -> Vec<String> {
+    events.iter()
+        .filter_map(|e| match e {
+            OutputEvent::ShowNarration { lines } => Some(lines.join("\n")),
+            _ => None,
+        })
+        .collect()
+}
+
+const CHOICE_SCRIPT: &str = r#"
+label init
+    choice "pick"
+        "A":
+            : picked A
+        "B":
+            : picked B
+    enco
+    : after
+enlb
+"#;
+
+#[test]
+fn repeated_choice_is_ignored() {
+    let (mut ctx, mut exe) = setup("repeated_choice", CHOICE_SCRIPT);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::ShowChoice { .. })));
+
+    exe.feed(InputEvent::ChoiceMade { index: 0 });
+    exe.feed(InputEvent::ChoiceMade { index: 1 });
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), vec!["picked A".to_string()]);
+}
+
+#[test]
+fn out_of_range_choice_keeps_menu() {
+    let (mut ctx, mut exe) = setup("out_of_range_choice", CHOICE_SCRIPT);
+    run_until_wait(&mut ctx, &mut exe);
+
+    exe.feed(InputEvent::ChoiceMade { index: 5 });
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(narration(&events).is_empty());
+
+    exe.feed(InputEvent::ChoiceMade { index: 1 });
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), vec!["picked B".to_string()]);
+}
+
+#[test]
+fn repeated_continue_does_not_skip_lines() {
+    let (mut ctx, mut exe) = setup("repeated_continue", CHOICE_SCRIPT);
+    run_until_wait(&mut ctx, &mut exe);
+
+    exe.feed(InputEvent::ChoiceMade { index: 0 });
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), vec!["picked A".to_string()]);
+
+    exe.feed(InputEvent::Continue);
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), vec!["after".to_string()]);
+}
+
+const LINES_SCRIPT: &str = r#"
+label init
+    : one
+    : two
+    : three
+enlb
+"#;
+
+#[test]
+fn each_wait_point_consumes_one_input() {
+    let manager = load_manager("handle_steps", LINES_SCRIPT);
+
+    // 与 TuiRenderer 相同的驱动方式，只是不绘制
+    let mut ctx = Ctx::default();
+    let mut driver = ExecutorHandle::new(&mut ctx, manager, "init");
+    let mut shown = Vec::new();
+    let mut inputs = 0;
+    loop {
+        let waiting = driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+        let events = ctx.drain();
+        if events.iter().any(|e| matches!(e, OutputEvent::End)) {
+            break;
+        }
+        let lines = narration(&events);
+        assert!(lines.len() <= 1, "one frame advanced past a wait point: {:?}", lines);
+        shown.extend(lines);
+
+        assert!(waiting);
+        driver.feed(&mut ctx, InputEvent::Continue);
+        driver.feed(&mut ctx, InputEvent::Continue);
+        inputs += 1;
+    }
+
+    assert_eq!(shown, vec!["one", "two", "three"]);
+    assert_eq!(inputs, 3);
+}
+
+#[test]
+fn nvl_page_accumulates_and_clears() {
+    let (mut ctx, mut exe) = setup("nvl_page", r#"
+label init
+    nvl on
+    : first
+    : second
+    nvl clear
+    : third
+    nvl off
+    : fourth
+enlb
+"#);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::SetPresentation { mode: PresentationMode::Nvl })));
+
+    exe.feed(InputEvent::Continue);
+    run_until_wait(&mut ctx, &mut exe);
+    let page: Vec<_> = ctx.nvl_page.iter().map(|r| r.text.as_str()).collect();
+    assert_eq!(page, vec!["first", "second"]);
+
+    exe.feed(InputEvent::Continue);
+    run_until_wait(&mut ctx, &mut exe);
+    let page: Vec<_> = ctx.nvl_page.iter().map(|r| r.text.as_str()).collect();
+    assert_eq!(page, vec!["third"]);
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::SetPresentation { mode: PresentationMode::Adv })));
+    assert_eq!(ctx.nvl_page.len(), 1);
+}
+
+#[test]
+fn debug_jump_to_unknown_label_is_an_error() {
+    let src = "label init\n    : first\nenlb\nlabel other\n    : second\nenlb\n";
+    let (mut ctx, mut exe) = setup("debug_jump", src);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), vec!["first"]);
+
+    assert!(exe.debug_jump("missing").is_err());
+    exe.debug_jump("other").unwrap();
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), vec!["second"]);
+
+    assert_eq!(exe.debug_eval("f.flag = true"), "ok");
+    assert_eq!(exe.debug_eval("return f.flag"), "true");
+}
+
+#[test]
+fn script_jump_to_unknown_label_stops_with_error() {
+    let src = "label init\n    : first\n    jump missing\n    : never\nenlb\n";
+    let (mut ctx, mut exe) = setup("script_jump_missing", src);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), vec!["first"]);
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::ScriptError { line: 3, msg } if msg.contains("missing"))), "{:?}", events);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::End)));
+    assert!(narration(&events).is_empty());
+}
+
+#[test]
+fn debug_goto_starts_mid_label() {
+    let src = "label init\n    : first\nenlb\nlabel chapter5\n    : a\n    : b\n    : c\nenlb\n";
+    let (mut ctx, mut exe) = setup("debug_goto", src);
+    run_until_wait(&mut ctx, &mut exe);
+
+    assert!(exe.debug_goto("chapter5", 3).is_err());
+    assert!(exe.debug_goto("missing", 0).is_err());
+    exe.debug_goto("chapter5", 1).unwrap();
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), vec!["b"]);
+    exe.feed(InputEvent::Continue);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), vec!["c"]);
+}
+
+#[test]
+fn pending_choice_with_stable_id_survives_script_edit() {
+    let (mut ctx, mut exe) = setup("choice_id_v1", r#"
+label init
+    : intro
+    choice "Where to?" as route:
+        "Left":
+            : went left
+        "Right":
+            : went right
+    enco
+enlb
+"#);
+    run_until_wait(&mut ctx, &mut exe);
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::ShowChoice { .. })));
+    let snapshot = exe.snapshot();
+
+    // 在选项前插入新的台词与一个无 id 的选项，原来的 pc 已经失效
+    let (mut ctx, mut edited) = setup("choice_id_v2", r#"
+label init
+    : new opening
+    : intro
+    choice "Warm up"
+        "Ok":
+            : fine
+    enco
+    : more
+    choice "Where to?" as route:
+        "Left":
+            : went left
+        "Right":
+            : went right
+    enco
+enlb
+"#);
+    assert_eq!(edited.restore(snapshot), LoadOutcome::Restored);
+    ctx.drain();
+
+    let events = run_until_wait(&mut ctx, &mut edited);
+    let title = events.iter().find_map(|e| match e {
+        OutputEvent::ShowChoice { title, .. } => title.clone(),
+        _ => None,
+    });
+    assert_eq!(title.as_deref(), Some("Where to?"));
+
+    edited.feed(InputEvent::ChoiceMade { index: 1 });
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut edited)), ["went right"]);
+}
+
+#[test]
+fn duplicate_choice_ids_are_rejected() {
+    let choice = "choice as route:\n    \"A\":\n        : a\nenco\n";
+    let (_, result) = common::try_load_files("choice_id_dup", &[
+        ("a.vivi", &format!("label a\n{}enlb\n", choice)),
+        ("b.vivi", &format!("label b\n{}enlb\n", choice)),
+    ]);
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("Choice id collision"), "{}", err);
+}
+
+/// 经 bincode 编解码存档后重建 Executor，与读档流程一致
+fn save_and_load(ctx: &Ctx, exe: &Executor, manager: Arc<ScriptManager>) -> (Ctx, Executor) {
+    let save = SaveFile { ctx: ctx.clone(), stack: exe.snapshot(), prompt: exe.pending_prompt(), chapter: None };
+    let config = bincode::config::standard();
+    let bytes = bincode::serde::encode_to_vec(&save, config).unwrap();
+    let (save, _): (SaveFile, usize) = bincode::serde::decode_from_slice(&bytes, config).unwrap();
+
+    let mut exe = Executor::new(manager);
+    exe.restore(save.stack);
+    if let Some(prompt) = save.prompt {
+        exe.restore_prompt(prompt);
+    }
+    (save.ctx, exe)
+}
+
+const SAVE_SCRIPT: &str = r#"
+label init
+    : first
+    choice "pick"
+        "A":
+            : picked A
+        "B":
+            : picked B
+    enco
+enlb
+"#;
+
+#[test]
+fn save_at_text_restores_without_duplicate_history() {
+    let manager = load_manager("save_text", SAVE_SCRIPT);
+    let mut ctx = Ctx::default();
+    let mut exe = Executor::new(manager.clone());
+    exe.start(&mut ctx, "init");
+    run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(ctx.dialogue_history.len(), 1);
+
+    let (mut ctx, mut exe) = save_and_load(&ctx, &exe, manager);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), ["first"]);
+    assert_eq!(ctx.dialogue_history.len(), 1);
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::ShowChoice { .. })));
+}
+
+#[test]
+fn save_at_choice_restores_menu() {
+    let manager = load_manager("save_choice", SAVE_SCRIPT);
+    let mut ctx = Ctx::default();
+    let mut exe = Executor::new(manager.clone());
+    exe.start(&mut ctx, "init");
+    run_until_wait(&mut ctx, &mut exe);
+    exe.feed(InputEvent::Continue);
+    run_until_wait(&mut ctx, &mut exe);
+
+    let (mut ctx, mut exe) = save_and_load(&ctx, &exe, manager);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    let options = events.iter().find_map(|e| match e {
+        OutputEvent::ShowChoice { title, options, .. } => Some((title.clone(), options.clone())),
+        _ => None,
+    });
+    assert_eq!(options, Some((Some("pick".to_string()), vec!["A".to_string(), "B".to_string()])));
+    assert_eq!(ctx.dialogue_history.len(), 1);
+
+    // 恢复后选项立即可选，不需要再执行一次选择语句
+    exe.feed(InputEvent::ChoiceMade { index: 1 });
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["picked B"]);
+}
+
+const HASH_SCRIPT: &str = "label init\n    : first\n    : second\nenlb\n";
+
+/// 在原脚本停在第一句时存档，用 `src` 加载后恢复
+fn restore_into(name: &str, src: &str) -> (Ctx, Executor, LoadOutcome) {
+    let (mut ctx, mut exe) = setup(&format!("{}_old", name), HASH_SCRIPT);
+    run_until_wait(&mut ctx, &mut exe);
+    let snapshot = exe.snapshot();
+
+    let mut edited = Executor::new(load_manager(name, src));
+    let outcome = edited.restore(snapshot);
+    (ctx, edited, outcome)
+}
+
+#[test]
+fn restore_identical_label() {
+    let (mut ctx, mut exe, outcome) = restore_into("hash_same", HASH_SCRIPT);
+    assert_eq!(outcome, LoadOutcome::Restored);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["first"]);
+}
+
+#[test]
+fn restore_ignores_whitespace_edits() {
+    let src = "\n-- 开场\nlabel init\n\n        : first   \n\n    : second\nenlb\n";
+    let (mut ctx, mut exe, outcome) = restore_into("hash_whitespace", src);
+    assert_eq!(outcome, LoadOutcome::Restored);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["first"]);
+    exe.feed(InputEvent::Continue);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["second"]);
+}
+
+#[test]
+fn restore_reports_changed_label() {
+    let src = "label init\n    : first\n    : rewritten\nenlb\n";
+    let (mut ctx, mut exe, outcome) = restore_into("hash_changed", src);
+    assert_eq!(outcome, LoadOutcome::LabelChanged { label: "init".to_string() });
+
+    exe.restart_label("init").unwrap();
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["first"]);
+    exe.feed(InputEvent::Continue);
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["rewritten"]);
+
+    let (_, _, outcome) = restore_into("hash_removed", "label other\n    : first\nenlb\n");
+    assert_eq!(outcome, LoadOutcome::LabelChanged { label: "init".to_string() });
+}
+
+#[test]
+fn voice_lines_count_dialogue_per_label() {
+    use viviscript_core::ast::Stmt;
+
+    let manager = load_manager("voice_lines", r#"
+character yuki name="Yuki" voice_tag="yuki"
+
+label start
+    yuki: "a"
+    : narration does not count
+    choice
+        "A":
+            yuki: "b"
+    enco
+    yuki: "c"
+enlb
+
+label other
+    yuki: "d"
+enlb
+"#);
+    let lines = |label: &str| -> Vec<String> {
+        manager.get_label(label).unwrap().iter()
+            .filter_map(|s| match s {
+                Stmt::Dialogue { voice_line, .. } => voice_line.clone(),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(lines("start"), vec!["start_1", "start_3"]);
+    assert_eq!(lines("start@choice_0_opt0"), vec!["start_2"]);
+    assert_eq!(lines("other"), vec!["other_1"]);
+}
+
+#[test]
+fn lua_audio_api_updates_channels() {
+    let src = r#"
+label init
+    lua
+        lumina.play_bgm("theme", { volume = 0.5, loop = false })
+        if lumina.is_playing("music") then
+            lumina.set_volume("music", 0.2, 1.5)
+        end
+    enlua
+    : first
+    lua
+        if lumina.is_playing("music") then
+            lumina.stop("music", 2.0)
+        end
+        if not lumina.is_playing("music") then
+            lumina.set_volume("sound", 0.3)
+        end
+    enlua
+    : second
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("lua_audio", src);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::PlayAudio { channel, path, volume, looping: false, speculative: false, .. }
+            if channel == "music" && path == "theme" && *volume == 0.5)));
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::SetVolume { channel, fade, .. } if channel == "music" && *fade == 1.5)));
+    assert!(ctx.audios["music"].is_some());
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::StopAudio { channel, fade_out } if channel == "music" && *fade_out == 2.0)));
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::SetVolume { channel, fade, .. } if channel == "sound" && *fade == 0.0)));
+    assert!(ctx.audios["music"].is_none());
+}
+
+#[test]
+fn handle_starts_from_given_entry_label() {
+    let manager = load_manager("entry_label", r#"
+label init
+    : from init
+enlb
+
+label chapter2
+    : from chapter2
+enlb
+"#);
+    let mut ctx = Ctx::default();
+    let mut driver = ExecutorHandle::new(&mut ctx, manager, "chapter2");
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    assert_eq!(narration(&driver.drain(&mut ctx)), vec!["from chapter2".to_string()]);
+}
+
+#[test]
+fn set_var_writes_script_variable() {
+    let src = r#"
+label init
+    : hello
+    : bye
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("set_var", src);
+    run_until_wait(&mut ctx, &mut exe);
+    exe.feed(InputEvent::SetVar { name: "player_name".to_string(), value: "Alice".to_string() });
+    assert_eq!(exe.debug_eval("return f.player_name"), "Alice");
+}
+
+#[test]
+fn debug_snapshot_reports_position() {
+    let (mut ctx, mut exe) = setup("debug_snapshot", CHOICE_SCRIPT);
+    run_until_wait(&mut ctx, &mut exe);
+    let snap = DebugSnapshot { frames: exe.positions() };
+    assert_eq!(snap.frames, vec![("init".to_string(), 0)]);
+    assert_eq!(snap.to_string(), "label 'init' pc 0 (stack: init:0)");
+    assert_eq!(DebugSnapshot { frames: Vec::new() }.to_string(), "<script finished>");
+}
+
+#[test]
+fn project_loads_from_pak() {
+    let root = common::project_dir("pak_project", &[
+        ("game/chapters/one.vivi", "label init\n    : packed\nenlb\n"),
+    ]);
+    std::fs::create_dir_all(root.join("assets")).unwrap();
+
+    let pak = root.join("game.pak");
+    lumina_shared::vfs::write_pak_sections(&[
+        (lumina_shared::vfs::SCRIPTS, root.join("game").as_path()),
+        (lumina_shared::vfs::ASSETS, root.join("assets").as_path()),
+    ], &pak).unwrap();
+    std::fs::remove_dir_all(root.join("game")).unwrap();
+
+    let mut manager = ScriptManager::new();
+    manager.load_project(&pak).expect("Failed to load packed project");
+    let mut ctx = Ctx::default();
+    let mut exe = Executor::new(Arc::new(manager));
+    exe.start(&mut ctx, "init");
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), vec!["packed".to_string()]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn active_speaker_follows_dialogue() {
+    let src = r#"
+character yuki name="Yuki" image_tag="yuki_sprite"
+label init
+    yuki: hello
+    hana: hi
+    : quiet
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("active_speaker", src);
+    run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(ctx.active_speaker.as_deref(), Some("yuki_sprite"));
+
+    exe.feed(InputEvent::Continue);
+    run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(ctx.active_speaker.as_deref(), Some("hana"));
+
+    exe.feed(InputEvent::Continue);
+    run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(ctx.active_speaker, None);
+}
+
+#[test]
+fn character_voice_volume_and_pitch_apply_to_voice() {
+    let src = r#"
+character yuki name="Yuki" voice_tag="yuki" voice_volume=0.5 voice_pitch=3
+label init
+    yuki: "Hello(001)"
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("voice_volume", src);
+    let base = lumina_shared::config::get::<lumina_core::config::AudioConfig>("audio").voice_volume;
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    let (volume, pitch) = events.iter()
+        .find_map(|e| match e {
+            OutputEvent::PlayAudio { channel, volume, pitch, .. } if channel == "voice" => Some((*volume, *pitch)),
+            _ => None,
+        })
+        .expect("voice not played");
+    assert_eq!(volume, base * 0.5);
+    // 超出范围的音高被限制到 2.0
+    assert_eq!(pitch, Some(2.0));
+}
+
+#[test]
+fn scene_with_attrs_uses_asset_naming() {
+    let src = r#"
+label init
+    : before
+    scene room day rain with fade
+    : after
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("scene_attrs", src);
+
+    // 预加载与实际切换使用同一个资源名
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::Preload { images, .. } if images.iter().any(|i| i == "room_day_rain"))));
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::NewScene { background: Some(bg), .. } if bg == "room_day_rain")));
+
+    let bg = &ctx.layer_record.layer["master"][0];
+    assert_eq!((bg.target.as_str(), bg.attrs.as_slice()), ("room", ["day".to_string(), "rain".to_string()].as_slice()));
+}
+
+#[test]
+fn wait_audio_holds_and_lua_reads_audio_state() {
+    use lumina_core::runtime::assets::ChannelState;
+
+    let src = r#"
+label init
+    play sound jingle
+    wait_audio sound
+    $ f.pos = lumina.audio_position("music")
+    $ f.playing = lumina.audio_playing("music") and "yes" or "no"
+    : {f.pos} {f.playing}
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("wait_audio", src);
+    ctx.audio_state.insert("music".into(), ChannelState { position: 12.5, playing: true });
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::WaitAudioEnd { channel } if channel == "sound")));
+    assert!(narration(&events).is_empty());
+
+    // 渲染层在声音播完后发送 Continue
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), ["12.5 yes"]);
+}
+
+#[test]
+fn character_names_change_at_runtime_and_survive_save() {
+    let src = r##"
+character hero name="{f.player_name}"
+label init
+    $ f.player_name = "Alice"
+    hero: hello
+    $ lumina.define_character("mira", { name = "Mira", color = "#ff0000" })
+    $ lumina.set_character_name("hero", "Captain")
+    mira: hi
+    hero: again
+enlb
+"##;
+    let manager = load_manager("character_runtime", src);
+    let mut ctx = Ctx::default();
+    let mut exe = Executor::new(manager.clone());
+    exe.start(&mut ctx, "init");
+
+    let speaker = |events: &[OutputEvent]| events.iter().find_map(|e| match e {
+        OutputEvent::ShowDialogue { name, .. } => Some(name.clone()),
+        _ => None,
+    });
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(speaker(&events).as_deref(), Some("Alice"));
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(speaker(&events).as_deref(), Some("Mira"));
+    assert_eq!(ctx.characters["mira"].color.as_deref(), Some("#ff0000"));
+
+    let (mut ctx, mut exe) = save_and_load(&ctx, &exe, manager);
+    assert_eq!(ctx.characters["hero"].name, "Captain");
+    run_until_wait(&mut ctx, &mut exe);
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(speaker(&events).as_deref(), Some("Captain"));
+}
+
+#[test]
+fn show_and_scene_targets_are_interpolated() {
+    let src = r#"
+label init
+    $ f.outfit = "summer"
+    $ f.time = "night"
+    scene bg_{f.time}
+    show alice_{f.outfit} happy
+    : look
+    hide alice_{f.outfit}
+    : gone
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("interpolated_targets", src);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::NewScene { background: Some(bg), .. } if bg == "bg_night")));
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::NewSprite { target, texture, attrs, .. }
+            if target == "alice_summer" && texture == "alice_summer" && attrs == &["happy"])));
+    assert!(ctx.layer_record.layer["master"].iter().any(|s| s.target == "alice_summer"));
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::HideSprite { target, .. } if target == "alice_summer")));
+    assert!(!ctx.layer_record.layer["master"].iter().any(|s| s.target == "alice_summer"));
+}
+
+#[test]
+fn narration_blank_lines_separate_paragraphs() {
+    let src = "label init\n    nvl on\n    :\"\"\"\n    first\n\n    second\n    \"\"\"\nenlb\n";
+    let (mut ctx, mut exe) = setup("narration_paragraphs", src);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert_eq!(narration(&events), ["first"]);
+
+    // 空行随下一句一起交给渲染器，作为段落间距
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    let lines = events.iter().find_map(|e| match e {
+        OutputEvent::ShowNarration { lines } => Some(lines.clone()),
+        _ => None,
+    });
+    assert_eq!(lines.unwrap(), ["", "second"]);
+
+    let page: Vec<&str> = ctx.nvl_page.iter().map(|r| r.text.as_str()).collect();
+    assert_eq!(page, ["first", "", "second"]);
+    assert_eq!(ctx.dialogue_history.len(), 2);
+
+    // 拆分后的每一句仍指向自己的源码行
+    let manager = load_manager("narration_paragraph_spans", src);
+    let body = manager.get_label("init").unwrap();
+    let lines: Vec<usize> = body.iter()
+        .filter_map(|s| match s {
+            viviscript_core::ast::Stmt::Narration { span, .. } => Some(span.line),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lines, [4, 6]);
+}
+
+#[test]
+fn inline_transition_duration_reaches_events() {
+    let src = r#"
+label init
+    scene bg room with fade 1.5
+    show alice with dissolve 0.8
+    show alice happy with dissolve
+    hide alice with fade 0
+    : done
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("inline_transition_duration", src);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    let durations: Vec<Option<f32>> = events.iter()
+        .filter_map(|e| match e {
+            OutputEvent::NewScene { duration, .. }
+            | OutputEvent::NewSprite { duration, .. }
+            | OutputEvent::UpdateSprite { duration, .. }
+            | OutputEvent::HideSprite { duration, .. } => Some(*duration),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(durations, [Some(1.5), Some(0.8), None, Some(0.0)]);
+}
+
+#[test]
+fn recovered_parse_error_warns_and_continues() {
+    let src = r#"
+label init
+    : before
+    play music theme bogus=1
+    : after
+enlb
+"#;
+    let manager = load_manager("recovered_parse_error", src);
+    let file = std::env::temp_dir().join("lumina_test_recovered_parse_error").join("test.vivi");
+    assert_eq!(manager.error_count(&file.to_string_lossy()), 1);
+
+    let mut ctx = Ctx::default();
+    let mut exe = Executor::new(manager);
+    exe.start(&mut ctx, "init");
+    assert_eq!(narration(&run_until_wait(&mut ctx, &mut exe)), ["before"]);
+
+    exe.feed(InputEvent::Continue);
+    let events = run_until_wait(&mut ctx, &mut exe);
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::Warning { line: 4, msg } if msg == "Unknown param 'bogus'")), "{:?}", events);
+    assert!(!events.iter().any(|e| matches!(e, OutputEvent::PlayAudio { .. })), "{:?}", events);
+    assert_eq!(narration(&events), ["after"]);
+}
+
+#[test]
+fn stopping_idle_channel_is_harmless() {
+    let src = r#"
+label init
+    play music theme fade_out=1.5
+    stop music
+    stop music
+    stop sound fade_out=0.5
+    stop sound
+    : done
+enlb
+"#;
+    let (mut ctx, mut exe) = setup("stop_idle_channel", src);
+
+    let events = run_until_wait(&mut ctx, &mut exe);
+    let stops: Vec<(&str, f32)> = events.iter()
+        .filter_map(|e| match e {
+            OutputEvent::StopAudio { channel, fade_out } => Some((channel.as_str(), *fade_out)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(stops.len(), 4, "{:?}", stops);
+    assert_eq!(stops[0], ("music", 1.5));
+    assert_eq!(stops[1].0, "music");
+    assert_eq!(stops[2], ("sound", 0.5));
+    // 没有播放过、也没写 fade_out 的声道按配置的默认淡出
+    assert_eq!(stops[3].0, "sound");
+    assert!(ctx.audios["music"].is_none());
+    assert!(ctx.audios["sound"].is_none());
+    assert_eq!(narration(&events), ["done"]);
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+use lumina_core::event::InputEvent;
+use lumina_core::renderer::headless::{HeadlessRenderer, HeadlessRunner};
+use lumina_core::{Ctx, OutputEvent, ScriptManager};
+
+mod common;
+
+fn load(name: &str, src: &str) -> Arc<ScriptManager> {
+    Arc::new(common::load(name, src))
+}
+
+fn play(name: &str, src: &str, mut renderer: HeadlessRenderer) -> HeadlessRenderer {
+    let mut ctx = Ctx::default();
+    renderer.play_until_end(&mut ctx, load(name, src), 1000).unwrap();
+    renderer
+}
+
+#[test]
+fn jump_and_call_return() {
+    let renderer = play("flow_jump_call", r#"
+character yuki name="Yuki"
+
+label init
+    : start
+    call greet
+    : back
+    jump finale
+    : unreachable
+enlb
+
+label greet
+    yuki: "hello"
+enlb
+
+label finale
+    : the end
+enlb
+"#, HeadlessRenderer::new());
+
+    renderer.assert_dialogue_sequence(&["start", "Yuki: hello", "back", "the end"]);
+}
+
+#[test]
+fn choice_follows_selected_arm() {
+    let src = r#"
+label init
+    choice "pick"
+        "A":
+            : picked A
+        "B":
+            : picked B
+    enco
+    : after
+enlb
+"#;
+    play("flow_choice_a", src, HeadlessRenderer::new().with_choices([0]))
+        .assert_dialogue_sequence(&["picked A", "after"]);
+    play("flow_choice_b", src, HeadlessRenderer::new().with_choices([1]))
+        .assert_dialogue_sequence(&["picked B", "after"]);
+}
+
+#[test]
+fn if_branches_on_lua_state() {
+    let renderer = play("flow_if", r#"
+label init
+    $ f.score = 0
+    choice
+        "five":
+            $ f.score = 5
+        "ten":
+            $ f.score = 10
+    enco
+    if f.score == 5
+        : got five
+    elif f.score == 10
+        : got ten
+    else
+        : got nothing
+    enif
+enlb
+"#, HeadlessRenderer::new().with_responder(|ev| match ev {
+        OutputEvent::ShowChoice { options, .. } => options.iter()
+            .position(|o| o == "ten")
+            .map(|index| InputEvent::ChoiceMade { index }),
+        _ => None,
+    }));
+
+    renderer.assert_dialogue_sequence(&["got ten"]);
+    let seqs: Vec<usize> = renderer.events().iter().map(|r| r.seq).collect();
+    assert!(seqs.windows(2).all(|w| w[1] == w[0] + 1));
+}
+
+#[test]
+fn unanswered_choice_and_endless_loop_are_errors() {
+    let mut ctx = Ctx::default();
+    let choice = load("flow_unanswered", "label init\n    choice\n        \"A\":\n            : a\n    enco\nenlb\n");
+    assert!(HeadlessRenderer::new().play_until_end(&mut ctx, choice, 1000).is_err());
+
+    let mut ctx = Ctx::default();
+    let endless = load("flow_endless", "label init\n    jump init\nenlb\n");
+    assert!(HeadlessRenderer::new().play_until_end(&mut ctx, endless, 1000).is_err());
+}
+
+#[test]
+fn call_into_choice_returns_after_call_site() {
+    let renderer = play("flow_call_choice", r#"
+label init
+    : before
+    call pick
+    : returned
+enlb
+
+label pick
+    choice
+        "A":
+            if true
+                : nested A
+            enif
+            : arm A
+        "B":
+            : arm B
+    enco
+    : after choice
+enlb
+"#, HeadlessRenderer::new().with_choices([0]));
+
+    renderer.assert_dialogue_sequence(&["before", "nested A", "arm A", "after choice", "returned"]);
+}
+
+#[test]
+fn advancing_stops_previous_voice_once() {
+    let renderer = play("flow_voice_stop", r#"
+character yuki name="Yuki" voice_tag="yuki"
+
+label init
+    yuki: "first" (001)
+    yuki: "second" (002)
+    : silent
+enlb
+"#, HeadlessRenderer::new());
+
+    let events: Vec<_> = renderer.events().iter().map(|r| &r.event).collect();
+    let lines: Vec<usize> = events.iter().enumerate()
+        .filter(|(_, e)| matches!(e, OutputEvent::ShowDialogue { .. } | OutputEvent::ShowNarration { .. }))
+        .map(|(i, _)| i)
+        .collect();
+    let stops_between = |from: usize, to: usize| events[from..to].iter()
+        .filter(|e| matches!(e, OutputEvent::StopAudio { channel, .. } if channel == "voice"))
+        .count();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(stops_between(0, lines[0]), 0);
+    assert_eq!(stops_between(lines[0], lines[1]), 1);
+    assert_eq!(stops_between(lines[1], lines[2]), 1);
+
+    // 停止排在新语音之前
+    let stop = (lines[0]..lines[1]).find(|&i| matches!(events[i], OutputEvent::StopAudio { .. })).unwrap();
+    let play = (lines[0]..lines[1]).find(|&i| matches!(events[i], OutputEvent::PlayAudio { .. })).unwrap();
+    assert!(stop < play);
+}
+
+#[test]
+fn movie_waits_for_renderer() {
+    let renderer = play("flow_movie", r#"
+label init
+    : before
+    movie "op" noskip
+    : after
+enlb
+"#, HeadlessRenderer::new());
+
+    let events: Vec<_> = renderer.events().iter().map(|r| &r.event).collect();
+    let movie = events.iter().position(|e| matches!(e, OutputEvent::PlayMovie { id, skippable: false } if id == "op"));
+    let after = events.iter().position(|e| matches!(e, OutputEvent::ShowNarration { lines } if lines[0] == "after"));
+    assert!(movie.unwrap() < after.unwrap());
+    renderer.assert_dialogue_sequence(&["before", "after"]);
+}
+
+#[test]
+fn runner_collects_events_for_chosen_branch() {
+    let _ = lumina_shared::config::init("test_dummy.toml");
+    let src = r#"
+label init
+    choice "route"
+        "A":
+            play music theme_a
+            : went A
+        "B":
+            play music theme_b
+            : went B
+    enco
+enlb
+"#;
+    let run = HeadlessRunner::from_source(src).unwrap()
+        .choose(|title, options| {
+            assert_eq!(title, Some("route"));
+            options.iter().position(|o| o == "B").unwrap()
+        })
+        .run()
+        .unwrap();
+
+    assert!(run.events.iter().any(|e| matches!(e, OutputEvent::PlayAudio { path, .. } if path == "theme_b")));
+    assert!(!run.events.iter().any(|e| matches!(e, OutputEvent::PlayAudio { path, .. } if path == "theme_a")));
+    assert!(run.events.iter().any(|e| matches!(e, OutputEvent::ShowNarration { lines } if lines == &["went B"])));
+    assert!(matches!(run.events.last(), Some(OutputEvent::End)));
+
+    assert!(HeadlessRunner::from_source(src).unwrap().run().is_err());
+}
@@ -0,0 +1,33 @@
+use lumina_core::runtime::assets::DialogueRecord;
+use lumina_core::runtime::history::History;
+
+fn rec(text: &str) -> DialogueRecord {
+    DialogueRecord { speaker: None, text: text.to_string(), voice_path: None }
+}
+
+fn texts<'a>(it: impl Iterator<Item = &'a DialogueRecord>) -> Vec<&'a str> {
+    it.map(|r| r.text.as_str()).collect()
+}
+
+#[test]
+fn capped_history_keeps_absolute_indices() {
+    let mut history = History::default();
+    for i in 0..10 {
+        history.push(rec(&i.to_string()), 4);
+    }
+
+    assert_eq!(history.len(), 4);
+    assert_eq!(history.first_index(), 6);
+    assert_eq!(history.total(), 10);
+    assert!(history.get(5).is_none());
+    assert_eq!(history.get(7).unwrap().text, "7");
+
+    assert_eq!(texts(history.slice(..)), ["6", "7", "8", "9"]);
+    assert_eq!(texts(history.slice(0..8)), ["6", "7"]);
+    assert_eq!(texts(history.slice(8..=20)), ["8", "9"]);
+    assert!(history.slice(12..).next().is_none());
+
+    history.keep_last(2);
+    assert_eq!(history.first_index(), 8);
+    assert_eq!(texts(history.iter()), ["8", "9"]);
+}
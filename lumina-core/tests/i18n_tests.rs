@@ -0,0 +1,122 @@
+use lumina_core::i18n::{text_key, Localizer};
+use lumina_shared::vfs::DirSource;
+use lumina_core::ScriptManager;
+use viviscript_core::ast::Stmt;
+
+mod common;
+
+#[test]
+fn text_key_is_stable() {
+    assert_eq!(text_key(""), "cbf29ce484222325");
+    assert_eq!(text_key("a"), "af63dc4c8601ec8c");
+    assert_eq!(text_key("你好"), text_key("你好"));
+    assert_ne!(text_key("你好"), text_key("你好。"));
+}
+
+#[test]
+fn lines_get_stable_translation_ids() {
+    let manager = common::load("tl_ids", r#"
+label start
+    : """
+    first
+    second
+    """
+    choice
+        "A":
+            : inside
+    enco
+enlb
+"#);
+
+    let ids = |label: &str| -> Vec<String> {
+        manager.get_label(label).unwrap().iter()
+            .filter_map(|s| match s {
+                Stmt::Narration { tl_id, .. } | Stmt::Dialogue { tl_id, .. } => tl_id.clone(),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(ids("start"), vec!["start@line_0", "start@line_1"]);
+    assert_eq!(ids("start@choice_0_opt0"), vec!["start@choice_0_opt0@line_0"]);
+}
+
+#[test]
+fn extract_strings_merges_existing_translations() {
+    let dir = common::project_dir("extract", &[("test.vivi", "label start\n    : hello\n    : world\nenlb\n")]);
+
+    let mut manager = ScriptManager::new();
+    manager.load_project(&dir).expect("Failed to load test project");
+
+    let out = dir.join("en.json");
+    std::fs::write(&out, r#"{ "start@line_0": "Hello!", "gone@line_0": "Bye" }"#).unwrap();
+
+    let report = manager.extract_strings(&out).unwrap();
+    assert_eq!((report.total, report.new, report.obsolete), (2, 1, 1));
+
+    let table: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(table["start@line_0"]["text"], "Hello!");
+    assert_eq!(table["start@line_0"]["source"], "hello");
+    assert_eq!(table["start@line_1"]["status"], "new");
+    assert_eq!(table["gone@line_0"]["status"], "obsolete");
+    assert_eq!(table["gone@line_0"]["text"], "Bye");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn export_strings_writes_toml_template() {
+    let dir = common::project_dir("export_toml", &[("test.vivi", r#"
+character alice name="Alice"
+label start
+    alice: "hello"
+    : hello
+    : hello
+    choice "pick"
+        "A":
+            : say "hi" now
+    enco
+enlb
+"#)]);
+
+    let mut manager = ScriptManager::new();
+    manager.load_project(&dir).expect("Failed to load test project");
+
+    let out = dir.join("locales/en.toml");
+    // 相同原文只导出一次
+    assert_eq!(manager.export_strings(&out).unwrap(), 6);
+
+    let content = std::fs::read_to_string(&out).unwrap();
+    let table: toml::Table = toml::from_str(&content).unwrap();
+    assert_eq!(table[&text_key("hello")]["source"].as_str(), Some("hello"));
+    assert_eq!(table[&text_key("hello")]["text"].as_str(), Some(""));
+    assert_eq!(table[&text_key("say \"hi\" now")]["source"].as_str(), Some("say \"hi\" now"));
+    assert_eq!(table[&text_key("Alice")]["source"].as_str(), Some("Alice"));
+
+    // 未填写的模板不改变任何文本
+    let source = DirSource::new(&dir);
+    assert_eq!(Localizer::from_source("en", &source).lookup(None, "hello"), None);
+
+    let entry = format!("[{}]\nsource = \"hello\"\ntext = \"\"", text_key("hello"));
+    let filled = format!("[{}]\nsource = \"hello\"\ntext = \"Hi\"", text_key("hello"));
+    assert!(content.contains(&entry), "{}", content);
+    std::fs::write(&out, content.replace(&entry, &filled)).unwrap();
+    assert_eq!(Localizer::from_source("en", &source).lookup(None, "hello"), Some("Hi"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn locale_toml_and_json_tables_merge() {
+    let dir = common::project_dir("locale_merge", &[
+        ("locales/en.toml", "greeting = \"Hello\"\nfarewell = \"Bye\"\n\n[start]\nsource = \"开始\"\ntext = \"Start\"\n"),
+        ("tl/en.json", r#"{ "farewell": "See you" }"#),
+    ]);
+    let localizer = Localizer::from_source("en", &DirSource::new(&dir));
+    assert_eq!(localizer.lookup(Some("greeting"), "你好"), Some("Hello"));
+    assert_eq!(localizer.lookup(Some("start"), "开始"), Some("Start"));
+    // 两种表都有的条目以 JSON 为准
+    assert_eq!(localizer.lookup(Some("farewell"), "再见"), Some("See you"));
+    assert_eq!(localizer.lookup(Some("missing"), "原文"), None);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,30 @@
+use lumina_core::manager::LabelMeta;
+
+mod common;
+
+#[test]
+fn label_titles_fall_back_to_id() {
+    let manager = common::load_files("label_meta", &[("a.vivi", r#"
+-- @title: Prologue
+label init
+    choice
+        "go":
+            : went
+    enco
+enlb
+"#), ("b.vivi", r#"
+label harbor
+    : waves
+enlb
+"#)]);
+
+    let meta: Vec<(&str, &str, usize)> = manager.label_meta().iter()
+        .map(|LabelMeta { id, title, line, .. }| (id.as_str(), title.as_str(), *line))
+        .collect();
+    assert_eq!(meta, vec![("init", "Prologue", 3), ("harbor", "harbor", 2)]);
+    assert!(manager.label_meta()[0].file.ends_with("a.vivi"));
+
+    // 选项分支块取所在 label 的标题
+    assert_eq!(manager.label_title("init@choice_0_a0"), Some("Prologue"));
+    assert_eq!(manager.label_title("missing"), None);
+}
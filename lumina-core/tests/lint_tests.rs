@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use lumina_core::manager::{IssueKind, ProjectError};
+use lumina_core::renderer::headless::HeadlessRenderer;
+use lumina_core::{Ctx, OutputEvent, ScriptManager};
+
+mod common;
+
+/// 角色定义与对话放在不同文件中
+fn load(name: &str) -> ScriptManager {
+    common::load_files(name, &[("characters.vivi", r#"
+character alice name="Alice"
+"#), ("story.vivi", r#"
+label init
+    alice: "hi"
+    alcie: "typo"
+    guard @ "Guard": "halt"
+    choice
+        "go":
+            bob: "nested"
+        "stay":
+            jump nowhere
+    enco
+    call init_end
+enlb
+
+label init_end
+    : done
+enlb
+"#)])
+}
+
+#[test]
+fn unknown_speakers_and_jumps_are_reported() {
+    let manager = load("lint_report");
+    let issues = manager.check_project();
+
+    let summary: Vec<_> = issues.iter()
+        .map(|i| (i.kind, i.file.ends_with("story.vivi"), i.line))
+        .collect();
+    assert_eq!(summary, [
+        (IssueKind::UnknownSpeaker, true, 4),
+        (IssueKind::UnknownSpeaker, true, 8),
+        (IssueKind::UnresolvedJump, true, 10),
+    ]);
+    assert!(issues[0].msg.contains("alcie"));
+
+    let err = ProjectError { issues }.to_string();
+    assert!(err.starts_with("3 problem(s)"));
+    assert!(err.contains("story.vivi:4: speaker 'alcie' is not a defined character"));
+}
+
+#[test]
+fn unknown_speaker_warns_at_runtime() {
+    let manager = Arc::new(load("lint_runtime"));
+    let mut renderer = HeadlessRenderer::new().with_choices([0]);
+    let _ = renderer.play_until_end(&mut Ctx::default(), manager, 100);
+
+    let warnings: Vec<_> = renderer.events().iter()
+        .filter_map(|r| match &r.event {
+            OutputEvent::Warning { line, msg } => Some((*line, msg.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(warnings, [
+        (4, "speaker 'alcie' is not a defined character"),
+        (8, "speaker 'bob' is not a defined character"),
+    ]);
+}
+
+fn load_conditions(name: &str, src: &str) -> (ScriptManager, anyhow::Result<()>) {
+    common::try_load_files(name, &[("cond.vivi", src)])
+}
+
+#[test]
+fn valid_conditions_pass() {
+    let (manager, result) = load_conditions("cond_valid", r#"
+label init
+    if f.score >= 3 and f.name ~= "a=b"
+        : high
+    elif f.score == 1
+        : one
+    enif
+enlb
+"#);
+    assert!(result.is_ok());
+    assert!(manager.check_project().is_empty());
+}
+
+#[test]
+fn malformed_conditions_block_loading() {
+    let (manager, result) = load_conditions("cond_invalid", r#"
+label init
+    : start
+    if f.score >
+        : never
+    elif f.score = 3
+        : typo
+    enif
+enlb
+"#);
+    let err = result.unwrap_err();
+    let issues = &err.downcast_ref::<ProjectError>().expect("ProjectError").issues;
+    let summary: Vec<_> = issues.iter().map(|i| (i.kind, i.line)).collect();
+    assert_eq!(summary, [(IssueKind::InvalidCondition, 4), (IssueKind::InvalidCondition, 4)]);
+    assert!(issues[0].msg.contains("'f.score >'"));
+    assert!(issues[1].msg.contains("use '=='"));
+    assert_eq!(manager.check_project().len(), 2);
+}
+
+#[test]
+fn reachability_reports_dead_labels_and_blocks() {
+    let _ = lumina_shared::config::init("test_dummy.toml");
+    let mut manager = ScriptManager::new();
+    manager.load_sources([("story.vivi", r#"
+label init
+    choice
+        "left":
+            jump ending_a
+        "right":
+            call side
+    enco
+    $ lumina.jump("ending_b")
+enlb
+
+label side
+    if f.flag
+        : flagged
+    enif
+enlb
+
+label ending_a
+    : a
+enlb
+
+label ending_b
+    : b
+enlb
+
+label ending_c
+    choice
+        "again":
+            jump init
+    enco
+enlb
+"#)]).unwrap();
+
+    let report = manager.reachability(&["init"]);
+    let ids = |metas: &[lumina_core::manager::LabelMeta]| metas.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&report.reachable_labels), ["init", "side", "ending_a", "ending_b"]);
+    assert_eq!(ids(&report.unreachable_labels), ["ending_c"]);
+    assert_eq!(report.unreachable_blocks, ["ending_c@choice_0_opt0"]);
+}
@@ -0,0 +1,28 @@
+use lumina_core::markup::{parse, reveal_units, to_plain, TextRun};
+
+#[test]
+fn parses_ruby_runs() {
+    let runs = parse("这是{rt=かんじ}漢字{/rt}です");
+    assert_eq!(runs, vec![
+        TextRun::Plain("这是".to_string()),
+        TextRun::Ruby { base: "漢字".to_string(), annotation: "かんじ".to_string() },
+        TextRun::Plain("です".to_string()),
+    ]);
+}
+
+#[test]
+fn malformed_ruby_is_plain_text() {
+    assert_eq!(parse("{rt=x}abc"), vec![TextRun::Plain("{rt=x}abc".to_string())]);
+    assert_eq!(to_plain("{rt=x}{/rt}"), "{rt=x}{/rt}");
+}
+
+#[test]
+fn plain_text_puts_annotation_in_parentheses() {
+    assert_eq!(to_plain("读{rt=かんじ}漢字{/rt}"), "读漢字(かんじ)");
+}
+
+#[test]
+fn ruby_is_revealed_as_one_unit() {
+    let units = reveal_units("a{rt=かんじ}漢字{/rt}b");
+    assert_eq!(units, vec!["a", "{rt=かんじ}漢字{/rt}", "b"]);
+}
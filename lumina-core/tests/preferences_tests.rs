@@ -0,0 +1,20 @@
+use lumina_core::storager::types::Preferences;
+
+#[test]
+fn missing_fields_fall_back_to_defaults() {
+    let _ = lumina_shared::config::init("test_dummy.toml");
+
+    // 只有语言的旧 preferences.json 仍能读取
+    let prefs: Preferences = serde_json::from_str(r#"{ "language": "en" }"#).unwrap();
+    assert_eq!(prefs.language.as_deref(), Some("en"));
+    assert_eq!(prefs.volume("music"), 1.0);
+    assert!(!prefs.fullscreen);
+
+    let prefs: Preferences = serde_json::from_str(
+        r#"{ "volumes": { "music": 0.25 }, "text_speed": 80.0, "fullscreen": true }"#
+    ).unwrap();
+    assert_eq!(prefs.volume("music"), 0.25);
+    assert_eq!(prefs.volume("voice"), 1.0);
+    assert_eq!(prefs.text_speed(), 80.0);
+    assert!(prefs.fullscreen);
+}
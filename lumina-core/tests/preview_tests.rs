@@ -0,0 +1,102 @@
+#![cfg(feature = "preview")]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use lumina_core::event::InputEvent;
+use lumina_core::renderer::preview::{self, PreviewCommand, PreviewOptions, PreviewResponse};
+
+#[test]
+fn parses_editor_commands() {
+    let goto: PreviewCommand = serde_json::from_str(r#"{"cmd":"goto","label":"x","pc":3}"#).unwrap();
+    assert_eq!(goto.into_input_event(), InputEvent::DebugGoto { label: "x".into(), pc: 3 });
+
+    let reload: PreviewCommand = serde_json::from_str(r#"{"cmd":"reload"}"#).unwrap();
+    assert_eq!(reload.into_input_event(), InputEvent::DebugReload);
+
+    let eval: PreviewCommand = serde_json::from_str(r#"{"cmd":"eval","code":"return 1"}"#).unwrap();
+    assert_eq!(eval.into_input_event(), InputEvent::DebugEval { code: "return 1".into() });
+}
+
+fn start(allow_eval: bool) -> u16 {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let rx = preview::start(port, PreviewOptions { token: "secret".into(), allow_eval }).unwrap();
+    // 测试不走驱动，收到的请求原样回复 ok
+    std::thread::spawn(move || {
+        for req in rx {
+            let _ = req.reply.send(PreviewResponse { ok: true, label: None, pc: None, output: None, error: None });
+        }
+    });
+    port
+}
+
+fn send(stream: &mut TcpStream, line: &str) -> Option<PreviewResponse> {
+    // 对方已断开时写入或读取可能报错，同样视为没有回复
+    let _ = stream.write_all(format!("{}\n", line).as_bytes());
+    let mut reply = String::new();
+    BufReader::new(&*stream).read_line(&mut reply).ok()?;
+    (!reply.is_empty()).then(|| serde_json::from_str(&reply).unwrap())
+}
+
+#[test]
+fn replies_over_localhost() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let token = preview::new_token();
+    assert_eq!(token.len(), 32);
+    let rx = preview::start(port, PreviewOptions { token: token.clone(), allow_eval: false }).unwrap();
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let auth = send(&mut stream, &format!("{{\"token\":\"{}\"}}", token)).unwrap();
+    assert!(auth.ok);
+
+    stream.write_all(b"{\"cmd\":\"goto\",\"label\":\"x\",\"pc\":3}\n").unwrap();
+
+    let req = rx.recv().unwrap();
+    assert_eq!(req.command, PreviewCommand::Goto { label: "x".into(), pc: 3 });
+    req.reply.send(PreviewResponse {
+        ok: true, label: Some("x".into()), pc: Some(3), output: None, error: None,
+    }).unwrap();
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).unwrap();
+    let response: PreviewResponse = serde_json::from_str(&line).unwrap();
+    assert!(response.ok);
+    assert_eq!((response.label.as_deref(), response.pc), (Some("x"), Some(3)));
+
+    // 格式错误的命令直接回复错误，不交给驱动
+    stream.write_all(b"{\"cmd\":\"nope\"}\n").unwrap();
+    line.clear();
+    BufReader::new(&stream).read_line(&mut line).unwrap();
+    let response: PreviewResponse = serde_json::from_str(&line).unwrap();
+    assert!(!response.ok && response.error.is_some());
+}
+
+#[test]
+fn rejects_connections_without_token() {
+    let port = start(true);
+
+    // 浏览器发来的请求不回复，直接断开
+    let mut browser = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    assert!(send(&mut browser, "POST / HTTP/1.1").is_none());
+
+    let mut stranger = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let response = send(&mut stranger, r#"{"token":"guess"}"#).unwrap();
+    assert!(!response.ok);
+    // 口令错误后连接关闭，后续命令不会执行
+    assert!(send(&mut stranger, r#"{"cmd":"reload"}"#).is_none());
+
+    // 不发口令直接发命令同样被拒
+    let mut eager = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    assert!(!send(&mut eager, r#"{"cmd":"reload"}"#).unwrap().ok);
+}
+
+#[test]
+fn eval_requires_opt_in() {
+    for allow_eval in [false, true] {
+        let port = start(allow_eval);
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        assert!(send(&mut stream, r#"{"token":"secret"}"#).unwrap().ok);
+        let response = send(&mut stream, r#"{"cmd":"eval","code":"return 1"}"#).unwrap();
+        assert_eq!(response.ok, allow_eval);
+        assert!(send(&mut stream, r#"{"cmd":"reload"}"#).unwrap().ok);
+    }
+}
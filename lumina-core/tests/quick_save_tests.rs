@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use lumina_core::event::InputEvent;
+use lumina_core::renderer::driver::{ExecutorHandle, MAX_STEPS_PER_FRAME};
+use lumina_core::runtime::assets::ChannelState;
+use lumina_core::storager;
+use lumina_core::{Ctx, OutputEvent, ScriptManager};
+
+fn narration(events: &[OutputEvent]) -> Vec<String> {
+    events.iter()
+        .filter_map(|e| match e {
+            OutputEvent::ShowNarration { lines } => Some(lines.join("\n")),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn quick_save_restores_position_and_scene() {
+    let root = std::env::temp_dir().join(format!("lumina_test_quick_save_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("game")).unwrap();
+    let save_dir = root.join("saves");
+    let overrides = [format!("system.save_path={:?}", save_dir.to_string_lossy())];
+    let _ = lumina_shared::config::init_with("test_dummy.toml", &overrides);
+
+    std::fs::write(root.join("game/test.vivi"), r#"
+label init
+    scene bg room
+    show yuki smile at left
+    : one
+    : two
+enlb
+"#).unwrap();
+    let mut manager = ScriptManager::new();
+    manager.load_project(root.join("game")).unwrap();
+
+    let mut ctx = Ctx::default();
+    let mut driver = ExecutorHandle::new(&mut ctx, Arc::new(manager), "init");
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    assert_eq!(narration(&ctx.drain()), vec!["one"]);
+
+    driver.feed(&mut ctx, InputEvent::QuickSave);
+    assert!(ctx.drain().iter().any(|e| matches!(e, OutputEvent::QuickSaveDone { load: false, ok: true })));
+    assert!(storager::quick_save_exists());
+    // 快速存档不占编号槽位
+    assert!(storager::list_saves().is_empty());
+
+    driver.feed(&mut ctx, InputEvent::Continue);
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    assert_eq!(narration(&ctx.drain()), vec!["two"]);
+
+    // 存档里没有记录、但正在播放的声道 (如 Lua 直接播放的) 读档时要停掉
+    ctx.audio_state.insert("ambient".into(), ChannelState { position: 1.0, playing: true });
+
+    driver.feed(&mut ctx, InputEvent::QuickLoad);
+    driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+    let events = ctx.drain();
+    assert!(events.iter().any(|e| matches!(e, OutputEvent::QuickSaveDone { load: true, ok: true })));
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::NewScene { background: Some(bg), .. } if bg == "bg_room")));
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::NewSprite { target, attrs, .. } if target == "yuki" && attrs == &vec!["smile".to_string()])));
+    assert!(events.iter().any(|e| matches!(e,
+        OutputEvent::StopAudio { channel, .. } if channel == "ambient")));
+    assert_eq!(narration(&events), vec!["one"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,22 @@
+use mlua::Lua;
+use lumina_core::lua_glue::{self, api::random};
+
+fn draw(lua: &Lua, n: usize) -> Vec<i64> {
+    (0..n)
+        .map(|_| lua.load("return lumina.random_int(1, 100)").eval::<i64>().unwrap())
+        .collect()
+}
+
+#[test]
+fn restored_state_replays_same_sequence() {
+    let _ = lumina_shared::config::init("test_dummy.toml");
+    let lua = Lua::new();
+    lua_glue::init_lua(&lua);
+
+    let saved = random::rng_state(&lua);
+    let first = draw(&lua, 8);
+    assert!(first.iter().all(|v| (1..=100).contains(v)));
+
+    random::set_rng_state(&lua, saved);
+    assert_eq!(draw(&lua, 8), first);
+}
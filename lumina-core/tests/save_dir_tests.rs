@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::PathBuf;
+use lumina_core::config::SystemConfig;
+use lumina_core::storager::{migrate_legacy_saves, resolve_save_dir};
+
+#[test]
+fn explicit_save_path_is_used_as_is() {
+    let cfg = SystemConfig { save_path: "my_saves/".into(), ..SystemConfig::default() };
+    assert_eq!(resolve_save_dir(&cfg), PathBuf::from("my_saves/"));
+
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn auto_save_path_uses_xdg_data_dir() {
+    // SAFETY: 本文件中只有这个测试读写环境变量
+    unsafe {
+        std::env::set_var("HOME", "/home/tester");
+        std::env::set_var("XDG_DATA_HOME", "/data/xdg");
+    }
+    for save_path in ["auto", "", "  AUTO "] {
+        let cfg = SystemConfig { save_path: save_path.into(), game_id: "demo".into(), ..SystemConfig::default() };
+        assert_eq!(resolve_save_dir(&cfg), PathBuf::from("/data/xdg/demo/saves"));
+    }
+
+    // XDG_DATA_HOME 不是绝对路径时按规范忽略，退回 ~/.local/share
+    unsafe { std::env::set_var("XDG_DATA_HOME", "relative") };
+    let cfg = SystemConfig { game_id: "demo".into(), ..SystemConfig::default() };
+    assert_eq!(resolve_save_dir(&cfg), PathBuf::from("/home/tester/.local/share/demo/saves"));
+}
+
+#[test]
+fn legacy_saves_migrate_once() {
+    let root = std::env::temp_dir().join(format!("lumina_save_migrate_{}", std::process::id()));
+    let legacy = root.join("old");
+    let dir = root.join("new");
+    fs::create_dir_all(&legacy).unwrap();
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(legacy.join("save1.bin"), b"slot").unwrap();
+    fs::write(legacy.join("global.json"), b"{}").unwrap();
+
+    assert_eq!(migrate_legacy_saves(&legacy, &dir), 2);
+    assert_eq!(fs::read(dir.join("save1.bin")).unwrap(), b"slot");
+    assert!(legacy.join("save1.bin").exists());
+
+    // 新目录已有文件时不再覆盖
+    fs::write(legacy.join("save1.bin"), b"newer").unwrap();
+    assert_eq!(migrate_legacy_saves(&legacy, &dir), 0);
+    assert_eq!(fs::read(dir.join("save1.bin")).unwrap(), b"slot");
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,80 @@
+use viviscript_core::ast::ShowAttr;
+use lumina_core::runtime::assets::{asset_name, Sprite};
+
+fn sprite(attrs: &[&str]) -> Sprite {
+    Sprite {
+        target: "alice".into(),
+        attrs: attrs.iter().map(|s| s.to_string()).collect(),
+        position: None,
+        zindex: 1,
+    }
+}
+
+/// `"smile -glasses"` 形式的修改列表
+fn changes(spec: &str) -> Vec<ShowAttr> {
+    spec.split_whitespace()
+        .map(|s| match s.strip_prefix('-') {
+            Some(r) => ShowAttr::Remove(r.to_string()),
+            None => ShowAttr::Add(s.to_string()),
+        })
+        .collect()
+}
+
+#[test]
+fn show_attribute_changes() {
+    // (已有属性, show 语句中的修改, 期望结果)
+    let cases: &[(&[&str], &str, &[&str])] = &[
+        // 追加
+        (&[], "happy", &["happy"]),
+        (&[], "happy glasses", &["happy", "glasses"]),
+        (&["happy"], "happy glasses", &["happy", "glasses"]),
+        // 按位置替换，不影响其他槽位
+        (&["happy"], "smile", &["smile"]),
+        (&["happy", "glasses"], "smile", &["smile", "glasses"]),
+        (&["happy", "glasses"], "happy sunglasses", &["happy", "sunglasses"]),
+        (&["happy", "glasses"], "sad hat", &["sad", "hat"]),
+        (&["happy", "glasses"], "sad hat scarf", &["sad", "hat", "scarf"]),
+        // 已有的属性不重复、不移动
+        (&["happy", "glasses"], "glasses", &["happy", "glasses"]),
+        (&["happy", "glasses"], "glasses happy", &["happy", "glasses"]),
+        (&["happy"], "smile smile", &["smile"]),
+        // 槽位上的属性也在本句中时不被替换，新属性追加
+        (&["happy", "glasses"], "glasses smile", &["happy", "glasses", "smile"]),
+        (&["happy", "glasses"], "smile glasses", &["smile", "glasses"]),
+        // 按值移除
+        (&["happy", "glasses"], "-glasses", &["happy"]),
+        (&["happy", "glasses"], "-happy", &["glasses"]),
+        (&["happy", "glasses"], "-happy -glasses", &[]),
+        // 移除不存在的属性是空操作
+        (&[], "-happy", &[]),
+        (&["happy"], "-glasses", &["happy"]),
+        (&["happy"], "-happy -happy", &[]),
+        // 先加后删
+        (&["happy", "glasses"], "sad -glasses", &["sad"]),
+        (&["happy", "glasses"], "-glasses sad", &["sad"]),
+        (&["happy", "glasses"], "-happy smile", &["smile", "glasses"]),
+        // 同一句里既加又删，不挤占已有槽位
+        (&["happy"], "glasses -glasses", &["happy"]),
+        (&["happy", "glasses"], "hat -hat", &["happy", "glasses"]),
+        // 空语句不改变属性
+        (&["happy", "glasses"], "", &["happy", "glasses"]),
+    ];
+
+    for (before, spec, expected) in cases {
+        let mut s = sprite(before);
+        s.apply_attrs(&changes(spec));
+        assert_eq!(s.attrs, *expected, "{:?} + show \"{}\"", before, spec);
+    }
+}
+
+#[test]
+fn asset_name_follows_slot_order() {
+    assert_eq!(asset_name("bg", &[]), "bg");
+    assert_eq!(asset_name("bg", &["night".into(), "rain".into()]), "bg_night_rain");
+
+    // 替换槽位不改变其余属性的位置
+    let mut s = sprite(&["happy", "glasses"]);
+    assert_eq!(s.full_asset_name(), "alice_happy_glasses");
+    s.apply_attrs(&changes("smile"));
+    assert_eq!(s.full_asset_name(), "alice_smile_glasses");
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::load;
+
+#[test]
+fn transcript_expands_choices_and_follows_jumps_once() {
+    let manager = load("transcript", r#"
+label start
+    : hello {f.name}
+    choice "pick"
+        "A":
+            jump other
+        "B":
+            : stay
+    enco
+enlb
+
+label other
+    : in other
+    jump start
+enlb
+"#);
+
+    let doc = manager.export_transcript("start").unwrap();
+    assert!(doc.contains("hello {f.name}"));
+    assert!(doc.contains("[choice] pick"));
+    assert!(doc.contains("<1> A"));
+    assert!(doc.contains("<2> B"));
+    assert!(doc.contains("stay"));
+    assert_eq!(doc.matches("== start ==").count(), 1);
+    assert_eq!(doc.matches("== other ==").count(), 1);
+}
+
+#[test]
+fn transcript_of_missing_label_is_error() {
+    let manager = load("transcript_missing", "label start\n    : x\nenlb\n");
+    assert!(manager.export_transcript("nope").is_err());
+}
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+use lumina_core::event::InputEvent;
+use lumina_core::renderer::driver::{ExecutorHandle, MAX_STEPS_PER_FRAME};
+use lumina_core::tts::TtsBackend;
+use lumina_core::{Ctx, OutputEvent};
+
+mod common;
+
+/// 记录朗读内容，`None` 表示一次停止
+#[derive(Clone, Default)]
+struct RecordingTts(Arc<Mutex<Vec<Option<String>>>>);
+
+impl TtsBackend for RecordingTts {
+    fn speak(&mut self, text: &str) {
+        self.0.lock().unwrap().push(Some(text.to_string()));
+    }
+
+    fn stop(&mut self) {
+        self.0.lock().unwrap().push(None);
+    }
+}
+
+const SCRIPT: &str = r#"
+character yuki name="Yuki" voice_tag="yuki"
+
+label init
+    : hello
+    yuki: "voiced line(001)"
+    yuki: "plain line"
+    choice "Where?"
+        "Left":
+            : left
+        "Right":
+            : right
+    enco
+enlb
+"#;
+
+#[test]
+fn driver_reads_text_and_cancels_on_advance() {
+    let manager = common::load("tts", SCRIPT);
+
+    let mut ctx = Ctx::default();
+    let mut driver = ExecutorHandle::new(&mut ctx, Arc::new(manager), "init");
+    let tts = RecordingTts::default();
+    driver.set_tts_backend(Box::new(tts.clone()), true);
+
+    for input in [InputEvent::Continue, InputEvent::Continue, InputEvent::Continue, InputEvent::ChoiceMade { index: 0 }] {
+        driver.step_until_wait(&mut ctx, MAX_STEPS_PER_FRAME);
+        let events = driver.drain(&mut ctx);
+        assert!(!events.iter().any(|e| matches!(e, OutputEvent::End)));
+        driver.feed(&mut ctx, input);
+    }
+
+    let spoken = tts.0.lock().unwrap().clone();
+    assert_eq!(spoken, vec![
+        Some("hello".to_string()),
+        None,
+        // 有配音的对话不朗读
+        None,
+        None,
+        Some("Yuki: plain line".to_string()),
+        None,
+        Some("Where?\n1. Left\n2. Right".to_string()),
+        None,
+    ]);
+}
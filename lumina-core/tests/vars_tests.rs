@@ -0,0 +1,62 @@
+use mlua::Lua;
+use lumina_core::lua_glue;
+
+/// 在 Lua 中构造 `f`，存出再读回到新的 Lua 实例，返回读回后的 Lua
+fn round_trip(setup: &str) -> (serde_json::Value, Lua) {
+    let _ = lumina_shared::config::init("test_dummy.toml");
+    let lua = Lua::new();
+    lua_glue::init_lua(&lua);
+    lua.load(setup).exec().unwrap();
+    let saved = lua_glue::extract_vars(&lua);
+
+    let restored = Lua::new();
+    lua_glue::init_lua(&restored);
+    lua_glue::inject_vars(&restored, &saved);
+    (saved, restored)
+}
+
+fn check(lua: &Lua, expr: &str) {
+    assert!(lua.load(format!("return {}", expr)).eval::<bool>().unwrap(), "failed: {}", expr);
+}
+
+#[test]
+fn nested_tables_survive_save_and_load() {
+    let (saved, lua) = round_trip(r#"
+        f.name = "yuki"
+        f.flag = true
+        f.off = false
+        f.gone = nil
+        f.count = 3
+        f.ratio = 0.5
+        f.list = { 1, "two", { deep = true } }
+        f.nested = { inner = { items = { 10, 20 } } }
+    "#);
+
+    assert_eq!(saved["list"], serde_json::json!([1, "two", { "deep": true }]));
+    assert!(saved.get("gone").is_none());
+
+    check(&lua, r#"f.name == "yuki" and f.flag == true and f.off == false and f.gone == nil"#);
+    check(&lua, "math.type(f.count) == 'integer' and f.count == 3 and f.ratio == 0.5");
+    check(&lua, r#"#f.list == 3 and f.list[2] == "two" and f.list[3].deep == true"#);
+    check(&lua, "f.nested.inner.items[1] == 10 and f.nested.inner.items[2] == 20");
+}
+
+#[test]
+fn empty_sparse_and_mixed_tables_keep_their_shape() {
+    let (saved, lua) = round_trip(r#"
+        f.empty = {}
+        f.sparse = { [1] = "a", [5] = "e" }
+        f.mixed = { 1, 2, label = "x" }
+        f.visited = { [10] = true }
+    "#);
+
+    assert_eq!(saved["empty"], serde_json::json!({}));
+
+    check(&lua, "next(f.empty) == nil");
+    check(&lua, r#"f.sparse[1] == "a" and f.sparse[5] == "e" and f.sparse[2] == nil"#);
+    check(&lua, r#"f.mixed[1] == 1 and f.mixed[2] == 2 and f.mixed.label == "x""#);
+    check(&lua, r#"f.visited[10] == true and f.visited["10"] == nil"#);
+
+    // 再存一次，结构不应漂移
+    assert_eq!(lua_glue::extract_vars(&lua), saved);
+}
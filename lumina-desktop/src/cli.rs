@@ -0,0 +1,149 @@
+//! 启动参数。影响配置的参数转换成 `--config key=value` 覆盖，与配置文件、环境变量走同一套分层
+
+const USAGE: &str = "\
+Usage: lumina-desktop [options]
+
+  --tui                    使用终端界面
+  --project <dir|pak>      脚本目录或打包好的 .pak (system.script_path)
+  --start <label>          新游戏的入口 label (system.start_label)
+  --save-dir <dir>         存档目录，auto 为系统用户目录 (system.save_path)
+  --width <px>             窗口宽度 (window.width)
+  --height <px>            窗口高度 (window.height)
+  --windowed               以窗口模式启动，忽略全屏偏好 (window.windowed)
+  --skip-menu              跳过主菜单直接开始新游戏 (debug.skip_menu)
+  --load <slot>            启动后直接读取存档槽位 (debug.load_slot)
+  --config <key=value>     覆盖任意配置项，可重复
+  --check                  检查脚本项目后退出
+  --unreachable            列出从入口 label 到不了的 label 与分支块后退出
+  --extract-strings <out>  提取可翻译文本后退出 (`.toml` 为 locales 模板，其余为 JSON)
+  --pack <out.pak>         把脚本目录与资源目录打成一个 pak 后退出
+  --help                   显示本说明";
+
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub tui: bool,
+    pub project: Option<String>,
+    pub start: Option<String>,
+    pub save_dir: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub windowed: bool,
+    pub skip_menu: bool,
+    pub load: Option<u32>,
+    pub check: bool,
+    pub unreachable: bool,
+    pub extract_strings: Option<String>,
+    pub pack: Option<String>,
+    pub help: bool,
+    /// `--config key=value` 原样保留
+    pub config: Vec<String>,
+}
+
+impl CliArgs {
+    /// 解析参数 (不含程序名)
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut out = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            // 同时支持 `--flag value` 与 `--flag=value`
+            let (flag, inline) = match arg.split_once('=') {
+                Some((f, v)) if f.starts_with("--") => (f, Some(v.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = |name: &str| -> Result<String, String> {
+                inline.clone()
+                    .or_else(|| iter.next().cloned())
+                    .ok_or_else(|| format!("{} expects a value", name))
+            };
+            match flag {
+                "--tui" => out.tui = true,
+                "--check" => out.check = true,
+                "--windowed" => out.windowed = true,
+                "--skip-menu" => out.skip_menu = true,
+                "--unreachable" => out.unreachable = true,
+                "--help" | "-h" => out.help = true,
+                "--project" => out.project = Some(value(flag)?),
+                "--start" => out.start = Some(value(flag)?),
+                "--save-dir" => out.save_dir = Some(value(flag)?),
+                "--width" => out.width = Some(parse_px(flag, &value(flag)?)?),
+                "--height" => out.height = Some(parse_px(flag, &value(flag)?)?),
+                "--load" => {
+                    let raw = value(flag)?;
+                    out.load = Some(raw.parse::<u32>()
+                        .map_err(|_| format!("{} expects a slot number, got '{}'", flag, raw))?);
+                }
+                "--config" => out.config.push(value(flag)?),
+                "--extract-strings" => out.extract_strings = Some(value(flag)?),
+                "--pack" => out.pack = Some(value(flag)?),
+                _ => return Err(format!("Unknown argument '{}'", arg)),
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+
+    /// 转换成配置覆盖，专用参数排在 `--config` 之后，同一项以专用参数为准
+    pub fn config_overrides(&self) -> Vec<String> {
+        let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+        let mut out = self.config.clone();
+        if let Some(dir) = &self.project {
+            out.push(format!("system.script_path={}", quote(dir)));
+            // 整个项目打成一个包时，资源也从同一个包读取
+            if dir.to_lowercase().ends_with(".pak") {
+                out.push(format!("system.assets_path={}", quote(dir)));
+            }
+        }
+        if let Some(label) = &self.start {
+            out.push(format!("system.start_label={}", quote(label)));
+        }
+        if let Some(dir) = &self.save_dir {
+            out.push(format!("system.save_path={}", quote(dir)));
+        }
+        if let Some(w) = self.width {
+            out.push(format!("window.width={}", w));
+        }
+        if let Some(h) = self.height {
+            out.push(format!("window.height={}", h));
+        }
+        if self.windowed {
+            out.push("window.windowed=true".to_string());
+        }
+        if self.skip_menu {
+            out.push("debug.skip_menu=true".to_string());
+        }
+        if let Some(slot) = self.load {
+            out.push(format!("debug.load_slot={}", slot));
+        }
+        out
+    }
+
+    /// 互相冲突或不起作用的参数组合
+    pub fn warnings(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.tui && (self.width.is_some() || self.height.is_some() || self.windowed) {
+            out.push("--width / --height / --windowed have no effect with --tui".to_string());
+        }
+        if self.tui && self.skip_menu {
+            out.push("--skip-menu has no effect with --tui (it has no main menu)".to_string());
+        }
+        let offline = [self.check, self.unreachable, self.extract_strings.is_some(), self.pack.is_some()];
+        if offline.iter().filter(|&&b| b).count() > 1 {
+            out.push("Only one of --pack, --check, --unreachable and --extract-strings runs (in that order)".to_string());
+        }
+        let launch = self.tui || self.width.is_some() || self.height.is_some()
+            || self.windowed || self.skip_menu || self.load.is_some();
+        if offline.contains(&true) && launch {
+            out.push("Renderer and launch options are ignored by --pack / --check / --unreachable / --extract-strings".to_string());
+        }
+        out
+    }
+}
+
+fn parse_px(flag: &str, raw: &str) -> Result<u32, String> {
+    raw.parse::<u32>().ok()
+        .filter(|v| *v > 0)
+        .ok_or_else(|| format!("{} expects a positive integer, got '{}'", flag, raw))
+}
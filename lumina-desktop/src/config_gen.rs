@@ -1,13 +1,15 @@
 use std::fs;
 use std::path::Path;
 use serde::Serialize;
-use lumina_core::config::{SystemConfig, AudioConfig, GraphicsConfig};
+use lumina_core::config::{SystemConfig, AudioConfig, GraphicsConfig, DebugConfig, TuiConfig};
 
 #[derive(Serialize)]
 struct FullConfig {
     system: SystemConfig,
     audio: AudioConfig,
     graphics: GraphicsConfig,
+    debug: DebugConfig,
+    tui: TuiConfig,
     // 只有开启 skia 时，才生成 window 配置节
     #[cfg(feature = "skia")]
     window: lumina_skia_renderer::config::WindowConfig,
@@ -26,6 +28,8 @@ pub fn ensure_config_exists(path: &str) {
         window: lumina_skia_renderer::config::WindowConfig::default(),
         audio: AudioConfig::default(),
         graphics: GraphicsConfig::default(),
+        debug: DebugConfig::default(),
+        tui: TuiConfig::default(),
     };
 
     let toml_str = toml::to_string_pretty(&default_config)
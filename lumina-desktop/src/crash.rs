@@ -0,0 +1,138 @@
+//! 崩溃报告：panic 时写出 `crash_<时间戳>.txt`，下次启动时提示玩家发给开发者
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use lumina_core::renderer::driver::ExecutorHandle;
+
+/// 报告中保留的最近日志行数
+pub const LOG_TAIL_LINES: usize = 50;
+
+const REPORT_PREFIX: &str = "crash_";
+/// 已提示过的报告改名为此后缀，不再重复提示
+const REPORTED_SUFFIX: &str = ".reported.txt";
+
+static IN_HOOK: AtomicBool = AtomicBool::new(false);
+
+/// 安装 panic hook，报告写到 `dir`；原有的 hook 仍会被调用
+pub fn install(dir: impl Into<PathBuf>) {
+    let dir = dir.into();
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // hook 内部再次 panic 时不再写报告，避免递归
+        if !IN_HOOK.swap(true, Ordering::SeqCst) {
+            match write_report(&dir, info) {
+                Ok(path) => eprintln!("Crash report written to {}", path.display()),
+                Err(e) => eprintln!("Failed to write crash report: {}", e),
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+fn write_report(dir: &Path, info: &PanicHookInfo) -> std::io::Result<PathBuf> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = dir.join(format!("{}{}.txt", REPORT_PREFIX, timestamp));
+
+    fs::create_dir_all(dir)?;
+    fs::write(&path, render_report(info))?;
+    Ok(path)
+}
+
+fn render_report(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".to_string());
+    let location = info.location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "<unknown>".to_string());
+    let thread = std::thread::current().name().unwrap_or("<unnamed>").to_string();
+
+    let mut out = String::new();
+    out.push_str(&format!("LuminaTale {} crash report\n\n", env!("CARGO_PKG_VERSION")));
+    out.push_str(&format!("Panic in thread '{}' at {}:\n{}\n\n", thread, location, message));
+
+    out.push_str("== Script position ==\n");
+    match ExecutorHandle::debug_snapshot() {
+        Some(snap) => out.push_str(&format!("{}\n\n", snap)),
+        None => out.push_str("<unavailable>\n\n"),
+    }
+
+    out.push_str("== Backtrace ==\n");
+    out.push_str(&format!("{}\n\n", Backtrace::force_capture()));
+
+    out.push_str("== Effective config ==\n");
+    for line in lumina_shared::config::effective_lines() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out.push_str(&format!("\n== Last {} log lines ==\n", LOG_TAIL_LINES));
+    for entry in lumina_shared::logging::recent(LOG_TAIL_LINES) {
+        out.push_str(&format!("[{} {}] {}\n", entry.level, entry.target, entry.message));
+    }
+    out
+}
+
+/// 上次运行留下、尚未提示过的崩溃报告
+pub fn pending_reports(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut reports: Vec<PathBuf> = entries.flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with(REPORT_PREFIX) && name.ends_with(".txt") && !name.ends_with(REPORTED_SUFFIX)
+        })
+        .collect();
+    reports.sort();
+    reports
+}
+
+/// 启动时检查上次是否崩溃，有则弹窗提示并把报告标记为已提示
+pub fn notify_previous_crash(dir: &Path) {
+    let reports = pending_reports(dir);
+    let Some(latest) = reports.last() else { return };
+
+    let message = format!(
+        "LuminaTale closed unexpectedly last time.\n\nPlease send this file to the developer:\n{}",
+        latest.display()
+    );
+    log::warn!("Found crash report from previous run: {}", latest.display());
+    show_message_box("LuminaTale crash report", &message);
+
+    for report in &reports {
+        let name = report.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let marked = report.with_file_name(name.replace(".txt", REPORTED_SUFFIX));
+        if let Err(e) = fs::rename(report, &marked) {
+            log::warn!("Failed to mark crash report {:?}: {}", report, e);
+        }
+    }
+}
+
+/// 用系统自带的对话框工具提示，工具不存在时只打印到终端
+fn show_message_box(title: &str, message: &str) {
+    let status = if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -AssemblyName PresentationFramework; [System.Windows.MessageBox]::Show('{}', '{}')",
+            message.replace('\'', "''"), title.replace('\'', "''")
+        );
+        Command::new("powershell").args(["-NoProfile", "-Command", &script]).status()
+    } else if cfg!(target_os = "macos") {
+        let script = format!(
+            "display dialog \"{}\" with title \"{}\" buttons {{\"OK\"}}",
+            message.replace('"', "\\\""), title.replace('"', "\\\"")
+        );
+        Command::new("osascript").args(["-e", &script]).status()
+    } else {
+        Command::new("zenity").args(["--warning", "--title", title, "--text", message]).status()
+    };
+
+    if !status.is_ok_and(|s| s.success()) {
+        eprintln!("{}", message);
+    }
+}
@@ -1,18 +1,32 @@
 mod setup;
 mod config_gen;
+mod cli;
+mod crash;
 
 use std::{env};
+use std::path::Path;
 use std::sync::Arc;
 use lumina_shared;
 use lumina_core::ScriptManager;
+use cli::CliArgs;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let arg_tui = args.iter().any(|a| a == "--tui");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let cli = match CliArgs::parse(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, CliArgs::usage());
+            std::process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", CliArgs::usage());
+        return;
+    }
 
     let is_tui_mode = if cfg!(feature = "tui") {
         if cfg!(feature = "skia") {
-            arg_tui
+            cli.tui
         } else {
             true
         }
@@ -20,20 +34,100 @@ fn main() {
         false
     };
 
-    setup::init(is_tui_mode);
+    setup::init(is_tui_mode, &cli.config_overrides());
     log::info!(">>> Lumina Desktop Launcher Started (TUI: {}) <<<", is_tui_mode);
+    for warning in cli.warnings() {
+        log::warn!("{}", warning);
+    }
 
     let sys_cfg: lumina_core::config::SystemConfig = lumina_shared::config::get("system");
     let project_root = &sys_cfg.script_path;
 
+    // 离线工具：脚本与资源打成一个 pak 后退出
+    if let Some(out) = &cli.pack {
+        let sections = [
+            (lumina_shared::vfs::SCRIPTS, Path::new(&sys_cfg.script_path)),
+            (lumina_shared::vfs::ASSETS, Path::new(&sys_cfg.assets_path)),
+        ];
+        match lumina_shared::vfs::write_pak_sections(&sections, Path::new(out)) {
+            Ok(count) => println!("Packed {} files into {}", count, out),
+            Err(e) => {
+                log::error!("Packing failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     log::info!("Loading project from: {:?}", project_root);
 
     let mut manager = ScriptManager::new();
     if let Err(e) = manager.load_project(project_root) {
         log::error!("Failed to load project: {}", e);
+        if cli.check {
+            std::process::exit(1);
+        }
         panic!("Project load failed");
     }
 
+    // 离线工具：检查项目，有问题时以非零状态退出
+    if cli.check {
+        let issues = manager.check_project();
+        for issue in &issues {
+            println!("{}", issue);
+        }
+        println!("{} problem(s) found", issues.len());
+        std::process::exit(if issues.is_empty() { 0 } else { 1 });
+    }
+
+    // 离线工具：从入口 label 做可达性分析，列出死代码
+    if cli.unreachable {
+        let report = manager.reachability(&[&sys_cfg.start_label]);
+        for m in &report.unreachable_labels {
+            println!("{}:{}: label '{}' is unreachable", m.file, m.line, m.id);
+        }
+        for block in &report.unreachable_blocks {
+            println!("block '{}' is unreachable", block);
+        }
+        println!(
+            "{} of {} label(s) reachable from '{}'",
+            report.reachable_labels.len(),
+            report.reachable_labels.len() + report.unreachable_labels.len(),
+            sys_cfg.start_label
+        );
+        return;
+    }
+
+    // 离线工具：提取可翻译文本后直接退出。`.toml` 输出 locales 模板，其余输出可增量合并的 JSON
+    if let Some(out) = &cli.extract_strings {
+        if out.ends_with(".toml") {
+            match manager.export_strings(out) {
+                Ok(count) => println!("Exported {} strings to {}", count, out),
+                Err(e) => {
+                    log::error!("String export failed: {:#}", e);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        match manager.extract_strings(out) {
+            Ok(report) => println!(
+                "Extracted {} strings to {} ({} new, {} obsolete)",
+                report.total, out, report.new, report.obsolete
+            ),
+            Err(e) => {
+                log::error!("String extraction failed: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if manager.get_label(&sys_cfg.start_label).is_none() {
+        log::error!("Start label '{}' not found in project", sys_cfg.start_label);
+        std::process::exit(2);
+    }
+
     let manager_arc = Arc::new(manager);
 
     log::info!("Project loaded successfully");
@@ -1,21 +1,32 @@
 use std::fs;
-use crate::config_gen;
+use crate::{config_gen, crash};
 
 use std::fs::OpenOptions;
 use std::io::Write;
 use env_logger::{Builder, Target};
+use log::{Log, Metadata, Record};
 use lumina_core::config::SystemConfig;
 
-pub fn init(is_tui: bool) {
+/// `config_overrides` 为启动参数转换出的 `key=value` 覆盖，优先级高于配置文件与环境变量
+pub fn init(is_tui: bool, config_overrides: &[String]) {
     let config_path = "config.toml";
 
     config_gen::ensure_config_exists(config_path);
 
-    if let Err(e) = lumina_shared::config::init(config_path) {
+    if let Err(e) = lumina_shared::config::init_with(config_path, config_overrides) {
         eprintln!("Config load warning: {}", e);
     }
 
     init_logger(is_tui);
+    lumina_shared::config::dump_effective();
+
+    // 崩溃报告与日志放在同一目录
+    let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+    crash::notify_previous_crash(std::path::Path::new(&sys_cfg.log_path));
+    crash::install(&sys_cfg.log_path);
+
+    // 启动时就解析存档目录，旧存档的迁移也在这里完成
+    log::info!("Save directory: {}", lumina_core::storager::save_dir().display());
 }
 
 fn init_logger(is_tui: bool) {
@@ -53,5 +64,30 @@ fn init_logger(is_tui: bool) {
         builder.target(Target::Pipe(Box::new(TeeWriter(std::io::stdout(), log_file))));
     }
 
-    builder.init();
+    // 另外写入共享的环形缓冲，供调试浮层、TUI 的 log 视图与崩溃报告读取
+    let logger = builder.build();
+    log::set_max_level(logger.filter());
+    if let Err(e) = log::set_boxed_logger(Box::new(RingTee(logger))) {
+        eprintln!("Failed to install logger: {}", e);
+    }
+}
+
+/// 在 env_logger 之外把通过过滤的记录写入 `lumina_shared::logging`
+struct RingTee(env_logger::Logger);
+
+impl Log for RingTee {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.0.matches(record) {
+            self.0.log(record);
+            lumina_shared::logging::record(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
 }
\ No newline at end of file
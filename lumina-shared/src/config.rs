@@ -1,13 +1,156 @@
-use std::sync::RwLock;
+//! 分层配置：编译期默认值 (各配置类型的 `Default`) < config.toml < `LUMINA_*` 环境变量 < 命令行 `--config key=value`
+//!
+//! 环境变量名为 `LUMINA_<节>__<键>`，如 `LUMINA_SYSTEM__SCRIPT_PATH=ci/game/`；
+//! 值按 TOML 解析 (`true`、`1.5`、`["a"]`)，解析失败时当作字符串。
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::{Mutex, RwLock};
 use std::path::Path;
 use std::fs;
 use once_cell::sync::OnceCell;
 use serde::de::DeserializeOwned;
-use toml::Table;
+use serde::Serialize;
+use toml::{Table, Value};
+
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "LUMINA_";
+
+static GLOBAL_CONFIG: OnceCell<RwLock<ConfigStore>> = OnceCell::new();
+
+/// 配置值的来源，后面的覆盖前面的
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Layer::Default => "default",
+            Layer::File => "file",
+            Layer::Env => "env",
+            Layer::Cli => "cli",
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ConfigStore {
+    layers: Vec<(Layer, Table)>,
+    /// 已按类型解析过的节 (含默认值)，供 `dump_effective` 输出
+    resolved: Mutex<BTreeMap<String, Table>>,
+    /// 已经警告过的未知键，避免每次 `get` 重复输出
+    warned: Mutex<HashSet<String>>,
+}
+
+impl ConfigStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入一层覆盖，需按 `Layer` 顺序加入
+    pub fn push_layer(&mut self, layer: Layer, table: Table) {
+        self.layers.push((layer, table));
+    }
+
+    /// 各层合并后的原始表 (不含默认值)
+    fn merged(&self) -> Table {
+        let mut out = Table::new();
+        for (_, table) in &self.layers {
+            merge(&mut out, table);
+        }
+        out
+    }
 
-static GLOBAL_CONFIG: OnceCell<RwLock<Table>> = OnceCell::new();
+    /// 读取一节配置：以 `T::default()` 为底，依次叠加各层；类型不匹配时整节使用默认值
+    pub fn get<T: DeserializeOwned + Serialize + Default>(&self, key: &str) -> T {
+        let overlay = self.merged();
+        let value = match (Value::try_from(T::default()), overlay.get(key)) {
+            (Ok(Value::Table(mut table)), Some(Value::Table(section))) => {
+                merge(&mut table, section);
+                Value::Table(table)
+            }
+            (_, Some(v)) => v.clone(),
+            (Ok(default), None) => default,
+            (Err(_), None) => return T::default(),
+        };
+
+        let parsed: T = value.clone().try_into().unwrap_or_else(|e| {
+            log::warn!("Config section '[{}]' mismatch: {}. Using default.", key, e);
+            T::default()
+        });
+
+        // 解析后再序列化回来，覆盖层中不存在于结果里的键即为未知键
+        let effective = match Value::try_from(&parsed) {
+            Ok(Value::Table(t)) => t,
+            _ => Table::new(),
+        };
+        if let (Some(Value::Table(section)), Value::Table(_)) = (overlay.get(key), &value) {
+            let mut unknown = Vec::new();
+            unknown_keys(section, &effective, key, &mut unknown);
+            let mut warned = self.warned.lock().unwrap();
+            for path in unknown {
+                if warned.insert(path.clone()) {
+                    log::warn!("Unknown config key '{}' ({}), ignored", path, self.origin(&path));
+                }
+            }
+        }
+        self.resolved.lock().unwrap().insert(key.to_string(), effective);
+
+        parsed
+    }
+
+    /// 读取单个值 (`节.键` 形式的路径) 并返回其来源；路径不存在或类型不符时为默认值
+    pub fn get_with_origin<T: DeserializeOwned + Default>(&self, path: &str) -> (T, Layer) {
+        let origin = self.origin(path);
+        let value = lookup(&self.merged(), path)
+            .and_then(|v| v.clone().try_into().ok());
+        match value {
+            Some(v) => (v, origin),
+            None => (T::default(), Layer::Default),
+        }
+    }
+
+    /// `节.键` 路径的值最终来自哪一层
+    pub fn origin(&self, path: &str) -> Layer {
+        self.layers.iter().rev()
+            .find(|(_, table)| lookup(table, path).is_some())
+            .map(|(layer, _)| *layer)
+            .unwrap_or(Layer::Default)
+    }
+
+    /// 最终生效的全部配置及来源，每行一个 `路径 = 值 (来源)`
+    pub fn effective_lines(&self) -> Vec<String> {
+        // 读取过的节用解析后的结果 (含默认值)，其余节只有各层中写出的值
+        let mut all = self.merged();
+        // 只尝试加锁：崩溃报告可能在持有该锁的线程里调用
+        let resolved = match self.resolved.try_lock() {
+            Ok(r) => Some(r),
+            Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        };
+        for (key, table) in resolved.iter().flat_map(|r| r.iter()) {
+            all.insert(key.clone(), Value::Table(table.clone()));
+        }
+
+        let mut leaves = Vec::new();
+        flatten(&all, "", &mut leaves);
+        leaves.into_iter()
+            .map(|(path, value)| format!("{} = {} ({})", path, value, self.origin(&path)))
+            .collect()
+    }
+}
 
 pub fn init<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
+    init_with(path, &[])
+}
+
+/// 读取配置文件、`LUMINA_*` 环境变量，再应用命令行传入的 `key=value` 覆盖
+pub fn init_with<P: AsRef<Path>>(path: P, cli_overrides: &[String]) -> anyhow::Result<()> {
     let path = path.as_ref();
 
     let content = if path.exists() {
@@ -23,22 +166,130 @@ pub fn init<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
         Table::new()
     });
 
-    GLOBAL_CONFIG.set(RwLock::new(table))
+    let mut store = ConfigStore::new();
+    store.push_layer(Layer::File, table);
+    store.push_layer(Layer::Env, parse_env(std::env::vars()));
+    // 命令行覆盖有误时仍然完成初始化，只忽略这一层
+    let cli = parse_overrides(cli_overrides);
+    store.push_layer(Layer::Cli, cli.as_ref().cloned().unwrap_or_default());
+
+    GLOBAL_CONFIG.set(RwLock::new(store))
         .map_err(|_| anyhow::anyhow!("Config already initialized"))?;
 
-    Ok(())
+    cli.map(|_| ())
 }
 
-pub fn get<T: DeserializeOwned + Default>(key: &str) -> T {
+pub fn get<T: DeserializeOwned + Serialize + Default>(key: &str) -> T {
     let store = GLOBAL_CONFIG.get().expect("lumina-shared config not initialized!");
-    let read_guard = store.read().unwrap();
+    store.read().unwrap().get(key)
+}
 
-    if let Some(value) = read_guard.get(key) {
-        value.clone().try_into().unwrap_or_else(|e| {
-            log::warn!("Config section '[{}]' mismatch: {}. Using default.", key, e);
-            T::default()
-        })
-    } else {
-        T::default()
+pub fn get_with_origin<T: DeserializeOwned + Default>(path: &str) -> (T, Layer) {
+    let store = GLOBAL_CONFIG.get().expect("lumina-shared config not initialized!");
+    store.read().unwrap().get_with_origin(path)
+}
+
+/// 在日志中输出最终生效的配置及每项的来源，启动时调用
+pub fn dump_effective() {
+    let Some(store) = GLOBAL_CONFIG.get() else { return };
+    log::info!("Effective config:");
+    for line in store.read().unwrap().effective_lines() {
+        log::info!("  {}", line);
+    }
+}
+
+/// 最终生效的配置，不阻塞也不 panic，供崩溃报告使用；未初始化或正在写入时为空
+pub fn effective_lines() -> Vec<String> {
+    GLOBAL_CONFIG.get()
+        .and_then(|store| store.try_read().ok())
+        .map(|store| store.effective_lines())
+        .unwrap_or_default()
+}
+
+/// `LUMINA_SYSTEM__SCRIPT_PATH=x` -> `system.script_path = "x"`
+pub fn parse_env(vars: impl IntoIterator<Item = (String, String)>) -> Table {
+    let mut table = Table::new();
+    for (name, raw) in vars {
+        let Some(rest) = name.strip_prefix(ENV_PREFIX) else { continue };
+        let path: Vec<String> = rest.split("__").map(|s| s.to_lowercase()).collect();
+        if path.len() < 2 || path.iter().any(|s| s.is_empty()) {
+            continue;
+        }
+        insert_path(&mut table, &path, parse_value(&raw));
+    }
+    table
+}
+
+/// 解析命令行的 `节.键=值` 覆盖
+pub fn parse_overrides(items: &[String]) -> anyhow::Result<Table> {
+    let mut table = Table::new();
+    for item in items {
+        let (key, raw) = item.split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid config override '{}', expected key=value", item))?;
+        let path: Vec<String> = key.trim().split('.').map(str::to_string).collect();
+        if path.len() < 2 || path.iter().any(|s| s.is_empty()) {
+            anyhow::bail!("Invalid config key '{}', expected section.key", key);
+        }
+        insert_path(&mut table, &path, parse_value(raw.trim()));
+    }
+    Ok(table)
+}
+
+fn parse_value(raw: &str) -> Value {
+    toml::from_str::<Table>(&format!("v = {}", raw)).ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn insert_path(table: &mut Table, path: &[String], value: Value) {
+    let (last, parents) = path.split_last().expect("empty config path");
+    let mut current = table;
+    for key in parents {
+        let entry = current.entry(key.clone()).or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        current = entry.as_table_mut().unwrap();
     }
-}
\ No newline at end of file
+    current.insert(last.clone(), value);
+}
+
+fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
+/// 表按键递归合并，其余值整体替换
+fn merge(base: &mut Table, over: &Table) {
+    for (key, value) in over {
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(b)), Value::Table(o)) => merge(b, o),
+            _ => { base.insert(key.clone(), value.clone()); }
+        }
+    }
+}
+
+fn unknown_keys(given: &Table, known: &Table, prefix: &str, out: &mut Vec<String>) {
+    for (key, value) in given {
+        let path = format!("{}.{}", prefix, key);
+        match (known.get(key), value) {
+            (None, _) => out.push(path),
+            (Some(Value::Table(k)), Value::Table(g)) => unknown_keys(g, k, &path, out),
+            _ => {}
+        }
+    }
+}
+
+fn flatten(table: &Table, prefix: &str, out: &mut Vec<(String, Value)>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            Value::Table(t) if !t.is_empty() => flatten(t, &path, out),
+            _ => out.push((path, value.clone())),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! 最近日志的环形缓冲，供调试浮层、TUI 的 `log` 视图与崩溃报告读取。
+//!
+//! 每个槽位一把 `Mutex`，并非无锁结构：写入端原子地领取序号后只锁自己的槽位替换其中的 `Arc`，
+//! 写入线程之间只有落到同一槽位时才会互等；读取端按序号从新到旧逐个加锁回扫，
+//! 槽位已被更新的记录覆盖时跳过。持锁期间只替换指针，不会在锁内 panic，panic hook 中读取也不会死锁。
+//! 开销只有一次格式化与一次分配，发行版也可以常开。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use log::{Level, LevelFilter, Record};
+
+/// 缓冲保留的记录条数
+pub const CAPACITY: usize = 1024;
+
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(0);
+static SLOTS: [Mutex<Option<Arc<LogEntry>>>; CAPACITY] = [const { Mutex::new(None) }; CAPACITY];
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// 写入序号，单调递增
+    pub seq: u64,
+    pub level: Level,
+    /// 日志来源模块，如 `lumina_core::executor`
+    pub target: String,
+    pub message: String,
+}
+
+/// 读取时的过滤条件
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    /// 最详细显示到哪一级，如 `Warn` 只显示警告与错误
+    pub level: LevelFilter,
+    /// 只显示来源以此开头的记录
+    pub module: Option<String>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self { level: LevelFilter::Trace, module: None }
+    }
+}
+
+impl LogFilter {
+    /// 解析 `[level] [module]`，如 `warn lumina_core::executor`；级别可省略
+    pub fn parse(args: &str) -> Self {
+        let mut filter = Self::default();
+        for arg in args.split_whitespace() {
+            match arg.parse::<LevelFilter>() {
+                Ok(level) => filter.level = level,
+                Err(_) => filter.module = Some(arg.to_string()),
+            }
+        }
+        filter
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        entry.level <= self.level
+            && self.module.as_deref().is_none_or(|m| entry.target.starts_with(m))
+    }
+}
+
+/// 写入一条记录
+pub fn push(level: Level, target: &str, message: String) {
+    let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    let entry = Arc::new(LogEntry { seq, level, target: target.to_string(), message });
+    let mut slot = SLOTS[(seq % CAPACITY as u64) as usize].lock().unwrap_or_else(|e| e.into_inner());
+    // 写得慢的线程不能用旧记录覆盖已经绕回一圈的新记录
+    if slot.as_ref().is_none_or(|old| old.seq < seq) {
+        *slot = Some(entry);
+    }
+}
+
+/// 写入一条 `log` 记录
+pub fn record(record: &Record) {
+    push(record.level(), record.target(), record.args().to_string());
+}
+
+/// 最近的 `n` 条记录，按时间从旧到新
+pub fn recent(n: usize) -> Vec<Arc<LogEntry>> {
+    recent_filtered(n, &LogFilter::default())
+}
+
+/// 最近的 `n` 条满足过滤条件的记录，按时间从旧到新
+pub fn recent_filtered(n: usize, filter: &LogFilter) -> Vec<Arc<LogEntry>> {
+    let end = NEXT_SEQ.load(Ordering::Relaxed);
+    let start = end.saturating_sub(CAPACITY as u64);
+
+    let mut out = Vec::new();
+    for seq in (start..end).rev() {
+        if out.len() >= n {
+            break;
+        }
+        let slot = SLOTS[(seq % CAPACITY as u64) as usize].lock().unwrap_or_else(|e| e.into_inner());
+        // 序号已领取但尚未写入，或已被更新的记录覆盖
+        let Some(entry) = slot.as_ref().filter(|e| e.seq == seq) else { continue };
+        if filter.matches(entry) {
+            out.push(entry.clone());
+        }
+    }
+    out.reverse();
+    out
+}
@@ -0,0 +1,301 @@
+//! 只读文件来源：开发期直接读目录，发行期读打包好的 pak 归档，调用方不关心具体来源
+//!
+//! pak 格式 (整数均为小端)：
+//! ```text
+//! "LPAK" | u32 条目数 | 条目 * N | 数据区
+//! 条目 = u16 路径长度 | 路径 (UTF-8，`/` 分隔) | u64 数据偏移 | u64 数据长度
+//! ```
+//! 偏移从文件开头算起。整个项目打成一个包时，脚本与资源分别放在
+//! `scripts/`、`assets/` 两个分区下。
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const PAK_MAGIC: &[u8; 4] = b"LPAK";
+
+/// 脚本分区 (`system.script_path` 的内容)
+pub const SCRIPTS: &str = "scripts";
+/// 资源分区 (`system.assets_path` 的内容)
+pub const ASSETS: &str = "assets";
+
+pub trait VfsSource: Send + Sync {
+    /// 列出全部文件，路径相对于来源根目录，以 `/` 分隔
+    fn list(&self) -> Vec<String>;
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>>;
+
+    /// 文件在磁盘上的实际路径，用于流式读取大文件；归档内的文件返回 `None`
+    fn file_path(&self, _path: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// 以流的方式读取，不必整体读入内存
+    fn open(&self, path: &str) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(Cursor::new(self.read(path)?)))
+    }
+
+    fn read_to_string(&self, path: &str) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.list().iter().any(|p| p == path)
+    }
+}
+
+/// 是否为 pak 归档路径
+pub fn is_pak(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|e| e.eq_ignore_ascii_case("pak"))
+}
+
+/// 按路径选择来源：`.pak` 文件读归档中的 `section` 分区，其余按目录处理
+pub fn open(root: impl AsRef<Path>, section: &str) -> io::Result<Arc<dyn VfsSource>> {
+    let path = root.as_ref();
+    if is_pak(path) {
+        Ok(Arc::new(PakSource::open_section(path, section)?))
+    } else {
+        Ok(Arc::new(DirSource::new(path)))
+    }
+}
+
+/// 同 [`open`]，归档打不开时记录错误并退回目录来源
+pub fn open_or_dir(root: impl AsRef<Path>, section: &str) -> Arc<dyn VfsSource> {
+    let root = root.as_ref();
+    open(root, section).unwrap_or_else(|e| {
+        log::error!("Failed to open {:?}: {}", root, e);
+        Arc::new(DirSource::new(root))
+    })
+}
+
+/// 归档内的文件复制到临时目录，返回可以直接打开的路径；磁盘上的文件原样返回。
+/// 供只接受文件路径的流式解码使用
+pub fn local_path(source: &dyn VfsSource, path: &str) -> io::Result<PathBuf> {
+    if let Some(file) = source.file_path(path) {
+        return Ok(file);
+    }
+
+    let dir = std::env::temp_dir().join(format!("lumina_stream_{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    // 转义而不是简单替换 `/`，保证不同路径不会落到同一个文件
+    let name = path.replace('%', "%25").replace('/', "%2F");
+    let target = dir.join(&name);
+    if !target.exists() {
+        // 先写临时名再改名，避免并发读取到写了一半的文件
+        let partial = dir.join(format!("{}.part", name));
+        io::copy(&mut source.open(path)?, &mut File::create(&partial)?)?;
+        fs::rename(&partial, &target)?;
+    }
+    Ok(target)
+}
+
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl VfsSource for DirSource {
+    fn list(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        walk(&self.root, &self.root, &mut out);
+        out
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.root.join(path))
+    }
+
+    fn file_path(&self, path: &str) -> Option<PathBuf> {
+        Some(self.root.join(path))
+    }
+
+    fn open(&self, path: &str) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(File::open(self.root.join(path))?))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.root.join(path).is_file()
+    }
+}
+
+pub struct PakSource {
+    path: PathBuf,
+    file: Mutex<File>,
+    entries: HashMap<String, (u64, u64)>,
+}
+
+impl PakSource {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)?;
+        if &magic != PAK_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{:?} is not a pak archive", path)));
+        }
+
+        let count = read_u32(&mut file)?;
+        let mut entries = HashMap::new();
+        for _ in 0..count {
+            let len = read_u16(&mut file)? as usize;
+            let mut name = vec![0u8; len];
+            file.read_exact(&mut name)?;
+            let name = String::from_utf8(name)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let offset = read_u64(&mut file)?;
+            let size = read_u64(&mut file)?;
+            // 截断或被改过的归档：条目超出文件末尾时直接拒绝，读取时才不会按错误的长度分配内存
+            if offset.checked_add(size).is_none_or(|end| end > file_len) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{:?}: entry '{}' extends past the end of the archive", path, name),
+                ));
+            }
+            entries.insert(name, (offset, size));
+        }
+
+        Ok(Self { path: path.to_path_buf(), file: Mutex::new(file), entries })
+    }
+
+    /// 只看 `section/` 分区下的文件，路径去掉分区前缀。
+    /// 没有分区的旧归档 (只含资源) 整体作为该分区
+    pub fn open_section(path: &Path, section: &str) -> io::Result<Self> {
+        let mut pak = Self::open(path)?;
+        let sectioned = pak.entries.keys()
+            .any(|k| [SCRIPTS, ASSETS].iter().any(|s| k.strip_prefix(s).is_some_and(|r| r.starts_with('/'))));
+        if sectioned {
+            let prefix = format!("{}/", section);
+            pak.entries = pak.entries.into_iter()
+                .filter_map(|(k, v)| k.strip_prefix(&prefix).map(|k| (k.to_string(), v)))
+                .collect();
+        }
+        Ok(pak)
+    }
+
+    fn entry(&self, path: &str) -> io::Result<(u64, u64)> {
+        self.entries.get(path).copied()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("'{}' not in archive", path)))
+    }
+}
+
+impl VfsSource for PakSource {
+    fn list(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    fn read(&self, path: &str) -> io::Result<Vec<u8>> {
+        let (offset, size) = self.entry(path)?;
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buf = vec![0u8; size as usize];
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// 单独打开一个文件句柄，流式读取时不占用共享句柄
+    fn open(&self, path: &str) -> io::Result<Box<dyn Read + Send>> {
+        let (offset, size) = self.entry(path)?;
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(Box::new(file.take(size)))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+}
+
+/// 把目录下的全部文件打包成 pak，返回打包的文件数
+pub fn write_pak(dir: &Path, out: &Path) -> io::Result<usize> {
+    write_pak_sections(&[("", dir)], out)
+}
+
+/// 把多个目录分别放进各自的分区打成一个包 (分区名为空时放在根下)，返回打包的文件数
+pub fn write_pak_sections(sections: &[(&str, &Path)], out: &Path) -> io::Result<usize> {
+    // (包内路径, 磁盘路径)
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    for (section, dir) in sections {
+        if !dir.is_dir() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{:?} is not a directory", dir)));
+        }
+        let mut names = DirSource::new(*dir).list();
+        names.sort();
+        for name in names {
+            let key = if section.is_empty() { name.clone() } else { format!("{}/{}", section, name) };
+            files.push((key, dir.join(&name)));
+        }
+    }
+
+    let header_len: u64 = 8 + files.iter().map(|(n, _)| 2 + n.len() as u64 + 16).sum::<u64>();
+    let mut header = Vec::with_capacity(header_len as usize);
+    header.extend_from_slice(PAK_MAGIC);
+    header.extend_from_slice(&(files.len() as u32).to_le_bytes());
+
+    let mut offset = header_len;
+    for (name, file) in &files {
+        let len = fs::metadata(file)?.len();
+        let name_len = u16::try_from(name.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("Path too long: {}", name)))?;
+        header.extend_from_slice(&name_len.to_le_bytes());
+        header.extend_from_slice(name.as_bytes());
+        header.extend_from_slice(&offset.to_le_bytes());
+        header.extend_from_slice(&len.to_le_bytes());
+        offset += len;
+    }
+
+    let mut writer = io::BufWriter::new(File::create(out)?);
+    writer.write_all(&header)?;
+    for (_, file) in &files {
+        io::copy(&mut File::open(file)?, &mut writer)?;
+    }
+    writer.flush()?;
+    Ok(files.len())
+}
+
+fn walk(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            walk(root, &path, out);
+        } else if file_type.is_file() {
+            out.extend(relative_key(root, &path));
+        }
+    }
+}
+
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let parts: Vec<&str> = rel.components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+fn read_u16(r: &mut impl Read) -> io::Result<u16> {
+    let mut b = [0u8; 2];
+    r.read_exact(&mut b)?;
+    Ok(u16::from_le_bytes(b))
+}
+
+fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use lumina_shared::config::{parse_env, parse_overrides, ConfigStore, Layer};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct System {
+    script_path: String,
+    log_level: String,
+    text_speed: f32,
+}
+
+impl Default for System {
+    fn default() -> Self {
+        Self { script_path: "game/".into(), log_level: "info".into(), text_speed: 40.0 }
+    }
+}
+
+fn store() -> ConfigStore {
+    let file: toml::Table = toml::from_str(r#"
+        [system]
+        script_path = "story/"
+        log_level = "debug"
+        typo_key = 1
+    "#).unwrap();
+    let env = parse_env(vec![
+        ("LUMINA_SYSTEM__LOG_LEVEL".to_string(), "warn".to_string()),
+        ("PATH".to_string(), "/usr/bin".to_string()),
+    ]);
+    let cli = parse_overrides(&["system.text_speed=80".to_string()]).unwrap();
+
+    let mut store = ConfigStore::new();
+    store.push_layer(Layer::File, file);
+    store.push_layer(Layer::Env, env);
+    store.push_layer(Layer::Cli, cli);
+    store
+}
+
+#[test]
+fn later_layers_override_earlier_ones() {
+    let store = store();
+    let sys: System = store.get("system");
+    assert_eq!(sys, System { script_path: "story/".into(), log_level: "warn".into(), text_speed: 80.0 });
+
+    assert_eq!(store.origin("system.script_path"), Layer::File);
+    assert_eq!(store.origin("system.log_level"), Layer::Env);
+    assert_eq!(store.origin("system.text_speed"), Layer::Cli);
+    assert_eq!(store.get_with_origin::<String>("system.log_level"), ("warn".to_string(), Layer::Env));
+
+    // 缺失的节整体取默认值
+    let missing: System = store.get("nothing");
+    assert_eq!(missing, System::default());
+}
+
+#[test]
+fn effective_dump_includes_defaults_and_sources() {
+    let store = store();
+    let _: System = store.get("system");
+    let lines = store.effective_lines();
+    assert!(lines.contains(&"system.text_speed = 80.0 (cli)".to_string()), "{:?}", lines);
+    assert!(lines.contains(&"system.script_path = \"story/\" (file)".to_string()), "{:?}", lines);
+}
+
+#[test]
+fn overrides_must_name_a_section_key() {
+    assert!(parse_overrides(&["text_speed=80".to_string()]).is_err());
+    assert!(parse_overrides(&["system.text_speed".to_string()]).is_err());
+
+    // 无法按 TOML 解析的值当作字符串
+    let table = parse_overrides(&["system.script_path=ci/game".to_string()]).unwrap();
+    assert_eq!(table["system"]["script_path"].as_str(), Some("ci/game"));
+}
@@ -0,0 +1,30 @@
+use log::{Level, LevelFilter};
+use lumina_shared::logging::{self, LogFilter, CAPACITY};
+
+#[test]
+fn recent_filters_and_keeps_newest_entries() {
+    logging::push(Level::Info, "game::audio", "bgm started".into());
+    logging::push(Level::Warn, "game::script", "unknown speaker".into());
+    logging::push(Level::Debug, "game::script", "step".into());
+    logging::push(Level::Error, "game::audio", "decode failed".into());
+
+    let filter = LogFilter::parse("warn game");
+    let messages: Vec<String> = logging::recent_filtered(10, &filter).iter().map(|e| e.message.clone()).collect();
+    assert_eq!(messages, vec!["unknown speaker", "decode failed"]);
+
+    let filter = LogFilter::parse("game::script");
+    assert_eq!(filter.level, LevelFilter::Trace);
+    let messages: Vec<String> = logging::recent_filtered(1, &filter).iter().map(|e| e.message.clone()).collect();
+    assert_eq!(messages, vec!["step"]);
+
+    // 缓冲是全局的，溢出检查放在同一个测试里，避免并行测试互相覆盖
+    for i in 0..CAPACITY + 10 {
+        logging::push(Level::Info, "ring::overflow", format!("line {}", i));
+    }
+
+    let filter = LogFilter::parse("ring::overflow");
+    let entries = logging::recent_filtered(CAPACITY * 2, &filter);
+    assert!(entries.len() <= CAPACITY);
+    assert_eq!(entries.last().unwrap().message, format!("line {}", CAPACITY + 9));
+    assert!(entries.windows(2).all(|w| w[0].seq < w[1].seq));
+}
@@ -0,0 +1,93 @@
+use std::io::Read;
+use lumina_shared::vfs::{self, write_pak, write_pak_sections, PakSource, VfsSource};
+
+fn project(name: &str) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("lumina_vfs_{}", name));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("game/tl")).unwrap();
+    std::fs::create_dir_all(root.join("assets/bgm")).unwrap();
+    std::fs::write(root.join("game/start.vivi"), "label init\nenlb\n").unwrap();
+    std::fs::write(root.join("game/tl/en.json"), "{}").unwrap();
+    std::fs::write(root.join("assets/bgm/theme.ogg"), b"ogg bytes").unwrap();
+    root
+}
+
+#[test]
+fn sections_split_scripts_and_assets() {
+    let root = project("sections");
+    let pak = root.join("game.pak");
+    let sections = [(vfs::SCRIPTS, root.join("game")), (vfs::ASSETS, root.join("assets"))];
+    let sections: Vec<(&str, &std::path::Path)> = sections.iter().map(|(s, p)| (*s, p.as_path())).collect();
+    assert_eq!(write_pak_sections(&sections, &pak).unwrap(), 3);
+
+    let scripts = vfs::open(&pak, vfs::SCRIPTS).unwrap();
+    let mut listed = scripts.list();
+    listed.sort();
+    assert_eq!(listed, ["start.vivi", "tl/en.json"]);
+    assert_eq!(scripts.read_to_string("start.vivi").unwrap(), "label init\nenlb\n");
+    assert!(!scripts.exists("bgm/theme.ogg"));
+
+    let assets = vfs::open(&pak, vfs::ASSETS).unwrap();
+    assert_eq!(assets.list(), ["bgm/theme.ogg"]);
+    assert!(assets.file_path("bgm/theme.ogg").is_none());
+
+    // 流式读取只读到该条目的末尾
+    let mut streamed = Vec::new();
+    assets.open("bgm/theme.ogg").unwrap().read_to_end(&mut streamed).unwrap();
+    assert_eq!(streamed, b"ogg bytes");
+
+    let local = vfs::local_path(assets.as_ref(), "bgm/theme.ogg").unwrap();
+    assert_eq!(std::fs::read(local).unwrap(), b"ogg bytes");
+
+    // 目录来源不经过归档
+    let dir = vfs::open(root.join("game"), vfs::SCRIPTS).unwrap();
+    assert_eq!(dir.file_path("start.vivi"), Some(root.join("game/start.vivi")));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn truncated_pak_is_rejected() {
+    let root = project("truncated");
+    let pak = root.join("assets.pak");
+    write_pak(&root.join("assets"), &pak).unwrap();
+
+    let bytes = std::fs::read(&pak).unwrap();
+    std::fs::write(&pak, &bytes[..bytes.len() - 1]).unwrap();
+    let err = PakSource::open(&pak).err().expect("truncated pak must not open");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn local_paths_do_not_collide() {
+    let root = project("collide");
+    std::fs::create_dir_all(root.join("assets/a")).unwrap();
+    std::fs::create_dir_all(root.join("assets/a_b")).unwrap();
+    std::fs::write(root.join("assets/a/b_c.ogg"), b"first").unwrap();
+    std::fs::write(root.join("assets/a_b/c.ogg"), b"second").unwrap();
+    let pak = root.join("assets.pak");
+    write_pak(&root.join("assets"), &pak).unwrap();
+
+    let assets = PakSource::open(&pak).unwrap();
+    let first = vfs::local_path(&assets, "a/b_c.ogg").unwrap();
+    let second = vfs::local_path(&assets, "a_b/c.ogg").unwrap();
+    assert_ne!(first, second);
+    assert_eq!(std::fs::read(first).unwrap(), b"first");
+    assert_eq!(std::fs::read(second).unwrap(), b"second");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn unsectioned_pak_is_used_whole() {
+    let root = project("legacy");
+    let pak = root.join("assets.pak");
+    write_pak(&root.join("assets"), &pak).unwrap();
+
+    let assets = PakSource::open_section(&pak, vfs::ASSETS).unwrap();
+    assert_eq!(assets.list(), ["bgm/theme.ogg"]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,7 +9,206 @@ pub struct WindowConfig {
     pub height: u32,
     pub resizable: bool,
     pub vsync: bool,
+    /// 以窗口模式启动，忽略设置页保存的全屏偏好
+    #[serde(default)]
+    pub windowed: bool,
+    /// 字体角色 -> 按优先级排列的字体族 (如 `dialogue = ["SourceHanSans", "NotoSans"]`)
+    #[serde(default = "default_fonts")]
+    pub fonts: BTreeMap<String, Vec<String>>,
+    /// 按语言覆盖的字体角色 (`[window.language_fonts.en]`)
+    #[serde(default)]
+    pub language_fonts: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    /// 整页切换 (`Replace`，如主菜单 -> 游戏) 的默认效果
+    #[serde(default = "default_screen_fx")]
+    pub screen_fx: ScreenFx,
+    /// 打开 / 关闭叠加界面 (`Push` / `Pop`，如设置页) 的默认效果
+    #[serde(default = "default_overlay_fx")]
+    pub overlay_fx: ScreenFx,
 }
+
+/// 界面切换效果，`duration` 为 0 时直接切换
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScreenFx {
+    #[serde(default)]
+    pub kind: ScreenFxKind,
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenFxKind {
+    /// 新界面淡入，盖住旧界面
+    #[default]
+    Fade,
+    /// 新界面从右侧滑入，旧界面向左滑出
+    SlideLeft,
+    /// 前半段旧界面渐暗到黑屏，后半段新界面从黑屏中渐亮
+    FadeBlack,
+}
+
+impl ScreenFx {
+    pub fn fade(duration: f32) -> Self {
+        Self { kind: ScreenFxKind::Fade, duration }
+    }
+
+    pub fn fade_black(duration: f32) -> Self {
+        Self { kind: ScreenFxKind::FadeBlack, duration }
+    }
+
+    /// 经过 `elapsed` 秒后的进度 (0~1，ease-out)
+    pub fn progress(&self, elapsed: f32) -> f32 {
+        if self.duration <= 0.0001 {
+            return 1.0;
+        }
+        let t = (elapsed / self.duration).clamp(0.0, 1.0);
+        t * (2.0 - t)
+    }
+
+    /// 淡黑过渡在 `elapsed` 秒时的状态：(是否已切到新界面, 黑色遮罩不透明度 0~1)
+    pub fn black_overlay(&self, elapsed: f32) -> (bool, f32) {
+        if self.duration <= 0.0001 {
+            return (true, 0.0);
+        }
+        let t = (elapsed / self.duration).clamp(0.0, 1.0);
+        if t < 0.5 { (false, t * 2.0) } else { (true, 2.0 - t * 2.0) }
+    }
+}
+
+/// 减少动态效果时动画的最长时长 (秒)
+pub const REDUCED_MOTION_MAX_DURATION: f32 = 0.2;
+
+static REDUCE_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// 是否减少动态效果，启动时由偏好设置初始化，设置页中切换后即时生效
+pub fn reduce_motion() -> bool {
+    REDUCE_MOTION.load(Ordering::Relaxed)
+}
+
+pub fn set_reduce_motion(on: bool) {
+    REDUCE_MOTION.store(on, Ordering::Relaxed);
+}
+
+/// 减少动态效果时把动画时长压到 `REDUCED_MOTION_MAX_DURATION` 以内
+pub fn motion_duration(duration: f32) -> f32 {
+    if reduce_motion() { duration.min(REDUCED_MOTION_MAX_DURATION) } else { duration }
+}
+
+fn default_screen_fx() -> ScreenFx {
+    ScreenFx::fade(0.4)
+}
+
+fn default_overlay_fx() -> ScreenFx {
+    ScreenFx::fade(0.15)
+}
+
+/// 主菜单背景 (`[main_menu]`)：图片之上可叠加一层飘落的粒子，都不配置时为渐变背景
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MainMenuConfig {
+    /// 背景图片资源名
+    #[serde(default)]
+    pub background: Option<String>,
+    #[serde(default)]
+    pub particles: Option<ParticleConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticleConfig {
+    #[serde(default)]
+    pub kind: ParticleKind,
+    #[serde(default = "default_particle_count")]
+    pub count: usize,
+    /// 下落速度 (像素/秒)
+    #[serde(default = "default_particle_speed")]
+    pub speed: f32,
+    /// 粒子图片，缺省时画圆点
+    #[serde(default)]
+    pub image: Option<String>,
+    /// 覆盖默认颜色 (RGBA)
+    #[serde(default)]
+    pub color: Option<[u8; 4]>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParticleKind {
+    /// 白色小点，垂直飘落、轻微摆动
+    #[default]
+    Snow,
+    /// 粉色花瓣，摆动更大并向右飘
+    Petals,
+}
+
+fn default_particle_count() -> usize {
+    60
+}
+
+fn default_particle_speed() -> f32 {
+    60.0
+}
+
+impl Default for ParticleConfig {
+    fn default() -> Self {
+        Self {
+            kind: ParticleKind::default(),
+            count: default_particle_count(),
+            speed: default_particle_speed(),
+            image: None,
+            color: None,
+        }
+    }
+}
+
+/// 对话时高亮说话角色的立绘 (`[speaker_highlight]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerHighlightConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 其他立绘的亮度 (0~1)
+    #[serde(default = "default_dim_brightness")]
+    pub dim_brightness: f32,
+    /// 说话者上移的像素
+    #[serde(default = "default_speaker_lift")]
+    pub lift: f32,
+    /// 切换说话者时的过渡秒数
+    #[serde(default = "default_highlight_duration")]
+    pub duration: f32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_dim_brightness() -> f32 {
+    0.6
+}
+
+fn default_speaker_lift() -> f32 {
+    12.0
+}
+
+fn default_highlight_duration() -> f32 {
+    0.25
+}
+
+impl Default for SpeakerHighlightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            dim_brightness: default_dim_brightness(),
+            lift: default_speaker_lift(),
+            duration: default_highlight_duration(),
+        }
+    }
+}
+
+fn default_fonts() -> BTreeMap<String, Vec<String>> {
+    let mut fonts = BTreeMap::new();
+    fonts.insert("dialogue".to_string(), vec![]);
+    fonts.insert("ui".to_string(), vec![]);
+    fonts.insert("title".to_string(), vec!["comforter".to_string()]);
+    fonts
+}
+
 impl Default for WindowConfig {
     fn default() -> Self {
         Self {
@@ -16,6 +217,11 @@ impl Default for WindowConfig {
             height: 720,
             resizable: true,
             vsync: true,
+            windowed: false,
+            fonts: default_fonts(),
+            language_fonts: BTreeMap::new(),
+            screen_fx: default_screen_fx(),
+            overlay_fx: default_overlay_fx(),
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
-use lumina_core::event::{LayoutConfig, TransitionConfig};
+use lumina_core::event::{AnimState, LayoutConfig, TransitionConfig};
+use lumina_core::runtime::assets::asset_name;
+use crate::config::{motion_duration, reduce_motion, SpeakerHighlightConfig};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Vec2 {
@@ -11,6 +13,16 @@ impl Vec2 {
     pub fn new(x: f32, y: f32) -> Self { Self { x, y } }
 }
 
+/// 立绘的帧动画：按固定间隔切换帧，帧名作为最后一个属性拼进资源名
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameAnim {
+    pub frames: Vec<String>,
+    /// 每帧停留的秒数
+    pub frame_time: f32,
+    /// 为 false 时播到最后一帧后停住
+    pub looping: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct RenderSprite {
     pub target: String,
@@ -25,11 +37,24 @@ pub struct RenderSprite {
 
     pub pos: Vec2,
     pub offset: Vec2,
+    /// 抖动特效的临时位移，每帧重新计算，不影响 `pos` / `offset`
+    pub shake: Vec2,
     pub scale: f32,
     pub alpha: f32,
     pub rotation: f32,
     pub anchor: Vec2,
     pub z_index: i32,
+    /// 说话者高亮：非说话立绘变暗 (1 为原亮度)，说话者上移的像素
+    pub brightness: f32,
+    pub lift: f32,
+
+    /// 平时播放的帧动画与说话时播放的帧动画 (没有时沿用 `idle_anim`)
+    pub idle_anim: Option<FrameAnim>,
+    pub talk_anim: Option<FrameAnim>,
+    pub talking: bool,
+    /// 当前动画的帧索引及其已停留的时间
+    pub frame: usize,
+    frame_elapsed: f32,
 
     pub pending_data: bool,
 }
@@ -47,24 +72,88 @@ impl RenderSprite {
             in_transition: false,
             pos: Vec2::new(0.0, 0.0),
             offset: Vec2::new(0.0, 0.0),
+            shake: Vec2::new(0.0, 0.0),
             scale: 1.0,
             alpha: 1.0,
             rotation: 0.0,
             anchor: Vec2::new(0.5, 1.0),
             z_index: 0,
+            brightness: 1.0,
+            lift: 0.0,
+            idle_anim: None,
+            talk_anim: None,
+            talking: false,
+            frame: 0,
+            frame_elapsed: 0.0,
             pending_data: false,
         }
     }
     pub fn full_asset_name(&self) -> String {
-        if self.attrs.is_empty() {
-            return self.texture.clone();
+        let frame = self.current_anim()
+            .and_then(|a| a.frames.get(self.frame.min(a.frames.len().saturating_sub(1))))
+            .filter(|f| !f.is_empty());
+        match frame {
+            Some(frame) => {
+                let mut attrs = self.attrs.clone();
+                attrs.push(frame.clone());
+                asset_name(&self.texture, &attrs)
+            },
+            None => asset_name(&self.texture, &self.attrs),
+        }
+    }
+
+    pub fn current_anim(&self) -> Option<&FrameAnim> {
+        if self.talking {
+            self.talk_anim.as_ref().or(self.idle_anim.as_ref())
+        } else {
+            self.idle_anim.as_ref()
+        }
+    }
+
+    /// 设置或移除 (`None`) 某个状态的帧动画，从第一帧重新播放
+    pub fn set_anim(&mut self, state: AnimState, anim: Option<FrameAnim>) {
+        match state {
+            AnimState::Idle => self.idle_anim = anim,
+            AnimState::Talk => self.talk_anim = anim,
+        }
+        self.restart_anim();
+    }
+
+    /// 切换说话状态，动画随之改变时从第一帧开始
+    pub fn set_talking(&mut self, talking: bool) {
+        if self.talking == talking {
+            return;
         }
-        let mut name = self.texture.clone();
-        for attr in &self.attrs {
-            name.push('_');
-            name.push_str(attr);
+        let before = self.current_anim().cloned();
+        self.talking = talking;
+        if self.current_anim() != before.as_ref() {
+            self.restart_anim();
+        }
+    }
+
+    fn restart_anim(&mut self) {
+        self.frame = 0;
+        self.frame_elapsed = 0.0;
+    }
+
+    fn advance_frames(&mut self, dt: f32) {
+        let Some(anim) = self.current_anim() else { return };
+        let (count, frame_time, looping) = (anim.frames.len(), anim.frame_time, anim.looping);
+        if count <= 1 || frame_time <= 0.0001 {
+            return;
+        }
+        self.frame_elapsed += dt;
+        while self.frame_elapsed >= frame_time {
+            if self.frame + 1 < count {
+                self.frame += 1;
+            } else if looping {
+                self.frame = 0;
+            } else {
+                self.frame_elapsed = 0.0;
+                break;
+            }
+            self.frame_elapsed -= frame_time;
         }
-        name
     }
 
     pub fn set_prop(&mut self, key: &str, val: f32) {
@@ -106,15 +195,53 @@ struct GenericTweener {
     // 存储 (属性名, (起始值, 目标值))
     props: HashMap<String, (f32, f32)>,
     easing: String,
+    /// 结束或被打断时报告的完成 id (`lumina.transform` 的 on_done)
+    on_done: Option<String>,
 }
 
+struct Shake {
+    target: String,
+    intensity: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+/// 全屏闪色，前半段淡入、后半段淡出
+#[derive(Clone, Copy, Debug)]
+pub struct Flash {
+    pub color: [u8; 4],
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+impl Flash {
+    pub fn alpha(&self) -> f32 {
+        let t = (self.elapsed / self.duration).clamp(0.0, 1.0);
+        1.0 - (2.0 * t - 1.0).abs()
+    }
+}
+
+/// 换场景时正在淡出的旧立绘 / 旧背景的键前缀，避免与新场景里同名的立绘冲突
+const SCENE_OUT_PREFIX: &str = "__scene_out:";
+
 pub struct SceneAnimator {
     pub sprites: HashMap<String, RenderSprite>,
     generic_tweens: Vec<GenericTweener>,
+    /// 已结束、等待回送给 core 的动画完成 id
+    completed: Vec<String>,
+    shakes: Vec<Shake>,
+    pub flash: Option<Flash>,
+    rng: u32,
     screen_size: (f32, f32),
+    /// 淡出立绘的序号，保证临时键唯一
+    fade_out_seq: u32,
 
     layouts: HashMap<String, LayoutConfig>,
     trans_registry: HashMap<String, TransitionConfig>,
+
+    /// 当前说话者的立绘 target
+    speaker: Option<String>,
+    highlight: SpeakerHighlightConfig,
 }
 
 impl SceneAnimator {
@@ -127,9 +254,27 @@ impl SceneAnimator {
         Self {
             sprites: HashMap::new(),
             generic_tweens: Vec::new(),
+            completed: Vec::new(),
+            shakes: Vec::new(),
+            flash: None,
+            rng: 0x9e3779b9,
             screen_size: (1920.0, 1080.0),
+            fade_out_seq: 0,
             layouts,
             trans_registry: HashMap::new(),
+            speaker: None,
+            highlight: SpeakerHighlightConfig::default(),
+        }
+    }
+
+    pub fn set_highlight_config(&mut self, config: SpeakerHighlightConfig) {
+        self.highlight = config;
+    }
+
+    /// 设置当前说话者，None (旁白) 时所有立绘恢复正常
+    pub fn set_speaker(&mut self, speaker: Option<&str>) {
+        if self.speaker.as_deref() != speaker {
+            self.speaker = speaker.map(str::to_string);
         }
     }
     pub fn handle_register_layout(&mut self, name: String, config: LayoutConfig) {
@@ -139,6 +284,14 @@ impl SceneAnimator {
         self.trans_registry.insert(name, config);
     }
 
+    /// 取已注册的转场配置，`duration` 为脚本内联的时长，优先于注册值；减少动态效果时缩短时长
+    fn transition(&self, name: &str, duration: Option<f32>) -> Option<TransitionConfig> {
+        self.trans_registry.get(name).cloned().map(|mut cfg| {
+            cfg.duration = motion_duration(duration.unwrap_or(cfg.duration));
+            cfg
+        })
+    }
+
     pub fn resize(&mut self, w: f32, h: f32) {
         self.screen_size = (w, h);
     }
@@ -147,6 +300,11 @@ impl SceneAnimator {
         !self.generic_tweens.is_empty()
     }
 
+    /// 取出自上次调用以来结束的动画完成 id
+    pub fn take_completed(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.completed)
+    }
+
     pub fn finish_all_animations(&mut self) {
         if self.generic_tweens.is_empty() { return; }
 
@@ -167,6 +325,88 @@ impl SceneAnimator {
         }
     }
 
+    /// `target` 为 `"screen"` 时抖动全部立绘与背景；同一目标的新抖动替换旧的
+    pub fn handle_shake(&mut self, target: String, intensity: f32, duration: f32) {
+        if duration <= 0.0001 || reduce_motion() { return; }
+        self.shakes.retain(|s| s.target != target);
+        self.shakes.push(Shake { target, intensity, duration, elapsed: 0.0 });
+    }
+
+    /// `frames` 为空时移除该状态的动画；立绘不存在时忽略
+    pub fn handle_set_anim(&mut self, target: &str, state: AnimState, frames: Vec<String>, fps: f32, looping: bool) {
+        let Some(sprite) = self.sprites.get_mut(target) else {
+            log::warn!("set_anim: sprite '{}' is not on screen", target);
+            return;
+        };
+        let anim = (!frames.is_empty() && fps > 0.0).then(|| FrameAnim { frames, frame_time: 1.0 / fps, looping });
+        sprite.set_anim(state, anim);
+    }
+
+    pub fn handle_flash(&mut self, color: [u8; 4], duration: f32) {
+        if duration <= 0.0001 { return; }
+        self.flash = Some(Flash { color, duration, elapsed: 0.0 });
+    }
+
+    fn update_effects(&mut self, dt: f32) {
+        for shake in &mut self.shakes {
+            shake.elapsed += dt;
+        }
+        self.shakes.retain(|s| s.elapsed < s.duration);
+
+        // 振幅随时间线性衰减，方向每帧随机
+        let rng = &mut self.rng;
+        for (name, sprite) in self.sprites.iter_mut() {
+            sprite.shake = Vec2::new(0.0, 0.0);
+            for shake in self.shakes.iter().filter(|s| s.target == "screen" || s.target == *name) {
+                let amp = shake.intensity * (1.0 - shake.elapsed / shake.duration);
+                sprite.shake.x += amp * next_signed(rng);
+                sprite.shake.y += amp * next_signed(rng);
+            }
+        }
+
+        self.update_highlight(dt);
+        self.update_frames(dt);
+
+        if let Some(flash) = &mut self.flash {
+            flash.elapsed += dt;
+            if flash.elapsed >= flash.duration {
+                self.flash = None;
+            }
+        }
+    }
+
+    /// 说话者在场时其余立绘渐暗、说话者渐渐上移；说话者不在场或旁白时全部恢复
+    fn update_highlight(&mut self, dt: f32) {
+        let cfg = &self.highlight;
+        let speaker = self.speaker.as_deref()
+            .filter(|s| cfg.enabled && self.sprites.get(*s).is_some_and(|sp| sp.z_index >= 0));
+        let duration = motion_duration(cfg.duration);
+        // 减少动态效果时只变暗，不移动
+        let lift = if reduce_motion() { 0.0 } else { cfg.lift };
+        let dim = cfg.dim_brightness.clamp(0.0, 1.0);
+
+        for (name, sprite) in self.sprites.iter_mut() {
+            if sprite.z_index < 0 || name.starts_with(SCENE_OUT_PREFIX) {
+                continue;
+            }
+            let (brightness, target_lift) = match speaker {
+                Some(s) if s == name => (1.0, lift),
+                Some(_) => (dim, 0.0),
+                None => (1.0, 0.0),
+            };
+            sprite.brightness = approach(sprite.brightness, brightness, (1.0 - dim).max(0.01), dt, duration);
+            sprite.lift = approach(sprite.lift, target_lift, cfg.lift.abs(), dt, duration);
+        }
+    }
+
+    /// 当前说话者播放说话动画，其余立绘播放平时的动画
+    fn update_frames(&mut self, dt: f32) {
+        for (name, sprite) in self.sprites.iter_mut() {
+            sprite.set_talking(self.speaker.as_deref() == Some(name.as_str()));
+            sprite.advance_frames(dt);
+        }
+    }
+
     pub fn update(&mut self, dt: f32) {
         let mut finished = Vec::new();
 
@@ -199,7 +439,18 @@ impl SceneAnimator {
         }
 
         for i in finished.iter().rev() {
-            self.generic_tweens.remove(*i);
+            let tween = self.generic_tweens.remove(*i);
+            self.completed.extend(tween.on_done);
+        }
+
+        self.update_effects(dt);
+
+        // 换图补间结束后释放旧贴图，回到普通绘制
+        for sprite in self.sprites.values_mut() {
+            if sprite.in_transition && sprite.trans_progress >= 1.0 {
+                sprite.in_transition = false;
+                sprite.old_texture = None;
+            }
         }
 
         self.sprites.retain(|target, sprite| {
@@ -214,27 +465,33 @@ impl SceneAnimator {
         target: String,
         props: HashMap<String, f32>,
         duration: f32,
-        easing: String
+        easing: String,
+        on_done: Option<String>,
     ) {
+        let duration = motion_duration(duration);
         if let Some(sprite) = self.sprites.get_mut(&target) {
-            self.generic_tweens.retain(|t| t.target != target);
+            retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target != target);
             if duration <= 0.001 {
                 for (k, v) in props {
                     sprite.set_prop(&k, v);
                 }
+                self.completed.extend(on_done);
             } else {
                 let mut tween_props = HashMap::new();
                 for (k, target_val) in props {
                     tween_props.insert(k.clone(), (sprite.get_prop(&k), target_val));
                 }
                 self.generic_tweens.push(GenericTweener {
-                    target, duration, elapsed: 0.0, props: tween_props, easing
+                    target, duration, elapsed: 0.0, props: tween_props, easing, on_done
                 });
             }
+        } else {
+            // 目标不存在时没有动画可播，直接视为完成
+            self.completed.extend(on_done);
         }
     }
 
-    pub fn handle_new_sprite(&mut self, target: String, texture: String, pos_str: Option<&str>, trans: Option<String>, attrs: Vec<String>, defer_visual: bool) {
+    pub fn handle_new_sprite(&mut self, target: String, texture: String, pos_str: Option<&str>, trans: Option<String>, duration: Option<f32>, attrs: Vec<String>, defer_visual: bool) {
         let mut sprite = RenderSprite::new(target.clone(), texture, attrs);
 
         let layout_key = pos_str.unwrap_or("center");
@@ -251,7 +508,7 @@ impl SceneAnimator {
         sprite.anchor = Vec2::new(layout.anchor_x, layout.anchor_y);
 
         if let Some(trans_name) = trans {
-            if let Some(cfg) = self.trans_registry.get(&trans_name).cloned() {
+            if let Some(cfg) = self.transition(&trans_name, duration) {
                 // 有静态配置
                 let mut tween_props = HashMap::new();
                 for (k, (from_opt, to_val)) in cfg.props {
@@ -271,6 +528,7 @@ impl SceneAnimator {
                     elapsed: 0.0,
                     props: tween_props,
                     easing: cfg.easing,
+                    on_done: None,
                 });
             }
         }
@@ -278,7 +536,8 @@ impl SceneAnimator {
         self.sprites.insert(target, sprite);
     }
 
-    pub fn handle_update_sprite(&mut self, target: String, trans: String, new_pos: Option<&str>, new_attrs: Vec<String>) {
+    /// `new_attrs` 为立绘更新后的完整属性集 (可以为空)，`None` 表示保持不变
+    pub fn handle_update_sprite(&mut self, target: String, trans: String, duration: Option<f32>, new_pos: Option<&str>, new_attrs: Option<Vec<String>>) {
         if let Some(sprite) = self.sprites.get_mut(&target) {
             let target_pos_vec = if let Some(pos_key) = new_pos {
                 let layout = self.layouts.get(pos_key).cloned().unwrap_or(LayoutConfig {
@@ -293,26 +552,29 @@ impl SceneAnimator {
             let mut visual_changed = false;
             let current_full_name = sprite.full_asset_name();
 
-            if !new_attrs.is_empty() && new_attrs != sprite.attrs {
+            if new_attrs.as_ref().is_some_and(|a| *a != sprite.attrs) {
                 visual_changed = true;
             }
 
             let mut applied_transition = false;
 
             if !trans.is_empty() {
-                if let Some(cfg) = self.trans_registry.get(&trans).cloned() {
+                if let Some(cfg) = self.transition(&trans, duration) {
                     let mut tween_props = HashMap::new();
-                    if visual_changed {
+                    // 换表情：新旧两张图交叉溶解，立绘本身保持可见，
+                    // 不套用转场里的入场透明度 (否则会先消失再淡入)
+                    let morph = visual_changed && cfg.duration > 0.0001;
+                    if morph {
                         sprite.old_texture = Some(current_full_name);
                         sprite.rule_texture = cfg.mask_img.clone();
                         sprite.trans_vague = cfg.vague.unwrap_or(0.1);
                         sprite.in_transition = true;
                         sprite.trans_progress = 0.0;
 
-                        sprite.attrs = new_attrs.clone();
+                        if let Some(attrs) = &new_attrs { sprite.attrs = attrs.clone(); }
                         tween_props.insert("trans_progress".to_string(), (0.0, 1.0));
-                    } else {
-                        if !new_attrs.is_empty() { sprite.attrs = new_attrs.clone(); }
+                    } else if let Some(attrs) = &new_attrs {
+                        sprite.attrs = attrs.clone();
                     }
 
                     if let Some(tp) = target_pos_vec {
@@ -326,17 +588,21 @@ impl SceneAnimator {
                     }
 
                     for (k, (from_opt, to_val)) in cfg.props {
+                        if morph && matches!(k.as_str(), "alpha" | "opacity") {
+                            continue;
+                        }
                         let start = from_opt.unwrap_or(sprite.get_prop(&k));
                         tween_props.insert(k, (start, to_val));
                     }
 
-                    self.generic_tweens.retain(|t| t.target != target);
+                    retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target != target);
                     self.generic_tweens.push(GenericTweener {
                         target: target.clone(),
                         duration: cfg.duration,
                         elapsed: 0.0,
                         props: tween_props,
                         easing: cfg.easing,
+                        on_done: None,
                     });
                     applied_transition = true;
                 } else {
@@ -345,8 +611,8 @@ impl SceneAnimator {
             }
 
             if !applied_transition {
-                if !new_attrs.is_empty() {
-                    sprite.attrs = new_attrs;
+                if let Some(attrs) = new_attrs {
+                    sprite.attrs = attrs;
                 }
 
                 if let Some(tp) = target_pos_vec {
@@ -360,53 +626,100 @@ impl SceneAnimator {
         }
     }
 
-    pub fn handle_hide_sprite(&mut self, target: String, trans: Option<String>) {
+    pub fn handle_hide_sprite(&mut self, target: String, trans: Option<String>, duration: Option<f32>) {
         if let Some(t_name) = trans {
-            if let Some(cfg) = self.trans_registry.get(&t_name).cloned() {
+            if let Some(cfg) = self.transition(&t_name, duration) {
                 let mut tween_props = HashMap::new();
                 for (k, (from_opt, to_val)) in cfg.props {
                     let start = from_opt.unwrap_or_else(|| self.sprites.get(&target).map(|s| s.get_prop(&k)).unwrap_or(0.0));
                     tween_props.insert(k, (start, to_val));
                 }
                 self.generic_tweens.push(GenericTweener {
-                    target: target.clone(), duration: cfg.duration, elapsed: 0.0, props: tween_props, easing: cfg.easing
+                    target: target.clone(), duration: cfg.duration, elapsed: 0.0, props: tween_props, easing: cfg.easing, on_done: None
                 });
                 return;
             }
         }
         self.sprites.remove(&target);
-        self.generic_tweens.retain(|t| t.target != target);
+        retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target != target);
     }
 
-    pub fn handle_new_scene(&mut self, bg_name: Option<String>, trans: String) {
-        self.sprites.retain(|key, _| key == "bg");
-        self.generic_tweens.retain(|t| t.target == "bg");
+    /// 有转场时旧背景与新背景交叉溶解，旧立绘在转场时长内淡出后移除；
+    /// 没有转场 (或转场未注册) 时立即清空
+    pub fn handle_new_scene(&mut self, bg_name: Option<String>, trans: String, duration: Option<f32>) {
+        // 上一次换场景还没淡完的立绘直接丢掉，连续换场景时不会越积越多
+        self.sprites.retain(|key, _| !key.starts_with(SCENE_OUT_PREFIX));
+        retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target == "bg");
+
+        let cfg = self.transition(&trans, duration).filter(|c| c.duration > 0.0001);
+        let characters: Vec<String> = self.sprites.keys().filter(|k| *k != "bg").cloned().collect();
+        for key in characters {
+            let sprite = self.sprites.remove(&key);
+            if let (Some(sprite), Some(cfg)) = (sprite, &cfg) {
+                self.fade_out(sprite, cfg);
+            }
+        }
 
         let new_bg_tex = bg_name.unwrap_or_default();
 
         if new_bg_tex.is_empty() {
-            self.sprites.remove("bg");
+            let old_bg = self.sprites.remove("bg");
+            retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target != "bg");
+            if let (Some(sprite), Some(cfg)) = (old_bg, &cfg) {
+                self.fade_out(sprite, cfg);
+            }
             return;
         }
 
-        if self.sprites.contains_key("bg") {
-            if let Some(cfg) = self.trans_registry.get(&trans).cloned() {
+        if let Some(bg) = self.sprites.get_mut("bg") {
+            // 新背景总是完全不透明，上一次的淡入补间即将被替换
+            bg.alpha = 1.0;
+            if let Some(cfg) = cfg {
                 self.strat_texture_transition("bg".to_string(), new_bg_tex, cfg);
             } else {
+                retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target != "bg");
                 if let Some(s) = self.sprites.get_mut("bg") {
                     s.texture = new_bg_tex;
                     s.in_transition = false;
                     s.trans_progress = 1.0;
+                    s.old_texture = None;
                 }
             }
         } else {
             let mut bg_sprite = RenderSprite::new("bg".to_string(), new_bg_tex, vec![]);
             bg_sprite.z_index = -100;
             bg_sprite.anchor = Vec2::new(0.0, 0.0);
+            // 之前没有背景：新背景淡入
+            if let Some(cfg) = cfg {
+                bg_sprite.alpha = 0.0;
+                self.push_alpha_tween("bg".to_string(), 0.0, 1.0, &cfg);
+            }
             self.sprites.insert("bg".to_string(), bg_sprite);
         }
     }
 
+    /// 把立绘换到临时键下淡出，透明度归零后由 `update` 移除
+    fn fade_out(&mut self, mut sprite: RenderSprite, cfg: &TransitionConfig) {
+        self.fade_out_seq = self.fade_out_seq.wrapping_add(1);
+        let key = format!("{}{}:{}", SCENE_OUT_PREFIX, self.fade_out_seq, sprite.target);
+        sprite.target = key.clone();
+        self.push_alpha_tween(key.clone(), sprite.alpha, 0.0, cfg);
+        self.sprites.insert(key, sprite);
+    }
+
+    fn push_alpha_tween(&mut self, target: String, from: f32, to: f32, cfg: &TransitionConfig) {
+        let mut props = HashMap::new();
+        props.insert("alpha".to_string(), (from, to));
+        self.generic_tweens.push(GenericTweener {
+            target,
+            duration: cfg.duration,
+            elapsed: 0.0,
+            props,
+            easing: cfg.easing.clone(),
+            on_done: None,
+        });
+    }
+
     fn strat_texture_transition(&mut self, target: String, new_tex: String, trans_cfg: TransitionConfig) {
         if let Some(sprite) = self.sprites.get_mut(&target) {
             if sprite.texture == new_tex { return; }
@@ -421,15 +734,50 @@ impl SceneAnimator {
             let mut props = std::collections::HashMap::new();
             props.insert("trans_progress".to_string(), (0.0, 1.0));
 
-            self.generic_tweens.retain(|t| t.target != target);
+            retain_tweens(&mut self.generic_tweens, &mut self.completed, |t| t.target != target);
             self.generic_tweens.push(GenericTweener {
                 target,
                 duration: trans_cfg.duration,
                 elapsed: 0.0,
                 props,
                 easing: trans_cfg.easing,
+                on_done: None,
             });
         }
     }
 
 }
+
+/// 移除不满足 `keep` 的补间；被打断的补间同样报告完成，避免等待方永远等不到
+fn retain_tweens(tweens: &mut Vec<GenericTweener>, completed: &mut Vec<String>, keep: impl Fn(&GenericTweener) -> bool) {
+    tweens.retain(|t| {
+        let kept = keep(t);
+        if !kept {
+            completed.extend(t.on_done.clone());
+        }
+        kept
+    });
+}
+
+/// xorshift32，返回 [-1, 1) 的伪随机数
+fn next_signed(state: &mut u32) -> f32 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    *state = x;
+    (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+/// 以 `range / duration` 的速度从 `current` 线性趋近 `target`
+fn approach(current: f32, target: f32, range: f32, dt: f32, duration: f32) -> f32 {
+    if duration <= 0.0001 {
+        return target;
+    }
+    let step = range * dt / duration;
+    if (target - current).abs() <= step {
+        target
+    } else {
+        current + step * (target - current).signum()
+    }
+}
@@ -1,10 +1,9 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::fs;
+use std::io::Cursor;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
-use walkdir::WalkDir;
 use skia_safe::{Image, Data, FontMgr};
 use skia_safe::textlayout::TypefaceFontProvider;
 use kira::sound::static_sound::StaticSoundData;
@@ -12,6 +11,7 @@ use kira::sound::streaming::StreamingSoundData;
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use kira::sound::FromFileError;
 use log::{debug, error, info, warn};
+use crate::core::source::{self, AssetSource, DirSource};
 
 #[derive(Clone)]
 pub enum AssetData {
@@ -27,10 +27,11 @@ enum AssetState {
     Failed(String),
 }
 
+/// `path` 为资源在 `AssetSource` 中的路径
 enum LoadRequest {
-    LoadImage { id: String, path: PathBuf },
-    LoadStaticAudio { id: String, path: PathBuf },
-    LoadStreamingAudio { id: String, path: PathBuf },
+    LoadImage { id: String, path: String },
+    LoadStaticAudio { id: String, path: String },
+    LoadStreamingAudio { id: String, path: String },
 }
 
 enum LoadResult {
@@ -43,10 +44,10 @@ enum LoadResult {
 
 
 pub struct AssetManager {
-    root_path: PathBuf,
-    image_paths: HashMap<String, PathBuf>,
-    audio_paths: HashMap<String, PathBuf>,
-    font_paths: HashMap<String, PathBuf>,
+    source: Arc<dyn AssetSource>,
+    image_paths: HashMap<String, String>,
+    audio_paths: HashMap<String, String>,
+    font_paths: HashMap<String, String>,
 
     cache: HashMap<String, AssetState>,
 
@@ -55,19 +56,30 @@ pub struct AssetManager {
 }
 
 impl AssetManager {
+    /// `root_path` 为资源目录或 `.pak` 归档 (读取其中的 `assets/` 分区)
     pub fn new(root_path: &str) -> Self {
+        let source = source::open(root_path).unwrap_or_else(|e| {
+            error!("Failed to open assets at {}: {}", root_path, e);
+            Arc::new(DirSource::new(root_path))
+        });
+        Self::with_source(source)
+    }
+
+    pub fn with_source(source: Arc<dyn AssetSource>) -> Self {
         let (tx_request, rx_request) = unbounded::<LoadRequest>();
         let (tx_result, rx_result) = unbounded::<LoadResult>();
         let tx_res_worker = tx_result.clone();
+        let worker_source = source.clone();
 
         thread::Builder::new()
             .name("AssetWorker".into())
             .spawn(move || {
                 info!("AssetWorker started");
+                let source = worker_source;
                 while let Ok(req) = rx_request.recv() {
                     match req {
                         LoadRequest::LoadImage { id, path } => {
-                            match fs::read(&path) {
+                            match source.read(&path) {
                                 Ok(bytes) => {
                                     let data = Data::new_copy(&bytes);
                                     let _ = tx_result.send(LoadResult::ImageBytes { id, data });
@@ -78,22 +90,29 @@ impl AssetManager {
                             }
                         },
                         LoadRequest::LoadStaticAudio { id, path } => {
-                            match StaticSoundData::from_file(&path) {
+                            let data = source.read(&path)
+                                .map_err(|e| e.to_string())
+                                .and_then(|bytes| StaticSoundData::from_cursor(Cursor::new(bytes)).map_err(|e| e.to_string()));
+                            match data {
                                 Ok(data) => {
                                     let _ = tx_res_worker.send(LoadResult::StaticAudioData { id, data });
                                 }
-                                Err(e) => {
-                                    let _ = tx_res_worker.send(LoadResult::Error { id, msg: e.to_string() });
+                                Err(msg) => {
+                                    let _ = tx_res_worker.send(LoadResult::Error { id, msg });
                                 }
                             }
                         },
                         LoadRequest::LoadStreamingAudio { id, path } => {
-                            match StreamingSoundData::from_file(&path) {
+                            // 磁盘上的文件直接流式读取，归档内的先解到临时目录，不整体读入内存
+                            let data = source::local_path(source.as_ref(), &path)
+                                .map_err(|e| e.to_string())
+                                .and_then(|file| StreamingSoundData::from_file(file).map_err(|e| e.to_string()));
+                            match data {
                                 Ok(data) => {
                                     let _ = tx_res_worker.send(LoadResult::StreamingAudioData { id, data });
                                 }
-                                Err(e) => {
-                                    let _ = tx_res_worker.send(LoadResult::Error { id, msg: e.to_string() });
+                                Err(msg) => {
+                                    let _ = tx_res_worker.send(LoadResult::Error { id, msg });
                                 }
                             }
                         }
@@ -102,7 +121,7 @@ impl AssetManager {
             }).expect("Failed to spawn asset worker");
 
         let mut manager = Self {
-            root_path: PathBuf::from(root_path),
+            source,
             image_paths: HashMap::new(),
             audio_paths: HashMap::new(),
             font_paths: HashMap::new(),
@@ -116,28 +135,26 @@ impl AssetManager {
     }
 
     fn scan_assets(&mut self) {
-        info!("Scanning assets in {:?}...", self.root_path);
+        info!("Scanning assets...");
 
-        for entry in WalkDir::new(&self.root_path).into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-                    let ext = ext.to_lowercase();
-                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                        let key = stem.to_string();
+        for rel in self.source.list() {
+            let path = Path::new(&rel);
+            if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+                let ext = ext.to_lowercase();
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    let key = stem.to_string();
 
-                        match ext.as_str() {
-                            "png" | "jpg" | "jpeg" => {
-                                self.image_paths.insert(key, path.to_path_buf());
-                            },
-                            "mp3" | "wav" | "ogg" | "flac" => {
-                                self.audio_paths.insert(key, path.to_path_buf());
-                            },
-                            "ttf" | "otf" | "ttc" => {
-                                self.font_paths.insert(key, path.to_path_buf());
-                            }
-                            _ => {}
+                    match ext.as_str() {
+                        "png" | "jpg" | "jpeg" => {
+                            self.image_paths.insert(key, rel);
+                        },
+                        "mp3" | "wav" | "ogg" | "flac" => {
+                            self.audio_paths.insert(key, rel);
+                        },
+                        "ttf" | "otf" | "ttc" => {
+                            self.font_paths.insert(key, rel);
                         }
+                        _ => {}
                     }
                 }
             }
@@ -185,6 +202,27 @@ impl AssetManager {
         None
     }
 
+    /// 过场动画 `id` 的帧 (`<id>_<序号>` 的图片)，按序号排列
+    pub fn movie_frames(&self, id: &str) -> Vec<String> {
+        let prefix = format!("{}_", id);
+        let mut frames: Vec<(u32, &String)> = self.image_paths.keys()
+            .filter_map(|name| {
+                let n = name.strip_prefix(&prefix)?;
+                if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                Some((n.parse().ok()?, name))
+            })
+            .collect();
+        frames.sort();
+        frames.into_iter().map(|(_, name)| name.clone()).collect()
+    }
+
+    /// 扫描到的音频中是否有该资源
+    pub fn has_audio(&self, name: &str) -> bool {
+        self.audio_paths.contains_key(name)
+    }
+
     pub fn get_static_audio(&mut self, name: &str) -> Option<StaticSoundData> {
         if let Some(state) = self.cache.get_mut(name) {
             return match state {
@@ -222,10 +260,17 @@ impl AssetManager {
         None
     }
 
-    pub fn register_fonts_to(&self, provider: &mut TypefaceFontProvider) {
-        for (name, path) in &self.font_paths {
+    /// 按 `priority` 中的顺序先注册字体，其余字体按名称排序注册在后
+    pub fn register_fonts_to(&self, provider: &mut TypefaceFontProvider, priority: &[String]) {
+        let mut ordered: Vec<(&String, &String)> = self.font_paths.iter().collect();
+        ordered.sort_by_key(|(name, _)| {
+            let rank = priority.iter().position(|p| p == *name).unwrap_or(usize::MAX);
+            (rank, name.to_string())
+        });
+
+        for (name, path) in ordered {
             // 读取文件字节
-            match fs::read(path) {
+            match self.source.read(path) {
                 Ok(bytes) => {
                     let data = Data::new_copy(&bytes);
                     // 创建 Typeface
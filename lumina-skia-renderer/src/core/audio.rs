@@ -1,10 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-use kira::{AudioManager, DefaultBackend, AudioManagerSettings, sound::static_sound::{StaticSoundData, StaticSoundHandle}, Tween, Decibels, Value};
-use kira::sound::FromFileError;
+use kira::{AudioManager, DefaultBackend, AudioManagerSettings, sound::static_sound::{StaticSoundData, StaticSoundHandle}, Tween, Decibels, PlaybackRate, Value};
+use kira::clock::{ClockHandle, ClockSpeed, ClockTime};
+use kira::sound::{EndPosition, FromFileError, PlaybackPosition, PlaybackState, Region};
 use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle};
 use log::{debug, error};
 use crate::core::AssetManager;
+use lumina_core::config::AudioConfig;
+use lumina_core::runtime::assets::ChannelState;
+
+/// 压低 / 恢复音乐的渐变时长
+const DUCK_FADE: Duration = Duration::from_millis(200);
+/// 语音播放时被压低的通道
+const MUSIC_CHANNELS: [&str; 2] = ["music", "bgm"];
+
+/// `voice` 以及 `voice2` 等以 voice 开头的通道触发压低
+fn is_voice_channel(channel: &str) -> bool {
+    channel.starts_with("voice")
+}
 
 enum AudioSource {
     Static(StaticSoundData),
@@ -14,14 +27,22 @@ enum AudioSource {
 enum AudioHandle {
     Static(StaticSoundHandle),
     Streaming(StreamingSoundHandle<FromFileError>),
+    /// 前奏与排在其后的主曲，共用一个时钟，音量与停止同时作用于两者；
+    /// 前奏期间停止会连同尚未开始的主曲一起取消
+    Chained { _clock: ClockHandle, intro: StaticSoundHandle, main: Box<AudioHandle> },
 }
 
 impl AudioHandle {
     // 辅助方法：统一设置音量
     fn set_volume(&mut self, volume: impl Into<Value<Decibels>>, tween: Tween) {
+        let volume = volume.into();
         match self {
             Self::Static(h) => { h.set_volume(volume, tween); },
             Self::Streaming(h) => { h.set_volume(volume, tween); },
+            Self::Chained { intro, main, .. } => {
+                intro.set_volume(volume, tween);
+                main.set_volume(volume, tween);
+            }
         }
     }
 
@@ -30,8 +51,73 @@ impl AudioHandle {
         match self {
             Self::Static(h) => { h.stop(tween); },
             Self::Streaming(h) => { h.stop(tween); },
+            Self::Chained { intro, main, .. } => {
+                intro.stop(tween);
+                main.stop(tween);
+            }
         }
     }
+
+    fn is_playing(&self) -> bool {
+        let state = match self {
+            Self::Static(h) => h.state(),
+            Self::Streaming(h) => h.state(),
+            Self::Chained { main, .. } => return main.is_playing(),
+        };
+        !matches!(state, PlaybackState::Stopping | PlaybackState::Stopped)
+    }
+
+    /// 播放位置 (秒)；前奏期间为前奏内的位置，之后为主曲内的位置
+    fn position(&self) -> f64 {
+        match self {
+            Self::Static(h) => h.position(),
+            Self::Streaming(h) => h.position(),
+            Self::Chained { intro, main, .. } => {
+                if matches!(intro.state(), PlaybackState::Stopped) { main.position() } else { intro.position() }
+            }
+        }
+    }
+}
+
+/// 压低音乐的变化，由 [`AudioPlayer`] 作用到音乐通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuckChange {
+    Duck,
+    Restore,
+}
+
+/// 语音压低音乐 (ducking) 的状态机：任一语音通道仍在播放时保持压低，最后一条结束或被停止时恢复。
+/// 不直接持有 kira 句柄，播放状态由调用方查询
+#[derive(Debug, Default)]
+pub struct Ducker {
+    voices: HashSet<String>,
+}
+
+impl Ducker {
+    pub fn is_ducked(&self) -> bool {
+        !self.voices.is_empty()
+    }
+
+    /// 语音通道开始播放
+    pub fn voice_started(&mut self, channel: &str) -> Option<DuckChange> {
+        let was_ducked = self.is_ducked();
+        self.voices.insert(channel.to_string());
+        (!was_ducked).then_some(DuckChange::Duck)
+    }
+
+    /// 语音通道被手动停止
+    pub fn voice_stopped(&mut self, channel: &str) -> Option<DuckChange> {
+        (self.voices.remove(channel) && !self.is_ducked()).then_some(DuckChange::Restore)
+    }
+
+    /// 去掉已播完的语音通道，`is_playing` 查询该通道当前句柄的状态
+    pub fn poll(&mut self, is_playing: impl Fn(&str) -> bool) -> Option<DuckChange> {
+        if !self.is_ducked() {
+            return None;
+        }
+        self.voices.retain(|ch| is_playing(ch));
+        (!self.is_ducked()).then_some(DuckChange::Restore)
+    }
 }
 
 struct PendingPlay {
@@ -40,6 +126,9 @@ struct PendingPlay {
     volume: f32,
     fade_in_secs: f32,
     looping: bool,
+    loop_region: Option<(f32, Option<f32>)>,
+    pitch: Option<f32>,
+    intro: Option<String>,
     is_streaming: bool,
 }
 
@@ -49,6 +138,11 @@ pub struct AudioPlayer{
 
     pending_queue: Vec<PendingPlay>,
     channel_volumes: HashMap<String, f32>,
+    /// 各通道句柄当前的音量 (振幅)，压低与恢复音乐时以此为准
+    levels: HashMap<String, f32>,
+    ducker: Ducker,
+    /// `audio.duck_music_db`，不大于 0 时不压低
+    duck_db: f32,
 }
 
 impl AudioPlayer{
@@ -61,6 +155,31 @@ impl AudioPlayer{
             active_channels: HashMap::new(),
             pending_queue: Vec::new(),
             channel_volumes: HashMap::new(),
+            levels: HashMap::new(),
+            ducker: Ducker::default(),
+            duck_db: lumina_shared::config::get::<AudioConfig>("audio").duck_music_db,
+        }
+    }
+
+    /// 通道的目标音量，音乐在压低期间减去 `duck_db`
+    fn target_db(&self, channel: &str, amplitude: f32) -> Decibels {
+        let db = Self::amplitude_to_db(amplitude);
+        if self.ducker.is_ducked() && MUSIC_CHANNELS.contains(&channel) && db != Decibels::SILENCE {
+            Decibels(db.0 - self.duck_db)
+        } else {
+            db
+        }
+    }
+
+    fn apply_duck(&mut self, change: Option<DuckChange>) {
+        let Some(change) = change else { return };
+        debug!("Music ducking: {:?}", change);
+        for channel in MUSIC_CHANNELS {
+            let Some(&level) = self.levels.get(channel) else { continue };
+            let db = self.target_db(channel, level);
+            if let Some(handle) = self.active_channels.get_mut(channel) {
+                handle.set_volume(db, Tween { duration: DUCK_FADE, ..Default::default() });
+            }
         }
     }
 
@@ -73,16 +192,23 @@ impl AudioPlayer{
     }
 
     pub fn set_channel_volume(&mut self, channel: &str, volume: f32) {
+        self.fade_channel_volume(channel, volume, 0.1);
+    }
+
+    /// 在 `fade_secs` 秒内把声道音量渐变到 `volume`
+    pub fn fade_channel_volume(&mut self, channel: &str, volume: f32, fade_secs: f32) {
         self.channel_volumes.insert(channel.to_string(), volume);
+        let db = self.target_db(channel, volume);
         if let Some(handle) = self.active_channels.get_mut(channel) {
-            let db = Self::amplitude_to_db(volume);
+            self.levels.insert(channel.to_string(), volume);
             handle.set_volume(db, Tween {
-                duration: Duration::from_millis(100),
+                duration: Duration::from_secs_f32(fade_secs.max(0.0)),
                 ..Default::default()
             });
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn play(
         &mut self,
         assets: &mut AssetManager,
@@ -90,24 +216,29 @@ impl AudioPlayer{
         resource_id: &str,
         base_volume: f32,
         fade_in_secs: f32,
-        looping: bool
+        looping: bool,
+        loop_region: Option<(f32, Option<f32>)>,
+        pitch: Option<f32>,
+        intro: Option<&str>,
     ) {
-        self.stop(channel, 0.1);
+        self.stop_channel(channel, 0.1);
+
+        // 前奏缺失时直接播放主曲，否则会一直等下去
+        let intro = intro.filter(|id| {
+            let found = assets.has_audio(id);
+            if !found {
+                log::warn!("Intro '{}' not found, playing '{}' without it", id, resource_id);
+            }
+            found
+        });
 
         let system_vol = *self.channel_volumes.get(channel).unwrap_or(&1.0);
         let final_volume = base_volume * system_vol;
 
         let is_streaming = channel == "music" || channel == "bgm" || resource_id.starts_with("bgm_");
 
-        let source = if is_streaming {
-            // 注意：这里 assets.get_... 会把数据从缓存中 take() 走
-            assets.get_streaming_audio(resource_id).map(AudioSource::Streaming)
-        } else {
-            assets.get_static_audio(resource_id).map(AudioSource::Static)
-        };
-
-        if let Some(audio_source) = source {
-            self.play_internal(channel, audio_source, final_volume, fade_in_secs, looping);
+        if let Some((audio_source, intro_data)) = Self::fetch(assets, resource_id, is_streaming, intro) {
+            self.play_internal(channel, audio_source, intro_data, final_volume, fade_in_secs, looping, loop_region, pitch);
         } else {
             // 没加载好，加入队列
             self.pending_queue.push(PendingPlay {
@@ -116,12 +247,25 @@ impl AudioPlayer{
                 volume: base_volume,
                 fade_in_secs,
                 looping,
+                loop_region,
+                pitch,
+                intro: intro.map(str::to_string),
                 is_streaming,
             });
         }
     }
 
     pub fn stop(&mut self, channel: &str, fade_out_secs: f32) {
+        self.stop_channel(channel, fade_out_secs);
+
+        if is_voice_channel(channel) {
+            let change = self.ducker.voice_stopped(channel);
+            self.apply_duck(change);
+        }
+    }
+
+    /// 停止通道但不恢复音乐，换播下一条语音时压低保持不变
+    fn stop_channel(&mut self, channel: &str, fade_out_secs: f32) {
         if let Some(mut handle) = self.active_channels.remove(channel) {
             let tween = if fade_out_secs > 0.0 {
                 Tween { duration: Duration::from_secs_f32(fade_out_secs), ..Default::default() }
@@ -132,28 +276,39 @@ impl AudioPlayer{
         self.pending_queue.retain(|p| p.channel != channel);
     }
 
-    pub fn update(&mut self, assets: &mut AssetManager) {
+    /// 通道上的声音是否仍在播放，尚在等待加载的也算作播放中
+    pub fn is_channel_playing(&self, channel: &str) -> bool {
+        self.pending_queue.iter().any(|p| p.channel == channel)
+            || self.active_channels.get(channel).is_some_and(AudioHandle::is_playing)
+    }
+
+    /// 推进等待队列，并把各通道的播放状态写入 `state` (即 `Ctx.audio_state`)
+    pub fn update(&mut self, assets: &mut AssetManager, state: &mut HashMap<String, ChannelState>) {
+        self.write_state(state);
+
+        // 语音播完后恢复音乐
+        let channels = &self.active_channels;
+        let change = self.ducker.poll(|ch| channels.get(ch).is_some_and(AudioHandle::is_playing));
+        self.apply_duck(change);
+
         // 检查等待队列中的资源是否加载完毕
         if self.pending_queue.is_empty() { return; }
 
         let pending = std::mem::take(&mut self.pending_queue);
 
         for req in pending {
-            let source = if req.is_streaming {
-                assets.get_streaming_audio(&req.resource_id).map(AudioSource::Streaming)
-            } else {
-                assets.get_static_audio(&req.resource_id).map(AudioSource::Static)
-            };
-
-            if let Some(audio_source) = source {
+            if let Some((audio_source, intro_data)) = Self::fetch(assets, &req.resource_id, req.is_streaming, req.intro.as_deref()) {
                 let system_vol = *self.channel_volumes.get(&req.channel).unwrap_or(&1.0);
                 let final_volume = req.volume * system_vol;
                 self.play_internal(
                     &req.channel,
                     audio_source,
+                    intro_data,
                     final_volume,
                     req.fade_in_secs,
-                    req.looping
+                    req.looping,
+                    req.loop_region,
+                    req.pitch,
                 );
             } else {
                 // 没好 -> 放回去
@@ -162,14 +317,111 @@ impl AudioPlayer{
         }
     }
 
-    fn play_internal(&mut self, channel: &str, source: AudioSource, volume: f32, fade_in: f32, looping: bool) {
-        let target_db = Self::amplitude_to_db(volume);
+    fn write_state(&self, state: &mut HashMap<String, ChannelState>) {
+        state.clear();
+        for (channel, handle) in &self.active_channels {
+            state.insert(channel.clone(), ChannelState { position: handle.position(), playing: handle.is_playing() });
+        }
+        for req in &self.pending_queue {
+            state.insert(req.channel.clone(), ChannelState { position: 0.0, playing: true });
+        }
+    }
 
+    /// 取出可以播放的音源；有前奏时先确认前奏 (总是静态音频) 已就绪，
+    /// 以免流式主曲被取走后还要等前奏
+    fn fetch(
+        assets: &mut AssetManager,
+        resource_id: &str,
+        is_streaming: bool,
+        intro: Option<&str>,
+    ) -> Option<(AudioSource, Option<StaticSoundData>)> {
+        let intro = match intro {
+            Some(id) => Some(assets.get_static_audio(id)?),
+            None => None,
+        };
+        let source = if is_streaming {
+            // 注意：这里 assets.get_... 会把数据从缓存中 take() 走
+            assets.get_streaming_audio(resource_id).map(AudioSource::Streaming)
+        } else {
+            assets.get_static_audio(resource_id).map(AudioSource::Static)
+        }?;
+        Some((source, intro))
+    }
+
+    /// 在一个新时钟上排好前奏，返回主曲应开始的时刻 (前奏最后一帧之后)。
+    /// 时钟以前奏的采样率 (乘以播放速率) 计拍，接缝精确到采样；
+    /// 前奏的时长取自解码后的帧数，WAV / OGG / FLAC 是准确的，
+    /// MP3 的编码器延迟与尾部填充会留下可闻的空隙，前奏请避免使用 MP3
+    fn queue_intro(
+        &mut self,
+        data: StaticSoundData,
+        volume: Decibels,
+        rate: PlaybackRate,
+    ) -> Option<(ClockHandle, StaticSoundHandle, ClockTime)> {
+        let speed = ClockSpeed::TicksPerSecond(data.sample_rate as f64 * rate.0);
+        let clock = self.manager.add_clock(speed)
+            .map_err(|e| error!("Kira clock error: {}", e))
+            .ok()?;
+        let start = clock.time();
+        let end = start + data.num_frames() as u64;
+        let handle = self.manager.play(data.volume(volume).playback_rate(rate).start_time(start))
+            .map_err(|e| error!("Kira play error: {}", e))
+            .ok()?;
+        Some((clock, handle, end))
+    }
+
+    /// 把 `(起点, 终点)` 换成 kira 的循环区间，按音频时长裁剪；
+    /// 起点越过曲末时退回整曲循环
+    fn region_for(loop_region: Option<(f32, Option<f32>)>, duration: Duration) -> Region {
+        let total = duration.as_secs_f64();
+        let Some((start, end)) = loop_region else {
+            return (..).into();
+        };
+        let start = start.max(0.0) as f64;
+        if start >= total {
+            log::warn!("Loop start {}s is past the end of the track ({}s), looping whole track", start, total);
+            return (..).into();
+        }
+        Region {
+            start: PlaybackPosition::Seconds(start),
+            end: match end {
+                Some(end) if (end as f64) < total => EndPosition::Custom(PlaybackPosition::Seconds(end as f64)),
+                _ => EndPosition::EndOfAudio,
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn play_internal(
+        &mut self,
+        channel: &str,
+        source: AudioSource,
+        intro: Option<StaticSoundData>,
+        volume: f32,
+        fade_in: f32,
+        looping: bool,
+        loop_region: Option<(f32, Option<f32>)>,
+        pitch: Option<f32>,
+    ) {
+        if self.duck_db > 0.0 && is_voice_channel(channel) {
+            let change = self.ducker.voice_started(channel);
+            self.apply_duck(change);
+        }
+        let target_db = self.target_db(channel, volume);
+        let start_db = if fade_in > 0.0 { Decibels::SILENCE } else { target_db };
+        let rate = PlaybackRate(pitch.unwrap_or(1.0) as f64);
+
+        // 淡入只作用于最先听到的前奏，主曲直接以目标音量接上
+        let intro = intro.and_then(|d| self.queue_intro(d, start_db, rate));
+        let main_db = if intro.is_some() { target_db } else { start_db };
+        let main_start = intro.as_ref().map(|(_, _, end)| *end);
+
+        // 静态音频的时长直接可得；流式音频需由解码器给出时长，二者的 loop_region 都按秒计
         let handle_result = match source {
             AudioSource::Static(mut d) => {
-                if looping { d = d.loop_region(..); }
-                if fade_in > 0.0 { d = d.volume(Decibels::SILENCE); }
-                else { d = d.volume(target_db); }
+                if looping { d = d.loop_region(Self::region_for(loop_region, d.duration())); }
+                if let Some(t) = main_start { d = d.start_time(t); }
+                d = d.volume(main_db).playback_rate(rate);
 
                 // 播放并包装成 Static 类型
                 self.manager.play(d)
@@ -177,9 +429,9 @@ impl AudioPlayer{
                     .map_err(|e| e.to_string())
             },
             AudioSource::Streaming(mut d) => {
-                if looping { d = d.loop_region(..); }
-                if fade_in > 0.0 { d = d.volume(Decibels::SILENCE); }
-                else { d = d.volume(target_db); }
+                if looping { d = d.loop_region(Self::region_for(loop_region, d.duration())); }
+                if let Some(t) = main_start { d = d.start_time(t); }
+                d = d.volume(main_db).playback_rate(rate);
 
                 // 播放并包装成 Streaming 类型
                 self.manager.play(d)
@@ -188,6 +440,21 @@ impl AudioPlayer{
             },
         };
 
+        // 两者都已排在时钟上，此时启动时钟
+        let handle_result = match intro {
+            Some((mut clock, mut intro, _)) => match handle_result {
+                Ok(main) => {
+                    clock.start();
+                    Ok(AudioHandle::Chained { _clock: clock, intro, main: Box::new(main) })
+                }
+                Err(e) => {
+                    intro.stop(Tween::default());
+                    Err(e)
+                }
+            },
+            None => handle_result,
+        };
+
         match handle_result {
             Ok(mut handle) => {
                 // 如果有淡入，现在统一设置
@@ -199,7 +466,11 @@ impl AudioPlayer{
                     handle.set_volume(target_db, tween);
                 }
                 debug!("Audio playing: {}", channel);
-                self.active_channels.insert(channel.to_string(), handle);
+                self.levels.insert(channel.to_string(), volume);
+                // 等待队列里的请求可能在同通道已有新声音后才就绪，旧声音短暂淡出避免爆音
+                if let Some(mut old) = self.active_channels.insert(channel.to_string(), handle) {
+                    old.stop(Tween { duration: Duration::from_millis(50), ..Default::default() });
+                }
             },
             Err(e) => error!("Kira play error: {}", e),
         }
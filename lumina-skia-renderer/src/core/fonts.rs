@@ -0,0 +1,71 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use skia_safe::FontStyle;
+use skia_safe::textlayout::FontCollection;
+use crate::config::WindowConfig;
+
+/// 未指定角色时使用的字体角色
+pub const DEFAULT_ROLE: &str = "ui";
+
+/// 逻辑字体角色 ("dialogue", "ui", "title") -> 按优先级排列的字体族
+pub struct FontRoles {
+    default: HashMap<String, Vec<String>>,
+    by_language: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl FontRoles {
+    /// 根据配置解析各角色的回退链，丢弃 FontCollection 中找不到的字体族 (每个只警告一次)
+    pub fn resolve(cfg: &WindowConfig, collection: &mut FontCollection) -> Self {
+        let mut missing = HashSet::new();
+
+        let default = Self::resolve_chains(&cfg.fonts, collection, &mut missing);
+        let by_language = cfg.language_fonts.iter()
+            .map(|(lang, roles)| {
+                // 语言覆盖只替换声明过的角色，其余沿用默认链
+                let mut merged = default.clone();
+                merged.extend(Self::resolve_chains(roles, collection, &mut missing));
+                (lang.clone(), merged)
+            })
+            .collect();
+
+        Self { default, by_language }
+    }
+
+    fn resolve_chains(
+        roles: &BTreeMap<String, Vec<String>>,
+        collection: &mut FontCollection,
+        missing: &mut HashSet<String>
+    ) -> HashMap<String, Vec<String>> {
+        roles.iter()
+            .map(|(role, families)| {
+                let available = families.iter()
+                    .filter(|family| {
+                        let found = !collection.find_typefaces(&[family.as_str()], FontStyle::normal()).is_empty();
+                        if !found && missing.insert(family.to_string()) {
+                            log::warn!("Font family '{}' (role '{}') not found, skipped.", family, role);
+                        }
+                        found
+                    })
+                    .cloned()
+                    .collect();
+                (role.clone(), available)
+            })
+            .collect()
+    }
+
+    /// 当前语言下某个角色的字体族链；未知角色返回 None
+    pub fn families(&self, role: &str) -> Option<&[String]> {
+        let lang = lumina_core::i18n::current_language();
+        self.by_language.get(&lang)
+            .and_then(|roles| roles.get(role))
+            .or_else(|| self.default.get(role))
+            .map(|v| v.as_slice())
+    }
+
+    /// 默认对话字体链的首选字体族 (用作 FontCollection 的默认族名)
+    pub fn primary_family(&self) -> Option<&str> {
+        self.default.get("dialogue")
+            .or_else(|| self.default.get(DEFAULT_ROLE))
+            .and_then(|v| v.first())
+            .map(|s| s.as_str())
+    }
+}
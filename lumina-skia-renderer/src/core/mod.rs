@@ -3,9 +3,16 @@ pub mod audio;
 pub mod painter;
 pub mod animator;
 pub mod typewriter;
+pub mod fonts;
+pub mod movie;
+pub mod source;
+pub mod particles;
 
 pub use animator::SceneAnimator;
 pub use assets::AssetManager;
-pub use audio::AudioPlayer;
+pub use audio::{AudioPlayer, DuckChange, Ducker};
 pub use painter::Painter;
-pub use typewriter::Typewriter;
\ No newline at end of file
+pub use typewriter::Typewriter;
+pub use fonts::FontRoles;
+pub use movie::MoviePlayer;
+pub use particles::ParticleField;
\ No newline at end of file
@@ -0,0 +1,40 @@
+/// 图片序列过场动画：帧为 `<id>_0001`、`<id>_0002`… 的图片资源，
+/// 音轨为与动画同名的音频资源 (可选)
+pub struct MoviePlayer {
+    pub id: String,
+    pub skippable: bool,
+    frames: Vec<String>,
+    fps: f32,
+    elapsed: f32,
+}
+
+impl MoviePlayer {
+    pub fn new(id: String, frames: Vec<String>, fps: f32, skippable: bool) -> Self {
+        Self { id, skippable, frames, fps: fps.max(1.0), elapsed: 0.0 }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    fn index(&self) -> usize {
+        (self.elapsed * self.fps) as usize
+    }
+
+    /// 当前应显示的帧，播完后停在最后一帧
+    pub fn current_frame(&self) -> Option<&str> {
+        let last = self.frames.len().checked_sub(1)?;
+        Some(&self.frames[self.index().min(last)])
+    }
+
+    /// 当前帧之后的 `n` 帧，用于提前加载
+    pub fn upcoming(&self, n: usize) -> &[String] {
+        let start = (self.index() + 1).min(self.frames.len());
+        let end = (start + n).min(self.frames.len());
+        &self.frames[start..end]
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.index() >= self.frames.len()
+    }
+}
@@ -1,14 +1,46 @@
 use crate::core::animator::{RenderSprite, SceneAnimator};
+use lumina_core::config::GraphicsConfig;
 use lumina_ui::{Color, Rect, ShaderSpec, Transform, UiRenderer};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::Path;
 
+/// 立绘的基础缩放：把不同分辨率出图的素材归一到设计分辨率
+#[derive(Debug, Clone, Default)]
+pub struct SpriteScale {
+    reference_height: Option<f32>,
+    overrides: BTreeMap<String, f32>,
+}
+
+impl SpriteScale {
+    pub fn new(cfg: &GraphicsConfig) -> Self {
+        Self {
+            reference_height: cfg.sprite_reference_height,
+            overrides: cfg.sprite_scale.clone(),
+        }
+    }
+
+    /// `texture` 为不含属性的立绘名，同一角色的各表情共用一个比例
+    pub fn factor(&self, texture: &str, design_height: f32) -> f32 {
+        if let Some(&scale) = self.overrides.get(texture) {
+            return scale;
+        }
+        match self.reference_height {
+            Some(h) if h > 0.0 => design_height / h,
+            _ => 1.0,
+        }
+    }
+}
+
 pub struct Painter {
+    sprite_scale: SpriteScale,
 }
 
 impl Painter {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            sprite_scale: SpriteScale::new(&lumina_shared::config::get::<GraphicsConfig>("graphics")),
+        }
     }
 
     fn extract_key(path_str: &str) -> Cow<'_, str> {
@@ -47,12 +79,15 @@ impl Painter {
             };
 
             let mut t = Transform::default();
+            t.x = sprite.shake.x;
+            t.y = sprite.shake.y;
             if !is_bg {
-                t.x = sprite.pos.x + sprite.offset.x;
-                t.y = sprite.pos.y + sprite.offset.y;
+                let scale = sprite.scale * self.sprite_scale.factor(&sprite.texture, win_h);
+                t.x += sprite.pos.x + sprite.offset.x;
+                t.y += sprite.pos.y + sprite.offset.y - sprite.lift;
                 t.rotation = sprite.rotation;
-                t.scale_x = sprite.scale;
-                t.scale_y = sprite.scale;
+                t.scale_x = scale;
+                t.scale_y = scale;
             }
 
             let mut drawn = false;
@@ -65,7 +100,8 @@ impl Painter {
                 let uniforms = [
                     sprite.trans_progress, // u_progress
                     sprite.trans_vague,    // u_vague
-                    use_rule               // u_use_rule
+                    use_rule,              // u_use_rule
+                    sprite.alpha,          // u_alpha
                 ];
 
                 let images = [
@@ -80,26 +116,19 @@ impl Painter {
                     images: &images,
                 };
 
-                if is_bg {
+                ui.with_transform(t, &mut |ui| {
                     ui.draw_shader(draw_rect, spec);
-                } else {
-                    ui.with_transform(t, &mut |ui| {
-                        ui.draw_shader(draw_rect, spec);
-                    });
-                }
+                });
                 drawn = true;
             }
             if !drawn {
                 let alpha_byte = (sprite.alpha * 255.0) as u8;
-                let tint = Color::rgba(255, 255, 255, alpha_byte);
+                let shade = (sprite.brightness.clamp(0.0, 1.0) * 255.0) as u8;
+                let tint = Color::rgba(shade, shade, shade, alpha_byte);
 
-                if is_bg {
+                ui.with_transform(t, &mut |ui| {
                     ui.draw_image(&full_name, draw_rect, tint);
-                } else {
-                    ui.with_transform(t, &mut |ui| {
-                        ui.draw_image(&full_name, draw_rect, tint);
-                    });
-                }
+                });
             }
         }
     }
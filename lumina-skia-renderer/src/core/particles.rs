@@ -0,0 +1,108 @@
+use lumina_ui::{Color, Rect, UiRenderer};
+use crate::config::{ParticleConfig, ParticleKind};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    /// 相对于基础速度的倍数，远处的粒子小而慢
+    pub depth: f32,
+    pub phase: f32,
+}
+
+/// 飘落粒子层 (雪 / 花瓣)，坐标相对于所在区域左上角，落出底部后从顶部重新出现
+pub struct ParticleField {
+    cfg: ParticleConfig,
+    pub particles: Vec<Particle>,
+    size: (f32, f32),
+    time: f32,
+    rng: u32,
+}
+
+impl ParticleField {
+    pub fn new(cfg: ParticleConfig, width: f32, height: f32) -> Self {
+        let mut field = Self { particles: Vec::with_capacity(cfg.count), cfg, size: (width, height), time: 0.0, rng: 0x2545f491 };
+        for _ in 0..field.cfg.count {
+            let y = field.rand() * height;
+            let p = field.spawn(y);
+            field.particles.push(p);
+        }
+        field
+    }
+
+    /// [0, 1) 的伪随机数 (xorshift32)
+    fn rand(&mut self) -> f32 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng = x;
+        x as f32 / (u32::MAX as f32 + 1.0)
+    }
+
+    fn spawn(&mut self, y: f32) -> Particle {
+        let depth = 0.4 + self.rand() * 0.6;
+        let base_radius = match self.cfg.kind {
+            ParticleKind::Snow => 3.0,
+            ParticleKind::Petals => 5.0,
+        };
+        Particle {
+            x: self.rand() * self.size.0,
+            y,
+            radius: base_radius * depth,
+            depth,
+            phase: self.rand() * std::f32::consts::TAU,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+        let (w, h) = self.size;
+        let drift = match self.cfg.kind {
+            ParticleKind::Snow => 0.0,
+            ParticleKind::Petals => 0.4,
+        };
+        for i in 0..self.particles.len() {
+            let p = &mut self.particles[i];
+            p.y += self.cfg.speed * p.depth * dt;
+            p.x = (p.x + self.cfg.speed * drift * p.depth * dt).rem_euclid(w.max(1.0));
+            if p.y - p.radius > h {
+                let fresh = self.spawn(0.0);
+                self.particles[i] = Particle { y: -fresh.radius, ..fresh };
+            }
+        }
+    }
+
+    /// 区域大小变化时把粒子按比例映射过去
+    pub fn resize(&mut self, width: f32, height: f32) {
+        let (w, h) = self.size;
+        if (w, h) == (width, height) || w <= 0.0 || h <= 0.0 { return; }
+        for p in &mut self.particles {
+            p.x *= width / w;
+            p.y *= height / h;
+        }
+        self.size = (width, height);
+    }
+
+    pub fn draw(&self, ui: &mut impl UiRenderer, rect: Rect) {
+        let (sway, default_color) = match self.cfg.kind {
+            ParticleKind::Snow => (8.0, [255, 255, 255, 200]),
+            ParticleKind::Petals => (24.0, [255, 183, 197, 220]),
+        };
+        let [r, g, b, a] = self.cfg.color.unwrap_or(default_color);
+        let color = Color::rgba(r, g, b, a);
+
+        for p in &self.particles {
+            let x = rect.x + p.x + (self.time * 1.5 + p.phase).sin() * sway * p.depth;
+            let y = rect.y + p.y;
+            match &self.cfg.image {
+                Some(img) => {
+                    let size = p.radius * 4.0;
+                    ui.draw_image(img, Rect::new(x - size / 2.0, y - size / 2.0, size, size), color);
+                }
+                None => ui.draw_circle((x, y), p.radius, color),
+            }
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! 资源来源，实现见 [`lumina_shared::vfs`]；资源目录或项目 pak 的 `assets/` 分区
+
+pub use lumina_shared::vfs::{
+    local_path, write_pak, DirSource, PakSource, VfsSource as AssetSource,
+};
+use std::io;
+use std::sync::Arc;
+
+/// 按路径选择来源：`.pak` 文件读归档的资源分区，其余按目录处理
+pub fn open(root: &str) -> io::Result<Arc<dyn AssetSource>> {
+    lumina_shared::vfs::open(root, lumina_shared::vfs::ASSETS)
+}
@@ -7,7 +7,8 @@ pub struct Typewriter {
     blink_timer: f32,
 
     pub display_text: String,
-    chars: Vec<char>,
+    /// 逐步显示的单位 (注音与底字整体为一个单位)
+    units: Vec<String>,
     progress: f32,
     speed: f32,
     finished: bool,
@@ -24,9 +25,10 @@ impl Typewriter {
             blink_timer: 0.0,
 
             display_text: String::new(),
-            chars: Vec::new(),
+            units: Vec::new(),
             progress: 0.0,
-            speed: 30.0,
+            // 字/秒，不大于 0 时整行立即显示
+            speed: lumina_core::storager::load_preferences().text_speed(),
             finished: true,
         }
     }
@@ -40,12 +42,16 @@ impl Typewriter {
         self.suffix = suffix.to_string();
         self.cursor = cursor.to_string();
 
-        self.chars = text.chars().collect(); // 拆解为 Unicode 字符
+        self.units = lumina_core::markup::reveal_units(text); // 拆解为显示单位
         self.progress = 0.0;
         self.blink_timer = 0.0;
         self.display_text.clear();
         self.finished = text.is_empty();
 
+        if self.speed <= 0.0 {
+            self.skip();
+            return;
+        }
         self.update_display_text(0);
     }
 
@@ -55,7 +61,7 @@ impl Typewriter {
         if !self.finished {
 
             self.progress += self.speed * dt;
-            let char_count = self.chars.len();
+            let char_count = self.units.len();
 
             // 转换 float 进度为 整数索引
             let visible_count = (self.progress as usize).min(char_count);
@@ -66,13 +72,13 @@ impl Typewriter {
                 self.finished = true;
             }
         } else {
-            let visible_count = self.chars.len();
+            let visible_count = self.units.len();
             self.update_display_text(visible_count);
         }
     }
 
     fn update_display_text(&mut self, visible_count: usize) {
-        let main_part: String = self.chars[0..visible_count].iter().collect();
+        let main_part: String = self.units[0..visible_count].concat();
 
         let mut final_suffix = self.suffix.clone();
 
@@ -86,7 +92,7 @@ impl Typewriter {
     }
 
     pub fn skip(&mut self) {
-        self.progress = self.chars.len() as f32;
+        self.progress = self.units.len() as f32;
         self.display_text = format!("{}{}{}", self.prefix, self.full_text, self.suffix);
         self.finished = true;
     }
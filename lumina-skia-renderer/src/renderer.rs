@@ -1,37 +1,45 @@
-use crate::config::WindowConfig;
-use crate::core::{AssetManager, AudioPlayer, Painter};
-use crate::screens::{main_menu::MainMenuScreen, Screen, ScreenTransition};
+use crate::config::{ScreenFx, ScreenFxKind, WindowConfig};
+use crate::core::{AssetManager, AudioPlayer, FontRoles, Painter};
+use crate::screens::{debug_overlay::DebugOverlay, ingame::InGameScreen, main_menu::MainMenuScreen, Screen, ScreenTransition};
 use crate::ui::UiDrawer;
 use crate::vk_utils::context::VulkanRenderContext;
 use crate::vk_utils::renderer::VulkanRenderer;
 
-use lumina_core::config::SystemConfig;
+use lumina_core::config::{DebugConfig, SystemConfig};
 use lumina_core::manager::ScriptManager;
+use lumina_core::renderer::driver::ExecutorHandle;
 use lumina_core::Ctx;
 use lumina_shared;
 use lumina_ui::{
-    input::UiContext,
+    input::{Action, TextEvent, UiContext},
     Rect
 };
 use skia_safe::textlayout::{FontCollection, TypefaceFontProvider};
 use skia_safe::{FontMgr, RuntimeEffect};
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use winit::{
     application::ApplicationHandler,
-    dpi::PhysicalSize,
-    event::{ElementState, MouseButton, WindowEvent},
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{ElementState, Ime, MouseButton, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
-    window::{Window, WindowId}
+    keyboard::{Key, NamedKey},
+    window::{Fullscreen, Window, WindowId}
 };
 
 // 设计分辨率
 const DESIGN_WIDTH: f32 = 1920.0;
 const DESIGN_HEIGHT: f32 = 1080.0;
 
+/// 进行中的界面切换：期间旧界面照常绘制在下层，新界面按进度叠加在上面，鼠标输入被屏蔽
+struct ActiveScreenFx {
+    fx: ScreenFx,
+    elapsed: f32,
+    /// `Pop` / `Replace` 移出栈的旧界面；`None` 表示旧界面仍在栈中 (`Push`)，即栈顶之下那一个
+    outgoing: Option<Box<dyn Screen>>,
+}
+
 pub struct SkiaRenderer {
     render_ctx: VulkanRenderContext,
     renderer: Option<VulkanRenderer>,
@@ -39,15 +47,23 @@ pub struct SkiaRenderer {
     audio_player: AudioPlayer,
     painter: Painter,
     pub font_collection: FontCollection,
+    font_roles: FontRoles,
 
     shaders: HashMap<String, RuntimeEffect>,
     screens: Vec<Box<dyn Screen>>,
+    screen_fx: Option<ActiveScreenFx>,
+    default_screen_fx: ScreenFx,
+    default_overlay_fx: ScreenFx,
     start_time: Instant,
     ctx: Ctx,
 
     ui_ctx: UiContext,
     physical_cursor_pos: (f32, f32),
     scale_factor: f64,
+    /// 上一帧是否有输入框请求输入法
+    ime_allowed: bool,
+    /// 为 None 时未开启 `debug.enable_console`，F3 不响应
+    debug_overlay: Option<DebugOverlay>,
 
     gc_timer: Instant,
     last_frame: Instant,
@@ -58,18 +74,31 @@ impl SkiaRenderer {
         let sys_cfg: SystemConfig = lumina_shared::config::get("system");
         let assets = AssetManager::new(&sys_cfg.assets_path);
 
+        let win_cfg: WindowConfig = lumina_shared::config::get("window");
+        let mut priority: Vec<String> = Vec::new();
+        for chain in win_cfg.fonts.values().chain(win_cfg.language_fonts.values().flat_map(|r| r.values())) {
+            for family in chain {
+                if !priority.contains(family) {
+                    priority.push(family.clone());
+                }
+            }
+        }
+
         let mut font_collection = FontCollection::new();
         let mut font_provider = TypefaceFontProvider::new();
-        assets.register_fonts_to(&mut font_provider);
+        assets.register_fonts_to(&mut font_provider, &priority);
         font_collection.set_asset_font_manager(Some(font_provider.into()));
         font_collection.set_dynamic_font_manager(FontMgr::default());
 
+        let font_roles = FontRoles::resolve(&win_cfg, &mut font_collection);
+        font_collection.set_default_font_manager(FontMgr::default(), font_roles.primary_family());
+
         let mut shaders = HashMap::new();
-        let shader_dir = Path::new(&sys_cfg.script_path).join("system/core");
-        let trans_shader_path = shader_dir.join("transition.sksl");
+        let scripts = lumina_shared::vfs::open_or_dir(&sys_cfg.script_path, lumina_shared::vfs::SCRIPTS);
+        let trans_shader_path = "system/core/transition.sksl";
 
-        if trans_shader_path.exists() {
-            match fs::read_to_string(&trans_shader_path) {
+        if scripts.exists(trans_shader_path) {
+            match scripts.read_to_string(trans_shader_path) {
                 Ok(code) => {
                     match RuntimeEffect::make_for_shader(&code, None) {
                         Ok(effect) => {
@@ -87,27 +116,41 @@ impl SkiaRenderer {
             log::warn!("Shader file not found: {:?}", trans_shader_path);
         }
 
-        let ctx = Ctx::default();
+        let mut ctx = Ctx::default();
+
+        // 设置页保存的音量偏好
+        let mut audio_player = AudioPlayer::new();
+        let prefs = lumina_core::storager::load_preferences();
+        for (channel, volume) in &prefs.volumes {
+            audio_player.set_channel_volume(channel, *volume);
+        }
+        crate::config::set_reduce_motion(prefs.reduce_motion());
 
-        let initial_screen: Box<dyn Screen> =
-            Box::new(MainMenuScreen::new(manager.clone()));
+        let initial_screen = initial_screen(manager, &mut ctx);
 
         Self {
             render_ctx: VulkanRenderContext::default(),
             renderer: None,
             assets,
-            audio_player: AudioPlayer::new(),
+            audio_player,
             painter: Painter::new(),
             font_collection,
+            font_roles,
 
             shaders,
             screens: vec![initial_screen],
+            screen_fx: None,
+            default_screen_fx: win_cfg.screen_fx,
+            default_overlay_fx: win_cfg.overlay_fx,
             start_time: Instant::now(),
             ctx,
 
             ui_ctx: UiContext::new(),
             physical_cursor_pos: (0.0, 0.0),
             scale_factor: 1.0,
+            ime_allowed: false,
+            debug_overlay: lumina_shared::config::get::<DebugConfig>("debug").enable_console
+                .then(DebugOverlay::new),
 
             gc_timer: Instant::now(),
             last_frame: Instant::now(),
@@ -127,6 +170,15 @@ impl SkiaRenderer {
     }
 
 
+    fn start_screen_fx(&mut self, fx: ScreenFx, outgoing: Option<Box<dyn Screen>>) {
+        let mut fx = fx;
+        // 减少动态效果时不做滑动，只淡入淡出
+        if crate::config::reduce_motion() && fx.kind == ScreenFxKind::SlideLeft {
+            fx.kind = ScreenFxKind::Fade;
+        }
+        self.screen_fx = (fx.duration > 0.0001).then_some(ActiveScreenFx { fx, elapsed: 0.0, outgoing });
+    }
+
     fn to_logical(physical_x: f32, physical_y: f32, scale: f32, off_x: f32, off_y: f32) -> (f32, f32) {
         if scale == 0.0 { return (0.0, 0.0); }
         (
@@ -136,13 +188,31 @@ impl SkiaRenderer {
     }
 }
 
+/// 主菜单；设置了 `debug.skip_menu` 或 `debug.load_slot` 时直接进入游戏
+fn initial_screen(manager: Arc<ScriptManager>, ctx: &mut Ctx) -> Box<dyn Screen> {
+    let debug_cfg: DebugConfig = lumina_shared::config::get("debug");
+    if !debug_cfg.skip_menu && debug_cfg.load_slot.is_none() {
+        return Box::new(MainMenuScreen::new(manager));
+    }
+
+    let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+    let mut driver = ExecutorHandle::new(ctx, manager, &sys_cfg.start_label);
+    if let Some(slot) = debug_cfg.load_slot {
+        if !driver.continue_from(ctx, &lumina_core::storager::slot_filename(slot)) {
+            log::warn!("Failed to load slot {}, starting a new game", slot);
+        }
+    }
+    Box::new(InGameScreen::new(driver))
+}
+
 impl ApplicationHandler for SkiaRenderer {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         let cfg: WindowConfig = lumina_shared::config::get("window");
         let window_attributes = Window::default_attributes()
             .with_title(&cfg.title)
             .with_inner_size(PhysicalSize::new(cfg.width, cfg.height))
-            .with_resizable(cfg.resizable);
+            .with_resizable(cfg.resizable)
+            .with_fullscreen((lumina_core::storager::load_preferences().fullscreen && !cfg.windowed).then(|| Fullscreen::Borderless(None)));
 
         let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
 
@@ -175,18 +245,82 @@ impl ApplicationHandler for SkiaRenderer {
             WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
                 let pressed = state == ElementState::Pressed;
                 self.ui_ctx.mouse_pressed = pressed && !self.ui_ctx.mouse_held;
+                self.ui_ctx.mouse_released = !pressed && self.ui_ctx.mouse_held;
                 self.ui_ctx.mouse_held = pressed;
                 self.request_redraw();
             },
 
+            // 3. 键盘：转为文本编辑事件，由获得焦点的输入框消费
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                if let Some(overlay) = &mut self.debug_overlay {
+                    match &event.logical_key {
+                        Key::Named(NamedKey::F3) => overlay.visible = !overlay.visible,
+                        Key::Named(NamedKey::F4) if overlay.visible => overlay.cycle_level(),
+                        _ => {}
+                    }
+                    if matches!(event.logical_key, Key::Named(NamedKey::F3 | NamedKey::F4)) {
+                        self.request_redraw();
+                        return;
+                    }
+                }
+                // 快速存档 / 读档，由游戏界面处理
+                let action = match &event.logical_key {
+                    Key::Named(NamedKey::F5) => Some(Action::QuickSave),
+                    Key::Named(NamedKey::F9) => Some(Action::QuickLoad),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    self.ui_ctx.push_action(action);
+                    self.request_redraw();
+                    return;
+                }
+                let text_ev = match &event.logical_key {
+                    Key::Named(NamedKey::Backspace) => Some(TextEvent::Backspace),
+                    Key::Named(NamedKey::Delete) => Some(TextEvent::Delete),
+                    Key::Named(NamedKey::ArrowLeft) => Some(TextEvent::Left),
+                    Key::Named(NamedKey::ArrowRight) => Some(TextEvent::Right),
+                    Key::Named(NamedKey::Home) => Some(TextEvent::Home),
+                    Key::Named(NamedKey::End) => Some(TextEvent::End),
+                    Key::Named(NamedKey::Enter) => Some(TextEvent::Submit),
+                    _ => event.text.as_ref()
+                        .filter(|t| !t.chars().any(char::is_control))
+                        .map(|t| TextEvent::Insert(t.to_string())),
+                };
+                if let Some(ev) = text_ev {
+                    self.ui_ctx.push_text_event(ev);
+                    self.request_redraw();
+                }
+            },
+
+            // 4. 输入法：组字中的文本只做显示，提交后才写入
+            WindowEvent::Ime(ime) => {
+                match ime {
+                    Ime::Commit(text) => {
+                        self.ui_ctx.set_preedit(None);
+                        self.ui_ctx.push_text_event(TextEvent::Insert(text));
+                    }
+                    Ime::Preedit(text, _) => self.ui_ctx.set_preedit(Some(text)),
+                    Ime::Disabled => self.ui_ctx.set_preedit(None),
+                    Ime::Enabled => {}
+                }
+                self.request_redraw();
+            },
+
             WindowEvent::RedrawRequested => {
                 self.assets.update();
-                self.audio_player.update(&mut self.assets);
+                self.audio_player.update(&mut self.assets, &mut self.ctx.audio_state);
 
                 let now = Instant::now();
                 let dt = now.duration_since(self.last_frame).as_secs_f32();
                 self.last_frame = now;
 
+                if let Some(active) = &mut self.screen_fx {
+                    active.elapsed += dt;
+                    if active.elapsed >= active.fx.duration {
+                        self.screen_fx = None;
+                    }
+                }
+
                 let mut transition = ScreenTransition::None;
 
                 if let Some(screen) = self.screens.last_mut() {
@@ -199,12 +333,20 @@ impl ApplicationHandler for SkiaRenderer {
                     );
                 }
 
+                // 新的切换直接打断尚未完成的切换
                 match transition {
-                    ScreenTransition::Push(s) => self.screens.push(s),
-                    ScreenTransition::Pop => { self.screens.pop(); },
-                    ScreenTransition::Replace(s) => {
-                        self.screens.pop();
+                    ScreenTransition::Push(s, fx) => {
+                        self.screens.push(s);
+                        self.start_screen_fx(fx.unwrap_or(self.default_overlay_fx), None);
+                    },
+                    ScreenTransition::Pop(fx) => {
+                        let old = self.screens.pop();
+                        self.start_screen_fx(fx.unwrap_or(self.default_overlay_fx), old);
+                    },
+                    ScreenTransition::Replace(s, fx) => {
+                        let old = self.screens.pop();
                         self.screens.push(s);
+                        self.start_screen_fx(fx.unwrap_or(self.default_screen_fx), old);
                     },
                     ScreenTransition::Quit => event_loop.exit(),
                     ScreenTransition::None => {},
@@ -215,17 +357,22 @@ impl ApplicationHandler for SkiaRenderer {
 
                     // 准备引用，供闭包使用
                     let screens_ref = &mut self.screens;
+                    let screen_fx_ref = &mut self.screen_fx;
                     let ctx_ref = &mut self.ctx;
                     let ui_ctx_ref = &mut self.ui_ctx;
                     let painter_ref = &mut self.painter;
                     let assets_ref = &mut self.assets;
                     let fonts_ref = &self.font_collection;
+                    let font_roles_ref = &self.font_roles;
                     let shaders_ref = &self.shaders;
+                    let overlay_ref = self.debug_overlay.as_ref().filter(|o| o.visible);
 
                     let time = self.start_time.elapsed().as_secs_f32();
 
                     let (mx, my) = self.physical_cursor_pos;
                     let phy_win_size = renderer.window.inner_size();
+                    // 设计坐标 -> 窗口物理坐标的变换 (scale, off_x, off_y, content_scale)，供输入法定位
+                    let mut view = (1.0f32, 0.0f32, 0.0f32, 1.0f32);
 
                     renderer.draw_and_present(|canvas, size| {
                         // A. 布局计算 (含 DPI 修正)
@@ -242,10 +389,17 @@ impl ApplicationHandler for SkiaRenderer {
                         let scale = scale_x.min(scale_y);
                         let off_x = (win_w - DESIGN_WIDTH * scale) / 2.0;
                         let off_y = (win_h - DESIGN_HEIGHT * scale) / 2.0;
+                        view = (scale, off_x, off_y, content_scale);
 
-                        // B. 更新 UI 鼠标状态
+                        // B. 更新 UI 鼠标状态 (界面切换期间屏蔽点击)
                         let (lx, ly) = SkiaRenderer::to_logical(adj_mx, adj_my, scale, off_x, off_y);
-                        ui_ctx_ref.update(lx, ly, ui_ctx_ref.mouse_pressed, ui_ctx_ref.mouse_held);
+                        let blocked = screen_fx_ref.is_some();
+                        ui_ctx_ref.update(
+                            lx, ly,
+                            ui_ctx_ref.mouse_pressed && !blocked,
+                            ui_ctx_ref.mouse_released && !blocked,
+                            ui_ctx_ref.mouse_held && !blocked,
+                        );
 
                         // C. 设置画布
                         canvas.save();
@@ -255,22 +409,86 @@ impl ApplicationHandler for SkiaRenderer {
                         canvas.clip_rect(skia_safe::Rect::new(0.0, 0.0, DESIGN_WIDTH, DESIGN_HEIGHT), None, None);
 
                         // D. 委托给栈顶 Screen 绘制
-                        if let Some(screen) = screens_ref.last_mut() {
-                            let mut ui = UiDrawer::new(canvas, ui_ctx_ref, fonts_ref, assets_ref, time, shaders_ref);
-                            let design_rect = Rect::new(0.0, 0.0, DESIGN_WIDTH, DESIGN_HEIGHT);
-
-                            screen.draw(
-                                &mut ui,
-                                painter_ref,
-                                design_rect,
-                                ctx_ref
-                            );
+                        let design_rect = Rect::new(0.0, 0.0, DESIGN_WIDTH, DESIGN_HEIGHT);
+                        let mut draw_screen = |screen: &mut Box<dyn Screen>| {
+                            let mut ui = UiDrawer::new(canvas, ui_ctx_ref, fonts_ref, font_roles_ref, assets_ref, time, shaders_ref);
+                            screen.draw(&mut ui, painter_ref, design_rect, ctx_ref);
+                        };
+
+                        let top = screens_ref.len().checked_sub(1);
+                        match (screen_fx_ref.as_mut(), top) {
+                            (Some(active), Some(top)) if active.fx.kind == ScreenFxKind::FadeBlack => {
+                                // 同一时刻只画一个界面，再盖一层黑色
+                                let (incoming, black) = active.fx.black_overlay(active.elapsed);
+                                match (incoming, active.outgoing.as_mut()) {
+                                    (true, _) => draw_screen(&mut screens_ref[top]),
+                                    (false, Some(old)) => draw_screen(old),
+                                    (false, None) if top > 0 => draw_screen(&mut screens_ref[top - 1]),
+                                    (false, None) => {},
+                                }
+                                let mut paint = skia_safe::Paint::default();
+                                paint.set_color(skia_safe::Color::from_argb((black * 255.0) as u8, 0, 0, 0));
+                                canvas.draw_rect(skia_safe::Rect::new(0.0, 0.0, DESIGN_WIDTH, DESIGN_HEIGHT), &paint);
+                            },
+                            (Some(active), Some(top)) => {
+                                let p = active.fx.progress(active.elapsed);
+
+                                // 旧界面
+                                canvas.save();
+                                if active.fx.kind == ScreenFxKind::SlideLeft {
+                                    canvas.translate((-p * DESIGN_WIDTH, 0.0));
+                                }
+                                match active.outgoing.as_mut() {
+                                    Some(old) => draw_screen(old),
+                                    None if top > 0 => draw_screen(&mut screens_ref[top - 1]),
+                                    None => {},
+                                }
+                                canvas.restore();
+
+                                // 新界面
+                                match active.fx.kind {
+                                    ScreenFxKind::Fade | ScreenFxKind::FadeBlack => {
+                                        let bounds = skia_safe::Rect::new(0.0, 0.0, DESIGN_WIDTH, DESIGN_HEIGHT);
+                                        canvas.save_layer_alpha(bounds, (p * 255.0) as u32);
+                                    },
+                                    ScreenFxKind::SlideLeft => {
+                                        canvas.save();
+                                        canvas.translate(((1.0 - p) * DESIGN_WIDTH, 0.0));
+                                    },
+                                }
+                                draw_screen(&mut screens_ref[top]);
+                                canvas.restore();
+                            },
+                            (None, Some(top)) => draw_screen(&mut screens_ref[top]),
+                            _ => {},
+                        }
+
+                        // E. 调试浮层盖在所有界面之上
+                        if let Some(overlay) = overlay_ref {
+                            let mut ui = UiDrawer::new(canvas, ui_ctx_ref, fonts_ref, font_roles_ref, assets_ref, time, shaders_ref);
+                            overlay.draw(&mut ui, design_rect);
                         }
 
                         canvas.restore();
                     });
 
                     self.ui_ctx.mouse_pressed = false;
+                    self.ui_ctx.mouse_released = false;
+
+                    // 有输入框获得焦点时开启输入法，并把候选窗放到光标处
+                    let ime_area = self.ui_ctx.end_frame();
+                    if ime_area.is_some() != self.ime_allowed {
+                        self.ime_allowed = ime_area.is_some();
+                        renderer.window.set_ime_allowed(self.ime_allowed);
+                    }
+                    if let Some(area) = ime_area {
+                        let (scale, off_x, off_y, content_scale) = view;
+                        let cs = if content_scale > 0.0 { content_scale } else { 1.0 };
+                        renderer.window.set_ime_cursor_area(
+                            PhysicalPosition::new((area.x * scale + off_x) / cs, (area.y * scale + off_y) / cs),
+                            PhysicalSize::new(area.w * scale / cs, area.h * scale / cs),
+                        );
+                    }
 
                     if self.gc_timer.elapsed().as_secs() >= 30 {
                         self.assets.gc(Duration::from_secs(60));
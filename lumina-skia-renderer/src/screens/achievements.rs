@@ -0,0 +1,120 @@
+use crate::ui::UiDrawer;
+use crate::core::{AssetManager, Painter, AudioPlayer};
+use crate::screens::{Screen, ScreenTransition};
+use lumina_core::Ctx;
+use lumina_core::config::AchievementsConfig;
+use lumina_core::storager;
+use lumina_ui::{Rect, Color, Alignment, GradientDirection};
+use lumina_ui::widgets::{tooltip, Button, Image, Label, Panel};
+use std::collections::HashMap;
+use winit::event_loop::ActiveEventLoop;
+
+pub struct AchievementsScreen {
+    defs: AchievementsConfig,
+    unlocked: HashMap<String, u64>,
+
+    should_close: bool,
+}
+
+impl AchievementsScreen {
+    pub fn new() -> Self {
+        Self {
+            defs: lumina_shared::config::get("achievements"),
+            unlocked: storager::load_achievements("global.json"),
+            should_close: false,
+        }
+    }
+}
+
+impl Screen for AchievementsScreen {
+    fn update(
+        &mut self,
+        _dt: f32,
+        _ctx: &mut Ctx,
+        _el: &ActiveEventLoop,
+        _assets: &mut AssetManager,
+        _audio: &mut AudioPlayer
+    ) -> ScreenTransition {
+        if self.should_close {
+            return ScreenTransition::Pop(None);
+        }
+        ScreenTransition::None
+    }
+
+    fn draw(&mut self, ui: &mut UiDrawer, _painter: &mut Painter, rect: Rect, _ctx: &mut Ctx) {
+        Panel::new()
+            .color(Color::rgba(0, 0, 0, 220))
+            .show(ui, rect);
+
+        let panel_rect = rect.center(900.0, 800.0);
+        Panel::new()
+            .gradient(
+                GradientDirection::Vertical,
+                Color::rgb(60, 60, 70),
+                Color::rgb(30, 30, 40)
+            )
+            .stroke(Color::rgb(100, 100, 120), 2.0)
+            .rounded(16.0)
+            .show(ui, panel_rect);
+
+        let content = panel_rect.shrink(40.0);
+        let (header, body) = content.split_top(60.0);
+        let (row_btn, mut list) = body.split_bottom(60.0);
+
+        let title = format!("ACHIEVEMENTS  {}/{}", self.unlocked.len().min(self.defs.len()), self.defs.len());
+        Label::new(&title)
+            .size(40.0)
+            .align(Alignment::Center)
+            .show(ui, header);
+
+        if self.defs.is_empty() {
+            Label::new("No achievements defined")
+                .color(Color::GRAY)
+                .align(Alignment::Center)
+                .show(ui, list);
+        }
+
+        // 图标的悬停提示最后绘制，避免被下一行盖住
+        let mut tips = Vec::new();
+        for (id, def) in &self.defs {
+            if list.h < 90.0 { break; }
+            let (row, rest) = list.split_top(90.0);
+            list = rest;
+
+            let unlocked = self.unlocked.contains_key(id);
+            let (title_color, desc_color, tint) = if unlocked {
+                (Color::WHITE, Color::rgb(200, 200, 200), Color::WHITE)
+            } else {
+                (Color::GRAY, Color::DARK_GRAY, Color::rgba(80, 80, 80, 160))
+            };
+
+            let row = row.shrink(5.0);
+            let (icon_rect, text_rect) = row.split_left(80.0);
+            if let Some(icon) = &def.icon {
+                Image::new(icon).tint(tint).show(ui, icon_rect.shrink(5.0));
+                tips.push((icon_rect, if unlocked { "Unlocked" } else { "Locked" }));
+            }
+
+            let (title_rect, desc_rect) = text_rect.split_top(40.0);
+            Label::new(&def.title)
+                .size(28.0)
+                .color(title_color)
+                .show(ui, title_rect);
+            Label::new(&def.description)
+                .size(20.0)
+                .color(desc_color)
+                .show(ui, desc_rect);
+        }
+
+        if Button::new("Close")
+            .rounded(8.0)
+            .show(ui, row_btn.center(120.0, 50.0))
+        {
+            self.should_close = true;
+        }
+
+        for (area, text) in tips {
+            tooltip(ui, area, text);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::ui::UiDrawer;
+use crate::core::{AssetManager, Painter, AudioPlayer};
+use crate::screens::{Screen, ScreenTransition};
+use lumina_core::{markup, storager, Ctx};
+use lumina_ui::{Rect, Color, Alignment, GradientDirection, UiRenderer};
+use lumina_ui::widgets::{Button, Label, Panel};
+use winit::event_loop::ActiveEventLoop;
+
+/// 导出的历史文件名 (写入存档目录)
+const EXPORT_FILENAME: &str = "history.txt";
+const ENTRY_SIZE: f32 = 22.0;
+const ENTRY_GAP: f32 = 12.0;
+
+/// 对话历史 (Backlog)：显示最近的对话，可导出为文本
+pub struct BacklogScreen {
+    status: Option<String>,
+    should_close: bool,
+}
+
+impl BacklogScreen {
+    pub fn new() -> Self {
+        Self { status: None, should_close: false }
+    }
+}
+
+impl Screen for BacklogScreen {
+    fn update(
+        &mut self,
+        _dt: f32,
+        _ctx: &mut Ctx,
+        _el: &ActiveEventLoop,
+        _assets: &mut AssetManager,
+        _audio: &mut AudioPlayer
+    ) -> ScreenTransition {
+        if self.should_close {
+            return ScreenTransition::Pop(None);
+        }
+        ScreenTransition::None
+    }
+
+    fn draw(&mut self, ui: &mut UiDrawer, _painter: &mut Painter, rect: Rect, ctx: &mut Ctx) {
+        Panel::new()
+            .color(Color::rgba(0, 0, 0, 220))
+            .show(ui, rect);
+
+        let panel_rect = rect.center(1200.0, 900.0);
+        Panel::new()
+            .gradient(
+                GradientDirection::Vertical,
+                Color::rgb(60, 60, 70),
+                Color::rgb(30, 30, 40)
+            )
+            .stroke(Color::rgb(100, 100, 120), 2.0)
+            .rounded(16.0)
+            .show(ui, panel_rect);
+
+        let content = panel_rect.shrink(40.0);
+        let (header, body) = content.split_top(60.0);
+        let (row_btn, list) = body.split_bottom(60.0);
+
+        Label::new("HISTORY")
+            .size(40.0)
+            .align(Alignment::Center)
+            .show(ui, header);
+
+        // 自底向上放入能容纳的最近几条
+        let entries = ctx.history_slice(..).rev()
+            .map(|rec| {
+                let text = markup::to_plain(&rec.text);
+                match &rec.speaker {
+                    Some(name) => format!("【{}】{}", name, text),
+                    None => text,
+                }
+            });
+
+        let mut shown = Vec::new();
+        let mut used = 0.0;
+        for entry in entries {
+            let h = ui.measure_text(&entry, list.w, ENTRY_SIZE, Some("dialogue"));
+            if used + h > list.h && !shown.is_empty() {
+                break;
+            }
+            used += h + ENTRY_GAP;
+            shown.push((entry, h));
+        }
+
+        let mut y = list.y;
+        for (entry, h) in shown.into_iter().rev() {
+            Label::new(&entry)
+                .size(ENTRY_SIZE)
+                .font("dialogue")
+                .align(Alignment::Start)
+                .show(ui, Rect::new(list.x, y, list.w, h));
+            y += h + ENTRY_GAP;
+        }
+
+        let (status_rect, buttons) = row_btn.split_left(row_btn.w - 280.0);
+        if let Some(status) = &self.status {
+            Label::new(status)
+                .size(20.0)
+                .color(Color::GRAY)
+                .show(ui, status_rect);
+        }
+
+        let (export_rect, close_rect) = buttons.split_left(140.0);
+        if Button::new("Export")
+            .rounded(8.0)
+            .show(ui, export_rect.center(120.0, 50.0))
+        {
+            self.status = Some(match storager::export_history(EXPORT_FILENAME, &ctx.dialogue_history) {
+                Ok(path) => format!("Exported to {}", path.display()),
+                Err(e) => {
+                    log::error!("Export history failed: {}", e);
+                    format!("Export failed: {}", e)
+                }
+            });
+        }
+
+        if Button::new("Close")
+            .rounded(8.0)
+            .show(ui, close_rect.center(120.0, 50.0))
+        {
+            self.should_close = true;
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::ui::UiDrawer;
+use lumina_core::renderer::driver::ExecutorHandle;
+use lumina_shared::logging::{self, LogFilter};
+use log::{Level, LevelFilter};
+use lumina_ui::{Alignment, Color, Rect};
+use lumina_ui::widgets::{Label, Panel};
+
+/// 浮层显示的日志条数
+const LOG_LINES: usize = 24;
+const LINE_HEIGHT: f32 = 24.0;
+const TEXT_SIZE: f32 = 18.0;
+
+/// 调试浮层 (F3 开关，F4 切换日志级别)：存档目录、脚本位置与最近的日志。
+/// 需要 `debug.enable_console`
+pub(crate) struct DebugOverlay {
+    pub visible: bool,
+    filter: LogFilter,
+    save_dir: String,
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            filter: LogFilter { level: LevelFilter::Info, module: None },
+            save_dir: lumina_core::storager::save_dir().display().to_string(),
+        }
+    }
+
+    /// 日志级别在 Trace -> Debug -> Info -> Warn -> Error 之间循环
+    pub fn cycle_level(&mut self) {
+        self.filter.level = match self.filter.level {
+            LevelFilter::Trace => LevelFilter::Debug,
+            LevelFilter::Debug => LevelFilter::Info,
+            LevelFilter::Info => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            _ => LevelFilter::Trace,
+        };
+    }
+
+    pub fn draw(&self, ui: &mut UiDrawer, rect: Rect) {
+        let entries = logging::recent_filtered(LOG_LINES, &self.filter);
+        let position = ExecutorHandle::debug_snapshot()
+            .map(|snap| snap.to_string())
+            .unwrap_or_default();
+
+        let mut header = vec![format!("Save dir: {}", self.save_dir)];
+        header.extend(position.lines().map(str::to_string));
+        header.push(format!("Log (F4): {}", self.filter.level));
+
+        let panel_h = (header.len() + entries.len()) as f32 * LINE_HEIGHT + 24.0;
+        let panel = Rect::new(rect.x + 20.0, rect.y + 20.0, rect.w * 0.6, panel_h.min(rect.h - 40.0));
+        Panel::new()
+            .color(Color::rgba(0, 0, 0, 180))
+            .show(ui, panel);
+
+        let mut y = panel.y + 12.0;
+        let lines = header.iter().map(|text| (text.clone(), Color::rgb(140, 200, 255)))
+            .chain(entries.iter().map(|e| (format!("[{}] {}: {}", e.level, e.target, e.message), level_color(e.level))));
+        for (text, color) in lines {
+            if y + LINE_HEIGHT > panel.y + panel.h {
+                break;
+            }
+            Label::new(&text)
+                .size(TEXT_SIZE)
+                .color(color)
+                .align(Alignment::Start)
+                .show(ui, Rect::new(panel.x + 12.0, y, panel.w - 24.0, LINE_HEIGHT));
+            y += LINE_HEIGHT;
+        }
+    }
+}
+
+fn level_color(level: Level) -> Color {
+    match level {
+        Level::Error => Color::rgb(255, 90, 90),
+        Level::Warn => Color::rgb(255, 210, 80),
+        Level::Info => Color::WHITE,
+        Level::Debug => Color::rgb(160, 160, 160),
+        Level::Trace => Color::rgb(110, 110, 110),
+    }
+}
@@ -1,31 +1,115 @@
 use super::{Screen, ScreenTransition};
+use super::backlog::BacklogScreen;
 use crate::ui::UiDrawer;
-use crate::core::{AssetManager, Painter, AudioPlayer, Typewriter};
+use crate::core::{AssetManager, Painter, AudioPlayer, Typewriter, MoviePlayer};
 use crate::core::SceneAnimator;
 use lumina_core::{Ctx, OutputEvent};
 use lumina_core::event::InputEvent;
-use lumina_core::renderer::driver::ExecutorHandle;
-use lumina_ui::{Rect, Color, UiRenderer, Alignment, GradientDirection};
-use lumina_ui::widgets::{Button, Label, Panel};
+use lumina_core::renderer::driver::{ExecutorHandle, MAX_STEPS_PER_FRAME};
+use lumina_ui::{Rect, Color, UiRenderer, Alignment, GradientDirection, ImageFit};
+use lumina_ui::widgets::{Button, Image, Label, Panel, ToastStack};
+use lumina_ui::input::Action;
+use lumina_core::config::{AchievementsConfig, GraphicsConfig};
+use lumina_core::runtime::assets::PresentationMode;
 use winit::event_loop::ActiveEventLoop;
 
+/// 成就提示浮层的停留时间 (秒)
+const ACHIEVEMENT_TOAST_SECS: f32 = 3.0;
+/// NVL 页面的文字大小与条目间距
+const NVL_TEXT_SIZE: f32 = 26.0;
+const NVL_ENTRY_GAP: f32 = 18.0;
+/// 快速存档 / 读档提示的停留时间 (秒)
+const QUICK_SAVE_TOAST_SECS: f32 = 1.5;
+/// 过场动画提前加载的帧数
+const MOVIE_PREFETCH_FRAMES: usize = 8;
+/// 过场动画的音频通道
+const MOVIE_CHANNEL: &str = "movie";
+
+/// 对话 / 旁白两侧的装饰符号
+fn text_marks(has_speaker: bool) -> (&'static str, &'static str) {
+    if has_speaker { ("「", "」") } else { ("❀", "❀") }
+}
+
 pub struct InGameScreen {
     driver: ExecutorHandle,
     animator: SceneAnimator,
     typewriter: Typewriter,
     active_choices: Option<(Option<String>, Vec<String>)>,
+    /// 限时选择：(剩余秒数, 总秒数, 超时选择的序号)
+    choice_timer: Option<(f32, f32, usize)>,
+    presentation: PresentationMode,
+    open_backlog: bool,
+    /// 执行到的语法错误语句，显示到下一句文本出现为止
+    script_error: Option<String>,
+    /// 脚本警告 (如未定义的说话人)，同样显示到下一句文本出现为止
+    script_warning: Option<String>,
+
+    achievement_defs: AchievementsConfig,
+    // (成就 id, 剩余显示时间)
+    achievement_toasts: Vec<(String, f32)>,
+    /// 左上角的通知提示 (快速存档结果、`lumina.toast` 等)
+    toasts: ToastStack,
+
+    movie: Option<MoviePlayer>,
+    movie_fps: f32,
+    /// 最近一张已加载好的帧，下一帧还在加载时继续显示它
+    movie_frame: Option<String>,
+    /// 动画已结束，下一次 update 时停止音轨
+    stop_movie_audio: bool,
+    /// `wait_audio` 等待的通道，播完后自动继续，期间点击不推进剧情
+    audio_wait: Option<String>,
+    /// 当前旁白前的空行数 (段落分隔)，ADV 对话框顶部留出同样多的空行
+    paragraph_gap: usize,
 }
 
 impl InGameScreen {
     pub fn new(driver: ExecutorHandle) -> Self {
         let mut animator = SceneAnimator::new();
         animator.resize(1920.0, 1080.0);
+        animator.set_highlight_config(lumina_shared::config::get("speaker_highlight"));
 
         Self {
             driver,
             animator,
             active_choices: None,
+            choice_timer: None,
+            presentation: PresentationMode::Adv,
+            open_backlog: false,
+            script_error: None,
+            script_warning: None,
             typewriter: Typewriter::new(),
+            achievement_defs: lumina_shared::config::get("achievements"),
+            achievement_toasts: Vec::new(),
+            toasts: ToastStack::new(),
+            movie: None,
+            movie_fps: lumina_shared::config::get::<GraphicsConfig>("graphics").movie_fps,
+            movie_frame: None,
+            stop_movie_audio: false,
+            audio_wait: None,
+            paragraph_gap: 0,
+        }
+    }
+
+    fn start_movie(&mut self, ctx: &mut Ctx, id: String, skippable: bool, assets: &mut AssetManager, audio: &mut AudioPlayer) {
+        let frames = assets.movie_frames(&id);
+        if frames.is_empty() {
+            log::warn!("Movie '{}' has no frames, skipped", id);
+            self.driver.feed(ctx, InputEvent::Continue);
+            return;
+        }
+        if assets.has_audio(&id) {
+            audio.play(assets, MOVIE_CHANNEL, &id, 1.0, 0.0, false, None, None, None);
+        }
+        self.movie = Some(MoviePlayer::new(id, frames, self.movie_fps, skippable));
+        self.movie_frame = None;
+    }
+
+    /// 播完或跳过：停止音轨并让剧情继续
+    fn finish_movie(&mut self, ctx: &mut Ctx) {
+        if self.movie.take().is_some() {
+            self.movie_frame = None;
+            self.stop_movie_audio = true;
+            self.driver.feed(ctx, InputEvent::Continue);
         }
     }
 
@@ -38,7 +122,7 @@ impl InGameScreen {
         audio: &mut AudioPlayer
     ) {
         // 1. 收集事件，解开 ctx 的借用锁
-        let events: Vec<_> = ctx.drain().into_iter().collect();
+        let events: Vec<_> = self.driver.drain(ctx);
 
         // 辅助闭包：获取 Sprite 初始状态
         let get_sprite_info = |target: &str| -> (Option<String>, Option<Vec<String>>) {
@@ -50,47 +134,42 @@ impl InGameScreen {
             (None, None)
         };
 
+        // 同一批事件里先到的警告 (如被跳过的出错语句) 不被随后的文本清掉
+        let mut fresh_warning = false;
         for event in events {
             match event {
                 // --- 音频处理 ---
-                OutputEvent::PlayAudio { channel, path, fade_in, volume, looping } => {
-                    audio.play(assets, &channel, &path, volume, fade_in, looping);
+                OutputEvent::PlayAudio { channel, path, fade_in, volume, looping, loop_region, speculative, pitch, intro } => {
+                    if speculative && !assets.has_audio(&path) {
+                        log::debug!("No audio '{}' for channel {}, skipped", path, channel);
+                        continue;
+                    }
+                    audio.play(assets, &channel, &path, volume, fade_in, looping, loop_region, pitch, intro.as_deref());
                 },
                 OutputEvent::StopAudio { channel, fade_out } => {
                     audio.stop(&channel, fade_out);
                 },
 
                 // --- 视觉处理 (委托给 Animator) ---
-                OutputEvent::NewSprite { target, texture, pos_str, transition, attrs, defer_visual} => {
-                    self.animator.handle_new_sprite(target, texture, pos_str.as_deref(), transition, attrs, defer_visual);
+                OutputEvent::NewSprite { target, texture, pos_str, transition, duration, attrs, defer_visual} => {
+                    self.animator.handle_new_sprite(target, texture, pos_str.as_deref(), transition, duration, attrs, defer_visual);
                 }
-                OutputEvent::UpdateSprite { target, transition } => {
+                OutputEvent::UpdateSprite { target, transition, duration } => {
                     let (pos_str, attrs) = get_sprite_info(&target);
-                    let attrs = attrs.unwrap_or_default();
 
                     self.animator.handle_update_sprite(
                         target,
                         transition,
+                        duration,
                         pos_str.as_deref(),
                         attrs
                     );
                 },
-                OutputEvent::HideSprite { target, transition } => {
-                    self.animator.handle_hide_sprite(target, transition);
+                OutputEvent::HideSprite { target, transition, duration } => {
+                    self.animator.handle_hide_sprite(target, transition, duration);
                 },
-                OutputEvent::NewScene { transition } => {
-                    let mut bg_name = None;
-                    if let Some(layer) = ctx.layer_record.layer.get("master") {
-                        if let Some(bg) = layer.first() {
-                            let mut full_name = bg.target.clone();
-                            if !bg.attrs.is_empty() {
-                                full_name.push('_');
-                                full_name.push_str(&bg.attrs.join("_"));
-                            }
-                            bg_name = Some(full_name);
-                        }
-                    }
-                    self.animator.handle_new_scene(bg_name, transition);
+                OutputEvent::NewScene { background, transition, duration } => {
+                    self.animator.handle_new_scene(background, transition, duration);
                 },
                 OutputEvent::Preload { images, audios } => {
                     for img_id in images {
@@ -100,11 +179,11 @@ impl InGameScreen {
                         assets.get_static_audio(&audio_id);
                     }
                 },
-                OutputEvent::SetVolume {channel, value} => {
-                    audio.set_channel_volume(&channel, value);
+                OutputEvent::SetVolume {channel, value, fade} => {
+                    audio.fade_channel_volume(&channel, value, fade);
                 },
-                OutputEvent::ModifyVisual { target, props, duration, easing } => {
-                    self.animator.handle_modify_visual(target, props, duration, easing);
+                OutputEvent::ModifyVisual { target, props, duration, easing, on_done } => {
+                    self.animator.handle_modify_visual(target, props, duration, easing, on_done);
                 },
                 OutputEvent::RegisterLayout { name, config } => {
                     log::info!("Renderer registering layout: {}", name);
@@ -114,13 +193,64 @@ impl InGameScreen {
                     log::info!("Renderer registering transition: {}", name);
                     self.animator.handle_register_transition(name, config);
                 }
+                OutputEvent::Shake { target, intensity, duration } => {
+                    self.animator.handle_shake(target, intensity, duration);
+                }
+                OutputEvent::Flash { color, duration } => {
+                    self.animator.handle_flash(color, duration);
+                }
+                OutputEvent::SetSpriteAnim { target, state, frames, fps, looping } => {
+                    self.animator.handle_set_anim(&target, state, frames, fps, looping);
+                }
                 // --- 流程控制 ---
-                OutputEvent::ShowChoice { title, options } => {
+                OutputEvent::ShowChoice { title, options, timeout, default } => {
                     self.active_choices = Some((title, options));
+                    self.choice_timer = timeout.map(|t| (t, t, default));
+                    self.script_error = None;
                 },
-                OutputEvent::ShowDialogue { .. } | OutputEvent::ShowNarration { .. } => {
+                ev @ (OutputEvent::ShowDialogue { .. } | OutputEvent::ShowNarration { .. }) => {
+                    self.paragraph_gap = match &ev {
+                        OutputEvent::ShowNarration { lines } => lines.iter().take_while(|l| l.is_empty()).count(),
+                        _ => 0,
+                    };
                     // 进入对话时，清空之前的选项
                     self.active_choices = None;
+                    self.choice_timer = None;
+                    self.script_error = None;
+                    if !fresh_warning {
+                        self.script_warning = None;
+                    }
+                },
+                OutputEvent::ScriptError { line, msg } => {
+                    self.script_error = Some(format!("Script error (line {}): {}", line, msg));
+                },
+                OutputEvent::Warning { line, msg } => {
+                    self.script_warning = Some(format!("Warning (line {}): {}", line, msg));
+                    fresh_warning = true;
+                },
+                OutputEvent::SetPresentation { mode } => {
+                    self.presentation = mode;
+                },
+                OutputEvent::PlayMovie { id, skippable } => {
+                    self.start_movie(ctx, id, skippable, assets, audio);
+                },
+                OutputEvent::WaitAudioEnd { channel } => {
+                    self.audio_wait = Some(channel);
+                },
+                OutputEvent::AchievementUnlocked { id } => {
+                    self.achievement_toasts.push((id, ACHIEVEMENT_TOAST_SECS));
+                },
+                OutputEvent::QuickSaveDone { load, ok } => {
+                    let text = match (load, ok) {
+                        (false, true) => "Quick saved",
+                        (false, false) => "Quick save failed",
+                        (true, true) => "Quick loaded",
+                        (true, false) => "No quick save",
+                    };
+                    self.toasts.push(text, QUICK_SAVE_TOAST_SECS);
+                },
+                OutputEvent::Toast { text, duration } => {
+                    self.toasts.push(text, duration);
                 },
                 OutputEvent::End => el.exit(),
 
@@ -128,6 +258,71 @@ impl InGameScreen {
             }
         }
     }
+
+    /// NVL 模式：全屏半透明面板，放下当前页中能容纳的最近几条文本，
+    /// 最后一条由打字机逐字显示
+    fn draw_nvl_page(&self, ui: &mut UiDrawer, rect: Rect, ctx: &Ctx) {
+        if ctx.nvl_page.is_empty() {
+            return;
+        }
+
+        Panel::new()
+            .color(Color::rgba(10, 20, 30, 190))
+            .show(ui, rect);
+
+        let page = rect.shrink(120.0);
+        let last = ctx.nvl_page.len() - 1;
+
+        // (完整文本, 实际显示文本)，完整文本用于测量，避免打字过程中排版跳动
+        let entries: Vec<(String, String)> = ctx.nvl_page.iter().enumerate()
+            .map(|(i, rec)| {
+                // 段落分隔的空行不加引号，只占一行高度
+                if rec.speaker.is_none() && rec.text.is_empty() {
+                    return (String::new(), String::new());
+                }
+                let (prefix, suffix) = text_marks(rec.speaker.is_some());
+                let name = rec.speaker.as_ref().map(|n| format!("【{}】", n)).unwrap_or_default();
+                let full = format!("{}{}{}{}", name, prefix, rec.text, suffix);
+                let shown = if i == last {
+                    format!("{}{}", name, self.typewriter.display_text)
+                } else {
+                    full.clone()
+                };
+                (full, shown)
+            })
+            .collect();
+
+        // 自底向上累计高度，找出本页能放下的第一条
+        let mut heights = Vec::with_capacity(entries.len());
+        let mut used = 0.0;
+        let mut first = entries.len();
+        for (full, _) in entries.iter().rev() {
+            let h = if full.is_empty() {
+                NVL_TEXT_SIZE
+            } else {
+                ui.measure_text(full, page.w, NVL_TEXT_SIZE, Some("dialogue"))
+            };
+            let need = if heights.is_empty() { h } else { h + NVL_ENTRY_GAP };
+            if used + need > page.h && !heights.is_empty() {
+                break;
+            }
+            used += need;
+            heights.push(h);
+            first -= 1;
+        }
+        heights.reverse();
+
+        let mut y = page.y;
+        for ((_, shown), h) in entries[first..].iter().zip(heights) {
+            Label::new(shown)
+                .size(NVL_TEXT_SIZE)
+                .font("dialogue")
+                .color(Color::WHITE)
+                .align(Alignment::Start)
+                .show(ui, Rect::new(page.x, y, page.w, h));
+            y += h + NVL_ENTRY_GAP;
+        }
+    }
 }
 
 impl Screen for InGameScreen {
@@ -140,27 +335,70 @@ impl Screen for InGameScreen {
         audio: &mut AudioPlayer
     ) -> ScreenTransition {
 
-        // 1. 驱动 VM 执行脚本
-        let mut waiting = false;
-        for _ in 0..100 {
-            waiting = self.driver.step(ctx);
-            if waiting { break; }
+        if self.open_backlog {
+            self.open_backlog = false;
+            return ScreenTransition::Push(Box::new(BacklogScreen::new()), None);
         }
 
+        // 1. 驱动 VM 执行脚本
+        self.driver.step_until_wait(ctx, MAX_STEPS_PER_FRAME);
+
         // 2. 处理产生的事件 (音频播放、立绘移动)
         self.process_output_events(ctx, el, assets, audio);
 
         // 3. 更新动画状态
+        if std::mem::take(&mut self.stop_movie_audio) {
+            audio.stop(MOVIE_CHANNEL, 0.3);
+        }
+        if let Some(movie) = &mut self.movie {
+            movie.update(dt);
+            for frame in movie.upcoming(MOVIE_PREFETCH_FRAMES) {
+                assets.get_image(frame);
+            }
+            if let Some(frame) = movie.current_frame() {
+                if assets.get_image(frame).is_some() {
+                    self.movie_frame = Some(frame.to_string());
+                }
+            }
+        }
+        if self.movie.as_ref().is_some_and(|m| m.is_finished()) {
+            self.finish_movie(ctx);
+        }
+        // 直接查询播放器而非 ctx.audio_state，同一帧刚开始播放的声音也能等到
+        if self.audio_wait.as_deref().is_some_and(|ch| !audio.is_channel_playing(ch)) {
+            self.audio_wait = None;
+            self.driver.feed(ctx, InputEvent::Continue);
+        }
+        self.animator.set_speaker(ctx.active_speaker.as_deref());
         self.animator.update(dt);
+        for id in self.animator.take_completed() {
+            self.driver.feed(ctx, InputEvent::AnimationDone { id });
+        }
         self.typewriter.update(dt);
         self.driver.tick(dt);
 
+        // 倒计时只在本界面位于栈顶时推进，打开 Backlog 等界面期间暂停
+        if let Some((remaining, _, default)) = &mut self.choice_timer {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                let index = *default;
+                self.choice_timer = None;
+                self.active_choices = None;
+                self.driver.feed(ctx, InputEvent::ChoiceMade { index });
+            }
+        }
+
+        self.toasts.update(dt);
+
+        if let Some((_, remaining)) = self.achievement_toasts.first_mut() {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                self.achievement_toasts.remove(0);
+            }
+        }
+
         if let Some(last_dialogue) = ctx.dialogue_history.last() {
-            let (prefix, suffix) = if last_dialogue.speaker.is_some() {
-                ("「", "」")
-            } else {
-                ("❀", "❀")
-            };
+            let (prefix, suffix) = text_marks(last_dialogue.speaker.is_some());
             self.typewriter.set_text(prefix, &last_dialogue.text, suffix, " ▼");
         } else {
             // 没对话时清空
@@ -171,6 +409,34 @@ impl Screen for InGameScreen {
     }
 
     fn draw(&mut self, ui: &mut UiDrawer, painter: &mut Painter, rect: Rect, ctx: &mut Ctx) {
+        // 过场动画盖住整个画面，期间点击只用于跳过
+        if let Some(movie) = &self.movie {
+            Panel::new().color(Color::BLACK).show(ui, rect);
+            if let Some(frame) = &self.movie_frame {
+                Image::new(frame).fit(ImageFit::Contain).show(ui, rect);
+            }
+            let skippable = movie.skippable;
+            if ui.interact(rect).is_clicked() {
+                ui.consume_click();
+                if skippable {
+                    self.finish_movie(ctx);
+                }
+            }
+            return;
+        }
+
+        for action in ui.take_actions() {
+            match action {
+                Action::QuickSave => self.driver.quick_save(ctx),
+                Action::QuickLoad => {
+                    self.active_choices = None;
+                    self.choice_timer = None;
+                    self.audio_wait = None;
+                    self.driver.quick_load(ctx);
+                }
+            }
+        }
+
         // ============================
         // 1. 绘制场景 (Layer 0)
         // ============================
@@ -186,7 +452,9 @@ impl Screen for InGameScreen {
         // ============================
         // 3. 绘制对话框 (Layer 1)
         // ============================
-        if let Some(last_dialogue) = ctx.dialogue_history.last() {
+        if self.presentation == PresentationMode::Nvl {
+            self.draw_nvl_page(ui, rect, ctx);
+        } else if let Some(last_dialogue) = ctx.dialogue_history.last() {
             // 背景板
             Panel::new()
                 .gradient(
@@ -208,13 +476,17 @@ impl Screen for InGameScreen {
                 let name_text = format!("【{}】", name);
                 Label::new(&name_text)
                     .size(32.0)
+                    .auto_fit(18.0)
+                    .font("dialogue")
                     .color(Color::rgb(255, 230, 200)) // 米黄色
                     .align(Alignment::Start)
                     .show(ui, name_rect);
             }
 
+            let (_, text_rect) = text_rect.split_top(self.paragraph_gap as f32 * 26.0);
             Label::new(&self.typewriter.display_text)
                 .size(26.0)
+                .font("dialogue")
                 .color(Color::WHITE)
                 .align(Alignment::Start)
                 .show(ui, text_rect.shrink(10.0));
@@ -223,6 +495,78 @@ impl Screen for InGameScreen {
             let icon_y = bottom_area.y + bottom_area.h - 60.0;
 
             ui.draw_circle((icon_x, icon_y), 10.0, Color::rgba(255, 255, 255, 150));
+
+            let log_rect = Rect::new(bottom_area.x + bottom_area.w - 140.0, bottom_area.y + 20.0, 110.0, 44.0);
+            if Button::new("Log").rounded(8.0).show(ui, log_rect) {
+                self.open_backlog = true;
+            }
+        }
+
+        // ============================
+        // 3.4 脚本错误提示 (顶部横幅)
+        // ============================
+        if let Some(err) = &self.script_error {
+            let (banner, _) = rect.split_top(80.0);
+            Panel::new()
+                .color(Color::rgba(140, 20, 20, 220))
+                .show(ui, banner);
+            Label::new(err)
+                .size(24.0)
+                .color(Color::WHITE)
+                .align(Alignment::Start)
+                .show(ui, banner.shrink(20.0));
+        } else if let Some(warning) = &self.script_warning {
+            let (banner, _) = rect.split_top(56.0);
+            Panel::new()
+                .color(Color::rgba(150, 110, 10, 200))
+                .show(ui, banner);
+            Label::new(warning)
+                .size(20.0)
+                .color(Color::WHITE)
+                .align(Alignment::Start)
+                .show(ui, banner.shrink(16.0));
+        }
+
+        // ============================
+        // 3.5 成就提示 (右上角，逐个显示)
+        // ============================
+        if let Some((id, _)) = self.achievement_toasts.first() {
+            let (toast_rect, _) = rect.split_top(140.0);
+            let (toast_rect, _) = toast_rect.split_right(520.0);
+            let toast_rect = toast_rect.shrink(20.0);
+
+            Panel::new()
+                .color(Color::rgba(20, 20, 30, 220))
+                .stroke(Color::rgb(255, 215, 120), 2.0)
+                .rounded(12.0)
+                .show(ui, toast_rect);
+
+            let def = self.achievement_defs.get(id);
+            let (icon_rect, text_rect) = toast_rect.shrink(10.0).split_left(80.0);
+            if let Some(icon) = def.and_then(|d| d.icon.as_deref()) {
+                Image::new(icon).show(ui, icon_rect);
+            }
+
+            let (head_rect, title_rect) = text_rect.split_top(30.0);
+            Label::new("Achievement Unlocked")
+                .size(20.0)
+                .color(Color::rgb(255, 215, 120))
+                .show(ui, head_rect);
+            let title = def.map(|d| d.title.as_str()).unwrap_or(id.as_str());
+            Label::new(title)
+                .size(28.0)
+                .show(ui, title_rect);
+        }
+
+        // 通知提示 (左上角)
+        self.toasts.show(ui, rect);
+
+        // 闪色盖在场景与对话框之上，选项菜单仍可见
+        if let Some(flash) = self.animator.flash {
+            let [r, g, b, a] = flash.color;
+            Panel::new()
+                .color(Color::rgba(r, g, b, (a as f32 * flash.alpha()) as u8))
+                .show(ui, rect);
         }
 
         // ============================
@@ -242,16 +586,34 @@ impl Screen for InGameScreen {
                 Label::new(t).size(36.0).show(ui, header);
             }
 
+            // 限时选择：菜单上方的倒计时条
+            if let Some((remaining, total, _)) = self.choice_timer {
+                let track = Rect::new(menu_area.x, menu_area.y - 24.0, menu_area.w, 8.0);
+                Panel::new()
+                    .color(Color::rgba(255, 255, 255, 60))
+                    .show(ui, track);
+                let ratio = (remaining / total).clamp(0.0, 1.0);
+                Panel::new()
+                    .color(Color::rgb(230, 90, 80))
+                    .show(ui, Rect::new(track.x, track.y, track.w * ratio, track.h));
+            }
+
+            let mut chosen = None;
             for (idx, txt) in options.iter().enumerate() {
                 let (btn, rest) = body.split_top(80.0);
                 body = rest;
 
-                if Button::new(txt).show(ui, btn.shrink(10.0)) {
-                    self.driver.feed(ctx, InputEvent::ChoiceMade { index: idx });
-                    // 点击后清空 active_choices 由 process_output_events 决定
-                    // 但这里为了即时反馈可以先置空，或者等待下一帧更新
+                if Button::new(txt).auto_fit(14.0).show(ui, btn.shrink(10.0)) && chosen.is_none() {
+                    chosen = Some(idx);
                 }
             }
+
+            if let Some(index) = chosen {
+                self.driver.feed(ctx, InputEvent::ChoiceMade { index });
+                // 立即收起菜单，避免双击在下一句出现前再次选择
+                self.active_choices = None;
+                self.choice_timer = None;
+            }
             // 选项模式下，阻断后续点击
             return;
         }
@@ -259,8 +621,12 @@ impl Screen for InGameScreen {
         // ============================
         // 5. 点击继续逻辑 (Invisible Layer)
         // ============================
-        // 只有当鼠标点击了整个区域，且没有被上面的 Button 拦截时，才触发
+        // 只有当本帧点击没有被上面的控件消费时才触发
+        if self.open_backlog || self.audio_wait.is_some() {
+            return;
+        }
         if ui.interact(rect).is_clicked() {
+            ui.consume_click();
             if self.animator.is_busy() {
                 self.animator.finish_all_animations();
                 // 这里 return，消耗掉这次点击，不发 Continue
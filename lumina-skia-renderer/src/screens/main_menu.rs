@@ -5,28 +5,76 @@ use lumina_core::manager::ScriptManager;
 use super::{Screen, ScreenTransition};
 use crate::screens::ingame::InGameScreen;
 use crate::screens::settings::SettingsScreen;
+use crate::screens::achievements::AchievementsScreen;
 
+use crate::config::{MainMenuConfig, ParticleConfig, ScreenFx};
 use crate::ui::UiDrawer;
-use crate::core::{AssetManager, Painter, AudioPlayer};
+use crate::core::{AssetManager, Painter, AudioPlayer, ParticleField};
 use lumina_core::Ctx;
+use lumina_core::config::SystemConfig;
 use lumina_core::renderer::driver::ExecutorHandle;
+use lumina_core::storager;
 
-use lumina_ui::{Rect, Color, GradientDirection, Alignment, Transform, UiRenderer};
+use lumina_ui::{Rect, Color, GradientDirection, Alignment, ImageFit, Transform, UiRenderer};
 use lumina_ui::widgets::{Button, Label, Panel};
 
+/// 主菜单的动态背景层，按加入顺序绘制在背景图之上、菜单之下。
+/// 粒子之外的效果 (如 Live2D、循环视频) 实现该接口后通过 `MainMenuScreen::layer` 加入
+pub trait MenuLayer {
+    /// `time` 为 `UiRenderer::time()`，各层自行计算帧间隔
+    fn draw(&mut self, ui: &mut UiDrawer, rect: Rect, time: f32);
+}
+
+struct ParticleLayer {
+    field: Option<ParticleField>,
+    cfg: ParticleConfig,
+    last_time: Option<f32>,
+}
+
+impl MenuLayer for ParticleLayer {
+    fn draw(&mut self, ui: &mut UiDrawer, rect: Rect, time: f32) {
+        let field = self.field.get_or_insert_with(|| ParticleField::new(self.cfg.clone(), rect.w, rect.h));
+        field.resize(rect.w, rect.h);
+        let dt = self.last_time.map_or(0.0, |t| (time - t).clamp(0.0, 0.1));
+        self.last_time = Some(time);
+        // 减少动态效果时粒子静止
+        if !crate::config::reduce_motion() {
+            field.update(dt);
+        }
+        field.draw(ui, rect);
+    }
+}
+
 pub struct MainMenuScreen {
     manager: Arc<ScriptManager>,
     // 暂存这一帧 UI 点击产生的跳转指令
     pending_transition: ScreenTransition,
+    background: Option<String>,
+    layers: Vec<Box<dyn MenuLayer>>,
+    /// “继续游戏”读取的存档，没有存档时不显示该按钮
+    continue_save: Option<String>,
 }
 
 impl MainMenuScreen {
     pub fn new(manager: Arc<ScriptManager>) -> Self {
+        let cfg: MainMenuConfig = lumina_shared::config::get("main_menu");
+        let mut layers: Vec<Box<dyn MenuLayer>> = Vec::new();
+        if let Some(particles) = cfg.particles {
+            layers.push(Box::new(ParticleLayer { field: None, cfg: particles, last_time: None }));
+        }
         Self {
             manager,
             pending_transition: ScreenTransition::None,
+            background: cfg.background,
+            layers,
+            continue_save: storager::continue_save(),
         }
     }
+
+    pub fn layer(mut self, layer: Box<dyn MenuLayer>) -> Self {
+        self.layers.push(layer);
+        self
+    }
 }
 
 impl Screen for MainMenuScreen {
@@ -51,13 +99,21 @@ impl Screen for MainMenuScreen {
         ctx: &mut Ctx
     ) {
         // 1. 绘制背景
-        Panel::new()
-            .gradient(
-                GradientDirection::Vertical,
-                Color::rgb(20, 20, 30), // 深蓝黑
-                Color::rgb(40, 30, 60)  // 紫黑
-            )
-            .show(ui, rect);
+        match &self.background {
+            Some(bg) => ui.draw_image_fit(bg, rect, Color::WHITE, ImageFit::Cover),
+            None => Panel::new()
+                .gradient(
+                    GradientDirection::Vertical,
+                    Color::rgb(20, 20, 30), // 深蓝黑
+                    Color::rgb(40, 30, 60)  // 紫黑
+                )
+                .show(ui, rect),
+        }
+
+        let time = ui.time();
+        for layer in &mut self.layers {
+            layer.draw(ui, rect, time);
+        }
 
         let menu_area = rect.center(400.0, 600.0);
         let (title_area, content) = menu_area.split_top(200.0);
@@ -66,18 +122,29 @@ impl Screen for MainMenuScreen {
             .size(60.0)
             .color(Color::WHITE)
             .align(Alignment::Center)
-            .font("comforter")
+            .font("title")
             .show(ui, title_area);
 
         // 按钮区域布局
+        let (btn_continue, content) = if self.continue_save.is_some() {
+            let (btn, rest) = content.split_top(80.0);
+            (Some(btn), rest)
+        } else {
+            (None, content)
+        };
         let (btn_start, rest) = content.split_top(80.0);
         let (btn_settings, rest) = rest.split_top(80.0);
+        let (btn_achievements, rest) = rest.split_top(80.0);
         let (btn_quit, _) = rest.split_top(80.0);
 
         let time = ui.time;
 
-        let scale = 1.0 + (time * 3.0).sin() * 0.05;
-        let rotation = (time * 2.0).sin() * 2.0;
+        // 减少动态效果时按钮保持静止
+        let (scale, rotation) = if crate::config::reduce_motion() {
+            (1.0, 0.0)
+        } else {
+            (1.0 + (time * 3.0).sin() * 0.05, (time * 2.0).sin() * 2.0)
+        };
 
         let start_rect = btn_start.shrink(10.0);
         let center_x = start_rect.x + start_rect.w / 2.0;
@@ -114,17 +181,44 @@ impl Screen for MainMenuScreen {
 
         if start_clicked {
             *ctx = Ctx::default();
-            let driver = ExecutorHandle::new(ctx, self.manager.clone());
+            let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+            let driver = ExecutorHandle::new(ctx, self.manager.clone(), &sys_cfg.start_label);
             self.pending_transition = ScreenTransition::Replace(
-                Box::new(InGameScreen::new(driver))
+                Box::new(InGameScreen::new(driver)),
+                Some(ScreenFx::fade_black(0.8)),
             );
         }
 
+        if let (Some(btn), Some(save)) = (btn_continue, &self.continue_save) {
+            if Button::new("Continue")
+                .rounded(8.0)
+                .show(ui, btn.shrink(10.0))
+            {
+                *ctx = Ctx::default();
+                let sys_cfg: SystemConfig = lumina_shared::config::get("system");
+                let mut driver = ExecutorHandle::new(ctx, self.manager.clone(), &sys_cfg.start_label);
+                if !driver.continue_from(ctx, save) {
+                    log::warn!("Continue failed, starting a new game");
+                }
+                self.pending_transition = ScreenTransition::Replace(
+                    Box::new(InGameScreen::new(driver)),
+                    Some(ScreenFx::fade_black(0.8)),
+                );
+            }
+        }
+
         if Button::new("Settings")
             .rounded(8.0)
             .show(ui, btn_settings.shrink(10.0))
         {
-            self.pending_transition = ScreenTransition::Push(Box::new(SettingsScreen::new()));
+            self.pending_transition = ScreenTransition::Push(Box::new(SettingsScreen::new()), None);
+        }
+
+        if Button::new("Achievements")
+            .rounded(8.0)
+            .show(ui, btn_achievements.shrink(10.0))
+        {
+            self.pending_transition = ScreenTransition::Push(Box::new(AchievementsScreen::new()), None);
         }
 
         if Button::new("Quit")
@@ -1,20 +1,24 @@
 pub mod main_menu;
 pub(crate) mod ingame;
 pub mod settings;
+pub mod achievements;
+pub mod backlog;
+pub(crate) mod debug_overlay;
 
 use crate::ui::UiDrawer;
 use crate::core::{AssetManager, AudioPlayer, Painter};
+pub use crate::config::{ScreenFx, ScreenFxKind};
 use lumina_core::Ctx;
 use lumina_ui::Rect;
 use winit::event_loop::ActiveEventLoop;
 
-/// 屏幕切换指令
+/// 屏幕切换指令，`Option<ScreenFx>` 为切换效果，`None` 使用 `WindowConfig` 中的默认值
 pub enum ScreenTransition {
     None,
-    Push(Box<dyn Screen>),      // 打开新页面 (如设置)
-    Pop,                        // 关闭当前页 (如关闭设置)
-    Replace(Box<dyn Screen>),   // 彻底切换 (如 主菜单 -> 游戏)
-    Quit,                       // 退出程序
+    Push(Box<dyn Screen>, Option<ScreenFx>),      // 打开新页面 (如设置)
+    Pop(Option<ScreenFx>),                        // 关闭当前页 (如关闭设置)
+    Replace(Box<dyn Screen>, Option<ScreenFx>),   // 彻底切换 (如 主菜单 -> 游戏)
+    Quit,                                         // 退出程序
 }
 
 /// 所有界面必须实现的 Trait
@@ -2,16 +2,27 @@ use crate::ui::UiDrawer;
 use crate::core::{AssetManager, Painter, AudioPlayer};
 use crate::screens::{Screen, ScreenTransition};
 use lumina_core::Ctx;
+use lumina_core::storager::{self, types::Preferences};
 use lumina_ui::{Rect, Color, Alignment, Style, Background, Border, GradientDirection};
 use lumina_ui::widgets::{Button, Label, Panel, Slider, Checkbox};
 use winit::event_loop::ActiveEventLoop;
 
+/// 音量滑块对应的音频通道
+const VOLUME_CHANNELS: [&str; 2] = ["music", "sound"];
+/// 文字速度滑块的范围 (字/秒)，拉到最右为瞬间显示
+const TEXT_SPEED_MIN: f32 = 10.0;
+const TEXT_SPEED_MAX: f32 = 120.0;
+
 pub struct SettingsScreen {
-    // 模拟的设置状态
-    bgm_volume: f32,
-    se_volume: f32,
+    /// 编辑中的偏好，关闭时写回 preferences.json
+    prefs: Preferences,
+    volumes: [f32; 2],
+    text_speed: f32,
     fullscreen: bool,
+    reduce_motion: bool,
     auto_mode: bool,
+    // tl/ 目录下可选的语言
+    languages: Vec<String>,
 
     // 退出标识
     should_close: bool,
@@ -19,11 +30,18 @@ pub struct SettingsScreen {
 
 impl SettingsScreen {
     pub fn new() -> Self {
+        let prefs = storager::load_preferences();
         Self {
-            bgm_volume: 0.5,
-            se_volume: 0.8,
-            fullscreen: false,
+            volumes: VOLUME_CHANNELS.map(|ch| prefs.volume(ch)),
+            text_speed: match prefs.text_speed() {
+                s if s <= 0.0 => TEXT_SPEED_MAX,
+                s => s.clamp(TEXT_SPEED_MIN, TEXT_SPEED_MAX),
+            },
+            fullscreen: prefs.fullscreen,
+            reduce_motion: prefs.reduce_motion(),
+            prefs,
             auto_mode: true,
+            languages: lumina_core::i18n::available_languages(),
             should_close: false,
         }
     }
@@ -36,10 +54,30 @@ impl Screen for SettingsScreen {
         _ctx: &mut Ctx,
         _el: &ActiveEventLoop,
         _assets: &mut AssetManager,
-        _audio: &mut AudioPlayer
+        audio: &mut AudioPlayer
     ) -> ScreenTransition {
+        // 拖动滑块时即时生效
+        for (channel, volume) in VOLUME_CHANNELS.iter().zip(self.volumes) {
+            if self.prefs.volume(channel) != volume {
+                audio.set_channel_volume(channel, volume);
+                self.prefs.volumes.insert(channel.to_string(), volume);
+            }
+        }
+
+        if crate::config::reduce_motion() != self.reduce_motion {
+            crate::config::set_reduce_motion(self.reduce_motion);
+            self.prefs.reduce_motion = Some(self.reduce_motion);
+        }
+
         if self.should_close {
-            return ScreenTransition::Pop; // 返回上一层 (主菜单)
+            // 语言由 i18n::set_language 单独保存，这里以文件中的值为准
+            self.prefs.language = storager::load_preferences().language;
+            self.prefs.fullscreen = self.fullscreen;
+            self.prefs.text_speed = Some(if self.text_speed >= TEXT_SPEED_MAX { 0.0 } else { self.text_speed });
+            if let Err(e) = storager::save_preferences(&self.prefs) {
+                log::error!("Failed to save preferences: {}", e);
+            }
+            return ScreenTransition::Pop(None); // 返回上一层 (主菜单)
         }
         ScreenTransition::None
     }
@@ -51,7 +89,7 @@ impl Screen for SettingsScreen {
             .show(ui, rect);
 
         // 2. 居中设置面板
-        let panel_rect = rect.center(600.0, 500.0);
+        let panel_rect = rect.center(600.0, 700.0);
 
         // 面板背景：深灰 -> 黑色垂直渐变，带边框和圆角
         Panel::new()
@@ -77,15 +115,18 @@ impl Screen for SettingsScreen {
         // 分割各项 (每一行高 80px)
         let (row_bgm, rest) = body.split_top(80.0);
         let (row_se, rest) = rest.split_top(80.0);
+        let (row_speed, rest) = rest.split_top(80.0);
         let (row_check1, rest) = rest.split_top(60.0);
         let (row_check2, rest) = rest.split_top(60.0);
+        let (row_motion, rest) = rest.split_top(60.0);
+        let (row_lang, rest) = rest.split_top(60.0);
         let (row_btn, _) = rest.split_bottom(60.0); // 底部放按钮
 
         // --- 示例 1: 标准 Slider (BGM) ---
         let (label_rect, slider_rect) = row_bgm.shrink(10.0).split_left(150.0);
         Label::new("BGM Volume").align(Alignment::Start).show(ui, label_rect);
 
-        Slider::new(&mut self.bgm_volume, 0.0, 1.0)
+        Slider::new(&mut self.volumes[0], 0.0, 1.0)
             .show(ui, slider_rect); // 使用默认样式
 
         // --- 示例 2: 高度自定义 Slider (SE) ---
@@ -106,12 +147,24 @@ impl Screen for SettingsScreen {
         custom_knob.background = Background::Solid(Color::RED);
         custom_knob.border = Border { color: Color::WHITE, width: 2.0, radius: 2.0 };
 
-        Slider::new(&mut self.se_volume, 0.0, 1.0)
+        Slider::new(&mut self.volumes[1], 0.0, 1.0)
             .style_track(custom_track)
             .style_knob(custom_knob, 24.0) // 24px 大小的滑块
             .show(ui, slider_rect);
 
+        // --- 文字速度 (最右为瞬间显示) ---
+        let (label_rect, slider_rect) = row_speed.shrink(10.0).split_left(150.0);
+        let speed_text = if self.text_speed >= TEXT_SPEED_MAX {
+            "Text: Instant".to_string()
+        } else {
+            format!("Text: {:.0}/s", self.text_speed)
+        };
+        Label::new(&speed_text).align(Alignment::Start).show(ui, label_rect);
+        Slider::new(&mut self.text_speed, TEXT_SPEED_MIN, TEXT_SPEED_MAX)
+            .show(ui, slider_rect);
+
         // --- 示例 3: 标准 Checkbox ---
+        // 下次启动时生效
         Checkbox::new(&mut self.fullscreen, "Fullscreen Mode")
             .show(ui, row_check1.shrink(10.0));
 
@@ -130,6 +183,21 @@ impl Screen for SettingsScreen {
             // .font("pixel") // 如果你有自定义字体
             .show(ui, row_check2.shrink(10.0));
 
+        // 即时生效
+        Checkbox::new(&mut self.reduce_motion, "Reduce Motion")
+            .show(ui, row_motion.shrink(10.0));
+
+        // --- 语言 (点击切换到下一种，选择会持久化) ---
+        let (label_rect, btn_rect) = row_lang.shrink(10.0).split_left(150.0);
+        Label::new("Language").align(Alignment::Start).show(ui, label_rect);
+
+        let current = lumina_core::i18n::current_language();
+        if Button::new(&current).show(ui, btn_rect.split_left(160.0).0) && self.languages.len() > 1 {
+            let idx = self.languages.iter().position(|l| *l == current).unwrap_or(0);
+            let next = &self.languages[(idx + 1) % self.languages.len()];
+            lumina_core::i18n::set_language(next);
+        }
+
         // --- 关闭按钮 ---
         if Button::new("Close")
             // 自定义常态
@@ -1,15 +1,19 @@
 use std::collections::HashMap;
-use lumina_ui::input::{Interaction, UiContext};
-use lumina_ui::{Alignment, Color, Rect, Style, UiRenderer, Background, Transform, ShaderSpec};
+use lumina_ui::input::{Action, Interaction, TextEvent, UiContext};
+use lumina_ui::{Alignment, Color, Rect, Style, UiRenderer, Background, ImageFit, Transform, ShaderSpec, TextEffects};
 use lumina_ui::types::GradientDirection;
-use skia_safe::textlayout::{FontCollection, ParagraphBuilder, ParagraphStyle, TextAlign, TextStyle};
-use skia_safe::{Canvas, Paint, Point, RRect, Rect as SkRect, gradient_shader::linear, TileMode, RuntimeEffect, Data, SamplingOptions, Matrix, runtime_effect::ChildPtr, shaders};
-use crate::core::AssetManager;
+use skia_safe::textlayout::{FontCollection, TextAlign, TextShadow as SkTextShadow, TextStyle};
+use skia_safe::{Canvas, Paint, PaintJoin, PaintStyle, Point, RRect, Rect as SkRect, gradient_shader::linear, TileMode, RuntimeEffect, Data, SamplingOptions, Matrix, runtime_effect::ChildPtr, shaders, canvas::SrcRectConstraint};
+use crate::core::{AssetManager, FontRoles};
+use crate::core::fonts::DEFAULT_ROLE;
+
+pub mod rich_text;
 
 pub struct UiDrawer<'a> {
     pub(crate) canvas: &'a Canvas,
     input: &'a UiContext,
     fonts: &'a FontCollection,
+    font_roles: &'a FontRoles,
     pub assets: &'a mut AssetManager,
     pub time: f32,
     shaders: &'a HashMap<String, RuntimeEffect>,
@@ -21,11 +25,26 @@ impl<'a> UiDrawer<'a> {
         canvas: &'a Canvas,
         input: &'a UiContext,
         fonts: &'a FontCollection,
+        font_roles: &'a FontRoles,
         assets: &'a mut AssetManager,
         time: f32,
         shaders: &'a HashMap<String, RuntimeEffect>,
     ) -> Self {
-        Self { canvas, input, fonts, assets, time , transform_stack: Vec::new(),shaders}
+        Self { canvas, input, fonts, font_roles, assets, time , transform_stack: Vec::new(),shaders}
+    }
+
+    fn text_style(&self, color: Color, size: f32, font: Option<&str>) -> TextStyle {
+        let mut ts = TextStyle::new();
+        ts.set_color(self.to_skia_color(color));
+        ts.set_font_size(size);
+        // font 为逻辑字体角色，按配置解析为回退链；未知角色按字体族名处理
+        let role = font.unwrap_or(DEFAULT_ROLE);
+        match self.font_roles.families(role) {
+            Some(families) if !families.is_empty() => { ts.set_font_families(families); },
+            Some(_) => {},
+            None => { ts.set_font_families(&[role]); },
+        }
+        ts
     }
 
     fn to_skia_rect(&self, r: Rect) -> SkRect {
@@ -37,7 +56,11 @@ impl<'a> UiDrawer<'a> {
     }
 
     fn get_local_mouse_pos(&self) -> (f32, f32) {
-        let (mut mx, mut my) = self.input.mouse_pos;
+        self.to_local(self.input.mouse_pos)
+    }
+
+    /// 把逻辑坐标换算到当前变换栈下的局部坐标
+    fn to_local(&self, (mut mx, mut my): (f32, f32)) -> (f32, f32) {
 
         for t in &self.transform_stack {
             // 1. 逆平移
@@ -117,11 +140,11 @@ impl <'a> UiRenderer for UiDrawer<'a> {
                 paint.set_anti_alias(true);
                 self.canvas.draw_rrect(rrect, &paint);
             }
-            Background::Image(id) => {
+            Background::Image(id, fit) => {
                 self.canvas.save();
                 self.canvas.clip_rrect(rrect, None, true);
 
-                self.draw_image(id, rect, Color::WHITE);
+                self.draw_image_fit(id, rect, Color::WHITE, *fit);
 
                 self.canvas.restore();
             }
@@ -140,49 +163,109 @@ impl <'a> UiRenderer for UiDrawer<'a> {
     }
 
     fn draw_image(&mut self, image_id: &str, rect: Rect, tint: Color) {
+        self.draw_image_fit(image_id, rect, tint, ImageFit::Fill);
+    }
+
+    fn draw_image_fit(&mut self, image_id: &str, rect: Rect, tint: Color, fit: ImageFit) {
         if let Some(sk_image) = self.assets.get_image(image_id) {
-            let sk_rect = self.to_skia_rect(rect);
             let mut paint = Paint::default();
             paint.set_color(self.to_skia_color(tint));
+            // 图片只受 paint 的透明度影响，颜色部分用滤镜相乘 (如变暗的立绘)
+            if (tint.r, tint.g, tint.b) != (255, 255, 255) {
+                let rgb = skia_safe::Color::from_rgb(tint.r, tint.g, tint.b);
+                paint.set_color_filter(skia_safe::color_filters::blend(rgb, skia_safe::BlendMode::Modulate));
+            }
 
             paint.set_anti_alias(true);
 
-            self.canvas.draw_image_rect(sk_image, None, sk_rect, &paint);
+            if fit == ImageFit::Tile {
+                // 以目标矩形左上角为平铺原点
+                let matrix = Matrix::translate((rect.x, rect.y));
+                if let Some(shader) = sk_image.to_shader((TileMode::Repeat, TileMode::Repeat), SamplingOptions::default(), &matrix) {
+                    paint.set_shader(shader);
+                    self.canvas.draw_rect(self.to_skia_rect(rect), &paint);
+                }
+                return;
+            }
+
+            let (src, dst) = fit.rects(sk_image.width() as f32, sk_image.height() as f32, rect);
+            let src = self.to_skia_rect(src);
+            let dst = self.to_skia_rect(dst);
+            self.canvas.draw_image_rect(sk_image, Some((&src, SrcRectConstraint::Fast)), dst, &paint);
         }
     }
 
     fn draw_text(&mut self, text: &str, rect: Rect, color: Color, size: f32, align: Alignment, font: Option<&str>) {
-        let mut ts = TextStyle::new();
-        ts.set_color(self.to_skia_color(color));
-        ts.set_font_size(size);
-        if let Some(font_name) = font {
-            ts.set_font_families(&[font_name]);
-        }
+        self.draw_text_effects(text, rect, color, size, align, font, &TextEffects::default());
+    }
 
-        let mut ps = ParagraphStyle::new();
-        ps.set_text_style(&ts);
+    #[allow(clippy::too_many_arguments)]
+    fn draw_text_effects(
+        &mut self,
+        text: &str,
+        rect: Rect,
+        color: Color,
+        size: f32,
+        align: Alignment,
+        font: Option<&str>,
+        effects: &TextEffects,
+    ) {
+        let mut ts = self.text_style(color, size, font);
 
         let skia_align = match align {
             Alignment::Start => TextAlign::Left,
             Alignment::Center => TextAlign::Center,
             Alignment::End => TextAlign::Right,
         };
-        ps.set_text_align(skia_align);
 
-        let mut builder = ParagraphBuilder::new(&ps, self.fonts);
-        builder.push_style(&ts);
-        builder.add_text(text);
+        // 投影只加在最先绘制的那一遍上，避免描边和填充各投一次
+        let shadow = effects.shadow.map(|s| {
+            SkTextShadow::new(self.to_skia_color(s.color), Point::new(s.offset.0, s.offset.1), s.blur as f64)
+        });
+
+        // 两遍绘制：先用 stroke paint 画轮廓，再正常填充
+        let outline = effects.outline.map(|(outline_color, width)| {
+            let mut stroke_ts = ts.clone();
+            let mut paint = Paint::default();
+            paint.set_color(self.to_skia_color(outline_color));
+            paint.set_anti_alias(true);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_stroke_width(width * 2.0);
+            paint.set_stroke_join(PaintJoin::Round);
+            stroke_ts.set_foreground_paint(&paint);
+            if let Some(s) = shadow {
+                stroke_ts.add_shadow(s);
+            }
+            rich_text::layout(self.fonts, text, &stroke_ts, skia_align, rect.w)
+        });
+        if outline.is_none() {
+            if let Some(s) = shadow {
+                ts.add_shadow(s);
+            }
+        }
 
-        let mut paragraph = builder.build();
-        paragraph.layout(rect.w);
+        let paragraph = rich_text::layout(self.fonts, text, &ts, skia_align, rect.w);
 
         // 垂直居中计算
         let text_height = paragraph.height();
         let y = rect.y + (rect.h - text_height) / 2.0;
 
+        if let Some(outline) = outline {
+            outline.paint(self.canvas, Point::new(rect.x, y));
+        }
         paragraph.paint(self.canvas, Point::new(rect.x, y));
     }
 
+    fn measure_text(&mut self, text: &str, max_width: f32, size: f32, font: Option<&str>) -> f32 {
+        let ts = self.text_style(Color::WHITE, size, font);
+        rich_text::layout(self.fonts, text, &ts, TextAlign::Left, max_width).height()
+    }
+
+    fn measure_text_width(&mut self, text: &str, size: f32, font: Option<&str>) -> f32 {
+        let ts = self.text_style(Color::WHITE, size, font);
+        rich_text::layout(self.fonts, text, &ts, TextAlign::Left, f32::MAX).max_intrinsic_width()
+    }
+
     fn draw_circle(&mut self, center: (f32, f32), radius: f32, color: Color) {
         let mut paint = Paint::default();
         paint.set_color(self.to_skia_color(color));
@@ -191,26 +274,42 @@ impl <'a> UiRenderer for UiDrawer<'a> {
     }
 
     fn interact(&self, rect: Rect) -> Interaction {
-        let (mx, my) = self.get_local_mouse_pos();
-        let hovered = rect.contains(mx, my);
-
-        if hovered {
-            if self.input.mouse_pressed {
-                return Interaction::Clicked;
-            }
-            if self.input.mouse_held {
-                return Interaction::Held;
-            }
-            return Interaction::Hovered;
-        }
-
-        Interaction::None
+        let origin = self.input.press_origin().map(|p| self.to_local(p));
+        self.input.interact_at(rect, self.get_local_mouse_pos(), origin)
     }
 
     fn cursor_pos(&self) -> (f32, f32) {
         self.input.mouse_pos
     }
 
+    fn hover_time(&self, rect: Rect) -> f32 {
+        self.input.hover_time_at(rect, self.get_local_mouse_pos(), self.time)
+    }
+
+    fn consume_click(&mut self) {
+        self.input.consume_click();
+    }
+
+    fn mouse_pressed(&self) -> bool {
+        self.input.mouse_pressed && !self.input.is_click_consumed()
+    }
+
+    fn take_text_events(&mut self) -> Vec<TextEvent> {
+        self.input.take_text_events()
+    }
+
+    fn take_actions(&mut self) -> Vec<Action> {
+        self.input.take_actions()
+    }
+
+    fn ime_preedit(&self) -> Option<String> {
+        self.input.preedit().map(str::to_string)
+    }
+
+    fn request_ime(&mut self, rect: Rect) {
+        self.input.request_ime(rect);
+    }
+
     fn with_transform(&mut self, t: Transform, f: &mut dyn FnMut(&mut Self)) {
         self.canvas.save();
         self.canvas.translate((t.x, t.y));
@@ -0,0 +1,139 @@
+use std::ops::Range;
+use lumina_core::markup::{self, TextRun};
+use skia_safe::textlayout::{FontCollection, Paragraph, ParagraphBuilder, ParagraphStyle, RectHeightStyle, RectWidthStyle, TextAlign, TextStyle};
+use skia_safe::{Canvas, Point, Rect as SkRect};
+
+/// 注音字号相对正文字号的比例
+pub const RUBY_SCALE: f32 = 0.5;
+
+/// 插在注音底字之间，防止底字被折行拆开
+const WORD_JOINER: char = '\u{2060}';
+
+pub struct RubyPlacement {
+    /// 底字在排版文本中的 UTF-16 区间
+    pub base_range: Range<usize>,
+    /// 注音的绘制区域 (相对段落左上角，已计入 `top_inset`)
+    pub rect: SkRect,
+    paragraph: Paragraph,
+}
+
+/// 解析过标记并完成排版的段落
+pub struct RichParagraph {
+    pub paragraph: Paragraph,
+    pub rubies: Vec<RubyPlacement>,
+    /// 首行注音超出段落顶部时整体下移的距离
+    pub top_inset: f32,
+}
+
+impl RichParagraph {
+    pub fn height(&self) -> f32 {
+        self.paragraph.height() + self.top_inset
+    }
+
+    /// 不折行时最长一行的宽度
+    pub fn max_intrinsic_width(&self) -> f32 {
+        self.paragraph.max_intrinsic_width()
+    }
+
+    pub fn paint(&self, canvas: &Canvas, origin: Point) {
+        self.paragraph.paint(canvas, Point::new(origin.x, origin.y + self.top_inset));
+        for ruby in &self.rubies {
+            ruby.paragraph.paint(canvas, Point::new(origin.x + ruby.rect.left, origin.y + ruby.rect.top));
+        }
+    }
+}
+
+fn build_paragraph(fonts: &FontCollection, text: &str, ts: &TextStyle, align: TextAlign, width: f32) -> Paragraph {
+    let mut ps = ParagraphStyle::new();
+    ps.set_text_style(ts);
+    ps.set_text_align(align);
+
+    let mut builder = ParagraphBuilder::new(&ps, fonts);
+    builder.push_style(ts);
+    builder.add_text(text);
+
+    let mut paragraph = builder.build();
+    paragraph.layout(width);
+    paragraph
+}
+
+/// 排版带标记的文本：底字按普通文本参与排版，注音以较小字号居中绘制在底字上方，
+/// 含注音时加大行高，避免注音与上一行重叠或被裁切
+pub fn layout(fonts: &FontCollection, text: &str, ts: &TextStyle, align: TextAlign, width: f32) -> RichParagraph {
+    let runs = markup::parse(text);
+    let has_ruby = runs.iter().any(|r| matches!(r, TextRun::Ruby { .. }));
+
+    if !has_ruby {
+        return RichParagraph {
+            paragraph: build_paragraph(fonts, text, ts, align, width),
+            rubies: Vec::new(),
+            top_inset: 0.0,
+        };
+    }
+
+    let mut plain = String::new();
+    let mut utf16_len = 0;
+    let mut pending = Vec::new();
+    for run in runs {
+        match run {
+            TextRun::Plain(s) => {
+                utf16_len += s.encode_utf16().count();
+                plain.push_str(&s);
+            }
+            TextRun::Ruby { base, annotation } => {
+                let mut joined = String::new();
+                for (i, c) in base.chars().enumerate() {
+                    if i > 0 { joined.push(WORD_JOINER); }
+                    joined.push(c);
+                }
+                let start = utf16_len;
+                utf16_len += joined.encode_utf16().count();
+                plain.push_str(&joined);
+                pending.push((start..utf16_len, annotation));
+            }
+        }
+    }
+
+    let font_size = ts.font_size();
+    let ruby_size = font_size * RUBY_SCALE;
+
+    let mut line_ts = ts.clone();
+    line_ts.set_height_override(true);
+    line_ts.set_height(1.2 + RUBY_SCALE);
+    let paragraph = build_paragraph(fonts, &plain, &line_ts, align, width);
+
+    let mut ruby_ts = ts.clone();
+    ruby_ts.set_font_size(ruby_size);
+
+    let mut rubies = Vec::new();
+    let mut min_top = 0.0f32;
+    for (range, annotation) in pending {
+        let boxes = paragraph.get_rects_for_range(range.clone(), RectHeightStyle::Tight, RectWidthStyle::Tight);
+        let Some(first) = boxes.first() else { continue };
+
+        // 底字只在同一行上，取同行所有字形框的并集
+        let base = boxes.iter()
+            .filter(|b| (b.rect.top - first.rect.top).abs() < 0.5)
+            .fold(first.rect, |acc, b| SkRect::new(acc.left.min(b.rect.left), acc.top, acc.right.max(b.rect.right), acc.bottom));
+
+        let ann = build_paragraph(fonts, &annotation, &ruby_ts, TextAlign::Left, f32::MAX);
+        let w = ann.max_intrinsic_width();
+        let h = ann.height();
+        let left = base.center_x() - w / 2.0;
+        let top = base.top - h;
+        min_top = min_top.min(top);
+
+        rubies.push(RubyPlacement {
+            base_range: range,
+            rect: SkRect::from_xywh(left, top, w, h),
+            paragraph: ann,
+        });
+    }
+
+    let top_inset = -min_top;
+    for ruby in &mut rubies {
+        ruby.rect.offset((0.0, top_inset));
+    }
+
+    RichParagraph { paragraph, rubies, top_inset }
+}
@@ -0,0 +1,32 @@
+use lumina_skia_renderer::core::source::{write_pak, AssetSource, DirSource, PakSource};
+
+#[test]
+fn pak_matches_directory() {
+    let root = std::env::temp_dir().join("lumina_test_pak");
+    let _ = std::fs::remove_dir_all(&root);
+    let dir = root.join("assets");
+    std::fs::create_dir_all(dir.join("bg")).unwrap();
+    std::fs::write(dir.join("bg/room.png"), b"png bytes").unwrap();
+    std::fs::write(dir.join("click.ogg"), b"").unwrap();
+
+    let pak = root.join("assets.pak");
+    assert_eq!(write_pak(&dir, &pak).unwrap(), 2);
+
+    let dir_source = DirSource::new(&dir);
+    let pak_source = PakSource::open(&pak).unwrap();
+
+    let mut listed = pak_source.list();
+    listed.sort();
+    let mut expected = dir_source.list();
+    expected.sort();
+    assert_eq!(listed, expected);
+    assert_eq!(listed, ["bg/room.png", "click.ogg"]);
+
+    for path in &listed {
+        assert_eq!(pak_source.read(path).unwrap(), dir_source.read(path).unwrap());
+    }
+    assert!(pak_source.read("missing.png").is_err());
+    assert!(pak_source.file_path("bg/room.png").is_none());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
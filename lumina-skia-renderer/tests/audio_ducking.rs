@@ -0,0 +1,31 @@
+use std::collections::HashSet;
+use lumina_skia_renderer::core::{DuckChange, Ducker};
+
+#[test]
+fn duck_holds_until_last_voice_ends() {
+    let mut ducker = Ducker::default();
+    let mut playing: HashSet<&str> = HashSet::from(["voice", "voice2"]);
+
+    assert_eq!(ducker.voice_started("voice"), Some(DuckChange::Duck));
+    assert_eq!(ducker.voice_started("voice2"), None);
+    assert_eq!(ducker.poll(|ch| playing.contains(ch)), None);
+
+    // 一条播完，另一条仍在播放
+    playing.remove("voice");
+    assert_eq!(ducker.poll(|ch| playing.contains(ch)), None);
+    assert!(ducker.is_ducked());
+
+    playing.remove("voice2");
+    assert_eq!(ducker.poll(|ch| playing.contains(ch)), Some(DuckChange::Restore));
+    assert!(!ducker.is_ducked());
+    assert_eq!(ducker.poll(|_| false), None);
+}
+
+#[test]
+fn stopping_voice_restores_music() {
+    let mut ducker = Ducker::default();
+    assert_eq!(ducker.voice_started("voice"), Some(DuckChange::Duck));
+    assert_eq!(ducker.voice_stopped("music"), None);
+    assert_eq!(ducker.voice_stopped("voice"), Some(DuckChange::Restore));
+    assert_eq!(ducker.voice_stopped("voice"), None);
+}
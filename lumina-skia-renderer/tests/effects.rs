@@ -0,0 +1,36 @@
+use lumina_skia_renderer::core::SceneAnimator;
+
+#[test]
+fn shake_decays_without_moving_base_position() {
+    let mut animator = SceneAnimator::new();
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), Some("left"), None, None, vec![], false);
+    let (x, y) = (animator.sprites["yuki"].pos.x, animator.sprites["yuki"].pos.y);
+
+    animator.handle_shake("yuki".into(), 30.0, 0.5);
+    animator.update(0.1);
+    let sprite = &animator.sprites["yuki"];
+    assert!(sprite.shake.x != 0.0 || sprite.shake.y != 0.0);
+    assert!(sprite.shake.x.abs() <= 30.0 && sprite.shake.y.abs() <= 30.0);
+
+    animator.update(0.5);
+    let sprite = &animator.sprites["yuki"];
+    assert_eq!((sprite.shake.x, sprite.shake.y), (0.0, 0.0));
+    assert_eq!((sprite.pos.x, sprite.pos.y), (x, y));
+}
+
+#[test]
+fn flash_fades_in_then_out() {
+    let mut animator = SceneAnimator::new();
+    animator.handle_flash([255, 255, 255, 255], 1.0);
+
+    animator.update(0.25);
+    let early = animator.flash.unwrap().alpha();
+    animator.update(0.25);
+    let peak = animator.flash.unwrap().alpha();
+    animator.update(0.25);
+    let late = animator.flash.unwrap().alpha();
+    assert!(early < peak && late < peak);
+
+    animator.update(0.5);
+    assert!(animator.flash.is_none());
+}
@@ -0,0 +1,20 @@
+use lumina_skia_renderer::core::MoviePlayer;
+
+#[test]
+fn frames_advance_at_fps_and_finish() {
+    let frames: Vec<String> = (1..=3).map(|i| format!("op_{:04}", i)).collect();
+    let mut movie = MoviePlayer::new("op".into(), frames, 10.0, true);
+
+    assert_eq!(movie.current_frame(), Some("op_0001"));
+    assert_eq!(movie.upcoming(8), ["op_0002", "op_0003"]);
+
+    movie.update(0.15);
+    assert_eq!(movie.current_frame(), Some("op_0002"));
+    assert!(!movie.is_finished());
+
+    // 播完后停在最后一帧
+    movie.update(0.2);
+    assert!(movie.is_finished());
+    assert_eq!(movie.current_frame(), Some("op_0003"));
+    assert!(movie.upcoming(8).is_empty());
+}
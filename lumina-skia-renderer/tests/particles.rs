@@ -0,0 +1,22 @@
+use lumina_skia_renderer::config::{ParticleConfig, ParticleKind};
+use lumina_skia_renderer::core::ParticleField;
+
+#[test]
+fn particles_fall_and_wrap_to_the_top() {
+    let cfg = ParticleConfig { kind: ParticleKind::Petals, count: 30, ..Default::default() };
+    let mut field = ParticleField::new(cfg, 800.0, 600.0);
+    assert_eq!(field.particles.len(), 30);
+    assert!(field.particles.iter().all(|p| (0.0..800.0).contains(&p.x) && (0.0..600.0).contains(&p.y)));
+
+    let before: Vec<f32> = field.particles.iter().map(|p| p.y).collect();
+    field.update(0.5);
+    let moved = field.particles.iter().zip(&before).filter(|(p, y)| p.y > **y).count();
+    assert!(moved > 0);
+
+    // 足够长时间后所有粒子都落出过底部，仍然留在区域内
+    for _ in 0..200 {
+        field.update(0.1);
+    }
+    assert_eq!(field.particles.len(), 30);
+    assert!(field.particles.iter().all(|p| (0.0..800.0).contains(&p.x) && p.y <= 600.0 + p.radius));
+}
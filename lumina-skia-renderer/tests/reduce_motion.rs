@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+use lumina_core::event::TransitionConfig;
+use lumina_skia_renderer::config::{set_reduce_motion, REDUCED_MOTION_MAX_DURATION};
+use lumina_skia_renderer::core::SceneAnimator;
+
+// 减少动态效果是进程级开关，单独放在一个测试文件里避免影响其他测试
+#[test]
+fn registered_transition_is_shortened_when_reducing_motion() {
+    set_reduce_motion(true);
+
+    let mut props = HashMap::new();
+    props.insert("alpha".to_string(), (Some(0.0), 1.0));
+    let mut animator = SceneAnimator::new();
+    animator.handle_register_transition("slow_fade".into(), TransitionConfig {
+        duration: 2.0,
+        easing: "linear".into(),
+        props,
+        mask_img: None,
+        vague: None,
+    });
+
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, Some("slow_fade".into()), None, vec![], false);
+    assert!(animator.is_busy());
+
+    animator.update(REDUCED_MOTION_MAX_DURATION + 0.01);
+    assert!(!animator.is_busy());
+    assert_eq!(animator.sprites["yuki"].alpha, 1.0);
+
+    // 抖动直接忽略
+    animator.handle_shake("screen".into(), 20.0, 1.0);
+    animator.update(0.1);
+    assert_eq!(animator.sprites["yuki"].shake.x, 0.0);
+    assert_eq!(animator.sprites["yuki"].shake.y, 0.0);
+}
@@ -0,0 +1,59 @@
+use lumina_skia_renderer::ui::rich_text;
+use skia_safe::textlayout::{FontCollection, RectHeightStyle, RectWidthStyle, TextAlign, TextStyle};
+use skia_safe::FontMgr;
+
+const TEXT: &str = "hello world {rt=kan ji}kanji{/rt} end";
+
+fn fonts() -> FontCollection {
+    let mut fc = FontCollection::new();
+    fc.set_default_font_manager(FontMgr::default(), None);
+    fc
+}
+
+fn style() -> TextStyle {
+    let mut ts = TextStyle::new();
+    ts.set_font_size(20.0);
+    ts
+}
+
+/// 底字在排版中占据的各个字形框的顶边
+fn base_line_tops(layout: &rich_text::RichParagraph) -> Vec<f32> {
+    let range = layout.rubies[0].base_range.clone();
+    layout.paragraph
+        .get_rects_for_range(range, RectHeightStyle::Tight, RectWidthStyle::Tight)
+        .iter()
+        .map(|b| b.rect.top)
+        .collect()
+}
+
+#[test]
+fn ruby_on_first_line_is_not_clipped() {
+    let fc = fonts();
+    let layout = rich_text::layout(&fc, TEXT, &style(), TextAlign::Left, 10_000.0);
+
+    assert_eq!(layout.rubies.len(), 1);
+    let ruby = &layout.rubies[0];
+    assert!(ruby.rect.top >= 0.0);
+    assert!(layout.height() >= layout.paragraph.height());
+}
+
+#[test]
+fn ruby_base_wraps_as_one_unit() {
+    let fc = fonts();
+    let wide = rich_text::layout(&fc, TEXT, &style(), TextAlign::Left, 10_000.0);
+    let base = wide.paragraph.get_rects_for_range(
+        wide.rubies[0].base_range.clone(), RectHeightStyle::Tight, RectWidthStyle::Tight,
+    );
+    let (left, right) = (base[0].rect.left, base.last().unwrap().rect.right);
+
+    // 行宽截在底字中间，底字应整体换到下一行
+    let narrow = rich_text::layout(&fc, TEXT, &style(), TextAlign::Left, (left + right) / 2.0);
+    let tops = base_line_tops(&narrow);
+    assert!(tops.iter().all(|t| (t - tops[0]).abs() < 0.5));
+    assert!(narrow.paragraph.line_number() >= 2);
+
+    // 注音跟随底字，位于底字上方
+    let ruby = &narrow.rubies[0];
+    assert!(ruby.rect.bottom <= tops[0] + narrow.top_inset + 0.5);
+    assert!(ruby.rect.top >= 0.0);
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use lumina_core::event::TransitionConfig;
+use lumina_skia_renderer::core::SceneAnimator;
+
+fn dissolve() -> TransitionConfig {
+    let mut props = HashMap::new();
+    props.insert("alpha".to_string(), (Some(0.0), 1.0));
+    TransitionConfig {
+        duration: 0.3,
+        easing: "linear".into(),
+        props,
+        mask_img: None,
+        vague: None,
+    }
+}
+
+fn animator_with_scene() -> SceneAnimator {
+    let mut animator = SceneAnimator::new();
+    animator.handle_register_transition("dissolve".into(), dissolve());
+    animator.handle_new_scene(Some("room".into()), "none".into(), None);
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec![], false);
+    animator
+}
+
+#[test]
+fn scene_with_transition_fades_out_old_sprites() {
+    let mut animator = animator_with_scene();
+
+    animator.handle_new_scene(Some("street".into()), "dissolve".into(), None);
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec![], false);
+
+    // 旧背景交给换图转场，旧立绘改名后淡出，不影响新场景里的同名立绘
+    assert_eq!(animator.sprites["bg"].old_texture.as_deref(), Some("room"));
+    assert_eq!(animator.sprites.len(), 3);
+    assert_eq!(animator.sprites["yuki"].alpha, 1.0);
+
+    animator.update(0.15);
+    let fading = animator.sprites.iter().find(|(k, _)| !matches!(k.as_str(), "bg" | "yuki")).unwrap().1;
+    assert!(fading.alpha > 0.0 && fading.alpha < 1.0);
+
+    animator.update(0.2);
+    assert_eq!(animator.sprites.len(), 2);
+}
+
+#[test]
+fn rapid_scene_changes_do_not_leak_sprites() {
+    let mut animator = animator_with_scene();
+
+    for bg in ["a", "b", "c", "d"] {
+        animator.handle_new_scene(Some(bg.into()), "dissolve".into(), None);
+        animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec![], false);
+        animator.update(0.05);
+    }
+    // 只剩当前场景的背景、立绘，以及上一场景正在淡出的立绘
+    assert_eq!(animator.sprites.len(), 3);
+
+    animator.finish_all_animations();
+    assert_eq!(animator.sprites.len(), 2);
+}
+
+#[test]
+fn scene_without_transition_clears_instantly() {
+    let mut animator = animator_with_scene();
+    animator.handle_new_scene(Some("street".into()), "none".into(), None);
+    assert_eq!(animator.sprites.len(), 1);
+    assert!(animator.sprites["bg"].old_texture.is_none());
+}
+
+#[test]
+fn inline_duration_overrides_registered_one() {
+    let mut animator = animator_with_scene();
+
+    animator.handle_new_scene(Some("street".into()), "dissolve".into(), Some(1.0));
+    // 注册的 0.3 秒早已过去，按内联的 1 秒仍在转场中
+    animator.update(0.5);
+    assert!(animator.sprites["bg"].in_transition);
+    animator.update(0.6);
+    assert!(!animator.sprites["bg"].in_transition);
+    assert!(!animator.is_busy());
+}
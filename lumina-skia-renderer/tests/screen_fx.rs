@@ -0,0 +1,28 @@
+use lumina_skia_renderer::config::{ScreenFx, ScreenFxKind, WindowConfig};
+
+#[test]
+fn progress_eases_out_and_clamps() {
+    let fx = ScreenFx::fade(0.4);
+    assert_eq!(fx.progress(0.0), 0.0);
+    assert_eq!(fx.progress(0.2), 0.75);
+    assert_eq!(fx.progress(1.0), 1.0);
+
+    // 时长为 0 时直接切换
+    assert_eq!(ScreenFx { kind: ScreenFxKind::SlideLeft, duration: 0.0 }.progress(0.0), 1.0);
+}
+
+#[test]
+fn overlays_default_to_a_quicker_fade() {
+    let cfg = WindowConfig::default();
+    assert_eq!(cfg.overlay_fx.kind, ScreenFxKind::Fade);
+    assert!(cfg.overlay_fx.duration < cfg.screen_fx.duration);
+}
+
+#[test]
+fn fade_black_switches_screens_at_midpoint() {
+    let fx = ScreenFx::fade_black(1.0);
+    assert_eq!(fx.black_overlay(0.0), (false, 0.0));
+    assert_eq!(fx.black_overlay(0.25), (false, 0.5));
+    assert_eq!(fx.black_overlay(0.5), (true, 1.0));
+    assert_eq!(fx.black_overlay(1.0), (true, 0.0));
+}
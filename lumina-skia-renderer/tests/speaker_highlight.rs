@@ -0,0 +1,46 @@
+use lumina_skia_renderer::config::SpeakerHighlightConfig;
+use lumina_skia_renderer::core::SceneAnimator;
+
+fn animator() -> SceneAnimator {
+    let mut animator = SceneAnimator::new();
+    animator.set_highlight_config(SpeakerHighlightConfig { dim_brightness: 0.5, lift: 10.0, duration: 0.2, ..Default::default() });
+    for name in ["yuki", "hana"] {
+        animator.handle_new_sprite(name.into(), name.into(), None, None, None, vec![], false);
+    }
+    animator.update(5.0);
+    animator
+}
+
+#[test]
+fn speaker_is_lifted_and_others_dimmed() {
+    let mut animator = animator();
+    animator.set_speaker(Some("yuki"));
+
+    // 过渡进行到一半
+    animator.update(0.1);
+    assert!((animator.sprites["hana"].brightness - 0.75).abs() < 1e-4);
+    assert!((animator.sprites["yuki"].lift - 5.0).abs() < 1e-4);
+
+    animator.update(0.2);
+    assert_eq!(animator.sprites["yuki"].brightness, 1.0);
+    assert_eq!(animator.sprites["yuki"].lift, 10.0);
+    assert_eq!(animator.sprites["hana"].brightness, 0.5);
+    assert_eq!(animator.sprites["hana"].lift, 0.0);
+
+    // 旁白时全部恢复
+    animator.set_speaker(None);
+    animator.update(0.3);
+    for sprite in animator.sprites.values() {
+        assert_eq!((sprite.brightness, sprite.lift), (1.0, 0.0));
+    }
+}
+
+#[test]
+fn offscreen_speaker_dims_nobody() {
+    let mut animator = animator();
+    animator.set_speaker(Some("narrator_cat"));
+    animator.update(0.3);
+    for sprite in animator.sprites.values() {
+        assert_eq!(sprite.brightness, 1.0);
+    }
+}
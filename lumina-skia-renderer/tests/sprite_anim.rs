@@ -0,0 +1,60 @@
+use lumina_core::event::AnimState;
+use lumina_skia_renderer::core::SceneAnimator;
+
+fn frames(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+fn animator() -> SceneAnimator {
+    let mut animator = SceneAnimator::new();
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec!["happy".into()], false);
+    animator
+}
+
+#[test]
+fn idle_anim_cycles_frames() {
+    let mut animator = animator();
+    animator.handle_set_anim("yuki", AnimState::Idle, frames(&["", "blink"]), 4.0, true);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy");
+
+    animator.update(0.25);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy_blink");
+    animator.update(0.25);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy");
+}
+
+#[test]
+fn non_looping_anim_holds_last_frame() {
+    let mut animator = animator();
+    animator.handle_set_anim("yuki", AnimState::Idle, frames(&["a", "b", "c"]), 10.0, false);
+    animator.update(1.0);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy_c");
+}
+
+#[test]
+fn speaker_switches_to_talk_anim() {
+    let mut animator = animator();
+    animator.handle_set_anim("yuki", AnimState::Idle, frames(&["closed"]), 4.0, true);
+    animator.handle_set_anim("yuki", AnimState::Talk, frames(&["open", "closed"]), 4.0, true);
+
+    animator.set_speaker(Some("yuki"));
+    animator.update(0.0);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy_open");
+    animator.update(0.25);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy_closed");
+    animator.update(0.25);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy_open");
+
+    // 说完话回到平时的动画
+    animator.set_speaker(None);
+    animator.update(0.0);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy_closed");
+}
+
+#[test]
+fn empty_frames_remove_anim() {
+    let mut animator = animator();
+    animator.handle_set_anim("yuki", AnimState::Idle, frames(&["blink"]), 4.0, true);
+    animator.handle_set_anim("yuki", AnimState::Idle, vec![], 4.0, true);
+    assert_eq!(animator.sprites["yuki"].full_asset_name(), "yuki_happy");
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use lumina_core::event::TransitionConfig;
+use lumina_skia_renderer::core::SceneAnimator;
+
+fn dissolve() -> TransitionConfig {
+    let mut props = HashMap::new();
+    props.insert("alpha".to_string(), (Some(0.0), 1.0));
+    TransitionConfig {
+        duration: 0.3,
+        easing: "linear".into(),
+        props,
+        mask_img: None,
+        vague: None,
+    }
+}
+
+#[test]
+fn expression_change_crossfades_without_fading_sprite() {
+    let mut animator = SceneAnimator::new();
+    animator.handle_register_transition("dissolve".into(), dissolve());
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec!["normal".into()], false);
+
+    animator.handle_update_sprite("yuki".into(), "dissolve".into(), None, None, Some(vec!["angry".into()]));
+    animator.update(0.15);
+
+    let sprite = &animator.sprites["yuki"];
+    assert!(sprite.in_transition);
+    assert_eq!(sprite.old_texture.as_deref(), Some("yuki_normal"));
+    assert_eq!(sprite.full_asset_name(), "yuki_angry");
+    assert!(sprite.trans_progress > 0.0 && sprite.trans_progress < 1.0);
+    assert_eq!(sprite.alpha, 1.0);
+
+    animator.update(0.2);
+    let sprite = &animator.sprites["yuki"];
+    assert!(!sprite.in_transition);
+    assert!(sprite.old_texture.is_none());
+}
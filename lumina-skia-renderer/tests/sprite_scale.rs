@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+use lumina_core::config::GraphicsConfig;
+use lumina_skia_renderer::core::painter::SpriteScale;
+
+#[test]
+fn sprites_normalize_to_design_height() {
+    let cfg = GraphicsConfig {
+        sprite_reference_height: Some(2160.0),
+        sprite_scale: BTreeMap::from([("cg_hand".to_string(), 0.8)]),
+        ..Default::default()
+    };
+    let scale = SpriteScale::new(&cfg);
+
+    // 4K 出图在 1080 设计高度下缩小一半
+    assert_eq!(scale.factor("yuki", 1080.0), 0.5);
+    // 单独指定的素材不受参考高度影响
+    assert_eq!(scale.factor("cg_hand", 1080.0), 0.8);
+
+    // 未设置时保持原始像素
+    assert_eq!(SpriteScale::new(&GraphicsConfig::default()).factor("yuki", 1080.0), 1.0);
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use lumina_skia_renderer::core::SceneAnimator;
+
+fn move_to(x: f32) -> HashMap<String, f32> {
+    HashMap::from([("x".to_string(), x)])
+}
+
+#[test]
+fn named_tween_reports_completion_once() {
+    let mut animator = SceneAnimator::new();
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec![], false);
+    animator.update(5.0);
+
+    animator.handle_modify_visual("yuki".into(), move_to(100.0), 0.5, "linear".into(), Some("walk".into()));
+    animator.update(0.25);
+    assert!(animator.take_completed().is_empty());
+
+    animator.update(0.5);
+    assert_eq!(animator.take_completed(), vec!["walk".to_string()]);
+    assert!(animator.take_completed().is_empty());
+}
+
+#[test]
+fn interrupted_or_missing_tween_still_completes() {
+    let mut animator = SceneAnimator::new();
+    animator.handle_new_sprite("yuki".into(), "yuki".into(), None, None, None, vec![], false);
+    animator.update(5.0);
+
+    // 同一目标的新动画打断旧动画
+    animator.handle_modify_visual("yuki".into(), move_to(100.0), 1.0, "linear".into(), Some("first".into()));
+    animator.handle_modify_visual("yuki".into(), move_to(200.0), 1.0, "linear".into(), None);
+    assert_eq!(animator.take_completed(), vec!["first".to_string()]);
+
+    animator.handle_modify_visual("nobody".into(), move_to(0.0), 1.0, "linear".into(), Some("ghost".into()));
+    assert_eq!(animator.take_completed(), vec!["ghost".to_string()]);
+}
@@ -1,3 +1,4 @@
+use std::cell::{Cell, RefCell};
 use crate::Rect;
 
 pub struct UiContext {
@@ -5,8 +6,52 @@ pub struct UiContext {
     pub mouse_pos: (f32, f32),
     /// 鼠标左键是否刚刚按下 (本帧触发)
     pub mouse_pressed: bool,
+    /// 鼠标左键是否刚刚松开 (本帧触发)
+    pub mouse_released: bool,
     /// 鼠标左键是否处于按下状态 (拖拽用)
     pub mouse_held: bool,
+    /// 当前这次按下的位置，松开后的下一帧清除
+    press_origin: Option<(f32, f32)>,
+    /// 同一帧内按下又松开时推迟到下一帧的松开
+    deferred_release: bool,
+    /// 本帧报告 `Pressed` 的区域，供 `consume_click` 记录按下的归属
+    pressed_rect: Cell<Option<Rect>>,
+    /// 消费了按下的控件区域，松开时只有它能收到 `Released`
+    press_owner: Cell<Option<Rect>>,
+    /// 本帧的点击是否已被某个控件消费，`update` 时清除
+    click_consumed: Cell<bool>,
+    /// 上一帧以来的键盘文本输入，由获得焦点的输入框取走，`end_frame` 时清除
+    text_events: RefCell<Vec<TextEvent>>,
+    /// 上一帧以来触发的快捷键动作，由界面取走，`end_frame` 时清除
+    actions: RefCell<Vec<Action>>,
+    /// 输入法正在组字的文本 (尚未提交)
+    preedit: Option<String>,
+    /// 本帧有输入框获得焦点时请求的输入法位置
+    ime_area: Cell<Option<Rect>>,
+    /// 正在计时的悬停区域：(区域, 开始悬停的时间, 本帧是否查询过)，`end_frame` 时丢弃本帧没有查询的
+    hovers: RefCell<Vec<(Rect, f32, bool)>>,
+}
+
+/// 文本输入事件，由 Renderer 从键盘 / 输入法事件转换而来
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextEvent {
+    /// 输入的字符或输入法提交的文本
+    Insert(String),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    /// 回车
+    Submit,
+}
+
+/// 与具体按键无关的语义动作，由 Renderer 按快捷键映射
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    QuickSave,
+    QuickLoad,
 }
 
 impl UiContext {
@@ -14,32 +59,158 @@ impl UiContext {
         Self {
             mouse_pos: (0.0, 0.0),
             mouse_pressed: false,
+            mouse_released: false,
             mouse_held: false,
+            press_origin: None,
+            deferred_release: false,
+            pressed_rect: Cell::new(None),
+            press_owner: Cell::new(None),
+            click_consumed: Cell::new(false),
+            text_events: RefCell::new(Vec::new()),
+            actions: RefCell::new(Vec::new()),
+            preedit: None,
+            ime_area: Cell::new(None),
+            hovers: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// 记录一个文本输入事件 (由 Renderer 调用)
+    pub fn push_text_event(&mut self, ev: TextEvent) {
+        self.text_events.get_mut().push(ev);
+    }
+
+    /// 记录一个快捷键动作 (由 Renderer 调用)
+    pub fn push_action(&mut self, action: Action) {
+        self.actions.get_mut().push(action);
+    }
+
+    /// 更新输入法组字文本，空字符串表示组字结束 (由 Renderer 调用)
+    pub fn set_preedit(&mut self, text: Option<String>) {
+        self.preedit = text.filter(|t| !t.is_empty());
+    }
+
+    pub fn take_text_events(&self) -> Vec<TextEvent> {
+        self.text_events.take()
+    }
+
+    pub fn take_actions(&self) -> Vec<Action> {
+        self.actions.take()
+    }
+
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_deref()
+    }
+
+    /// 获得焦点的输入框每帧调用，Renderer 据此开启输入法并放置候选窗
+    pub fn request_ime(&self, rect: Rect) {
+        self.ime_area.set(Some(rect));
+    }
+
+    /// 鼠标在 `rect` 内连续停留的秒数，`now` 为当前时间。
+    /// 需要每帧查询，某一帧没有查询或鼠标离开后重新计时
+    pub fn hover_time(&self, rect: Rect, now: f32) -> f32 {
+        self.hover_time_at(rect, self.mouse_pos, now)
+    }
+
+    /// 以给定的鼠标位置计算悬停时间，供带变换的渲染器换算到局部坐标后调用
+    pub fn hover_time_at(&self, rect: Rect, mouse: (f32, f32), now: f32) -> f32 {
+        let mut hovers = self.hovers.borrow_mut();
+        let idx = hovers.iter().position(|(r, _, _)| *r == rect);
+        if !rect.contains(mouse.0, mouse.1) {
+            if let Some(i) = idx {
+                hovers.swap_remove(i);
+            }
+            return 0.0;
+        }
+        match idx {
+            Some(i) => {
+                hovers[i].2 = true;
+                (now - hovers[i].1).max(0.0)
+            }
+            None => {
+                hovers.push((rect, now, true));
+                0.0
+            }
         }
     }
 
+    /// 一帧绘制结束：丢弃没有输入框接收的文本输入与没有界面处理的动作，返回本帧请求的输入法位置
+    pub fn end_frame(&mut self) -> Option<Rect> {
+        self.text_events.get_mut().clear();
+        self.actions.get_mut().clear();
+        self.hovers.get_mut().retain_mut(|(_, _, seen)| std::mem::take(seen));
+        self.ime_area.take()
+    }
+
     /// 更新输入状态 (由 Renderer 调用)
-    pub fn update(&mut self, x: f32, y: f32, pressed: bool, held: bool) {
+    pub fn update(&mut self, x: f32, y: f32, pressed: bool, released: bool, held: bool) {
         self.mouse_pos = (x, y);
+        // 两次重绘之间完成的快速点击：本帧只报告按下，松开留到下一帧
+        let released = released || std::mem::take(&mut self.deferred_release);
+        let released = if pressed && released {
+            self.deferred_release = true;
+            false
+        } else {
+            released
+        };
+        if pressed {
+            self.press_origin = Some((x, y));
+            self.press_owner.set(None);
+        } else if !held && !released {
+            self.press_origin = None;
+            self.press_owner.set(None);
+        }
         self.mouse_pressed = pressed;
+        self.mouse_released = released;
         self.mouse_held = held;
+        self.pressed_rect.set(None);
+        self.click_consumed.set(false);
+    }
+
+    /// 控件处理了本帧的按下或点击后调用，之后的 `interact` 不再报告 `Pressed` / `Released`；
+    /// 按下被消费时记下该控件，松开时其他区域不会收到 `Released`
+    pub fn consume_click(&self) {
+        self.click_consumed.set(true);
+        if self.mouse_pressed && let Some(rect) = self.pressed_rect.get() {
+            self.press_owner.set(Some(rect));
+        }
+    }
+
+    pub fn is_click_consumed(&self) -> bool {
+        self.click_consumed.get()
+    }
+
+    /// 当前这次按下的位置 (逻辑坐标)
+    pub fn press_origin(&self) -> Option<(f32, f32)> {
+        self.press_origin
     }
 
     pub fn interact(&self, rect: Rect) -> Interaction {
-        let (mx, my) = self.mouse_pos;
-        let hovered = rect.contains(mx, my);
+        self.interact_at(rect, self.mouse_pos, self.press_origin)
+    }
 
-        if hovered {
-            if self.mouse_pressed {
-                return Interaction::Clicked;
-            }
-            if self.mouse_held {
-                return Interaction::Held;
-            }
-            return Interaction::Hovered;
+    /// 以给定的鼠标与按下位置判断交互，供带变换的渲染器换算到局部坐标后调用
+    pub fn interact_at(&self, rect: Rect, mouse: (f32, f32), origin: Option<(f32, f32)>) -> Interaction {
+        if !rect.contains(mouse.0, mouse.1) {
+            return Interaction::None;
         }
-
-        Interaction::None
+        if self.is_click_consumed() {
+            return if self.mouse_held { Interaction::Held } else { Interaction::Hovered };
+        }
+        if self.mouse_pressed {
+            self.pressed_rect.set(Some(rect));
+            return Interaction::Pressed;
+        }
+        // 在区域内按下并松开才算点击，按下后拖出区域再松开即取消
+        let pressed_here = origin.is_some_and(|(x, y)| rect.contains(x, y));
+        let owned_by_other = matches!(self.press_owner.get(), Some(owner) if owner != rect);
+        if self.mouse_released && pressed_here && !owned_by_other {
+            return Interaction::Released;
+        }
+        if self.mouse_held {
+            return Interaction::Held;
+        }
+        Interaction::Hovered
     }
 }
 
@@ -47,12 +218,18 @@ impl UiContext {
 pub enum Interaction {
     None,
     Hovered,
-    Clicked, // 刚刚点击
-    Held,    // 按住中
+    Pressed,  // 刚刚按下
+    Held,     // 按住中
+    Released, // 在区域内按下后刚刚松开
 }
 
 impl Interaction {
+    pub fn is_pressed(&self) -> bool {
+        matches!(self, Interaction::Pressed)
+    }
+
+    /// 按桌面惯例以松开作为点击
     pub fn is_clicked(&self) -> bool {
-        matches!(self, Interaction::Clicked)
+        matches!(self, Interaction::Released)
     }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ pub mod input;
 pub mod types;
 pub mod widgets;
 
-pub use types::{Rect, Color, Alignment, Style, Background, Border, GradientDirection, Transform, ShaderSpec};
+pub use types::{Rect, Color, Alignment, Style, Background, ImageFit, Border, GradientDirection, Transform, ShaderSpec, TextEffects, TextShadow, TextFit};
 use input::Interaction;
 
 pub trait UiRenderer {
@@ -14,18 +14,58 @@ pub trait UiRenderer {
     /// tint: 染色颜色 (Color::WHITE 为原色)
     fn draw_image(&mut self, image_id: &str, rect: Rect, tint: Color);
 
+    /// 按 `fit` 适配方式绘制图片，默认忽略适配直接拉伸
+    fn draw_image_fit(&mut self, image_id: &str, rect: Rect, tint: Color, _fit: ImageFit) {
+        self.draw_image(image_id, rect, tint);
+    }
+
     /// 文本绘制
     fn draw_text(&mut self, text: &str, rect: Rect, color: Color, size: f32, align: Alignment, font: Option<&str>);
 
+    /// 带描边 / 投影的文本绘制，默认忽略效果只画填充
+    #[allow(clippy::too_many_arguments)]
+    fn draw_text_effects(
+        &mut self,
+        text: &str,
+        rect: Rect,
+        color: Color,
+        size: f32,
+        align: Alignment,
+        font: Option<&str>,
+        _effects: &TextEffects,
+    ) {
+        self.draw_text(text, rect, color, size, align, font);
+    }
+
+    /// 测量文本在给定宽度下折行后的高度
+    fn measure_text(&mut self, text: &str, max_width: f32, size: f32, font: Option<&str>) -> f32;
+
+    /// 测量文本不折行时的宽度
+    fn measure_text_width(&mut self, text: &str, size: f32, font: Option<&str>) -> f32;
+
+    /// 按 `fit` 把文本收进 `width` 宽，返回 (实际绘制的文本, 字号)
+    fn fit_text(&mut self, text: &str, width: f32, size: f32, font: Option<&str>, fit: TextFit) -> (String, f32) {
+        fit.fit(text, width, size, |t, s| self.measure_text_width(t, s, font))
+    }
+
     /// 绘制圆形
     fn draw_circle(&mut self, center: (f32, f32), radius: f32, color: Color);
 
-    /// 核心交互：查询某个区域的状态 (Hover / Click / Held)
+    /// 核心交互：查询某个区域的状态 (Hover / Click / Held)。
+    /// 本帧点击已被消费时不再返回 `Clicked`
     fn interact(&self, rect: Rect) -> Interaction;
 
+    /// 标记本帧的点击已被控件处理，避免下层的点击区域重复响应
+    fn consume_click(&mut self) {}
+
     /// 获取当前鼠标位置 (用于滑块计算数值等)
     fn cursor_pos(&self) -> (f32, f32);
 
+    /// 鼠标在 `rect` 内连续停留的秒数 (用于悬停提示)，默认不计时
+    fn hover_time(&self, _rect: Rect) -> f32 {
+        0.0
+    }
+
     fn with_transform(&mut self, transform: Transform, f: &mut dyn FnMut(&mut Self));
 
     fn time(&self) -> f32;
@@ -33,4 +73,27 @@ pub trait UiRenderer {
     fn measure_image(&mut self, image_id: &str) -> Option<(f32, f32)>;
 
     fn draw_shader(&mut self, rect: Rect, spec: ShaderSpec);
+
+    /// 本帧鼠标左键是否刚按下且未被控件消费 (不限区域，用于点击空白处取消焦点)
+    fn mouse_pressed(&self) -> bool {
+        false
+    }
+
+    /// 取走本帧的文本输入事件，默认没有键盘输入
+    fn take_text_events(&mut self) -> Vec<input::TextEvent> {
+        Vec::new()
+    }
+
+    /// 取走本帧的快捷键动作，默认没有
+    fn take_actions(&mut self) -> Vec<input::Action> {
+        Vec::new()
+    }
+
+    /// 输入法正在组字的文本
+    fn ime_preedit(&self) -> Option<String> {
+        None
+    }
+
+    /// 请求在 `rect` 处开启输入法
+    fn request_ime(&mut self, _rect: Rect) {}
 }
\ No newline at end of file
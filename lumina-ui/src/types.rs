@@ -126,8 +126,44 @@ pub enum Background {
         colors: (Color, Color),
     },
 
-    /// 图片背景 (存资源ID)
-    Image(String),
+    /// 图片背景 (资源ID, 适配方式)
+    Image(String, ImageFit),
+}
+
+/// 图片在目标矩形内的适配方式
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ImageFit {
+    /// 拉伸填满，不保持比例
+    #[default]
+    Fill,
+    /// 保持比例完整显示，居中留边
+    Contain,
+    /// 保持比例铺满，居中裁掉多余部分
+    Cover,
+    /// 按原始尺寸从左上角重复平铺
+    Tile,
+}
+
+impl ImageFit {
+    /// 计算 (源矩形, 目标矩形)，源矩形以图片像素为单位。
+    /// `Tile` 不裁剪也不缩放，返回整张图与目标矩形，由渲染端平铺
+    pub fn rects(self, image_w: f32, image_h: f32, rect: Rect) -> (Rect, Rect) {
+        let full = Rect::new(0.0, 0.0, image_w, image_h);
+        if image_w <= 0.0 || image_h <= 0.0 {
+            return (full, rect);
+        }
+        match self {
+            ImageFit::Fill | ImageFit::Tile => (full, rect),
+            ImageFit::Contain => {
+                let scale = (rect.w / image_w).min(rect.h / image_h);
+                (full, rect.center(image_w * scale, image_h * scale))
+            }
+            ImageFit::Cover => {
+                let scale = (rect.w / image_w).max(rect.h / image_h);
+                (full.center(rect.w / scale, rect.h / scale), rect)
+            }
+        }
+    }
 }
 
 impl Default for Background {
@@ -162,6 +198,69 @@ impl From<Color> for Background {
     }
 }
 
+/// 文字投影
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextShadow {
+    pub color: Color,
+    pub offset: (f32, f32),
+    pub blur: f32,
+}
+
+/// 文字的附加效果 (描边 / 投影)，不支持的后端可以忽略
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextEffects {
+    /// (描边颜色, 描边宽度)
+    pub outline: Option<(Color, f32)>,
+    pub shadow: Option<TextShadow>,
+}
+
+impl TextEffects {
+    pub fn is_empty(&self) -> bool {
+        self.outline.is_none() && self.shadow.is_none()
+    }
+}
+
+/// 文字自适应：超出宽度时按比例缩小字号，缩到 `min_size` 仍放不下则截断并补省略号
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextFit {
+    pub min_size: f32,
+}
+
+impl TextFit {
+    /// `measure(text, size)` 返回文本单行排版的宽度。
+    /// 返回 (实际绘制的文本, 字号)
+    pub fn fit(self, text: &str, width: f32, size: f32, mut measure: impl FnMut(&str, f32) -> f32) -> (String, f32) {
+        let natural = measure(text, size);
+        if natural <= width || natural <= 0.0 {
+            return (text.to_string(), size);
+        }
+
+        // 宽度与字号近似成正比，先按比例缩，再逐步微调抵消取整误差
+        let min_size = self.min_size.min(size);
+        let mut fitted = (size * width / natural).max(min_size);
+        while fitted > min_size && measure(text, fitted) > width {
+            fitted = (fitted - 1.0).max(min_size);
+        }
+        if measure(text, fitted) <= width {
+            return (text.to_string(), fitted);
+        }
+
+        // 最小字号仍放不下：二分查找能放下的最长前缀
+        let chars: Vec<char> = text.chars().collect();
+        let truncated = |n: usize| format!("{}…", chars[..n].iter().collect::<String>().trim_end());
+        let (mut lo, mut hi) = (0, chars.len());
+        while lo < hi {
+            let mid = (lo + hi).div_ceil(2);
+            if measure(&truncated(mid), fitted) <= width {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        (truncated(lo), fitted)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Transform {
     pub x: f32,
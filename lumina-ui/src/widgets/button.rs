@@ -1,4 +1,4 @@
-use crate::{Rect, Color, UiRenderer, Alignment, Style, Background, Border};
+use crate::{Rect, Color, UiRenderer, Alignment, Style, Background, Border, TextFit};
 use crate::input::Interaction;
 
 pub struct Button<'a> {
@@ -11,6 +11,12 @@ pub struct Button<'a> {
     text_color: Color,
     font_size: f32,
     font: Option<&'a str>,
+    fit: Option<TextFit>,
+
+    /// 文字左侧的图标
+    icon: Option<&'a str>,
+    /// 常态 / 悬停 / 按下三张图片，设置后代替 Style 背景
+    images: Option<[&'a str; 3]>,
 }
 
 impl<'a> Button<'a> {
@@ -36,6 +42,9 @@ impl<'a> Button<'a> {
             text_color: Color::WHITE,
             font_size: 24.0,
             font: None,
+            fit: None,
+            icon: None,
+            images: None,
         }
     }
 
@@ -75,6 +84,12 @@ impl<'a> Button<'a> {
         self
     }
 
+    /// 文字放不下时缩小字号，最小到 `min_size`，仍放不下则截断加省略号
+    pub fn auto_fit(mut self, min_size: f32) -> Self {
+        self.fit = Some(TextFit { min_size });
+        self
+    }
+
     /// 设置边框 (同时应用到所有状态，保持形状一致)
     pub fn stroke(mut self, color: Color, width: f32) -> Self {
         let border = Border { color, width, radius: self.normal_style.border.radius };
@@ -92,6 +107,18 @@ impl<'a> Button<'a> {
         self
     }
 
+    /// 在文字左侧画图标，图标与文字整体居中
+    pub fn icon(mut self, image_id: &'a str) -> Self {
+        self.icon = Some(image_id);
+        self
+    }
+
+    /// 用图片代替背景样式，分别对应常态 / 悬停 / 按下；文字为空即为纯图片按钮
+    pub fn image_states(mut self, normal: &'a str, hover: &'a str, active: &'a str) -> Self {
+        self.images = Some([normal, hover, active]);
+        self
+    }
+
     // ==========================================
     //  高级自定义 (分别设置各状态样式)
     // ==========================================
@@ -116,28 +143,59 @@ impl<'a> Button<'a> {
     // ==========================================
 
     pub fn show(self, ui: &mut impl UiRenderer, rect: Rect) -> bool {
-        // 1. 获取交互状态
+        // 1. 获取交互状态：按下时占住这次点击，松开时才触发
         let interaction = ui.interact(rect);
+        if interaction.is_pressed() || interaction.is_clicked() {
+            ui.consume_click();
+        }
 
         // 2. 根据状态选择样式
-        let current_style = match interaction {
-            Interaction::Held | Interaction::Clicked => &self.active_style,
-            Interaction::Hovered => &self.hover_style,
-            Interaction::None => &self.normal_style,
+        let state = match interaction {
+            Interaction::Held | Interaction::Pressed => 2,
+            Interaction::Hovered | Interaction::Released => 1,
+            Interaction::None => 0,
+        };
+
+        // 3. 绘制背景：有状态图片时画图片，否则画样式盒子 (背景 + 边框)
+        match self.images {
+            Some(images) => ui.draw_image(images[state], rect, Color::WHITE),
+            None => {
+                let current_style = [&self.normal_style, &self.hover_style, &self.active_style][state];
+                ui.draw_style(rect, current_style);
+            }
+        }
+
+        // 4. 绘制图标与文字 (支持自定义字体)
+        let icon_size = (self.font_size * 1.2).min(rect.h);
+        let gap = if self.text.is_empty() { 0.0 } else { self.font_size * 0.4 };
+        let reserved = if self.icon.is_some() { icon_size + gap } else { 0.0 };
+
+        let (text, size) = match self.fit {
+            Some(fit) => ui.fit_text(self.text, rect.w - reserved, self.font_size, self.font, fit),
+            None => (self.text.to_string(), self.font_size),
         };
 
-        // 3. 绘制样式盒子 (背景 + 边框)
-        ui.draw_style(rect, current_style);
-
-        // 4. 绘制文字 (支持自定义字体)
-        ui.draw_text(
-            self.text,
-            rect,
-            self.text_color,
-            self.font_size,
-            Alignment::Center,
-            self.font // 传入字体
-        );
+        match self.icon {
+            Some(icon) => {
+                let text_w = if text.is_empty() { 0.0 } else { ui.measure_text_width(&text, size, self.font) };
+                let x = rect.x + (rect.w - reserved - text_w).max(0.0) / 2.0;
+                let icon_rect = Rect::new(x, rect.y + (rect.h - icon_size) / 2.0, icon_size, icon_size);
+                ui.draw_image(icon, icon_rect, Color::WHITE);
+                if !text.is_empty() {
+                    let text_rect = Rect::new(x + reserved, rect.y, text_w, rect.h);
+                    ui.draw_text(&text, text_rect, self.text_color, size, Alignment::Start, self.font);
+                }
+            }
+            None if !text.is_empty() => ui.draw_text(
+                &text,
+                rect,
+                self.text_color,
+                size,
+                Alignment::Center,
+                self.font // 传入字体
+            ),
+            None => {}
+        }
 
         // 5. 返回点击结果
         interaction.is_clicked()
@@ -1,4 +1,4 @@
-use crate::{Alignment, Background, Border, Color, Rect, Style, UiRenderer};
+use crate::{Alignment, Background, Border, ImageFit, Color, Rect, Style, UiRenderer};
 
 pub struct Checkbox<'a> {
     checked: &'a mut bool,
@@ -46,10 +46,10 @@ impl<'a> Checkbox<'a> {
 
     /// 快捷设置：图片 Checkbox
     pub fn images(mut self, unchecked_id: String, checked_id: String) -> Self {
-        self.unchecked_style.background = Background::Image(unchecked_id);
+        self.unchecked_style.background = Background::Image(unchecked_id, ImageFit::Fill);
         self.unchecked_style.border.width = 0.0; // 用图了就去掉边框
 
-        self.checked_style.background = Background::Image(checked_id);
+        self.checked_style.background = Background::Image(checked_id, ImageFit::Fill);
         self.checked_style.border.width = 0.0;
         self
     }
@@ -62,6 +62,9 @@ impl<'a> Checkbox<'a> {
     pub fn show(self, ui: &mut impl UiRenderer, rect: Rect) -> bool {
         let interaction = ui.interact(rect);
         let mut changed = false;
+        if interaction.is_pressed() || interaction.is_clicked() {
+            ui.consume_click();
+        }
         if interaction.is_clicked() {
             *self.checked = !*self.checked;
             changed = true;
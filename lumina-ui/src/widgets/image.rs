@@ -1,8 +1,9 @@
-use crate::{Rect, Color, UiRenderer};
+use crate::{Rect, Color, UiRenderer, ImageFit};
 
 pub struct Image<'a> {
     id: &'a str,
     tint: Color,
+    fit: ImageFit,
 }
 
 impl<'a> Image<'a> {
@@ -10,6 +11,7 @@ impl<'a> Image<'a> {
         Self {
             id,
             tint: Color::WHITE,
+            fit: ImageFit::Fill,
         }
     }
 
@@ -18,7 +20,13 @@ impl<'a> Image<'a> {
         self
     }
 
+    /// 图片在矩形内的适配方式，默认拉伸填满
+    pub fn fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
     pub fn show(self, ui: &mut impl UiRenderer, rect: Rect) {
-        ui.draw_image(self.id, rect, self.tint);
+        ui.draw_image_fit(self.id, rect, self.tint, self.fit);
     }
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
-use crate::{Rect, Color, UiRenderer, Alignment};
+use crate::{Rect, Color, UiRenderer, Alignment, TextEffects, TextShadow, TextFit};
 pub struct Label<'a> {
     text: &'a str,
     color: Color,
     size: f32,
     align: Alignment,
     font: Option<&'a str>,
+    effects: TextEffects,
+    fit: Option<TextFit>,
 }
 
 impl<'a> Label<'a> {
@@ -15,6 +17,8 @@ impl<'a> Label<'a> {
             size: 24.0,
             align: Alignment::Start,
             font: None,
+            effects: TextEffects::default(),
+            fit: None,
         }
     }
 
@@ -38,7 +42,33 @@ impl<'a> Label<'a> {
         self
     }
 
+    /// 文字描边，保证在任意背景上可读
+    pub fn outline(mut self, color: Color, width: f32) -> Self {
+        self.effects.outline = Some((color, width));
+        self
+    }
+
+    /// 文字投影
+    pub fn shadow(mut self, color: Color, offset: (f32, f32), blur: f32) -> Self {
+        self.effects.shadow = Some(TextShadow { color, offset, blur });
+        self
+    }
+
+    /// 单行放不下时缩小字号，最小到 `min_size`，仍放不下则截断加省略号
+    pub fn auto_fit(mut self, min_size: f32) -> Self {
+        self.fit = Some(TextFit { min_size });
+        self
+    }
+
     pub fn show(self, ui: &mut impl UiRenderer, rect: Rect) {
-        ui.draw_text(self.text, rect, self.color, self.size, self.align, self.font);
+        let (text, size) = match self.fit {
+            Some(fit) => ui.fit_text(self.text, rect.w, self.size, self.font, fit),
+            None => (self.text.to_string(), self.size),
+        };
+        if self.effects.is_empty() {
+            ui.draw_text(&text, rect, self.color, size, self.align, self.font);
+        } else {
+            ui.draw_text_effects(&text, rect, self.color, size, self.align, self.font, &self.effects);
+        }
     }
 }
\ No newline at end of file
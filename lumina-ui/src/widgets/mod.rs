@@ -4,10 +4,16 @@ pub mod slider;
 pub mod checkbox;
 pub mod panel;
 pub mod image;
+pub mod text_input;
+pub mod toast;
+pub mod tooltip;
 
 pub use button::Button;
 pub use label::Label;
 pub use slider::Slider;
 pub use checkbox::Checkbox;
 pub use panel::Panel;
-pub use image::Image;
\ No newline at end of file
+pub use image::Image;
+pub use text_input::{TextInput, TextInputState};
+pub use toast::ToastStack;
+pub use tooltip::tooltip;
\ No newline at end of file
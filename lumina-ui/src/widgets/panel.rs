@@ -1,4 +1,4 @@
-use crate::{Rect, UiRenderer, Style, Background, Color, Border, GradientDirection};
+use crate::{Rect, UiRenderer, Style, Background, Color, Border, GradientDirection, ImageFit};
 
 pub struct Panel {
     style: Style,
@@ -29,7 +29,15 @@ impl Panel {
     }
 
     pub fn image(mut self, id: &str) -> Self {
-        self.style.background = Background::Image(id.to_string());
+        self.style.background = Background::Image(id.to_string(), ImageFit::Fill);
+        self
+    }
+
+    /// 图片背景的适配方式，需在 `image` 之后调用
+    pub fn image_fit(mut self, fit: ImageFit) -> Self {
+        if let Background::Image(_, f) = &mut self.style.background {
+            *f = fit;
+        }
         self
     }
 
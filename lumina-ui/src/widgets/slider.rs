@@ -56,8 +56,12 @@ impl<'a> Slider<'a> {
     pub fn show(self, ui: &mut impl UiRenderer, rect: Rect) -> bool {
         let interaction = ui.interact(rect);
         let mut changed = false;
+        // 拖动从按下开始
+        if interaction.is_pressed() {
+            ui.consume_click();
+        }
 
-        if interaction == Interaction::Held || interaction == Interaction::Clicked {
+        if interaction == Interaction::Held || interaction == Interaction::Pressed {
             let (mx, _my) = ui.cursor_pos();
             let ratio = (mx - rect.x) / rect.w;
             let ratio = ratio.clamp(0.0, 1.0);
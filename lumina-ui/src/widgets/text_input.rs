@@ -0,0 +1,183 @@
+use crate::{Alignment, Background, Border, Color, Rect, Style, UiRenderer};
+use crate::input::TextEvent;
+
+/// 输入框跨帧保存的状态：内容、光标 (按字符计) 与焦点
+#[derive(Debug, Clone, Default)]
+pub struct TextInputState {
+    pub text: String,
+    /// 光标位于第几个字符之前
+    pub cursor: usize,
+    pub focused: bool,
+    submitted: bool,
+}
+
+impl TextInputState {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self { cursor: text.chars().count(), text, focused: false, submitted: false }
+    }
+
+    /// 应用一个输入事件，`max_len` 为最多字符数
+    pub fn apply(&mut self, ev: &TextEvent, max_len: Option<usize>) {
+        let len = self.text.chars().count();
+        self.cursor = self.cursor.min(len);
+        match ev {
+            TextEvent::Insert(s) => {
+                let room = max_len.map_or(usize::MAX, |m| m.saturating_sub(len));
+                let insert: String = s.chars().filter(|c| !c.is_control()).take(room).collect();
+                let at = self.byte_index(self.cursor);
+                self.text.insert_str(at, &insert);
+                self.cursor += insert.chars().count();
+            }
+            TextEvent::Backspace if self.cursor > 0 => {
+                let at = self.byte_index(self.cursor - 1);
+                self.text.remove(at);
+                self.cursor -= 1;
+            }
+            TextEvent::Delete if self.cursor < len => {
+                let at = self.byte_index(self.cursor);
+                self.text.remove(at);
+            }
+            TextEvent::Left => self.cursor = self.cursor.saturating_sub(1),
+            TextEvent::Right => self.cursor = (self.cursor + 1).min(len),
+            TextEvent::Home => self.cursor = 0,
+            TextEvent::End => self.cursor = len,
+            TextEvent::Submit => self.submitted = true,
+            _ => {}
+        }
+    }
+
+    /// 上次调用以来是否按过回车
+    pub fn take_submitted(&mut self) -> bool {
+        std::mem::take(&mut self.submitted)
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text.char_indices().nth(chars).map_or(self.text.len(), |(i, _)| i)
+    }
+}
+
+pub struct TextInput<'a> {
+    state: &'a mut TextInputState,
+    max_len: Option<usize>,
+    placeholder: &'a str,
+    font_size: f32,
+    font: Option<&'a str>,
+    text_color: Color,
+    normal_style: Style,
+    focus_style: Style,
+}
+
+impl<'a> TextInput<'a> {
+    pub fn new(state: &'a mut TextInputState) -> Self {
+        let normal = Style {
+            background: Background::Solid(Color::rgba(0, 0, 0, 120)),
+            border: Border { color: Color::GRAY, width: 2.0, radius: 6.0 },
+        };
+
+        let mut focus = normal.clone();
+        focus.border.color = Color::WHITE;
+
+        Self {
+            state,
+            max_len: None,
+            placeholder: "",
+            font_size: 24.0,
+            font: None,
+            text_color: Color::WHITE,
+            normal_style: normal,
+            focus_style: focus,
+        }
+    }
+
+    /// 最多输入的字符数
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// 内容为空时显示的提示文字
+    pub fn placeholder(mut self, text: &'a str) -> Self {
+        self.placeholder = text;
+        self
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    pub fn font(mut self, font: &'a str) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn style_normal(mut self, style: Style) -> Self {
+        self.normal_style = style;
+        self
+    }
+
+    pub fn style_focus(mut self, style: Style) -> Self {
+        self.focus_style = style;
+        self
+    }
+
+    /// 绘制并处理输入，返回当前内容。点击框内获得焦点，点击框外失去焦点
+    pub fn show(self, ui: &mut impl UiRenderer, rect: Rect) -> String {
+        // 与桌面输入框一致，按下即获得焦点
+        let interaction = ui.interact(rect);
+        if interaction.is_pressed() {
+            ui.consume_click();
+            self.state.focused = true;
+        } else if ui.mouse_pressed() {
+            self.state.focused = false;
+        }
+
+        if self.state.focused {
+            for ev in ui.take_text_events() {
+                self.state.apply(&ev, self.max_len);
+            }
+        }
+
+        let style = if self.state.focused { &self.focus_style } else { &self.normal_style };
+        ui.draw_style(rect, style);
+
+        let pad = self.font_size * 0.4;
+        let inner = Rect::new(rect.x + pad, rect.y, (rect.w - pad * 2.0).max(0.0), rect.h);
+
+        // 组字中的文本显示在光标处
+        let preedit = if self.state.focused { ui.ime_preedit() } else { None };
+        let cursor_byte = self.state.byte_index(self.state.cursor);
+        let (before, after) = self.state.text.split_at(cursor_byte);
+        let shown = format!("{}{}{}", before, preedit.as_deref().unwrap_or(""), after);
+
+        if shown.is_empty() && !self.state.focused {
+            ui.draw_text(self.placeholder, inner, Color::GRAY, self.font_size, Alignment::Start, self.font);
+        } else {
+            ui.draw_text(&shown, inner, self.text_color, self.font_size, Alignment::Start, self.font);
+        }
+
+        if self.state.focused {
+            let caret_text = format!("{}{}", before, preedit.as_deref().unwrap_or(""));
+            let caret_x = inner.x + ui.measure_text_width(&caret_text, self.font_size, self.font);
+            let caret = Rect::new(caret_x, rect.y + (rect.h - self.font_size) / 2.0, 2.0, self.font_size);
+
+            // 光标每 0.5 秒闪烁一次
+            if (ui.time() * 2.0) as i64 % 2 == 0 {
+                let caret_style = Style {
+                    background: Background::Solid(self.text_color),
+                    ..Default::default()
+                };
+                ui.draw_style(caret, &caret_style);
+            }
+            ui.request_ime(caret);
+        }
+
+        self.state.text.clone()
+    }
+}
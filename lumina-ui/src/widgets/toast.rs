@@ -0,0 +1,82 @@
+use crate::{Alignment, Color, Rect, UiRenderer};
+use crate::widgets::{Label, Panel};
+
+/// 淡入 / 淡出时长 (秒)
+const FADE: f32 = 0.25;
+const TOAST_W: f32 = 420.0;
+const TOAST_H: f32 = 56.0;
+const GAP: f32 = 10.0;
+const MARGIN: f32 = 20.0;
+
+#[derive(Debug, Clone)]
+struct Toast {
+    text: String,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl Toast {
+    /// 当前不透明度：开头淡入、结尾淡出
+    fn alpha(&self) -> f32 {
+        let fade_in = self.elapsed / FADE;
+        let fade_out = (self.duration - self.elapsed) / FADE;
+        fade_in.min(fade_out).clamp(0.0, 1.0)
+    }
+}
+
+/// 角落里的短暂提示，新的在上方，可以叠多条。状态由界面持有，按 `dt` 更新
+#[derive(Debug, Clone, Default)]
+pub struct ToastStack {
+    toasts: Vec<Toast>,
+}
+
+impl ToastStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `duration` 为包括淡入淡出在内的停留时间 (秒)
+    pub fn push(&mut self, text: impl Into<String>, duration: f32) {
+        self.toasts.push(Toast { text: text.into(), duration: duration.max(FADE * 2.0), elapsed: 0.0 });
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for toast in &mut self.toasts {
+            toast.elapsed += dt;
+        }
+        self.toasts.retain(|t| t.elapsed < t.duration);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// 当前显示的提示文本，从新到旧
+    pub fn texts(&self) -> Vec<&str> {
+        self.toasts.iter().rev().map(|t| t.text.as_str()).collect()
+    }
+
+    /// 画在 `rect` 的左上角，放不下的旧提示不画
+    pub fn show(&self, ui: &mut impl UiRenderer, rect: Rect) {
+        let mut y = rect.y + MARGIN;
+        for toast in self.toasts.iter().rev() {
+            if y + TOAST_H > rect.y + rect.h {
+                break;
+            }
+            let alpha = toast.alpha();
+            let area = Rect::new(rect.x + MARGIN, y, TOAST_W.min(rect.w - MARGIN * 2.0), TOAST_H);
+
+            Panel::new()
+                .color(Color::rgba(20, 20, 30, (210.0 * alpha) as u8))
+                .rounded(10.0)
+                .show(ui, area);
+            Label::new(&toast.text)
+                .size(22.0)
+                .color(Color::WHITE.with_alpha((255.0 * alpha) as u8))
+                .align(Alignment::Start)
+                .show(ui, area.shrink(16.0));
+
+            y += TOAST_H + GAP;
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{Alignment, Color, Rect, UiRenderer};
+use crate::input::Interaction;
+use crate::widgets::{Label, Panel};
+
+/// 悬停多久后出现 (秒)
+pub const TOOLTIP_DELAY: f32 = 0.5;
+/// 出现时的淡入时长 (秒)
+const FADE: f32 = 0.15;
+const SIZE: f32 = 20.0;
+const PADDING: f32 = 10.0;
+const MAX_WIDTH: f32 = 400.0;
+/// 气泡相对鼠标的偏移，避免被光标挡住
+const OFFSET: (f32, f32) = (16.0, 20.0);
+
+/// 鼠标在 `area` 上停留超过 [`TOOLTIP_DELAY`] 后，在鼠标旁显示写着 `text` 的气泡，返回是否显示。
+/// 悬停计时需要每帧对同一区域调用；放在界面最后绘制，以免被之后的控件盖住
+pub fn tooltip(ui: &mut impl UiRenderer, area: Rect, text: &str) -> bool {
+    let hovered_for = ui.hover_time(area);
+    // 按下或拖拽时不显示
+    if hovered_for < TOOLTIP_DELAY || text.is_empty() || ui.interact(area) != Interaction::Hovered {
+        return false;
+    }
+
+    let text_w = ui.measure_text_width(text, SIZE, None).min(MAX_WIDTH);
+    let text_h = ui.measure_text(text, text_w, SIZE, None);
+    let (x, y) = ui.cursor_pos();
+    let bubble = Rect::new(x + OFFSET.0, y + OFFSET.1, text_w + PADDING * 2.0, text_h + PADDING * 2.0);
+    let alpha = ((hovered_for - TOOLTIP_DELAY) / FADE).clamp(0.0, 1.0);
+
+    Panel::new()
+        .color(Color::rgba(20, 20, 30, (230.0 * alpha) as u8))
+        .stroke(Color::rgba(255, 255, 255, (60.0 * alpha) as u8), 1.0)
+        .rounded(6.0)
+        .show(ui, bubble);
+    Label::new(text)
+        .size(SIZE)
+        .color(Color::WHITE.with_alpha((255.0 * alpha) as u8))
+        .align(Alignment::Start)
+        .show(ui, bubble.shrink(PADDING));
+    true
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::input::Interaction;
+    use lumina_ui::widgets::Button;
+    use lumina_ui::{Alignment, Color, Rect, ShaderSpec, Style, Transform, UiRenderer};
+
+    /// 记录绘制调用，每个字宽为字号的一半
+    #[derive(Default)]
+    struct Recorder {
+        hovered: bool,
+        images: Vec<(String, Rect)>,
+        texts: Vec<(String, Rect)>,
+        styles: usize,
+    }
+
+    impl UiRenderer for Recorder {
+        fn draw_style(&mut self, _rect: Rect, _style: &Style) { self.styles += 1; }
+        fn draw_image(&mut self, image_id: &str, rect: Rect, _tint: Color) {
+            self.images.push((image_id.to_string(), rect));
+        }
+        fn draw_text(&mut self, text: &str, rect: Rect, _color: Color, _size: f32, _align: Alignment, _font: Option<&str>) {
+            self.texts.push((text.to_string(), rect));
+        }
+        fn measure_text(&mut self, _text: &str, _max_width: f32, size: f32, _font: Option<&str>) -> f32 { size }
+        fn measure_text_width(&mut self, text: &str, size: f32, _font: Option<&str>) -> f32 {
+            text.chars().count() as f32 * size * 0.5
+        }
+        fn draw_circle(&mut self, _center: (f32, f32), _radius: f32, _color: Color) {}
+        fn interact(&self, _rect: Rect) -> Interaction {
+            if self.hovered { Interaction::Hovered } else { Interaction::None }
+        }
+        fn cursor_pos(&self) -> (f32, f32) { (0.0, 0.0) }
+        fn with_transform(&mut self, _transform: Transform, f: &mut dyn FnMut(&mut Self)) { f(self) }
+        fn time(&self) -> f32 { 0.0 }
+        fn measure_image(&mut self, _image_id: &str) -> Option<(f32, f32)> { None }
+        fn draw_shader(&mut self, _rect: Rect, _spec: ShaderSpec) {}
+    }
+
+    #[test]
+    fn icon_and_text_are_centered_together() {
+        let mut ui = Recorder::default();
+        // 字号 20：图标 24，间距 8，文字 4 字 * 10 = 40，整体 72 宽
+        Button::new("Load").size(20.0).icon("ico_load").show(&mut ui, Rect::new(0.0, 0.0, 200.0, 40.0));
+
+        assert_eq!(ui.styles, 1);
+        assert_eq!(ui.images, vec![("ico_load".to_string(), Rect::new(64.0, 8.0, 24.0, 24.0))]);
+        assert_eq!(ui.texts, vec![("Load".to_string(), Rect::new(96.0, 0.0, 40.0, 40.0))]);
+    }
+
+    #[test]
+    fn image_states_replace_the_style_background() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+        let mut ui = Recorder { hovered: true, ..Default::default() };
+        Button::new("").image_states("btn_n", "btn_h", "btn_a").show(&mut ui, rect);
+
+        assert_eq!(ui.styles, 0);
+        assert_eq!(ui.images, vec![("btn_h".to_string(), rect)]);
+        assert!(ui.texts.is_empty());
+    }
+}
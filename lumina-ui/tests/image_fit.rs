@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::{ImageFit, Rect};
+
+    #[test]
+    fn test_image_fit() {
+        // 200x100 的图放进 100x100 的框
+        let rect = Rect::new(10.0, 10.0, 100.0, 100.0);
+        let full = Rect::new(0.0, 0.0, 200.0, 100.0);
+
+        assert_eq!(ImageFit::Fill.rects(200.0, 100.0, rect), (full, rect));
+        assert_eq!(ImageFit::Tile.rects(200.0, 100.0, rect), (full, rect));
+
+        // 完整显示，上下留边
+        let (src, dst) = ImageFit::Contain.rects(200.0, 100.0, rect);
+        assert_eq!(src, full);
+        assert_eq!(dst, Rect::new(10.0, 35.0, 100.0, 50.0));
+
+        // 铺满，左右各裁 50px
+        let (src, dst) = ImageFit::Cover.rects(200.0, 100.0, rect);
+        assert_eq!(src, Rect::new(50.0, 0.0, 100.0, 100.0));
+        assert_eq!(dst, rect);
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::Rect;
+    use lumina_ui::input::{Interaction, UiContext};
+
+    const BUTTON: Rect = Rect { x: 0.0, y: 0.0, w: 100.0, h: 40.0 };
+    const SCREEN: Rect = Rect { x: 0.0, y: 0.0, w: 1920.0, h: 1080.0 };
+
+    #[test]
+    fn consumed_click_is_not_reported_again_this_frame() {
+        let mut ctx = UiContext::new();
+
+        ctx.update(10.0, 10.0, true, false, true);
+        assert_eq!(ctx.interact(BUTTON), Interaction::Pressed);
+        ctx.consume_click();
+        assert_eq!(ctx.interact(SCREEN), Interaction::Held);
+
+        // 松开时只有占住按下的控件收到点击
+        ctx.update(10.0, 10.0, false, true, false);
+        assert_eq!(ctx.interact(BUTTON), Interaction::Released);
+        ctx.consume_click();
+        assert_eq!(ctx.interact(SCREEN), Interaction::Hovered);
+
+        ctx.update(10.0, 10.0, false, true, false);
+        assert_eq!(ctx.interact(SCREEN), Interaction::Hovered);
+    }
+
+    #[test]
+    fn dragging_out_before_release_cancels_the_click() {
+        let mut ctx = UiContext::new();
+
+        ctx.update(10.0, 10.0, true, false, true);
+        assert!(ctx.interact(BUTTON).is_pressed());
+        ctx.consume_click();
+
+        ctx.update(500.0, 10.0, false, false, true);
+        assert_eq!(ctx.interact(BUTTON), Interaction::None);
+
+        // 回到按钮外松开：按钮没有点击，按下被按钮占住，底层区域也不响应
+        ctx.update(500.0, 10.0, false, true, false);
+        assert!(!ctx.interact(BUTTON).is_clicked());
+        assert!(!ctx.interact(SCREEN).is_clicked());
+
+        // 在别处按下后移到按钮上松开同样不算按钮的点击
+        ctx.update(500.0, 10.0, true, false, true);
+        ctx.update(10.0, 10.0, false, true, false);
+        assert_eq!(ctx.interact(BUTTON), Interaction::Hovered);
+        assert!(ctx.interact(SCREEN).is_clicked());
+    }
+
+    #[test]
+    fn press_and_release_within_one_frame_clicks_on_the_next() {
+        let mut ctx = UiContext::new();
+
+        ctx.update(10.0, 10.0, true, true, false);
+        assert!(ctx.interact(BUTTON).is_pressed());
+
+        ctx.update(10.0, 10.0, false, false, false);
+        assert!(ctx.interact(BUTTON).is_clicked());
+
+        ctx.update(10.0, 10.0, false, false, false);
+        assert_eq!(ctx.interact(BUTTON), Interaction::Hovered);
+    }
+}
+
+#[cfg(test)]
+mod hover_tests {
+    use lumina_ui::Rect;
+    use lumina_ui::input::UiContext;
+
+    const AREA: Rect = Rect { x: 0.0, y: 0.0, w: 100.0, h: 40.0 };
+
+    #[test]
+    fn hover_time_counts_while_inside() {
+        let mut ctx = UiContext::new();
+        ctx.update(10.0, 10.0, false, false, false);
+        assert_eq!(ctx.hover_time(AREA, 1.0), 0.0);
+        ctx.end_frame();
+        assert_eq!(ctx.hover_time(AREA, 1.5), 0.5);
+        ctx.end_frame();
+
+        // 离开后重新计时
+        ctx.update(500.0, 10.0, false, false, false);
+        assert_eq!(ctx.hover_time(AREA, 2.0), 0.0);
+        ctx.end_frame();
+        ctx.update(10.0, 10.0, false, false, false);
+        assert_eq!(ctx.hover_time(AREA, 3.0), 0.0);
+        ctx.end_frame();
+
+        // 有一帧没有查询 (区域没有绘制) 同样重新计时
+        ctx.end_frame();
+        assert_eq!(ctx.hover_time(AREA, 4.0), 0.0);
+    }
+}
@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::TextFit;
+
+    /// 每个字宽为字号的一半
+    fn measure(text: &str, size: f32) -> f32 {
+        text.chars().count() as f32 * size * 0.5
+    }
+
+    #[test]
+    fn test_text_fit() {
+        let fit = TextFit { min_size: 10.0 };
+
+        // 放得下时原样返回
+        assert_eq!(fit.fit("abcd", 100.0, 20.0, measure), ("abcd".to_string(), 20.0));
+
+        // 10 个字 @20 = 100，收进 50 宽需要缩到 10
+        let (text, size) = fit.fit("abcdefghij", 50.0, 20.0, measure);
+        assert_eq!(text, "abcdefghij");
+        assert!(size <= 10.0 && measure(&text, size) <= 50.0);
+
+        // 最小字号仍放不下：截断加省略号
+        let (text, size) = fit.fit("abcdefghijklmnopqrst", 50.0, 20.0, measure);
+        assert_eq!(size, 10.0);
+        assert_eq!(text, "abcdefghi…");
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::input::TextEvent;
+    use lumina_ui::widgets::TextInputState;
+
+    fn insert(s: &str) -> TextEvent {
+        TextEvent::Insert(s.to_string())
+    }
+
+    #[test]
+    fn insert_respects_max_len() {
+        let mut state = TextInputState::default();
+        state.apply(&insert("Alice"), Some(3));
+        assert_eq!(state.text, "Ali");
+        state.apply(&insert("x"), Some(3));
+        assert_eq!(state.text, "Ali");
+        assert_eq!(state.cursor, 3);
+    }
+
+    #[test]
+    fn editing_multibyte_text_by_char() {
+        let mut state = TextInputState::new("李小明");
+        state.apply(&TextEvent::Left, None);
+        state.apply(&TextEvent::Backspace, None);
+        assert_eq!(state.text, "李明");
+        assert_eq!(state.cursor, 1);
+
+        state.apply(&insert("晓"), None);
+        assert_eq!(state.text, "李晓明");
+        state.apply(&TextEvent::Delete, None);
+        assert_eq!(state.text, "李晓");
+
+        state.apply(&TextEvent::Home, None);
+        state.apply(&TextEvent::Backspace, None);
+        assert_eq!(state.text, "李晓");
+        state.apply(&TextEvent::End, None);
+        state.apply(&TextEvent::Right, None);
+        assert_eq!(state.cursor, 2);
+    }
+
+    #[test]
+    fn submit_is_taken_once() {
+        let mut state = TextInputState::new("Bob");
+        state.apply(&insert("\n"), None);
+        assert_eq!(state.text, "Bob");
+        state.apply(&TextEvent::Submit, None);
+        assert!(state.take_submitted());
+        assert!(!state.take_submitted());
+    }
+}
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::widgets::ToastStack;
+
+    #[test]
+    fn toasts_stack_newest_first_and_expire() {
+        let mut toasts = ToastStack::new();
+        toasts.push("Quick saved", 1.0);
+        toasts.update(0.5);
+        toasts.push("Achievement", 2.0);
+        assert_eq!(toasts.texts(), vec!["Achievement", "Quick saved"]);
+
+        toasts.update(0.6);
+        assert_eq!(toasts.texts(), vec!["Achievement"]);
+        toasts.update(1.5);
+        assert!(toasts.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use lumina_ui::input::Interaction;
+    use lumina_ui::widgets::tooltip;
+    use lumina_ui::{Alignment, Color, Rect, ShaderSpec, Style, Transform, UiRenderer};
+
+    const AREA: Rect = Rect { x: 0.0, y: 0.0, w: 200.0, h: 120.0 };
+
+    /// 记录绘制调用，每个字宽为字号的一半
+    #[derive(Default)]
+    struct Recorder {
+        interaction: Option<Interaction>,
+        hover: f32,
+        texts: Vec<(String, Rect)>,
+        styles: Vec<Rect>,
+    }
+
+    impl UiRenderer for Recorder {
+        fn draw_style(&mut self, rect: Rect, _style: &Style) { self.styles.push(rect); }
+        fn draw_image(&mut self, _image_id: &str, _rect: Rect, _tint: Color) {}
+        fn draw_text(&mut self, text: &str, rect: Rect, _color: Color, _size: f32, _align: Alignment, _font: Option<&str>) {
+            self.texts.push((text.to_string(), rect));
+        }
+        fn measure_text(&mut self, _text: &str, _max_width: f32, size: f32, _font: Option<&str>) -> f32 { size }
+        fn measure_text_width(&mut self, text: &str, size: f32, _font: Option<&str>) -> f32 {
+            text.chars().count() as f32 * size * 0.5
+        }
+        fn draw_circle(&mut self, _center: (f32, f32), _radius: f32, _color: Color) {}
+        fn interact(&self, _rect: Rect) -> Interaction {
+            self.interaction.unwrap_or(Interaction::Hovered)
+        }
+        fn cursor_pos(&self) -> (f32, f32) { (50.0, 60.0) }
+        fn hover_time(&self, _rect: Rect) -> f32 { self.hover }
+        fn with_transform(&mut self, _transform: Transform, f: &mut dyn FnMut(&mut Self)) { f(self) }
+        fn time(&self) -> f32 { 0.0 }
+        fn measure_image(&mut self, _image_id: &str) -> Option<(f32, f32)> { None }
+        fn draw_shader(&mut self, _rect: Rect, _spec: ShaderSpec) {}
+    }
+
+    #[test]
+    fn appears_next_to_the_cursor_after_the_delay() {
+        let mut ui = Recorder { hover: 0.2, ..Default::default() };
+        assert!(!tooltip(&mut ui, AREA, "Sunset"));
+        assert!(ui.styles.is_empty() && ui.texts.is_empty());
+
+        ui.hover = 1.0;
+        assert!(tooltip(&mut ui, AREA, "Sunset"));
+        // 6 字 * 10 = 60 宽，四周留 10
+        assert_eq!(ui.styles, vec![Rect::new(66.0, 80.0, 80.0, 40.0)]);
+        assert_eq!(ui.texts, vec![("Sunset".to_string(), Rect::new(76.0, 90.0, 60.0, 20.0))]);
+    }
+
+    #[test]
+    fn hidden_while_pressed() {
+        let mut ui = Recorder { hover: 1.0, interaction: Some(Interaction::Held), ..Default::default() };
+        assert!(!tooltip(&mut ui, AREA, "Sunset"));
+        assert!(ui.styles.is_empty());
+    }
+}
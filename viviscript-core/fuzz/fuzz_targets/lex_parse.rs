@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use viviscript_core::lexer::Lexer;
+use viviscript_core::parser::Parser;
+
+fuzz_target!(|data: &[u8]| {
+    let src = String::from_utf8_lossy(data);
+    let tokens = Lexer::new(&src).run();
+    let _ = Parser::new(&tokens).parse();
+});
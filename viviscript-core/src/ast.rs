@@ -21,11 +21,19 @@ pub enum Stmt {
         name: String,
         image_tag: Option<String>,
         voice_tag: Option<String>,
+        /// Display color for the speaker name (`#rrggbb` or a color name).
+        color: Option<String>,
+        /// Multiplier over the global voice volume, clamped to `0.0..=2.0`.
+        voice_volume: Option<f32>,
+        /// Voice playback rate, clamped to `0.5..=2.0`.
+        voice_pitch: Option<f32>,
     },
     /// Defines a label that can be jumped to or called.
     Label {
         span: Span,
         id: String,
+        /// Human-readable name from a `-- @title: ...` comment on the line right above.
+        title: Option<String>,
         body: Vec<Stmt>
     },
     /// Displays a menu of choices to the player.
@@ -33,7 +41,13 @@ pub enum Stmt {
         span: Span,
         title: Option<String>,
         arms: Vec<ChoiceArm>,
+        /// Stable id written as `choice "title" as <id>`. When absent, preprocessing
+        /// generates a positional one (`<scope>@choice_<n>`) that shifts as the script is edited.
         id: Option<String>,
+        /// Seconds before `default` is picked automatically; `None` waits forever.
+        timeout: Option<f32>,
+        /// Zero-based arm picked on timeout.
+        default: usize,
     },
     /// Unconditional jump to another label.
     Jump {
@@ -56,11 +70,22 @@ pub enum Stmt {
         speaker: Speaker,
         text: String,
         voice_index: Option<String>,
+        /// Stable translation id, assigned during preprocessing.
+        tl_id: Option<String>,
+        /// `<label>_<n>` where `n` counts the dialogue lines of the enclosing
+        /// label from 1 (narration is not counted), assigned during preprocessing.
+        /// Used to look up voice files automatically.
+        voice_line: Option<String>,
     },
     /// Narration or internal monologue that does not belong to any character.
     Narration {
         span: Span,
         lines: Vec<String>,
+        /// Source span of each entry in `lines`. Preprocessing splits narration into
+        /// one statement per line, each taking its own entry as `span`.
+        line_spans: Vec<Span>,
+        /// Stable translation id, assigned during preprocessing.
+        tl_id: Option<String>,
     },
     /// Controls audio playback on a specific channel.
     Audio {
@@ -90,10 +115,31 @@ pub enum Stmt {
         image: Option<SceneImage>,
         transition: Option<Transition>
     },
+    /// Switches between textbox (ADV) and full-screen page (NVL) presentation.
+    Nvl {
+        span: Span,
+        action: NvlAction,
+    },
+    /// Plays a pre-rendered movie; the story waits until it ends or is skipped.
+    Movie {
+        span: Span,
+        id: String,
+        /// `false` when written with `noskip`.
+        skippable: bool,
+    },
+    /// Holds the story until the sound on `channel` finishes playing.
+    WaitAudio {
+        span: Span,
+        channel: String,
+    },
     /// Placeholder node emitted when the parser encounters a syntax error.
     Error {
         span: Span,
         msg: String,
+        /// `true` for low-severity problems (unknown parameters, stray tokens, `with`
+        /// without an effect) that do not fail [`crate::parser::Parser::parse`];
+        /// the runtime skips such statements with a warning.
+        recovered: bool,
     },
     If {
         span: Span,
@@ -122,6 +168,14 @@ pub enum AudioAction {
     Stop,
 }
 
+/// Available `nvl` actions.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum NvlAction {
+    On,
+    Off,
+    Clear,
+}
+
 /// Fine-grained configuration for an audio command.
 #[derive(Debug, PartialEq, Clone)]
 pub struct AudioOptions {
@@ -129,6 +183,12 @@ pub struct AudioOptions {
     pub fade_in: Option<f32>,
     pub fade_out: Option<f32>,
     pub r#loop: bool,
+    /// Loop region in seconds: the part before `loop_start` plays once as an intro;
+    /// `loop_end` defaults to the end of the track.
+    pub loop_start: Option<f32>,
+    pub loop_end: Option<f32>,
+    /// A separate track played once before `resource`, which starts exactly when it ends.
+    pub intro: Option<String>,
 }
 
 /// A single selectable option inside a `Choice`.
@@ -139,6 +199,25 @@ pub struct ChoiceArm {
 }
 
 /// Attribute modification for use in `Show`.
+///
+/// A sprite's attributes form an ordered set: the image asset is named
+/// `<target>_<attr0>_<attr1>…`, so position `i` is the attribute's "slot".
+/// When a `show` updates a sprite that is already on screen:
+///
+/// - The k-th `Add` of the statement (counting only additions) targets slot k.
+///   An attribute the sprite already has is left alone; otherwise it replaces
+///   the current value of slot k, or is appended when the sprite has fewer
+///   than k + 1 attributes. `show alice smile` on `alice happy glasses`
+///   therefore yields `alice smile glasses`.
+/// - A slot held by an attribute the same statement adds is never replaced;
+///   the new attribute is appended instead, so `show alice glasses smile` on
+///   `alice happy glasses` yields `alice happy glasses smile`.
+/// - An attribute that the statement both adds and removes counts as neither
+///   and takes no slot.
+/// - Every `Remove` is applied after the additions and deletes by value.
+///   Removing an attribute the sprite does not have is a warning and a no-op.
+///
+/// A newly shown sprite takes its `Add` attributes in order, without duplicates.
 #[derive(Debug, PartialEq, Clone)]
 pub enum ShowAttr {
     Add(String),
@@ -149,9 +228,13 @@ pub enum ShowAttr {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Transition {
     pub effect: String,
+    /// Seconds written inline as `with <effect> <secs>`, overriding the effect's registered duration.
+    pub duration: Option<f32>,
 }
 
 /// Configuration for a scene image.
+///
+/// The runtime resolves it to the asset `<prefix>_<attr0>_<attr1>...`, attributes in written order.
 #[derive(Debug, PartialEq, Clone)]
 pub struct SceneImage {
     pub prefix: String,
@@ -0,0 +1,86 @@
+//! `vivifmt [--check] <file|dir>...`
+//!
+//! Formats `.vivi` scripts in place; directories are searched recursively.
+//! With `--check` nothing is written and the files that would change are
+//! listed instead. Exits with 1 if a script fails to parse or, with
+//! `--check`, if any file is not formatted.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use viviscript_core::fmt::format_source;
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut roots = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--help" | "-h" => {
+                println!("Usage: vivifmt [--check] <file|dir>...");
+                return ExitCode::SUCCESS;
+            }
+            _ => roots.push(PathBuf::from(arg)),
+        }
+    }
+    if roots.is_empty() {
+        eprintln!("Usage: vivifmt [--check] <file|dir>...");
+        return ExitCode::from(2);
+    }
+
+    let mut files = Vec::new();
+    for root in &roots {
+        collect_scripts(root, &mut files);
+    }
+
+    let mut failed = false;
+    for path in &files {
+        let src = match std::fs::read_to_string(path) {
+            Ok(src) => src,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+        let formatted = match format_source(&src) {
+            Ok(out) => out,
+            Err(errors) => {
+                for e in errors {
+                    eprintln!("{}:{}: {}", path.display(), e.line, e.msg);
+                }
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == src {
+            continue;
+        }
+        if check {
+            println!("{}", path.display());
+            failed = true;
+        } else if let Err(e) = std::fs::write(path, formatted) {
+            eprintln!("{}: {}", path.display(), e);
+            failed = true;
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+/// `root` itself if it is a file, otherwise every `.vivi` file below it in path order.
+fn collect_scripts(root: &Path, out: &mut Vec<PathBuf>) {
+    if !root.is_dir() {
+        out.push(root.to_path_buf());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(root) else { return };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_scripts(&path, out);
+        } else if path.extension().is_some_and(|ext| ext == "vivi") {
+            out.push(path);
+        }
+    }
+}
@@ -0,0 +1,546 @@
+//! Canonical pretty-printer for `.vivi` scripts.
+//!
+//! The source is parsed and printed back from the AST, so the output parses
+//! to the same tree. Comments are not part of the AST: they are taken from the
+//! token stream and re-attached by line number. A comment on the line of a
+//! statement stays behind it, any other comment is printed above the next
+//! statement or block keyword. Lua blocks are written back verbatim, and a
+//! single blank line is kept wherever the source had blank lines.
+
+use std::collections::VecDeque;
+use unicode_xid::UnicodeXID;
+use crate::ast::{AudioAction, NvlAction, ShowAttr, Stmt, Transition, UiProp, UiStmt, ContainerKind, WidgetKind};
+use crate::lexer::{Lexer, Span, Tok, TokKind};
+use crate::parser::{ParseError, Parser};
+use crate::visit::{walk_stmt, walk_stmts, Visitor};
+
+const INDENT: &str = "    ";
+
+/// Formats a whole script. Fails with the parse errors if the source does not parse cleanly,
+/// including problems the parser recovered from.
+pub fn format_source(src: &str) -> Result<String, Vec<ParseError>> {
+    let src = src.replace("\r\n", "\n");
+    let toks = Lexer::new(&src).run();
+    let script = Parser::new(&toks).parse()?;
+    let mut recovered = RecoveredErrors(Vec::new());
+    walk_stmts(&script.body, &mut recovered);
+    if !recovered.0.is_empty() {
+        return Err(recovered.0);
+    }
+
+    let mut printer = Printer::new(&src, &toks);
+    for stmt in &script.body {
+        printer.stmt(stmt, 0);
+    }
+    Ok(printer.finish())
+}
+
+/// Collects the [`Stmt::Error`] nodes left by recovered parse problems.
+struct RecoveredErrors(Vec<ParseError>);
+
+impl Visitor for RecoveredErrors {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if let Stmt::Error { span, msg, .. } = stmt {
+            self.0.push(ParseError { line: span.line, msg: msg.clone() });
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+struct Printer<'a> {
+    src_lines: Vec<&'a str>,
+    /// `(line, text)` of every comment not yet printed, in source order.
+    comments: VecDeque<(usize, String)>,
+    /// Lines of block keywords (`enlb`, `elif`, choice arms, ...) in source order.
+    closers: VecDeque<usize>,
+    out: String,
+    /// Source line of the last thing printed.
+    line: usize,
+    /// Nothing has been printed in the current block yet.
+    fresh: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(src: &'a str, toks: &[Tok]) -> Self {
+        let mut comments = Vec::new();
+        let mut closers = VecDeque::new();
+        for (i, t) in toks.iter().enumerate() {
+            match &t.tok {
+                TokKind::Comment(text) => comments.push((t.span.line, text.trim_end().to_string())),
+                TokKind::Condition(_) => comments.extend(condition_comments(src, t.span)),
+                TokKind::EnLabel | TokKind::EnChoice | TokKind::EnIf | TokKind::Elif | TokKind::Else
+                | TokKind::EnScreen | TokKind::EnVBox | TokKind::EnHBox | TokKind::EnZBox | TokKind::EnFrame => {
+                    closers.push_back(t.span.line);
+                }
+                // A choice arm: a string that starts a line and is followed by `:`.
+                TokKind::Str(_) => {
+                    let at_line_start = i > 0 && toks[i - 1].tok == TokKind::Newline;
+                    if at_line_start && toks.get(i + 1).is_some_and(|n| n.tok == TokKind::Colon) {
+                        closers.push_back(t.span.line);
+                    }
+                }
+                _ => {}
+            }
+        }
+        comments.sort_by_key(|(line, _)| *line);
+
+        Self {
+            src_lines: src.lines().collect(),
+            comments: comments.into(),
+            closers,
+            out: String::new(),
+            line: 0,
+            fresh: true,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.leading(usize::MAX, 0);
+        self.out
+    }
+
+    /// Writes `text` as a line at `indent`, followed by the comment sitting on source line `line`.
+    fn emit(&mut self, indent: usize, line: usize, text: &str) {
+        for _ in 0..indent {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        if self.comments.front().is_some_and(|(l, _)| *l == line) {
+            let (_, comment) = self.comments.pop_front().unwrap();
+            self.out.push_str(" --");
+            self.out.push_str(&comment);
+        }
+        self.out.push('\n');
+    }
+
+    /// Keeps one blank line before an item that had blank lines above it in the source.
+    fn gap(&mut self, line: usize) {
+        let blank_above = line >= 2 && self.src_lines.get(line - 2).is_some_and(|l| l.trim().is_empty());
+        if blank_above && !self.fresh {
+            self.out.push('\n');
+        }
+        self.fresh = false;
+    }
+
+    /// Prints every pending comment from before `line` on its own line.
+    fn leading(&mut self, line: usize, indent: usize) {
+        while self.comments.front().is_some_and(|(l, _)| *l < line) {
+            let (l, comment) = self.comments.pop_front().unwrap();
+            self.gap(l);
+            self.emit(indent, l, &format!("--{}", comment));
+        }
+    }
+
+    /// Source line of the next block keyword.
+    fn next_closer(&mut self) -> usize {
+        while self.closers.front().is_some_and(|&l| l < self.line) {
+            self.closers.pop_front();
+        }
+        self.closers.pop_front().unwrap_or(self.line)
+    }
+
+    /// Prints a block keyword, with the comments before it indented as `comment_indent`.
+    fn keyword(&mut self, indent: usize, comment_indent: usize, text: &str) {
+        let line = self.next_closer();
+        self.leading(line, comment_indent);
+        self.line = line;
+        self.emit(indent, line, text);
+    }
+
+    /// Prints the `end...` keyword closing a block whose body is at `indent + 1`.
+    fn end(&mut self, indent: usize, text: &str) {
+        self.keyword(indent, indent + 1, text);
+        self.fresh = false;
+    }
+
+    fn block(&mut self, body: &[Stmt], indent: usize) {
+        self.fresh = true;
+        for stmt in body {
+            self.stmt(stmt, indent);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt, indent: usize) {
+        let line = stmt_span(stmt).line;
+        self.leading(line, indent);
+        self.gap(line);
+        self.line = line;
+
+        match stmt {
+            Stmt::CharacterDef { id, name, image_tag, voice_tag, color, voice_volume, voice_pitch, .. } => {
+                let mut text = format!("character {} name={}", id, quote(name));
+                for (key, val) in [("image_tag", image_tag), ("voice_tag", voice_tag), ("color", color)] {
+                    if let Some(val) = val {
+                        text.push_str(&format!(" {}={}", key, quote(val)));
+                    }
+                }
+                for (key, val) in [("voice_volume", voice_volume), ("voice_pitch", voice_pitch)] {
+                    if let Some(val) = val {
+                        text.push_str(&format!(" {}={}", key, val));
+                    }
+                }
+                self.emit(indent, line, &text);
+            }
+            Stmt::Label { id, body, .. } => {
+                self.emit(indent, line, &format!("label {}", id));
+                self.block(body, indent + 1);
+                self.end(indent, "enlb");
+            }
+            Stmt::Choice { title, arms, id, timeout, default, .. } => {
+                let mut text = "choice".to_string();
+                if let Some(title) = title {
+                    text.push(' ');
+                    text.push_str(&quote(title));
+                }
+                if let Some(id) = id {
+                    text.push_str(&format!(" as {}", id));
+                }
+                if let Some(timeout) = timeout {
+                    text.push_str(&format!(" timeout={}", timeout));
+                    if *default > 0 {
+                        text.push_str(&format!(" default={}", default + 1));
+                    }
+                }
+                self.emit(indent, line, &text);
+                self.fresh = true;
+                for arm in arms {
+                    let arm_line = self.next_closer();
+                    self.leading(arm_line, indent + 1);
+                    self.gap(arm_line);
+                    self.line = arm_line;
+                    self.emit(indent + 1, arm_line, &format!("{}:", quote(&arm.text)));
+                    self.block(&arm.body, indent + 2);
+                    self.fresh = false;
+                }
+                self.end(indent, "enco");
+            }
+            Stmt::Jump { target, .. } => self.emit(indent, line, &format!("jump {}", target)),
+            Stmt::Call { target, .. } => self.emit(indent, line, &format!("call {}", target)),
+            Stmt::LuaBlock { code, .. } => {
+                // `lua` keeps the whitespace after the keyword, `$` skips it and stops at the line end.
+                if code.is_empty() {
+                    self.emit(indent, line, "$");
+                } else if !code.contains('\n') && !code.starts_with(char::is_whitespace) {
+                    self.emit(indent, line, &format!("$ {}", code));
+                } else {
+                    self.emit(indent, line, &format!("lua{}enlua", code));
+                }
+            }
+            Stmt::Dialogue { speaker, text, voice_index, .. } => {
+                let raw = match voice_index {
+                    Some(index) => format!("{}({})", text, index),
+                    None => text.clone(),
+                };
+                let out = match &speaker.alias {
+                    // `:` after a quoted alias is plain punctuation, so the text has to be quoted too.
+                    Some(alias) if !is_word(alias, false, true) => {
+                        format!("{}@{}: {}", speaker.name, quote(alias), quote(&raw))
+                    }
+                    Some(alias) => format!("{}@{}{}", speaker.name, alias, colon_text(&raw)),
+                    None => format!("{}{}", speaker.name, colon_text(&raw)),
+                };
+                self.emit(indent, line, &out);
+            }
+            Stmt::Narration { lines, .. } => {
+                let text = match lines.as_slice() {
+                    [] => ":".to_string(),
+                    [only] => format!(": {}", only),
+                    [first, rest @ ..] => {
+                        // The parser strips the common indentation, so every line is re-indented to the block.
+                        let pad = INDENT.repeat(indent);
+                        let mut text = format!(":\"\"\"\n{}{}", pad, escape_triple(first));
+                        for l in rest {
+                            text.push('\n');
+                            if !l.is_empty() {
+                                text.push_str(&pad);
+                                text.push_str(&escape_triple(l));
+                            }
+                        }
+                        text.push_str(&format!("\n{}\"\"\"", pad));
+                        text
+                    }
+                };
+                self.emit(indent, line, &text);
+            }
+            Stmt::Audio { action: AudioAction::Play, channel, resource, options, .. } => {
+                let mut text = format!("play {} {}", atom(channel, false, false), atom(resource.as_deref().unwrap_or(""), false, false));
+                let nums = [
+                    ("volume", options.volume),
+                    ("fade_in", options.fade_in),
+                    ("fade_out", options.fade_out),
+                    ("loop_start", options.loop_start),
+                    ("loop_end", options.loop_end),
+                ];
+                for (key, val) in nums {
+                    if let Some(val) = val {
+                        text.push_str(&format!(" {}={}", key, val));
+                    }
+                }
+                if let Some(intro) = &options.intro {
+                    text.push_str(&format!(" intro={}", atom(intro, false, false)));
+                }
+                // A loop region already implies `loop`.
+                if options.r#loop && options.loop_start.is_none() && options.loop_end.is_none() {
+                    text.push_str(" loop");
+                }
+                self.emit(indent, line, &text);
+            }
+            Stmt::Audio { action: AudioAction::Stop, channel, options, .. } => {
+                let mut text = format!("stop {}", atom(channel, false, false));
+                if let Some(fade_out) = options.fade_out {
+                    text.push_str(&format!(" fade_out={}", fade_out));
+                }
+                self.emit(indent, line, &text);
+            }
+            Stmt::Hide { target, transition, .. } => {
+                let mut text = format!("hide {}", atom(target, true, false));
+                if let Some(t) = transition {
+                    text.push_str(&with_clause(t));
+                }
+                self.emit(indent, line, &text);
+            }
+            Stmt::Show { target, attrs, position, transition, .. } => {
+                let mut text = format!("show {}", atom(target, true, false));
+                for attr in attrs.iter().flatten() {
+                    match attr {
+                        ShowAttr::Add(a) => text.push_str(&format!(" {}", atom(a, false, true))),
+                        ShowAttr::Remove(a) => text.push_str(&format!(" -{}", atom(a, false, false))),
+                    }
+                }
+                if let Some(position) = position {
+                    text.push_str(&format!(" at {}", atom(position, false, false)));
+                }
+                if let Some(t) = transition {
+                    text.push_str(&with_clause(t));
+                }
+                self.emit(indent, line, &text);
+            }
+            Stmt::Scene { image, transition, .. } => {
+                let mut text = "scene".to_string();
+                if let Some(image) = image {
+                    text.push_str(&format!(" {}", atom(&image.prefix, true, true)));
+                    for attr in image.attrs.iter().flatten() {
+                        text.push_str(&format!(" {}", atom(attr, false, true)));
+                    }
+                }
+                if let Some(t) = transition {
+                    text.push_str(&with_clause(t));
+                }
+                self.emit(indent, line, &text);
+            }
+            Stmt::Nvl { action, .. } => {
+                let action = match action {
+                    NvlAction::On => "on",
+                    NvlAction::Off => "off",
+                    NvlAction::Clear => "clear",
+                };
+                self.emit(indent, line, &format!("nvl {}", action));
+            }
+            Stmt::Movie { id, skippable, .. } => {
+                let noskip = if *skippable { "" } else { " noskip" };
+                self.emit(indent, line, &format!("movie {}{}", atom(id, true, false), noskip));
+            }
+            Stmt::WaitAudio { channel, .. } => {
+                self.emit(indent, line, &format!("wait_audio {}", atom(channel, false, false)));
+            }
+            // Never reached: `format_source` refuses scripts with error placeholders.
+            Stmt::Error { .. } => {}
+            Stmt::If { branches, else_branch, .. } => {
+                for (i, (cond, body)) in branches.iter().enumerate() {
+                    if i == 0 {
+                        self.emit(indent, line, &format!("if {}", cond));
+                    } else {
+                        self.keyword(indent, indent + 1, &format!("elif {}", cond));
+                    }
+                    self.block(body, indent + 1);
+                }
+                if let Some(body) = else_branch {
+                    self.keyword(indent, indent + 1, "else");
+                    self.block(body, indent + 1);
+                }
+                self.end(indent, "enif");
+            }
+            Stmt::ScreenDef { id, root, .. } => {
+                self.emit(indent, line, &format!("screen {}", id));
+                self.fresh = true;
+                for node in root {
+                    self.ui(node, indent + 1);
+                }
+                self.end(indent, "enscreen");
+            }
+        }
+    }
+
+    fn ui(&mut self, node: &UiStmt, indent: usize) {
+        let line = match node {
+            UiStmt::Container { span, .. } | UiStmt::Widget { span, .. } => span.line,
+        };
+        self.leading(line, indent);
+        self.gap(line);
+        self.line = line;
+
+        match node {
+            UiStmt::Container { kind, props, children, .. } => {
+                let (open, close) = match kind {
+                    ContainerKind::VBox => ("vbox", "envbox"),
+                    ContainerKind::HBox => ("hbox", "enhbox"),
+                    ContainerKind::ZBox => ("zbox", "enzbox"),
+                    ContainerKind::Frame => ("frame", "enframe"),
+                };
+                self.emit(indent, line, &format!("{}{}", open, ui_props(props)));
+                self.fresh = true;
+                for child in children {
+                    self.ui(child, indent + 1);
+                }
+                self.end(indent, close);
+            }
+            UiStmt::Widget { kind, value, props, .. } => {
+                let mut text = match kind {
+                    WidgetKind::Button => "button",
+                    WidgetKind::Image => "image",
+                    WidgetKind::Text => "text",
+                }.to_string();
+                if let Some(value) = value {
+                    text.push(' ');
+                    text.push_str(&quote(value));
+                }
+                text.push_str(&ui_props(props));
+                self.emit(indent, line, &text);
+            }
+        }
+    }
+}
+
+fn stmt_span(stmt: &Stmt) -> Span {
+    match stmt {
+        Stmt::CharacterDef { span, .. }
+        | Stmt::Label { span, .. }
+        | Stmt::Choice { span, .. }
+        | Stmt::Jump { span, .. }
+        | Stmt::Call { span, .. }
+        | Stmt::LuaBlock { span, .. }
+        | Stmt::Dialogue { span, .. }
+        | Stmt::Narration { span, .. }
+        | Stmt::Audio { span, .. }
+        | Stmt::Hide { span, .. }
+        | Stmt::Show { span, .. }
+        | Stmt::Scene { span, .. }
+        | Stmt::Nvl { span, .. }
+        | Stmt::Movie { span, .. }
+        | Stmt::WaitAudio { span, .. }
+        | Stmt::Error { span, .. }
+        | Stmt::If { span, .. }
+        | Stmt::ScreenDef { span, .. } => *span,
+    }
+}
+
+fn with_clause(t: &Transition) -> String {
+    match t.duration {
+        Some(secs) => format!(" with {} {}", atom(&t.effect, false, false), secs),
+        None => format!(" with {}", atom(&t.effect, false, false)),
+    }
+}
+
+fn ui_props(props: &[UiProp]) -> String {
+    props.iter()
+        .map(|p| format!(" {}={}", p.key, atom(&p.val, false, false)))
+        .collect()
+}
+
+/// The text after a speaker or narration `:`. The rest of the line is taken
+/// verbatim, so only text that cannot survive that is triple-quoted.
+fn colon_text(text: &str) -> String {
+    if text.is_empty() || text.contains('\n') || text.starts_with(char::is_whitespace) {
+        format!(":\"\"\"{}\"\"\"", escape_triple(text))
+    } else {
+        format!(": {}", text)
+    }
+}
+
+/// Writes `s` bare when it lexes back as a single word, quoted otherwise.
+/// `resource` allows path names as after `show`; `ident_only` rejects
+/// parameter keys and flags where the parser only takes identifiers.
+fn atom(s: &str, resource: bool, ident_only: bool) -> String {
+    if is_word(s, resource, ident_only) {
+        s.to_string()
+    } else {
+        quote(s)
+    }
+}
+
+fn is_word(s: &str, resource: bool, ident_only: bool) -> bool {
+    let plain = !s.is_empty()
+        && s.chars().all(|c| UnicodeXID::is_xid_continue(c) || "_/.-{}".contains(c));
+    if !plain {
+        return false;
+    }
+    let probe = format!("{} {}", if resource { "show" } else { "=" }, s);
+    match Lexer::new(&probe).run().as_slice() {
+        [_, word, eof] if eof.tok == TokKind::Eof && word.tok.as_str() == Some(s) => match word.tok {
+            TokKind::Ident(_) => true,
+            TokKind::ParamKey(_) | TokKind::Flag(_) => !ident_only,
+            _ => false,
+        },
+        // Numbers come back through `f64::to_string`, so only write them bare when that is lossless.
+        [_, num, eof] if eof.tok == TokKind::Eof => match num.tok {
+            TokKind::Num(n) => !ident_only && n.to_string() == s,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// A double-quoted string literal for `s`.
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Content of a triple-quoted string for `s`; line breaks stay literal.
+fn escape_triple(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\r', "\\r")
+}
+
+/// The lexer drops comments inside `if` / `elif` conditions; read them back from the source.
+fn condition_comments(src: &str, span: Span) -> Vec<(usize, String)> {
+    let mut out = Vec::new();
+    let Some(text) = src.get(span.start..span.end) else { return out };
+    let mut line = span.line;
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\n' => {
+                line += 1;
+                quote = None;
+            }
+            '\\' if quote.is_some() => {
+                chars.next_if(|&(_, n)| n != '\n');
+            }
+            c if Some(c) == quote => quote = None,
+            _ if quote.is_some() => {}
+            '"' | '\'' => quote = Some(c),
+            '-' if text[i + 1..].starts_with('-') => {
+                let body = &text[i + 2..];
+                let end = body.find('\n').unwrap_or(body.len());
+                out.push((line, body[..end].trim_end().to_string()));
+                // Leave the line break for the loop to count.
+                while chars.next_if(|&(_, n)| n != '\n').is_some() {}
+            }
+            _ => {}
+        }
+    }
+    out
+}
@@ -6,6 +6,7 @@
 //! 
 
 use std::iter::Peekable;
+use std::ops::Range;
 use std::str::Chars;
 use unicode_xid::UnicodeXID;
 
@@ -30,6 +31,7 @@ pub enum TokKind {
     Character,
     Scene, Show, Hide, Play, Stop, 
     Label, Choice, Lua, Jump, Call,
+    Nvl, Movie, WaitAudio,
 
     If, Else, Elif, EnIf,
     Condition(String),
@@ -39,6 +41,10 @@ pub enum TokKind {
     LuaBlock(String),
     Ident(String),
     Str(String),
+    /// Literal part of a string, only emitted in interpolation mode.
+    StrPart(String),
+    /// `{expr}` inside a string, only emitted in interpolation mode.
+    Interp(String),
     Num(f64),
     Colon,
     At, Equals, Minus, Dollar,
@@ -83,16 +89,72 @@ define_content_access!(
     Reserved(String),
     Flag(String),
     Str(String),
+    StrPart(String),
+    Interp(String),
     Ident(String),
     LuaBlock(String),
     Condition(String),
     Comment(String),
 );
 
+/// A piece of string content split at `{expr}` interpolations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrPiece {
+    Text(String),
+    Interp(String),
+}
+
+/// Split string content into literal text and `{expr}` interpolations.
+///
+/// Follows the runtime rules: `{{` is a literal `{`, `\{...}` is kept as
+/// literal `{...}` (a `\` before the closing brace is dropped too), and an
+/// empty or unclosed `{` is plain text. Each piece comes with its byte range
+/// in `content`; an interpolation's range includes the braces.
+pub fn split_interpolation(content: &str) -> Vec<(StrPiece, Range<usize>)> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    fn flush(text: &mut String, range: Range<usize>, pieces: &mut Vec<(StrPiece, Range<usize>)>) {
+        if !text.is_empty() {
+            pieces.push((StrPiece::Text(std::mem::take(text)), range));
+        }
+    }
+
+    while i < content.len() {
+        let rest = &content[i..];
+        let escaped_end = rest.strip_prefix("\\{").and_then(|r| r.find('}'));
+        if rest.starts_with("{{") {
+            text.push('{');
+            i += 2;
+        } else if let Some(end) = escaped_end {
+            // An escaped tag stays literal, braces included.
+            let inner = &rest[2..end + 2];
+            text.push('{');
+            text.push_str(inner.strip_suffix('\\').unwrap_or(inner));
+            text.push('}');
+            i += end + 3;
+        } else if let Some(end) = rest.strip_prefix('{').and_then(|r| r.find('}')).filter(|&e| e > 0) {
+            flush(&mut text, text_start..i, &mut pieces);
+            pieces.push((StrPiece::Interp(rest[1..end + 1].to_string()), i..i + end + 2));
+            i += end + 2;
+            text_start = i;
+        } else {
+            let c = rest.chars().next().unwrap();
+            text.push(c);
+            i += c.len_utf8();
+        }
+    }
+    flush(&mut text, text_start..i, &mut pieces);
+    pieces
+}
+
 /// Lexical mode the lexer is currently in.
 
 /// All tokens that can be produced by the lexer.
 pub struct Lexer<'a> {
+    src: &'a str,
     /// Character iterator with one-character look-ahead.
     chars: Peekable<Chars<'a>>,
     /// Current line number (1-based).
@@ -101,17 +163,29 @@ pub struct Lexer<'a> {
     col: usize,
     /// Are we lexing inside a choice block?
     offset: usize,
+    /// Emit `StrPart` / `Interp` sequences instead of a single `Str`.
+    interpolation: bool,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(src: &'a str) -> Self {
         Lexer {
+            src,
             chars: src.chars().peekable(),
             line: 1,
             col: 0,
             offset: 0,
+            interpolation: false,
         }
     }
+
+    /// Interpolation mode, for editor tooling: every string is emitted as a
+    /// sequence of `StrPart` and `Interp` tokens whose spans point at the
+    /// source. The parser expects the default mode.
+    pub fn with_interpolation(mut self, on: bool) -> Self {
+        self.interpolation = on;
+        self
+    }
     
     /// Advance the cursor by one character, updating line/column bookkeeping.
     fn bump(&mut self) -> Option<char> {
@@ -159,48 +233,124 @@ impl<'a> Lexer<'a> {
     }
 
     /// Convert an escape sequence into the corresponding character.
-    fn consume_escape(&mut self) -> char {
+    /// An invalid `\u{…}` sequence is dropped with a warning.
+    fn consume_escape(&mut self) -> Option<char> {
         match self.bump() {
-            Some('n') => '\n',
-            Some('t') => '\t',
-            Some('r') => '\r',
-            Some('"') => '"',
-            Some('\'') => '\'',
-            Some('\\') => '\\',
-            Some(c) => c,
-            None => '\\',
+            Some('u') => match unicode_escape(&self.src[self.offset..]) {
+                Some((c, len)) => {
+                    for _ in 0..len { self.bump(); }
+                    Some(c)
+                }
+                None => {
+                    log::warn!("line {}: invalid unicode escape, expected \\u{{XXXX}}", self.line);
+                    None
+                }
+            },
+            Some(c) => Some(escape_char(c)),
+            None => Some('\\'),
+        }
+    }
+
+    /// Push a string token, split at interpolations in interpolation mode.
+    /// `escapes` tells whether the source between `start` and `end` had its
+    /// back-slash escapes resolved (quoted strings) or is taken verbatim.
+    fn push_str(&self, tokens: &mut Vec<Tok>, content: String, start: usize, end: usize, escapes: bool) {
+        if !self.interpolation {
+            tokens.push(Tok { tok: TokKind::Str(content), span: Span { start, end, line: self.line } });
+            return;
+        }
+
+        // Source offset of every content byte; an escaped char maps to its back-slash.
+        // Verbatim content may follow skipped leading whitespace, so align it to `end`.
+        let start = if escapes { start } else { end - content.len() };
+        let raw = &self.src[start..end];
+        let mut map = Vec::with_capacity(content.len() + 1);
+        let mut chars = raw.char_indices();
+        while let Some((i, c)) = chars.next() {
+            let out = if escapes && c == '\\' {
+                match chars.next() {
+                    Some((j, 'u')) => unicode_escape(&raw[j + 1..]).map(|(e, len)| {
+                        for _ in 0..len { chars.next(); }
+                        e
+                    }),
+                    Some((_, e)) => Some(escape_char(e)),
+                    None => Some('\\'),
+                }
+            } else {
+                Some(c)
+            };
+            if let Some(out) = out {
+                map.extend(std::iter::repeat_n(start + i, out.len_utf8()));
+            }
+        }
+        map.push(end);
+
+        for (piece, range) in split_interpolation(&content) {
+            let span = Span { start: map[range.start], end: map[range.end], line: self.line };
+            let tok = match piece {
+                StrPiece::Text(t) => TokKind::StrPart(t),
+                StrPiece::Interp(e) => TokKind::Interp(e),
+            };
+            tokens.push(Tok { tok, span });
         }
     }
 
     /// Parse a quoted string until `delim` is reached.
     /// Handles `\"`, `\'`, and other back-slash escapes.
-    fn string_literal(&mut self, delim: char) -> String {
+    /// Returns the content and whether the closing delimiter was found.
+    fn string_literal(&mut self, delim: char) -> (String, bool) {
         let mut out = String::new();
         while let Some(c) = self.bump() {
             match c {
-                '\\' => out.push(self.consume_escape()),
-                c if c == delim => break,
+                '\\' => out.extend(self.consume_escape()),
+                c if c == delim => return (out, true),
                 _ => out.push(c),
             }
         }
-        out
+        log::warn!("line {}: unterminated string literal", self.line);
+        (out, false)
     }
 
     /// Parse a triple-quoted string `""" … """`.
-    fn triple_quote(&mut self) -> String {
+    /// Returns the content and whether the closing quotes were found.
+    fn triple_quote(&mut self) -> (String, bool) {
         let mut out = String::new();
         while let Some(c) = self.bump() {
             if c == '"' && self.peek() == Some('"') && self.peek_nth(1) == Some('"') {
                 for _ in 0..2{self.bump();}
-                break;
+                return (out, true);
             }
             if c == '\\' {
-                out.push(self.consume_escape());
+                out.extend(self.consume_escape());
             } else {
                 out.push(c);
             }
         }
-        out
+        log::warn!("line {}: unterminated triple-quoted string", self.line);
+        (out, false)
+    }
+
+    /// Parse a raw string until `delim` (or `"""` when `triple`) is reached.
+    /// Back-slashes are taken literally, so the delimiter cannot appear inside.
+    /// Returns the content and whether the closing delimiter was found.
+    fn raw_string(&mut self, delim: char, triple: bool) -> (String, bool) {
+        let mut out = String::new();
+        while let Some(c) = self.bump() {
+            let closes = c == delim
+                && (!triple || (self.peek() == Some(delim) && self.peek_nth(1) == Some(delim)));
+            if closes {
+                if triple { for _ in 0..2 { self.bump(); } }
+                return (out, true);
+            }
+            out.push(c);
+        }
+        log::warn!("line {}: unterminated raw string literal", self.line);
+        (out, false)
+    }
+
+    /// End of a string token's content, excluding the closing delimiter if present.
+    fn string_end(&self, closed: bool, delim_len: usize) -> usize {
+        if closed { self.offset - delim_len } else { self.offset }
     }
     
     /// Parse the remainder of a `:` line as a string.
@@ -250,6 +400,9 @@ impl<'a> Lexer<'a> {
             "lua" => TokKind::Lua,
             "jump" => TokKind::Jump,
             "call" => TokKind::Call,
+            "nvl" => TokKind::Nvl,
+            "movie" => TokKind::Movie,
+            "wait_audio" => TokKind::WaitAudio,
 
             "if" => TokKind::If,
             "else" => TokKind::Else,
@@ -275,34 +428,82 @@ impl<'a> Lexer<'a> {
             "text" => TokKind::Text,
 
             "with" | "at" | "as"=> TokKind::Reserved(s),
-            "loop" | "noloop" => TokKind::Flag(s),
-            "volume" | "fade_in" | "fade_out" | "image_tag" | "name" | "voice_tag"=> {
+            "loop" | "noloop" | "noskip" => TokKind::Flag(s),
+            "volume" | "fade_in" | "fade_out" | "image_tag" | "name" | "voice_tag" | "color"
+            | "timeout" | "default" | "loop_start" | "loop_end" | "voice_volume" | "voice_pitch" => {
                 TokKind::ParamKey(s)
             }
+            // `intro` is a common label name, so it is only a key when directly followed by `=`.
+            "intro" if self.peek() == Some('=') => TokKind::ParamKey(s),
             _ => TokKind::Ident(s),
         }
     }
 
-    /// Slurp everything until the terminating `enlua` keyword.
-    fn lua_block(&mut self) -> String {
-        let mut out = String::new();
-        while let Some(c) = self.bump() {
-            let mut look = String::new();
-            let mut iter = self.chars.clone();
-            for _ in 0..5 {
-                if let Some(ch) = iter.next() {
-                    look.push(ch);
-                } else {
+    /// Continue an identifier in resource-name position as a path:
+    /// `/`, `.` and `-` are accepted when directly followed by an identifier
+    /// character, so `bg/room` or `cg.special` stay a single token.
+    fn resource_path_tail(&mut self, s: &mut String) -> bool {
+        let mut extended = false;
+        while let Some(c) = self.peek() {
+            let is_sep = matches!(c, '/' | '.' | '-');
+            let next_ok = self.peek_nth(1).is_some_and(|n| UnicodeXID::is_xid_continue(n) || n == '_');
+            if UnicodeXID::is_xid_continue(c) || c == '_' || (is_sep && next_ok) {
+                s.push(self.bump().unwrap());
+                extended |= is_sep;
+            } else {
+                break;
+            }
+        }
+        extended
+    }
+
+    /// Extend a name with `{expr}` segments and the name characters following
+    /// them. The braces are kept; the runtime evaluates them. Returns whether
+    /// anything was appended.
+    fn interp_word_tail(&mut self, s: &mut String) -> bool {
+        let mut extended = false;
+        while self.peek() == Some('{') {
+            while let Some(c) = self.peek() {
+                if c == '\n' {
+                    break;
+                }
+                s.push(self.bump().unwrap());
+                if c == '}' {
                     break;
                 }
             }
-            if look == "enlua" {
-                break
+            self.resource_path_tail(s);
+            extended = true;
+        }
+        extended
+    }
+
+    /// Slurp everything until the terminating `enlua` keyword.
+    ///
+    /// `enlua` only ends the block when it stands as a separate word: preceded
+    /// by whitespace (or the start of the block) and followed by whitespace or
+    /// the end of input. Identifiers such as `enluax` and string contents such
+    /// as `print("enlua")` stay part of the Lua code.
+    fn lua_block(&mut self) -> String {
+        let mut out = String::new();
+        let mut at_boundary = true;
+        loop {
+            if at_boundary && self.at_enlua() {
+                break;
             }
+            let Some(c) = self.bump() else { break };
+            at_boundary = c.is_whitespace();
             out.push(c);
         }
         out
     }
+
+    /// Whether the input continues with `enlua` followed by whitespace or EOF.
+    fn at_enlua(&self) -> bool {
+        let mut iter = self.chars.clone();
+        "enlua".chars().all(|e| iter.next() == Some(e))
+            && iter.next().is_none_or(char::is_whitespace)
+    }
     
     /// Parse a number literal or fall back to an identifier.
     fn number_or_ident(&mut self, first: char) -> TokKind {
@@ -312,7 +513,7 @@ impl<'a> Lexer<'a> {
 
         while let Some(&c) = self.chars.peek(){
             match c {
-                '0'..'9' => s.push(self.bump().unwrap()),
+                '0'..='9' => s.push(self.bump().unwrap()),
                 '.' if allow_dot => {
                     allow_dot = false;
                     s.push(self.bump().unwrap());
@@ -346,19 +547,79 @@ impl<'a> Lexer<'a> {
         TokKind::Num(val)
     }
 
+    /// Reads an `if` / `elif` condition. It continues onto the next line while a
+    /// bracket is open or the line ends with `and`, `or` or `\`; brackets inside
+    /// strings and `--` comments do not count.
     fn read_condition_line(&mut self) -> String {
         let mut out = String::new();
+        let mut depth = 0usize;
+        let mut quote = None;
         self.skip_space_no_nl();
 
         while let Some(c) = self.peek() {
-            if c == '\n' {
-                break;
+            if let Some(q) = quote {
+                // An unterminated string ends at the line break.
+                if c == '\n' {
+                    quote = None;
+                    continue;
+                }
+                out.push(self.bump().unwrap());
+                if c == '\\' {
+                    if let Some(next) = self.peek().filter(|&n| n != '\n') {
+                        self.bump();
+                        out.push(next);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+                continue;
             }
 
-            if c == '-' && self.peek_nth(1) == Some('-') {
-                break;
+            match c {
+                '\n' => {
+                    let end = out.trim_end().len();
+                    out.truncate(end);
+                    let backslash = out.ends_with('\\');
+                    let continues = depth > 0
+                        || backslash
+                        || ends_with_keyword(&out, "and")
+                        || ends_with_keyword(&out, "or");
+                    if !continues {
+                        break;
+                    }
+                    if backslash {
+                        out.pop();
+                        let end = out.trim_end().len();
+                        out.truncate(end);
+                    }
+                    self.bump();
+                    self.skip_space_no_nl();
+                    // A blank line ends the condition even if a bracket was left open.
+                    if matches!(self.peek(), Some('\n') | None) {
+                        break;
+                    }
+                    out.push(' ');
+                }
+                // The comment is dropped; the line break after it decides whether to continue.
+                '-' if self.peek_nth(1) == Some('-') => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                '"' | '\'' => {
+                    quote = Some(c);
+                    out.push(self.bump().unwrap());
+                }
+                '(' | '[' | '{' => {
+                    depth += 1;
+                    out.push(self.bump().unwrap());
+                }
+                ')' | ']' | '}' => {
+                    depth = depth.saturating_sub(1);
+                    out.push(self.bump().unwrap());
+                }
+                _ => out.push(self.bump().unwrap()),
             }
-            out.push(self.bump().unwrap());
         }
 
         let trimmed = out.trim();
@@ -415,19 +676,32 @@ impl<'a> Lexer<'a> {
                 if self.peek() == Some('"') && self.peek_nth(1) == Some('"') {
                     for _ in 0..2 {self.bump();}
                     let start = self.offset;
-                    let content = self.triple_quote();
-                    tokens.push(Tok{tok: TokKind::Str(content),span:Span{start,end:self.offset - 3, line: self.line}});
+                    let (content, closed) = self.triple_quote();
+                    self.push_str(tokens, content, start, self.string_end(closed, 3), true);
                 } else {
                     let start = self.offset;
-                    let content = self.string_literal('"');
-                    tokens.push(Tok{tok: TokKind::Str(content),span:Span{start,end:self.offset - 1, line: self.line}});
+                    let (content, closed) = self.string_literal('"');
+                    self.push_str(tokens, content, start, self.string_end(closed, 1), true);
                 }
             }
             '\'' => {
                 self.bump();
                 let start = self.offset;
-                let content = self.string_literal('\'');
-                tokens.push(Tok{tok: TokKind::Str(content),span:Span{start,end:self.offset - 1, line: self.line}});
+                let (content, closed) = self.string_literal('\'');
+                self.push_str(tokens, content, start, self.string_end(closed, 1), true);
+            },
+            'r' if matches!(self.peek_nth(1), Some('"' | '\'')) => {
+                // Raw string: `r"…"`, `r'…'` or `r"""…"""`, no escape processing.
+                self.bump();
+                let delim = self.bump().unwrap();
+                let triple = delim == '"' && self.peek() == Some('"') && self.peek_nth(1) == Some('"');
+                if triple {
+                    for _ in 0..2 { self.bump(); }
+                }
+                let start = self.offset;
+                let (content, closed) = self.raw_string(delim, triple);
+                let delim_len = if triple { 3 } else { 1 };
+                self.push_str(tokens, content, start, self.string_end(closed, delim_len), false);
             },
             ':' => {
                 let last_tok = tokens.last().map(|t| &t.tok);
@@ -439,16 +713,15 @@ impl<'a> Lexer<'a> {
                 if self.peek_nth(1) == Some('"') && self.peek_nth(2) == Some('"') && self.peek_nth(3) == Some('"') {
                     for _ in 0..4 {self.bump();}
                     let start = self.offset;
-                    let mut content = String::new();
-                    content.push_str(&self.triple_quote());
-                    tokens.push(self.tok(TokKind::Str(content), start)); 
+                    let (content, closed) = self.triple_quote();
+                    self.push_str(tokens, content, start, self.string_end(closed, 3), true);
                 } else if is_start_of_line || is_after_ident {
                     self.bump(); // 吃掉冒号
                     let start = self.offset;
                     let content = self.colon_line();
 
                     if !content.is_empty() {
-                        tokens.push(self.tok(TokKind::Str(content), start));
+                        self.push_str(tokens, content, start, self.offset, false);
                     }
                 } else {
                     self.bump();
@@ -466,6 +739,13 @@ impl<'a> Lexer<'a> {
                 }
                 tokens.push(self.tok(TokKind::Comment(comments),start));
             },
+            '{' => {
+                // A name starting with an interpolation, e.g. `show {f.outfit}`.
+                let start = self.offset;
+                let mut name = String::new();
+                self.interp_word_tail(&mut name);
+                tokens.push(self.tok(TokKind::Ident(name), start));
+            },
             '@' => {
                 tokens.push(self.tok_one_str(TokKind::At));
                 self.bump();
@@ -494,7 +774,29 @@ impl<'a> Lexer<'a> {
             c if UnicodeXID::is_xid_continue(c) || c == '_' => {
                 let start = self.offset;
                 let ch = self.bump().unwrap();
-                let tok = self.keyword_or_ident(ch);
+                let mut tok = self.keyword_or_ident(ch);
+
+                // Resource names after `scene` / `show` / `hide` / `movie` may be paths.
+                let at_resource = matches!(
+                    tokens.last().map(|t| &t.tok),
+                    Some(TokKind::Scene | TokKind::Show | TokKind::Hide | TokKind::Movie)
+                );
+                if at_resource {
+                    let mut path = match &tok {
+                        TokKind::Ident(s) | TokKind::Reserved(s) | TokKind::Flag(s) | TokKind::ParamKey(s) => s.clone(),
+                        _ => String::new(),
+                    };
+                    if !path.is_empty() && self.resource_path_tail(&mut path) {
+                        tok = TokKind::Ident(path);
+                    }
+                }
+                // A name may embed `{expr}` segments, e.g. `bg_{f.time}`.
+                if let TokKind::Ident(name) = &tok {
+                    let mut name = name.clone();
+                    if self.interp_word_tail(&mut name) {
+                        tok = TokKind::Ident(name);
+                    }
+                }
 
                 let is_cond_kw = matches!(tok, TokKind::If|TokKind::Elif);
 
@@ -505,10 +807,15 @@ impl<'a> Lexer<'a> {
                     tokens.push(self.tok(TokKind::LuaBlock(content),start + 4));
                 } else if is_cond_kw {
                     let cond_start = self.offset;
+                    let cond_line = self.line;
                     let cond_str = self.read_condition_line();
 
                     if !cond_str.is_empty() {
-                        tokens.push(self.tok(TokKind::Condition(cond_str), cond_start))
+                        // A condition continued over several lines is reported at its first line.
+                        tokens.push(Tok {
+                            tok: TokKind::Condition(cond_str),
+                            span: Span { start: cond_start, end: self.offset, line: cond_line },
+                        });
                     }
                 }
             },
@@ -518,4 +825,34 @@ impl<'a> Lexer<'a> {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Whether `text` ends with the word `kw` (not just a longer identifier ending in it).
+fn ends_with_keyword(text: &str, kw: &str) -> bool {
+    text.strip_suffix(kw)
+        .is_some_and(|rest| !rest.chars().next_back().is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.'))
+}
+
+/// Parse the `{XXXX}` part of a `\u{XXXX}` escape at the start of `rest`:
+/// one to six hex digits naming a valid codepoint. Returns the character and
+/// the byte length of the braced part.
+fn unicode_escape(rest: &str) -> Option<(char, usize)> {
+    let body = rest.strip_prefix('{')?;
+    let end = body.find('}')?;
+    let hex = &body[..end];
+    if hex.is_empty() || hex.len() > 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let c = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+    Some((c, end + 2))
+}
+
+/// Character produced by the escape sequence `\c`.
+fn escape_char(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        other => other,
+    }
+}
@@ -1,3 +1,5 @@
 pub mod lexer;
 pub mod ast;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod fmt;
+pub mod visit;
\ No newline at end of file
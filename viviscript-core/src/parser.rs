@@ -4,10 +4,10 @@
 //! with a descriptive message.  This keeps the implementation small and makes
 //! test failures easy to diagnose.
 
-use crate::ast::{AudioAction, AudioOptions, ChoiceArm, ContainerKind, SceneImage, Script, ShowAttr, Speaker, Stmt, Transition, UiProp, UiStmt, WidgetKind};
+use crate::ast::{AudioAction, AudioOptions, ChoiceArm, ContainerKind, NvlAction, SceneImage, Script, ShowAttr, Speaker, Stmt, Transition, UiProp, UiStmt, WidgetKind};
 use crate::lexer::{Span, Tok, TokKind};
 use regex::Regex;
-use log::{debug, error, warn};
+use log::{debug, warn};
 
 #[derive(Debug, Clone)]
 pub struct ParseError {
@@ -20,6 +20,8 @@ pub struct Parser<'a> {
     toks: &'a [Tok],
     cursor: usize,
     errors: Vec<ParseError>,
+    /// Message of the recoverable problem that aborted the current statement.
+    recovered: Option<String>,
 }
 
 impl<'a> Parser<'a> {
@@ -30,6 +32,7 @@ impl<'a> Parser<'a> {
             toks,
             cursor: 0,
             errors: Vec::new(),
+            recovered: None,
         }
     }
 
@@ -83,6 +86,17 @@ impl<'a> Parser<'a> {
     fn error<T>(&mut self, msg: impl Into<String>) -> Result<T, ()> {
         let line = self.peek_line();
         self.errors.push(ParseError { line, msg: msg.into() });
+        self.recovered = None;
+        Err(())
+    }
+
+    /// Aborts the current statement like [`Parser::error`], but the problem is
+    /// not reported as a parse error: the statement becomes a recovered
+    /// [`Stmt::Error`] and the rest of the script still loads.
+    fn soft_error<T>(&mut self, msg: impl Into<String>) -> Result<T, ()> {
+        let msg = msg.into();
+        warn!("line {}: {}", self.peek_line(), msg);
+        self.recovered = Some(msg);
         Err(())
     }
 
@@ -134,9 +148,14 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Consumes and returns a numeric literal token.
+    /// Consumes and returns a numeric literal token, with an optional leading `-`.
     fn num(&mut self) -> Result<f64, ()> {
+        let negative = matches!(self.peek(), Some(TokKind::Minus));
+        if negative {
+            self.bump();
+        }
         match &self.bump().tok {
+            TokKind::Num(n) if negative => Ok(-*n),
             TokKind::Num(n) => Ok(*n),
             x => self.error(format!("Expected number, got {:?}", x)),
         }
@@ -175,6 +194,15 @@ impl<'a> Parser<'a> {
             ))
     }
 
+    /// Key of a `key=` pair whose key is not a known parameter name (the lexer
+    /// reads it as a plain identifier).
+    fn at_unknown_param(&self) -> Option<&'a str> {
+        match (self.peek(), self.peek_nth(1)) {
+            (Some(TokKind::Ident(k)), Some(TokKind::Equals)) => Some(k),
+            _ => None,
+        }
+    }
+
     /// Skips over any trivia (new-lines and comments) at the current position.
     fn skip_trivia(&mut self) {
         while let Some(k) = self.peek() {
@@ -187,23 +215,57 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Span of the next token, i.e. where the statement about to be parsed starts.
+    fn next_span(&self) -> Span {
+        self.toks.get(self.cursor).map_or_else(|| self.span(), |t| t.span)
+    }
+
+    /// Builds a [`Stmt::Error`] placeholder for a statement that failed to
+    /// parse, carrying the most recent error message.
+    fn error_stmt(&mut self, span: Span) -> Stmt {
+        if let Some(msg) = self.recovered.take() {
+            return Stmt::Error { span, msg, recovered: true };
+        }
+        let msg = self.errors.last()
+            .map(|e| e.msg.clone())
+            .unwrap_or_else(|| "syntax error".to_string());
+        Stmt::Error { span, msg, recovered: false }
+    }
+
+    /// Replaces the statement that started at token `from` with an error
+    /// placeholder and moves on to the next line.
+    fn recover(&mut self, span: Span, from: usize) -> Stmt {
+        let stmt = self.error_stmt(span);
+        // 出错时已经吃掉了换行符，说明已经位于下一行开头，不能再跳过一行
+        let at_line_start = self.cursor > from
+            && self.toks.get(self.cursor - 1).is_some_and(|t| t.tok == TokKind::Newline);
+        if !at_line_start {
+            self.synchronize();
+        }
+        stmt
+    }
+
+    /// Skips to the start of the next line. `Eof` is left in place so the
+    /// enclosing loops can still see the end of input.
     fn synchronize(&mut self) {
         while let Some(k) = self.peek() {
-            if matches!(k, TokKind::Newline | TokKind::Eof) {
-                self.bump(); // 吃掉换行符
-                return;
+            match k {
+                TokKind::Newline => {
+                    self.bump(); // 吃掉换行符
+                    return;
+                }
+                TokKind::Eof => return,
+                _ => { self.bump(); }
             }
-            self.bump();
         }
     }
 
     fn parse_block(&mut self, terminators: &[TokKind]) -> Result<Vec<Stmt>, ()> {
         let mut body = Vec::new();
         loop {
-            // 安全性检查
-            if self.at(TokKind::Eof) {
-                error!("Unexpected EOF inside block");
-                std::process::exit(1);
+            // 游标越过 Eof 时 peek 为 None，同样视为文件结束
+            if self.at(TokKind::Eof) || self.peek().is_none() {
+                return self.error("Unexpected EOF inside block");
             }
 
             // 检查终止符
@@ -218,19 +280,33 @@ impl<'a> Parser<'a> {
 
             // 解析下一条语句
             // 注意：stmt() 内部会处理 Newline/Comment 并返回 None
+            let (start, from) = (self.next_span(), self.cursor);
             match self.stmt() {
                 Ok(Some(s)) => body.push(s),
                 Ok(None) => {}, // 空行
                 Err(_) => {
-                    self.synchronize();
-                    // 在 block 内出错不应直接跳出 block，而是尝试解析下一行
+                    // 在 block 内出错不应直接跳出 block，留下占位节点后尝试解析下一行
+                    let stmt = self.recover(start, from);
+                    body.push(stmt);
                 }
             }
         }
     }
 
     /// Entry-point: parses the entire token stream into a [`Script`].
-    pub fn parse(mut self) -> Result<Script, Vec<ParseError>> {
+    pub fn parse(self) -> Result<Script, Vec<ParseError>> {
+        let (script, errors) = self.parse_lenient();
+        if errors.is_empty() {
+            Ok(script)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Like [`Parser::parse`], but always returns the recovered script.
+    /// Statements that failed to parse are replaced by [`Stmt::Error`]
+    /// placeholders so the rest of the script stays runnable.
+    pub fn parse_lenient(mut self) -> (Script, Vec<ParseError>) {
         debug!("Starting parse");
         let mut body = Vec::new();
         while self.peek().is_some() {
@@ -238,20 +314,18 @@ impl<'a> Parser<'a> {
                 break;
             }
 
+            let (start, from) = (self.next_span(), self.cursor);
             match self.stmt() {
                 Ok(Some(s)) => body.push(s),
                 Ok(None) => {}
                 Err(_) => {
-                    self.synchronize();
+                    let stmt = self.recover(start, from);
+                    body.push(stmt);
                 }
             }
         }
         debug!("Parse complete: {} top-level statements", body.len());
-        if self.errors.is_empty() {
-            Ok(Script { body })
-        } else {
-            Err(self.errors)
-        }
+        (Script { body }, self.errors)
     }
 
     /// Top-level statement dispatcher.
@@ -268,6 +342,9 @@ impl<'a> Parser<'a> {
             Some(TokKind::Stop) => Ok(Some(self.stop_audio()?)),
             Some(TokKind::Scene) => Ok(Some(self.scene()?)),
             Some(TokKind::Hide) => Ok(Some(self.hide()?)),
+            Some(TokKind::Nvl) => Ok(Some(self.nvl()?)),
+            Some(TokKind::Movie) => Ok(Some(self.movie()?)),
+            Some(TokKind::WaitAudio) => Ok(Some(self.wait_audio()?)),
             Some(TokKind::Dollar) => Ok(Some(self.dollar_luablock()?)),
             Some(TokKind::Lua) => Ok(Some(self.luablock()?)),
             Some(TokKind::Ident(_)) => Ok(Some(self.dialogue()?)),
@@ -282,18 +359,18 @@ impl<'a> Parser<'a> {
                 Ok(None)
             }
             _ => {
-                let t = self.bump();
                 // 不使用 self.error 以避免这里产生 parse error，
-                // 对于未知的 token 我们只是由 log 警告并跳过
-                warn!("line {}: skipped unexpected token {:?}", t.span.line, t.tok);
-                Ok(None)
+                // 留下占位节点并跳过这一行，执行到这里时给出警告
+                let msg = format!("Unexpected {:?} where a statement was expected", self.bump().tok);
+                self.soft_error(msg)
             }
         }
     }
 
     /// Parses a `label <id> enlb` statement.
     fn label(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
+        let title = self.doc_title();
         self.expect(TokKind::Label)?;
         let id = self.ident()?;
         let mut body = Vec::new();
@@ -301,19 +378,45 @@ impl<'a> Parser<'a> {
             if self.at(TokKind::Eof) {
                 return self.error(format!("Unexpected EOF inside label '{}'", id));
             }
+            let (start, from) = (self.next_span(), self.cursor);
             match self.stmt() {
                 Ok(Some(s)) => body.push(s),
                 Ok(None) => {},
-                Err(_) => self.synchronize(),
+                Err(_) => {
+                    let stmt = self.recover(start, from);
+                    body.push(stmt);
+                }
             }
         }
         self.expect(TokKind::EnLabel)?;
-        Ok(Stmt::Label { span, id, body })
+        Ok(Stmt::Label { span, id, title, body })
+    }
+
+    /// Looks back past the trivia already skipped for a `-- @title: ...` comment
+    /// on the line directly above the next token. Blank lines collapse into a
+    /// single `Newline` token, so adjacency is checked by line number.
+    fn doc_title(&self) -> Option<String> {
+        let line = self.next_span().line;
+        let mut i = self.cursor.checked_sub(1)?;
+        if matches!(self.toks[i].tok, TokKind::Newline) {
+            i = i.checked_sub(1)?;
+        }
+        let tok = &self.toks[i];
+        if tok.span.line + 1 != line {
+            return None;
+        }
+        match &tok.tok {
+            TokKind::Comment(text) => text.trim()
+                .strip_prefix("@title:")
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            _ => None,
+        }
     }
 
     /// Parses a `jump <label>` statement.
     fn jump(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Jump)?;
         let target = self.ident()?;
         Ok(Stmt::Jump { span, target })
@@ -321,15 +424,19 @@ impl<'a> Parser<'a> {
     
     /// Parses a `call <label>` statement.
     fn call(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Call)?;
         let target = self.ident()?;
         Ok(Stmt::Call { span, target })
     }
     
-    /// Parses a `choice [title] ... enco` statement.
+    /// Parses a `choice [title] [as <id>][:] [timeout=<secs> [default=<n>]] ... enco` statement.
+    ///
+    /// `as <id>` gives the choice a stable id for seen-tracking and saves.
+    /// `default` is 1-based like the numbers shown to the player and falls back
+    /// to the first option.
     fn choice(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Choice)?;
 
         self.skip_trivia();
@@ -341,6 +448,31 @@ impl<'a> Parser<'a> {
             }
         }
 
+        let mut id = None;
+        if matches!(self.peek(), Some(TokKind::Reserved(k)) if k == "as") {
+            self.bump();
+            id = Some(self.ident()?);
+            self.consume(TokKind::Colon);
+        }
+
+        let mut timeout = None;
+        let mut default = None;
+        while let Some(TokKind::ParamKey(k)) = self.peek() {
+            let key = k.clone();
+            self.bump();
+            self.expect(TokKind::Equals)?;
+            let val = self.num()?;
+            match key.as_str() {
+                "timeout" if val > 0.0 => timeout = Some(val as f32),
+                "timeout" => return self.error("Choice timeout must be positive"),
+                "default" => default = Some(val),
+                _ => return self.error(format!("Unknown choice parameter '{}'", key)),
+            }
+        }
+        if default.is_some() && timeout.is_none() {
+            return self.error("Choice 'default' requires 'timeout'");
+        }
+
         let mut arms = Vec::new();
 
         while !self.at(TokKind::EnChoice) {
@@ -363,31 +495,50 @@ impl<'a> Parser<'a> {
             arms.push(ChoiceArm { text, body });
         }
 
+        let default = match default {
+            None => 0,
+            Some(n) if n.fract() == 0.0 && n >= 1.0 && (n as usize) <= arms.len() => n as usize - 1,
+            Some(n) => return self.error(format!("Choice default {} is out of range 1..={}", n, arms.len())),
+        };
+
         self.expect(TokKind::EnChoice)?;
-        Ok(Stmt::Choice { span, title, arms, id: None })
+        Ok(Stmt::Choice { span, title, arms, id, timeout, default })
     }
 
     /// Parses a character statement.
     fn character(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Character)?;
         let id = self.ident()?;
         let mut name = None;
         let mut image_tag = None;
         let mut voice_tag = None;
+        let mut color = None;
+        let mut voice_volume = None;
+        let mut voice_pitch = None;
         while let Some(TokKind::ParamKey(k)) = self.peek() {
             let key = k.clone();
             self.bump();
             self.expect(TokKind::Equals)?;
-            let val = self.str_or_ident()?;
             match key.as_str() {
-                "name" => name = Some(val),
-                "image_tag" => image_tag = Some(val),
-                "voice_tag" => voice_tag = Some(val),
-                _ => return self.error(format!("Unknown parameter key '{}'", key)),
+                "voice_volume" => voice_volume = Some(self.clamped_num(&key, 0.0, 2.0)?),
+                "voice_pitch" => voice_pitch = Some(self.clamped_num(&key, 0.5, 2.0)?),
+                _ => {
+                    let val = self.str_or_ident()?;
+                    match key.as_str() {
+                        "name" => name = Some(val),
+                        "image_tag" => image_tag = Some(val),
+                        "voice_tag" => voice_tag = Some(val),
+                        "color" => color = Some(val),
+                        _ => return self.soft_error(format!("Unknown parameter key '{}'", key)),
+                    }
+                }
             }
         }
 
+        if let Some(key) = self.at_unknown_param() {
+            return self.soft_error(format!("Unknown parameter key '{}'", key));
+        }
         if name.is_none() {
             return self.error("Character definition requires 'name' parameter");
         }
@@ -398,12 +549,26 @@ impl<'a> Parser<'a> {
             name: name.unwrap(),
             image_tag,
             voice_tag,
+            color,
+            voice_volume,
+            voice_pitch,
         })
     }
+
+    /// Reads a number for `key`, clamping it into `min..=max` with a warning.
+    fn clamped_num(&mut self, key: &str, min: f32, max: f32) -> Result<f32, ()> {
+        let line = self.next_span().line;
+        let val = self.num()? as f32;
+        let clamped = val.clamp(min, max);
+        if clamped != val {
+            warn!("line {}: {}={} is out of range, clamped to {}", line, key, val, clamped);
+        }
+        Ok(clamped)
+    }
     
     /// Parses `<speaker> [ @ alias ]: "text"` dialogue.
     fn dialogue(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         let name = self.ident()?;
         let alias = if self.at(TokKind::At) {
             self.bump();
@@ -429,26 +594,64 @@ impl<'a> Parser<'a> {
             speaker: Speaker { name, alias },
             text,
             voice_index,
+            tl_id: None,
+            voice_line: None,
         })
     }
 
     /// Parses a colon-style narration block.
     fn narration(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Colon)?;
         let mut lines = Vec::new();
+        let mut line_spans = Vec::new();
         if self.at(TokKind::Str("".into())) {
             let s = self.string()?;
-            for i in s.trim().lines() {
-                lines.push(i.to_string());
+            let str_span = self.span();
+            // 只把空格与制表符当作缩进，全角空格等排版用的首行缩进保留在文本中
+            let indent_of = |l: &str| l.len() - l.trim_start_matches([' ', '\t']).len();
+            // 首行紧跟引号，不参与公共缩进的计算
+            let indent = s.split('\n').skip(1)
+                .filter(|l| !l.trim().is_empty())
+                .map(indent_of)
+                .min()
+                .unwrap_or(0);
+            let mut offset = 0;
+            for (i, raw) in s.split('\n').enumerate() {
+                let start = str_span.start + offset;
+                offset += raw.len() + 1;
+                let raw = raw.strip_suffix('\r').unwrap_or(raw);
+                // 空行保留为段落分隔，只含空白的行也视为空行
+                if raw.trim().is_empty() {
+                    lines.push(String::new());
+                    line_spans.push(Span { start, end: start, line: span.line + i });
+                    continue;
+                }
+                let strip = if i == 0 { indent_of(raw) } else { indent };
+                let text = raw[strip..].trim_end();
+                // 偏移按转义后的内容计算，含转义符时列位置可能略有偏差，行号不受影响
+                line_spans.push(Span {
+                    start: start + strip,
+                    end: start + strip + text.len(),
+                    line: span.line + i,
+                });
+                lines.push(text.to_string());
             }
+            // 首尾的空行只是引号所在的行，不是段落分隔
+            while lines.last().is_some_and(String::is_empty) {
+                lines.pop();
+                line_spans.pop();
+            }
+            let leading = lines.iter().take_while(|l| l.is_empty()).count();
+            lines.drain(..leading);
+            line_spans.drain(..leading);
         }
-        Ok(Stmt::Narration { span, lines })
+        Ok(Stmt::Narration { span, lines, line_spans, tl_id: None })
     }
 
     /// Parses a `lua ... enlua` block.
     fn luablock(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Lua)?;
         if !matches!(self.peek(), Some(TokKind::LuaBlock(_))) {
             return self.error("Expected lua block content");
@@ -465,7 +668,7 @@ impl<'a> Parser<'a> {
 
     /// Parses a `$lua_block` inline Lua expression.
     fn dollar_luablock(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Dollar)?;
         if !matches!(self.peek(), Some(TokKind::LuaBlock(_))) {
             return self.error("Expected lua block content after $");
@@ -480,7 +683,7 @@ impl<'a> Parser<'a> {
 
     /// Parses `play <channel> <resource> [options...] `.
     fn play_audio(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Play)?;
         let action = AudioAction::Play;
         let mut r#loop = false;
@@ -490,6 +693,9 @@ impl<'a> Parser<'a> {
         let mut volume = None;
         let mut fade_in = None;
         let mut fade_out = None;
+        let mut loop_start = None;
+        let mut loop_end = None;
+        let mut intro = None;
         let mut have_a_loop = false;
 
         loop {
@@ -508,6 +714,11 @@ impl<'a> Parser<'a> {
                     }
                     have_a_loop = true;
                 }
+                Some(TokKind::ParamKey(k)) if k == "intro" => {
+                    self.bump();
+                    self.expect(TokKind::Equals)?;
+                    intro = Some(self.str_or_ident()?);
+                }
                 Some(TokKind::ParamKey(k)) => {
                     let key = k.clone();
                     self.bump();
@@ -517,18 +728,37 @@ impl<'a> Parser<'a> {
                         "volume" => volume = Some(val),
                         "fade_in" => fade_in = Some(val),
                         "fade_out" => fade_out = Some(val),
-                        _ => return self.error(format!("Unknown param '{}'", key)),
+                        "loop_start" => loop_start = Some(val),
+                        "loop_end" => loop_end = Some(val),
+                        _ => return self.soft_error(format!("Unknown param '{}'", key)),
                     }
                 }
                 _ => break,
             }
         }
+        if let Some(key) = self.at_unknown_param() {
+            return self.soft_error(format!("Unknown param '{}'", key));
+        }
+
+        // A loop region implies `loop`; combining it with `noloop` is a mistake.
+        if loop_start.is_some() || loop_end.is_some() {
+            if have_a_loop && !r#loop {
+                return self.error("loop_start/loop_end cannot be used with noloop");
+            }
+            r#loop = true;
+        }
+        if let (Some(start), Some(end)) = (loop_start, loop_end) && end <= start {
+            return self.error(format!("loop_end ({}) must be after loop_start ({})", end, start));
+        }
 
         let options = AudioOptions {
             volume,
             fade_in,
             fade_out,
             r#loop,
+            loop_start,
+            loop_end,
+            intro,
         };
         Ok(Stmt::Audio {
             span,
@@ -541,7 +771,7 @@ impl<'a> Parser<'a> {
 
     /// Parses `stop <channel> [ options... ]`.
     fn stop_audio(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Stop)?;
         let action = AudioAction::Stop;
         let channel = self.str_or_ident()?;
@@ -554,15 +784,21 @@ impl<'a> Parser<'a> {
             let val = self.num()? as f32;
             match key.as_str() {
                 "fade_out" => fade_out = Some(val),
-                _ => return self.error(format!("Unknown param '{}'", key)),
+                _ => return self.soft_error(format!("Unknown param '{}'", key)),
             }
         }
+        if let Some(key) = self.at_unknown_param() {
+            return self.soft_error(format!("Unknown param '{}'", key));
+        }
 
         let options = AudioOptions {
             volume: None,
             fade_in: None,
             r#loop: false,
             fade_out,
+            loop_start: None,
+            loop_end: None,
+            intro: None,
         };
         Ok(Stmt::Audio {
             span,
@@ -573,9 +809,26 @@ impl<'a> Parser<'a> {
         })
     }
 
-    /// Parses `scene [ <image> [ attrs... ] ] [ with <effect> ]`.
+    /// Parses the `<effect> [<secs>]` following `with`.
+    fn transition(&mut self) -> Result<Transition, ()> {
+        if !self.at_val() {
+            return self.soft_error("Expected an effect name after 'with'");
+        }
+        let effect = self.str_or_ident()?;
+        let mut duration = None;
+        if let Some(TokKind::Num(_)) = self.peek() {
+            let secs = self.num()? as f32;
+            if secs < 0.0 {
+                return self.error(format!("Transition duration must not be negative, got {}", secs));
+            }
+            duration = Some(secs);
+        }
+        Ok(Transition { effect, duration })
+    }
+
+    /// Parses `scene [ <image> [ attrs... ] ] [ with <effect> [<secs>] ]`.
     fn scene(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         let mut image = None;
         let mut transition = None;
         self.expect(TokKind::Scene)?;
@@ -606,8 +859,7 @@ impl<'a> Parser<'a> {
         if let Some(TokKind::Reserved(k)) = self.peek() {
             if k == "with" {
                 self.bump(); // eat 'with'
-                let effect = self.str_or_ident()?;
-                transition = Some(Transition { effect });
+                transition = Some(self.transition()?);
             }
         }
 
@@ -622,9 +874,9 @@ impl<'a> Parser<'a> {
         })
     }
 
-    /// Parses `show <target> [attr|-attr...] [at <pos>] [with <effect>]`.
+    /// Parses `show <target> [attr|-attr...] [at <pos>] [with <effect> [<secs>]]`.
     fn show(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Show)?;
         let target = self.str_or_ident()?;
         let mut attrs = None;
@@ -651,8 +903,7 @@ impl<'a> Parser<'a> {
         while let Some(TokKind::Reserved(k)) = self.peek() {
             if k == "with" {
                 self.bump();
-                let effect = self.str_or_ident()?;
-                transition = Some(Transition { effect });
+                transition = Some(self.transition()?);
             } else if k == "at" {
                 self.bump();
                 position = Some(self.str_or_ident()?);
@@ -674,9 +925,9 @@ impl<'a> Parser<'a> {
         })
     }
 
-    /// Parses `hide <target>`.
+    /// Parses `hide <target> [with <effect> [<secs>]]`.
     fn hide(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Hide)?;
         let target = self.str_or_ident()?;
 
@@ -684,8 +935,7 @@ impl<'a> Parser<'a> {
         if let Some(TokKind::Reserved(k)) = self.peek() {
             if k == "with" {
                 self.bump();
-                let effect = self.str_or_ident()?;
-                transition = Some(Transition { effect });
+                transition = Some(self.transition()?);
             }
         }
 
@@ -699,8 +949,58 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Parses `nvl on|off|clear`.
+    fn nvl(&mut self) -> Result<Stmt, ()> {
+        let span = self.next_span();
+        self.expect(TokKind::Nvl)?;
+        let action = match self.ident()?.as_str() {
+            "on" => NvlAction::On,
+            "off" => NvlAction::Off,
+            "clear" => NvlAction::Clear,
+            x => return self.error(format!("Expected 'on', 'off' or 'clear' after 'nvl', got '{}'", x)),
+        };
+
+        if !self.at(TokKind::Comment("".into())) {
+            self.expect_any([TokKind::Eof, TokKind::Newline])?;
+        }
+        Ok(Stmt::Nvl { span, action })
+    }
+
+    /// Parses `movie <id> [noskip]`.
+    fn movie(&mut self) -> Result<Stmt, ()> {
+        let span = self.next_span();
+        self.expect(TokKind::Movie)?;
+        let id = self.str_or_ident()?;
+
+        let mut skippable = true;
+        if let Some(TokKind::Flag(f)) = self.peek() {
+            if f != "noskip" {
+                return self.error(format!("Unknown flag '{}' for movie", f));
+            }
+            self.bump();
+            skippable = false;
+        }
+
+        if !self.at(TokKind::Comment("".into())) {
+            self.expect_any([TokKind::Eof, TokKind::Newline])?;
+        }
+        Ok(Stmt::Movie { span, id, skippable })
+    }
+
+    /// Parses `wait_audio <channel>`.
+    fn wait_audio(&mut self) -> Result<Stmt, ()> {
+        let span = self.next_span();
+        self.expect(TokKind::WaitAudio)?;
+        let channel = self.str_or_ident()?;
+
+        if !self.at(TokKind::Comment("".into())) {
+            self.expect_any([TokKind::Eof, TokKind::Newline])?;
+        }
+        Ok(Stmt::WaitAudio { span, channel })
+    }
+
     fn if_stmt(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::If)?;
 
         let mut branches = Vec::new();
@@ -744,7 +1044,7 @@ impl<'a> Parser<'a> {
     }
 
     fn screen_def(&mut self) -> Result<Stmt, ()> {
-        let span = self.span();
+        let span = self.next_span();
         self.expect(TokKind::Screen)?;
         let id = self.ident()?;
 
@@ -766,7 +1066,7 @@ impl<'a> Parser<'a> {
     }
 
     fn ui_node(&mut self) -> Result<Option<UiStmt>, ()> {
-        let span = self.span();
+        let span = self.next_span();
 
         if let Some(kind) = self.match_container_start() {
             self.bump(); // consume vbox/zbox...
@@ -810,7 +1110,7 @@ impl<'a> Parser<'a> {
         }
 
         // 4. 注释
-        if let TokKind::Comment(_) = self.peek().unwrap() {
+        if let Some(TokKind::Comment(_)) = self.peek() {
             self.bump();
             return Ok(None);
         }
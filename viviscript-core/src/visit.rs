@@ -0,0 +1,116 @@
+//! Traversal helpers over the statement tree.
+//!
+//! [`walk_stmt`] and [`walk_stmt_mut`] are the only places that know which
+//! statements contain nested blocks (label bodies, choice arms, if branches).
+//! Passes implement [`Visitor`] or [`VisitorMut`], override the hooks they care
+//! about and call back into the `walk_*` functions to keep descending.
+
+use crate::ast::{ChoiceArm, Stmt};
+
+/// Which container a nested block belongs to.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Block<'a> {
+    /// Body of the label with this id.
+    Label(&'a str),
+    /// Body of the choice arm at this index.
+    Arm(usize),
+    /// Body of the `if`/`elif` branch at this index.
+    Branch(usize),
+    /// Body of an `else` branch.
+    Else,
+}
+
+/// Read-only traversal. Every hook defaults to recursing into its children.
+pub trait Visitor {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_label(&mut self, id: &str, body: &[Stmt]) {
+        self.visit_block(Block::Label(id), body);
+    }
+
+    fn visit_choice_arm(&mut self, index: usize, arm: &ChoiceArm) {
+        self.visit_block(Block::Arm(index), &arm.body);
+    }
+
+    fn visit_block(&mut self, _block: Block<'_>, stmts: &[Stmt]) {
+        walk_stmts(stmts, self);
+    }
+}
+
+/// Visits each statement of `stmts` in order.
+pub fn walk_stmts<V: Visitor + ?Sized>(stmts: &[Stmt], visitor: &mut V) {
+    for stmt in stmts {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+/// Visits the nested blocks of `stmt`, if it has any.
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::Label { id, body, .. } => visitor.visit_label(id, body),
+        Stmt::Choice { arms, .. } => {
+            for (idx, arm) in arms.iter().enumerate() {
+                visitor.visit_choice_arm(idx, arm);
+            }
+        },
+        Stmt::If { branches, else_branch, .. } => {
+            for (idx, (_, body)) in branches.iter().enumerate() {
+                visitor.visit_block(Block::Branch(idx), body);
+            }
+            if let Some(body) = else_branch {
+                visitor.visit_block(Block::Else, body);
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Mutable counterpart of [`Visitor`]. Blocks are handed out as `Vec`s so a
+/// pass can insert or remove statements.
+pub trait VisitorMut {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_label_mut(&mut self, id: &str, body: &mut Vec<Stmt>) {
+        self.visit_block_mut(Block::Label(id), body);
+    }
+
+    fn visit_choice_arm_mut(&mut self, index: usize, arm: &mut ChoiceArm) {
+        self.visit_block_mut(Block::Arm(index), &mut arm.body);
+    }
+
+    fn visit_block_mut(&mut self, _block: Block<'_>, stmts: &mut Vec<Stmt>) {
+        walk_stmts_mut(stmts, self);
+    }
+}
+
+/// Visits each statement of `stmts` in order.
+pub fn walk_stmts_mut<V: VisitorMut + ?Sized>(stmts: &mut [Stmt], visitor: &mut V) {
+    for stmt in stmts {
+        visitor.visit_stmt_mut(stmt);
+    }
+}
+
+/// Visits the nested blocks of `stmt`, if it has any.
+pub fn walk_stmt_mut<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut Stmt) {
+    match stmt {
+        Stmt::Label { id, body, .. } => visitor.visit_label_mut(id, body),
+        Stmt::Choice { arms, .. } => {
+            for (idx, arm) in arms.iter_mut().enumerate() {
+                visitor.visit_choice_arm_mut(idx, arm);
+            }
+        },
+        Stmt::If { branches, else_branch, .. } => {
+            for (idx, (_, body)) in branches.iter_mut().enumerate() {
+                visitor.visit_block_mut(Block::Branch(idx), body);
+            }
+            if let Some(body) = else_branch {
+                visitor.visit_block_mut(Block::Else, body);
+            }
+        },
+        _ => {}
+    }
+}
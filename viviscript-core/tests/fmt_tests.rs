@@ -0,0 +1,164 @@
+use regex::Regex;
+use viviscript_core::fmt::format_source;
+use viviscript_core::lexer::Lexer;
+use viviscript_core::parser::Parser;
+
+/// Debug dump of the parsed script with every span blanked out.
+fn ast_without_spans(src: &str) -> String {
+    let tokens = Lexer::new(src).run();
+    let script = Parser::new(&tokens).parse().unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}\nsource:\n{}", errs, src);
+    });
+    let spans = Regex::new(r"Span \{[^}]*\}").unwrap();
+    spans.replace_all(&format!("{:?}", script), "Span").into_owned()
+}
+
+/// Formatting keeps the AST and a second pass changes nothing.
+fn assert_round_trip(src: &str) -> String {
+    let once = format_source(src).unwrap_or_else(|errs| panic!("Format failed: {:#?}", errs));
+    assert_eq!(ast_without_spans(src), ast_without_spans(&once), "AST changed, formatted:\n{}", once);
+    let twice = format_source(&once).unwrap();
+    assert_eq!(once, twice, "formatting is not idempotent");
+    once
+}
+
+const MESSY: &str = r##"
+character  alice   name = "Alice"  image_tag="alice" color="#ff8800" voice_volume=1.5
+-- @title: The Beginning
+label start   -- entry point
+scene bg  room with   fade   1.25
+  show alice happy -glasses at left with dissolve
+      play music "bgm/theme" volume=0.5 fade_in=2 loop
+alice: Hello there!(v001)
+alice @ hidden:   who's that?
+:A narration line
+
+:"""
+    First line
+      keeps its indent
+
+    after a blank
+    """
+$ f.score = 1
+lua
+    if f.score > 0 then
+        print("-- not a comment")
+    end
+enlua
+    choice "Pick one" as pick timeout=3 default=2
+ "Left":  -- going left
+    jump left
+   -- between arms
+
+ "Right":
+   if f.a and -- first half
+      f.b
+  : both
+ elif f.c
+  call other
+   else
+    nvl clear
+   enif
+enco
+stop music fade_out=1.5
+wait_audio voice
+movie op noskip
+hide alice with dissolve 0.8
+-- trailing body comment
+enlb
+
+screen hud
+  vbox spacing=10 at center
+     text "Score" size=30
+     button "Start" action="jump start"
+  envbox
+enscreen
+-- end of file
+"##;
+
+#[test]
+fn test_format_round_trip() {
+    assert_round_trip(MESSY);
+}
+
+#[test]
+fn test_format_canonical_layout() {
+    let out = assert_round_trip(MESSY);
+    let expected = r##"character alice name="Alice" image_tag="alice" color="#ff8800" voice_volume=1.5
+-- @title: The Beginning
+label start -- entry point
+    scene bg room with fade 1.25
+    show alice happy -glasses at left with dissolve
+    play music "bgm/theme" volume=0.5 fade_in=2 loop
+    alice: Hello there!(v001)
+    alice@hidden: who's that?
+    : A narration line
+
+    :"""
+    First line
+      keeps its indent
+
+    after a blank
+    """
+    $ f.score = 1
+    lua
+    if f.score > 0 then
+        print("-- not a comment")
+    end
+enlua
+    choice "Pick one" as pick timeout=3 default=2
+        "Left": -- going left
+            jump left
+        -- between arms
+
+        "Right":
+            if f.a and f.b -- first half
+                : both
+            elif f.c
+                call other
+            else
+                nvl clear
+            enif
+    enco
+    stop music fade_out=1.5
+    wait_audio voice
+    movie op noskip
+    hide alice with dissolve 0.8
+    -- trailing body comment
+enlb
+
+screen hud
+    vbox spacing=10 align=center
+        text "Score" size=30
+        button "Start" action="jump start"
+    envbox
+enscreen
+-- end of file
+"##;
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_format_quotes_only_when_needed() {
+    let src = r#"
+label start
+    play music "theme" intro="intro piece"
+    show "with" "two words" -"a b"
+    alice@"Mr. X": "quoted (line)"
+    bob:"""  leading spaces"""
+    :"""back\\slash and \"quote\"
+    second"""
+enlb
+"#;
+    let out = assert_round_trip(src);
+    assert!(out.contains("play music theme intro=\"intro piece\""), "{}", out);
+    assert!(out.contains("show \"with\" \"two words\" -\"a b\""), "{}", out);
+    assert!(out.contains("alice@\"Mr. X\": \"quoted(line)\""), "{}", out);
+    assert!(out.contains("bob:\"\"\"  leading spaces\"\"\""), "{}", out);
+}
+
+#[test]
+fn test_format_rejects_broken_script() {
+    let errs = format_source("label start\n    jump\nenlb\n").unwrap_err();
+    assert!(!errs.is_empty());
+}
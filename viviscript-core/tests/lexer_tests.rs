@@ -66,6 +66,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unterminated_strings_end_at_eof() {
+        for src in ["\"abc", "'abc", "\"\"\"abc"] {
+            let toks = Lexer::new(src).run();
+            assert_eq!(toks[0].tok, TokKind::Str("abc".into()), "{}", src);
+            assert_eq!(toks[0].span.end, src.len(), "{}", src);
+            assert_eq!(toks[1].tok, TokKind::Eof);
+        }
+    }
+
+    #[test]
+    fn numbers_with_nine() {
+        assert_lex(
+            "9 19 99 9.9 9e9 0.9",
+            vec![
+                TokKind::Num(9.0),
+                TokKind::Num(19.0),
+                TokKind::Num(99.0),
+                TokKind::Num(9.9),
+                TokKind::Num(9e9),
+                TokKind::Num(0.9),
+            ],
+        );
+    }
+
     #[test]
     fn comments() {
         assert_lex(
@@ -87,6 +112,29 @@ enlua
         assert!(matches!(toks[1].tok, TokKind::LuaBlock(ref s) if s.trim() == r#"print("hello")"#));
     }
 
+    #[test]
+    fn lua_block_ignores_enlua_inside_words() {
+        let src = r#"
+lua
+    print("enlua")
+    local enluax = 1
+    x.enlua = enluax
+enlua
+: after
+"#;
+        let toks: Vec<TokKind> = Lexer::new(src).run().into_iter().map(|t| t.tok).collect();
+        assert!(matches!(toks[0], TokKind::Lua));
+        let TokKind::LuaBlock(code) = &toks[1] else { panic!("expected lua block, got {:?}", toks[1]) };
+        assert_eq!(code.trim(), "print(\"enlua\")\n    local enluax = 1\n    x.enlua = enluax");
+        assert!(matches!(toks[2], TokKind::EnLua));
+        assert!(toks.contains(&TokKind::Str("after".into())));
+
+        // 同一行内以空白分隔的 enlua 也能结束代码块
+        let toks: Vec<TokKind> = Lexer::new("lua f() enlua").run().into_iter().map(|t| t.tok).collect();
+        assert_eq!(toks[1], TokKind::LuaBlock(" f() ".into()));
+        assert_eq!(toks[2], TokKind::EnLua);
+    }
+
     #[test]
     fn choice_block() {
         let src = r#"
@@ -147,6 +195,43 @@ enco"#;
         assert_lex("scene ^ hide", vec![TokKind::Scene, TokKind::Hide]);
     }
 
+    #[test]
+    fn resource_paths() {
+        assert_lex(
+            "scene bg/room.night with fade\nshow cg.special-01 -smile\nhide a/b",
+            vec![
+                TokKind::Scene,
+                TokKind::Ident("bg/room.night".into()),
+                TokKind::Reserved("with".into()),
+                TokKind::Ident("fade".into()),
+                TokKind::Newline,
+                TokKind::Show,
+                TokKind::Ident("cg.special-01".into()),
+                TokKind::Minus,
+                TokKind::Ident("smile".into()),
+                TokKind::Newline,
+                TokKind::Hide,
+                TokKind::Ident("a/b".into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn interpolated_names() {
+        assert_lex(
+            "show {f.outfit} -{f.old}\nscene bg_{f.time}.night",
+            vec![
+                TokKind::Show,
+                TokKind::Ident("{f.outfit}".into()),
+                TokKind::Minus,
+                TokKind::Ident("{f.old}".into()),
+                TokKind::Newline,
+                TokKind::Scene,
+                TokKind::Ident("bg_{f.time}.night".into()),
+            ],
+        );
+    }
+
     #[test]
     fn character_definition() {
         assert_lex(
@@ -158,4 +243,94 @@ enco"#;
             ],
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn interpolation_mode_splits_strings() {
+        let src = "a: hi {f.name}!\n\"{{x}} {n}\"";
+        let toks = Lexer::new(src).with_interpolation(true).run();
+        let got: Vec<(TokKind, &str)> = toks.iter()
+            .filter(|t| !matches!(t.tok, TokKind::Eof))
+            .map(|t| (t.tok.clone(), &src[t.span.start..t.span.end]))
+            .collect();
+        assert_eq!(got, vec![
+            (TokKind::Ident("a".into()), "a"),
+            (TokKind::Colon, ":"),
+            (TokKind::StrPart("hi ".into()), "hi "),
+            (TokKind::Interp("f.name".into()), "{f.name}"),
+            (TokKind::StrPart("!".into()), "!"),
+            (TokKind::Newline, "\n"),
+            (TokKind::StrPart("{x}} ".into()), "{{x}} "),
+            (TokKind::Interp("n".into()), "{n}"),
+        ]);
+    }
+
+    #[test]
+    fn split_interpolation_follows_runtime_escapes() {
+        use viviscript_core::lexer::{split_interpolation, StrPiece};
+
+        let pieces: Vec<StrPiece> = split_interpolation(r"\{a\} {} {b} {unclosed")
+            .into_iter().map(|(p, _)| p).collect();
+        assert_eq!(pieces, vec![
+            StrPiece::Text("{a} {} ".into()),
+            StrPiece::Interp("b".into()),
+            StrPiece::Text(" {unclosed".into()),
+        ]);
+        // The default mode keeps strings whole.
+        assert_lex("\"{x}\"", vec![TokKind::Str("{x}".into())]);
+    }
+
+    #[test]
+    fn raw_strings_keep_backslashes() {
+        assert_lex(
+            r##"r"C:\assets\bg\n.png" r'say "hi" \' r"it's" r"""a\tb "quoted" c""""##,
+            vec![
+                TokKind::Str(r"C:\assets\bg\n.png".into()),
+                TokKind::Str(r##"say "hi" \"##.into()),
+                TokKind::Str("it's".into()),
+                TokKind::Str(r#"a\tb "quoted" c"#.into()),
+            ],
+        );
+        // A lone `r` is still a name.
+        assert_lex("show r", vec![TokKind::Show, TokKind::Ident("r".into())]);
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        assert_lex(
+            r#""\u{2764}\u{1F600}!" '\u{41}'"#,
+            vec![
+                TokKind::Str("\u{2764}\u{1F600}!".into()),
+                TokKind::Str("A".into()),
+            ],
+        );
+        // Invalid sequences are dropped instead of inserting the next char.
+        assert_lex(
+            r#""a\uzz" "b\u{110000}" "c\u{}""#,
+            vec![
+                TokKind::Str("azz".into()),
+                TokKind::Str("b{110000}".into()),
+                TokKind::Str("c{}".into()),
+            ],
+        );
+    }
+
+    #[test]
+    fn condition_continues_over_lines() {
+        let src = "if f.a and\n    (f.b or -- note (\n     f.c == \")\") \\\n    or f.brand:\n    : x";
+        let toks = lex(src);
+        assert_eq!(toks[..3], [
+            TokKind::If,
+            TokKind::Condition("f.a and (f.b or f.c == \")\") or f.brand".into()),
+            TokKind::Newline,
+        ]);
+
+        // `brand` is not the keyword `and`, so the condition ends at the line break.
+        assert_lex("if f.brand\njump x", vec![
+            TokKind::If,
+            TokKind::Condition("f.brand".into()),
+            TokKind::Newline,
+            TokKind::Jump,
+            TokKind::Ident("x".into()),
+        ]);
+    }
+}
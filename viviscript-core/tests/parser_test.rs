@@ -1,6 +1,6 @@
 use viviscript_core::lexer::Lexer;
 use viviscript_core::parser::Parser;
-use viviscript_core::ast::{ContainerKind, Stmt, UiStmt};
+use viviscript_core::ast::{ContainerKind, NvlAction, ShowAttr, Stmt, UiStmt};
 
 fn parse_code(input: &str) -> Result<viviscript_core::ast::Script, Vec<viviscript_core::parser::ParseError>> {
     let tokens = Lexer::new(input).run();
@@ -92,6 +92,111 @@ enlb
     assert!(errs[0].line >= 4);
 }
 
+#[test]
+fn test_lenient_parse_keeps_error_placeholder() {
+    let input = r#"
+label start
+    :"Line 1"
+    UNKNOWN_COMMAND_ERROR !!!
+    :"Line 2"
+enlb
+"#;
+    let tokens = Lexer::new(input).run();
+    let (script, errs) = Parser::new(&tokens).parse_lenient();
+    assert_eq!(errs.len(), 1);
+
+    let Some(Stmt::Label { body, .. }) = script.body.first() else {
+        panic!("expected label, got {:?}", script.body);
+    };
+    assert_eq!(body.len(), 3);
+    match &body[1] {
+        Stmt::Error { span, msg, recovered } => {
+            assert_eq!(span.line, 4);
+            assert_eq!(msg, &errs[0].msg);
+            assert!(!recovered);
+        }
+        other => panic!("expected error placeholder, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_timed_choice() {
+    let input = r#"
+choice "Dodge!" timeout=2.5 default=2
+    "Left":
+        :"left"
+    "Right":
+        :"right"
+enco
+"#;
+    let script = parse_code(input).unwrap_or_else(|errs| panic!("Parse failed: {:#?}", errs));
+    match &script.body[0] {
+        Stmt::Choice { title, arms, timeout, default, .. } => {
+            assert_eq!(title.as_deref(), Some("Dodge!"));
+            assert_eq!(arms.len(), 2);
+            assert_eq!(*timeout, Some(2.5));
+            assert_eq!(*default, 1);
+        }
+        other => panic!("expected choice, got {:?}", other),
+    }
+
+    let out_of_range = "choice timeout=3 default=3\n    \"A\":\n        :\"a\"\nenco\n";
+    assert!(parse_code(out_of_range).is_err());
+}
+
+#[test]
+fn test_choice_explicit_id() {
+    let input = r#"
+choice "Pick one" as route_choice:
+    "A":
+        :"a"
+enco
+choice as bare timeout=1
+    "B":
+        :"b"
+enco
+"#;
+    let script = parse_code(input).unwrap_or_else(|errs| panic!("Parse failed: {:#?}", errs));
+    let ids: Vec<_> = script.body.iter()
+        .map(|s| match s {
+            Stmt::Choice { title, id, .. } => (title.as_deref(), id.as_deref()),
+            other => panic!("expected choice, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(ids, [(Some("Pick one"), Some("route_choice")), (None, Some("bare"))]);
+}
+
+#[test]
+fn test_audio_loop_region() {
+    let script = parse_code("play music bgm loop_start=4.2 loop_end=60\n").unwrap();
+    match &script.body[0] {
+        Stmt::Audio { options, .. } => {
+            assert!(options.r#loop);
+            assert_eq!(options.loop_start, Some(4.2));
+            assert_eq!(options.loop_end, Some(60.0));
+        }
+        other => panic!("expected audio, got {:?}", other),
+    }
+
+    assert!(parse_code("play music bgm noloop loop_start=4\n").is_err());
+    assert!(parse_code("play music bgm loop_start=10 loop_end=5\n").is_err());
+}
+
+#[test]
+fn test_audio_intro() {
+    let script = parse_code("play music town intro=town_intro loop\nlabel intro\nenlb\n").unwrap();
+    match &script.body[0] {
+        Stmt::Audio { resource, options, .. } => {
+            assert_eq!(resource.as_deref(), Some("town"));
+            assert_eq!(options.intro.as_deref(), Some("town_intro"));
+            assert!(options.r#loop);
+        }
+        other => panic!("expected audio, got {:?}", other),
+    }
+    // `intro` without `=` is still an ordinary identifier
+    assert!(matches!(&script.body[1], Stmt::Label { id, .. } if id == "intro"));
+}
+
 #[test]
 fn test_complex_nested_layout() {
     let input = r#"
@@ -112,4 +217,240 @@ enscreen
     parse_code(input).unwrap_or_else(|errs| {
         panic!("Parse failed: {:#?}", errs);
     });
-}
\ No newline at end of file
+}
+#[test]
+fn test_nvl_statements() {
+    let input = r#"
+label start
+    nvl on
+    : page line
+    nvl clear
+    nvl off
+enlb
+"#;
+
+    let script = parse_code(input).unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}", errs);
+    });
+
+    let body = match &script.body[0] {
+        Stmt::Label { body, .. } => body,
+        _ => panic!("First statement should be Label"),
+    };
+    let actions: Vec<NvlAction> = body.iter()
+        .filter_map(|s| match s {
+            Stmt::Nvl { action, .. } => Some(*action),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(actions, vec![NvlAction::On, NvlAction::Clear, NvlAction::Off]);
+}
+
+#[test]
+fn test_nvl_unknown_action_is_error() {
+    let input = "nvl sideways\n";
+    assert!(parse_code(input).is_err());
+}
+
+#[test]
+fn test_multiline_narration_line_spans() {
+    let input = "label start\n    :\"\"\"\n    first\n    second\n    \"\"\"\nenlb\n";
+
+    let script = parse_code(input).unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}", errs);
+    });
+
+    let body = match &script.body[0] {
+        Stmt::Label { body, .. } => body,
+        _ => panic!("First statement should be Label"),
+    };
+    match &body[0] {
+        Stmt::Narration { span, lines, line_spans, .. } => {
+            assert_eq!(span.line, 2);
+            assert_eq!(lines, &["first", "second"]);
+            let located: Vec<(usize, &str)> = line_spans.iter()
+                .map(|s| (s.line, &input[s.start..s.end]))
+                .collect();
+            assert_eq!(located, vec![(3, "first"), (4, "second")]);
+        }
+        other => panic!("Expected Narration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_narration_keeps_blank_lines() {
+    let input = "label start\n    :\"\"\"\n    first\n\n    \n    second\n    \"\"\"\nenlb\n";
+
+    let script = parse_code(input).unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}", errs);
+    });
+    let body = match &script.body[0] {
+        Stmt::Label { body, .. } => body,
+        _ => panic!("First statement should be Label"),
+    };
+    match &body[0] {
+        Stmt::Narration { lines, line_spans, .. } => {
+            assert_eq!(lines, &["first", "", "", "second"]);
+            let line_numbers: Vec<usize> = line_spans.iter().map(|s| s.line).collect();
+            assert_eq!(line_numbers, [3, 4, 5, 6]);
+        }
+        other => panic!("Expected Narration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_narration_strips_common_indent() {
+    let input = "label start\n    :\"\"\"\n        poem\n          indented\n        \u{3000}full width\n    \"\"\"\nenlb\n";
+
+    let script = parse_code(input).unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}", errs);
+    });
+    let body = match &script.body[0] {
+        Stmt::Label { body, .. } => body,
+        _ => panic!("First statement should be Label"),
+    };
+    match &body[0] {
+        Stmt::Narration { lines, line_spans, .. } => {
+            assert_eq!(lines, &["poem", "  indented", "\u{3000}full width"]);
+            let located: Vec<&str> = line_spans.iter().map(|s| &input[s.start..s.end]).collect();
+            assert_eq!(located, ["poem", "  indented", "\u{3000}full width"]);
+        }
+        other => panic!("Expected Narration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_movie_statement() {
+    let input = "label start\n    movie \"op\"\n    movie movies/ed noskip\nenlb\n";
+
+    let script = parse_code(input).unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}", errs);
+    });
+    let body = match &script.body[0] {
+        Stmt::Label { body, .. } => body,
+        _ => panic!("First statement should be Label"),
+    };
+    let movies: Vec<(&str, bool)> = body.iter()
+        .filter_map(|s| match s {
+            Stmt::Movie { id, skippable, .. } => Some((id.as_str(), *skippable)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(movies, vec![("op", true), ("movies/ed", false)]);
+
+    assert!(parse_code("movie op loop\n").is_err());
+}
+
+#[test]
+fn test_wait_audio_statement() {
+    let script = parse_code("wait_audio music\nwait_audio \"jingle\"\n").unwrap();
+    let channels: Vec<&str> = script.body.iter()
+        .filter_map(|s| match s {
+            Stmt::WaitAudio { channel, .. } => Some(channel.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(channels, vec!["music", "jingle"]);
+
+    assert!(parse_code("wait_audio\n").is_err());
+}
+
+#[test]
+fn test_label_title_comment() {
+    let input = r#"
+-- @title: Chapter 2 — The Harbor
+label harbor
+    :"Waves."
+enlb
+
+-- @title: Detached
+
+label untitled
+enlb
+"#;
+    let tokens = Lexer::new(input).run();
+    let script = Parser::new(&tokens).parse().unwrap();
+    let titles: Vec<_> = script.body.iter()
+        .map(|s| match s {
+            Stmt::Label { id, title, .. } => (id.as_str(), title.clone()),
+            other => panic!("expected label, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(titles, vec![
+        ("harbor", Some("Chapter 2 — The Harbor".to_string())),
+        // a comment separated by a blank line is not a title
+        ("untitled", None),
+    ]);
+}
+
+#[test]
+fn test_transition_inline_duration() {
+    let input = "scene bg room with dissolve 1.2\nshow alice with fade 0.5 at left\nhide alice with dissolve\n";
+    let script = parse_code(input).unwrap();
+    let transitions: Vec<_> = script.body.iter()
+        .map(|s| match s {
+            Stmt::Scene { transition: Some(t), .. }
+            | Stmt::Show { transition: Some(t), .. }
+            | Stmt::Hide { transition: Some(t), .. } => (t.effect.as_str(), t.duration),
+            other => panic!("expected a transition, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(transitions, vec![("dissolve", Some(1.2)), ("fade", Some(0.5)), ("dissolve", None)]);
+
+    // the duration belongs to `with`, not to the image attributes
+    let Stmt::Scene { image: Some(image), .. } = &script.body[0] else { panic!() };
+    assert_eq!(image.attrs, Some(vec!["room".to_string()]));
+}
+
+#[test]
+fn test_recoverable_problems_become_error_nodes() {
+    let input = r#"
+label start
+    play music theme bogus=1
+    : still here
+    show alice with
+    enco
+    : end
+enlb
+"#;
+    let script = parse_code(input).unwrap_or_else(|errs| panic!("Parse failed: {:#?}", errs));
+    let Some(Stmt::Label { body, .. }) = script.body.first() else {
+        panic!("expected label, got {:?}", script.body);
+    };
+    assert_eq!(body.len(), 5, "{:#?}", body);
+    let errors: Vec<_> = body.iter()
+        .filter_map(|s| match s {
+            Stmt::Error { span, msg, recovered } => Some((span.line, msg.as_str(), *recovered)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(errors, [
+        (3, "Unknown param 'bogus'", true),
+        (5, "Expected an effect name after 'with'", true),
+        (6, "Unexpected EnChoice where a statement was expected", true),
+    ]);
+    assert!(matches!(body[4], Stmt::Narration { .. }));
+}
+
+#[test]
+fn test_negative_param_values() {
+    let script = parse_code("play music x volume=-6 fade_in=0.5\nshow alice -happy smile\n").unwrap();
+    match &script.body[0] {
+        Stmt::Audio { resource, options, .. } => {
+            assert_eq!(resource.as_deref(), Some("x"));
+            assert_eq!(options.volume, Some(-6.0));
+            assert_eq!(options.fade_in, Some(0.5));
+        }
+        other => panic!("expected audio, got {:?}", other),
+    }
+    // `-` before a name still removes an attribute
+    match &script.body[1] {
+        Stmt::Show { attrs, .. } => assert_eq!(attrs.as_deref(), Some(&[
+            ShowAttr::Remove("happy".to_string()),
+            ShowAttr::Add("smile".to_string()),
+        ][..])),
+        other => panic!("expected show, got {:?}", other),
+    }
+
+    assert!(parse_code("play music x volume=-\n").is_err());
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use viviscript_core::ast::{Script, Stmt};
+use viviscript_core::lexer::Lexer;
+use viviscript_core::parser::Parser;
+use viviscript_core::visit::{walk_stmt, walk_stmts, walk_stmts_mut, Block, Visitor, VisitorMut};
+
+fn parse_code(input: &str) -> Script {
+    let tokens = Lexer::new(input).run();
+    Parser::new(&tokens).parse().unwrap_or_else(|errs| {
+        panic!("Parse failed: {:#?}", errs);
+    })
+}
+
+const NESTED: &str = r#"
+character alice name="Alice"
+label start
+    alice: Hi
+    choice "Where?"
+        "Left":
+            : You go left.
+            if f.a
+                alice: Deep
+            elif f.b
+                jump start
+            else
+                : Nothing
+            enif
+        "Right":
+            call other
+    enco
+    label inner
+        : Inside
+    enlb
+enlb
+label other
+    alice: Bye
+enlb
+"#;
+
+#[derive(Default)]
+struct KindCounter {
+    counts: BTreeMap<&'static str, usize>,
+    blocks: Vec<String>,
+}
+
+impl Visitor for KindCounter {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        let kind = match stmt {
+            Stmt::CharacterDef { .. } => "character",
+            Stmt::Label { .. } => "label",
+            Stmt::Choice { .. } => "choice",
+            Stmt::If { .. } => "if",
+            Stmt::Dialogue { .. } => "dialogue",
+            Stmt::Narration { .. } => "narration",
+            Stmt::Jump { .. } => "jump",
+            Stmt::Call { .. } => "call",
+            _ => "other",
+        };
+        *self.counts.entry(kind).or_insert(0) += 1;
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_block(&mut self, block: Block<'_>, stmts: &[Stmt]) {
+        self.blocks.push(format!("{:?}", block));
+        walk_stmts(stmts, self);
+    }
+}
+
+#[test]
+fn test_visitor_reaches_every_nested_block() {
+    let script = parse_code(NESTED);
+    let mut counter = KindCounter::default();
+    walk_stmts(&script.body, &mut counter);
+
+    let expected: BTreeMap<_, _> = [
+        ("character", 1),
+        ("label", 3),
+        ("choice", 1),
+        ("if", 1),
+        ("dialogue", 3),
+        ("narration", 3),
+        ("jump", 1),
+        ("call", 1),
+    ].into_iter().collect();
+    assert_eq!(counter.counts, expected);
+    assert_eq!(counter.blocks, [
+        "Label(\"start\")",
+        "Arm(0)",
+        "Branch(0)",
+        "Branch(1)",
+        "Else",
+        "Arm(1)",
+        "Label(\"inner\")",
+        "Label(\"other\")",
+    ]);
+}
+
+/// Drops every `jump`, wherever it is nested.
+struct DropJumps;
+
+impl VisitorMut for DropJumps {
+    fn visit_block_mut(&mut self, _block: Block<'_>, stmts: &mut Vec<Stmt>) {
+        stmts.retain(|s| !matches!(s, Stmt::Jump { .. }));
+        walk_stmts_mut(stmts, self);
+    }
+}
+
+#[test]
+fn test_visitor_mut_can_rewrite_blocks() {
+    let mut script = parse_code(NESTED);
+    walk_stmts_mut(&mut script.body, &mut DropJumps);
+
+    let mut counter = KindCounter::default();
+    walk_stmts(&script.body, &mut counter);
+    assert_eq!(counter.counts.get("jump"), None);
+    assert_eq!(counter.counts["call"], 1);
+}